@@ -672,6 +672,32 @@ impl<'de> Deserialize<'de> for MoveType {
 }
 
 impl MoveType {
+    /// Replaces generic type params with the given type arguments
+    pub fn instantiate(&self, type_args: &[MoveType]) -> anyhow::Result<MoveType> {
+        Ok(match self {
+            MoveType::GenericTypeParam { index } => type_args
+                .get(*index as usize)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Missing type argument T{}", index))?,
+            MoveType::Vector { items } => MoveType::Vector {
+                items: Box::new(items.instantiate(type_args)?),
+            },
+            MoveType::Struct(tag) => MoveType::Struct(MoveStructTag {
+                generic_type_params: tag
+                    .generic_type_params
+                    .iter()
+                    .map(|typ| typ.instantiate(type_args))
+                    .collect::<anyhow::Result<_>>()?,
+                ..tag.clone()
+            }),
+            MoveType::Reference { mutable, to } => MoveType::Reference {
+                mutable: *mutable,
+                to: Box::new(to.instantiate(type_args)?),
+            },
+            _ => self.clone(),
+        })
+    }
+
     pub fn is_signer(&self) -> bool {
         match self {
             MoveType::Signer => true,
@@ -1251,6 +1277,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_instantiate_move_type() {
+        let typ = MoveType::Vector {
+            items: Box::new(MoveType::Struct(MoveStructTag {
+                address: address("0x1").into(),
+                module: identifier("coin").into(),
+                name: identifier("Coin").into(),
+                generic_type_params: vec![MoveType::GenericTypeParam { index: 1 }],
+            })),
+        };
+        let type_args = vec![MoveType::U8, "0x1::aptos_coin::AptosCoin".parse().unwrap()];
+        assert_eq!(
+            "vector<0x1::coin::Coin<0x1::aptos_coin::AptosCoin>>",
+            typ.instantiate(&type_args).unwrap().to_string()
+        );
+        assert!(typ.instantiate(&type_args[..1]).is_err());
+    }
+
     #[test]
     fn test_serialize_move_resource() {
         use AnnotatedMoveValue::*;
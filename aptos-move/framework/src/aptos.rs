@@ -113,6 +113,8 @@ impl ReleaseTarget {
                     collapsed_sections: true,
                     landing_page_template: Some("doc_template/overview.md".to_string()),
                     references_file: Some("doc_template/references.md".to_string()),
+                    output_directory: None,
+                    check: false,
                 }),
             },
             packages: packages.iter().map(|(path, _)| path.to_owned()).collect(),
@@ -7,6 +7,7 @@ use crate::natives::code::{
     ModuleMetadata, MoveOption, PackageDep, PackageMetadata, UpgradePolicy,
 };
 use crate::{zip_metadata, zip_metadata_str, RuntimeModuleMetadata, APTOS_METADATA_KEY};
+use anyhow::bail;
 use aptos_module_verifier::module_init::verify_module_init_function;
 use aptos_types::account_address::AccountAddress;
 use aptos_types::transaction::EntryABI;
@@ -82,6 +83,7 @@ pub(crate) fn build_model(
     package_path: &Path,
     additional_named_addresses: BTreeMap<String, AccountAddress>,
     target_filter: Option<String>,
+    install_dir: Option<PathBuf>,
 ) -> anyhow::Result<GlobalEnv> {
     let build_config = BuildConfig {
         dev_mode: false,
@@ -89,7 +91,7 @@ pub(crate) fn build_model(
         architecture: None,
         generate_abis: false,
         generate_docs: false,
-        install_dir: None,
+        install_dir,
         test_mode: false,
         force_recompilation: false,
         fetch_deps_only: false,
@@ -134,6 +136,7 @@ impl BuiltPackage {
                 package_path.as_path(),
                 options.named_addresses.clone(),
                 None,
+                options.install_dir.clone(),
             )?;
             if options.with_docs {
                 let docgen = if let Some(opts) = options.docgen_options.clone() {
@@ -234,6 +237,22 @@ impl BuiltPackage {
             .collect()
     }
 
+    /// Returns the names of the scripts in the package, which are the names of their functions.
+    pub fn script_names(&self) -> Vec<String> {
+        self.package
+            .scripts()
+            .map(|unit_with_source| unit_with_source.unit.name().to_string())
+            .collect()
+    }
+
+    /// Returns the serialized bytecode of the script with the given name, if there is one.
+    pub fn extract_script_code_by_name(&self, name: &str) -> Option<Vec<u8>> {
+        self.package
+            .scripts()
+            .find(|unit_with_source| unit_with_source.unit.name().as_str() == name)
+            .map(|unit_with_source| unit_with_source.unit.serialize(None))
+    }
+
     /// Extracts metadata, as needed for releasing a package, from the built package.
     pub fn extract_metadata(&self) -> anyhow::Result<PackageMetadata> {
         let source_digest = self
@@ -245,7 +264,7 @@ impl BuiltPackage {
         let manifest_file = self.package_path.join("Move.toml");
         let manifest = std::fs::read_to_string(&manifest_file)?;
         let custom_props = extract_custom_fields(&manifest)?;
-        let manifest = zip_metadata_str(&manifest)?;
+        let manifest = zip_metadata_str(&normalize_manifest(&manifest, &self.package_path)?)?;
         let upgrade_policy = if let Some(val) = custom_props.get(UPGRADE_POLICY_CUSTOM_FIELD) {
             str::parse::<UpgradePolicy>(val.as_ref())?
         } else {
@@ -255,7 +274,9 @@ impl BuiltPackage {
         for u in self.package.root_modules() {
             let name = u.unit.name().to_string();
             let source = if self.options.with_srcs {
-                zip_metadata_str(&std::fs::read_to_string(&u.source_path)?)?
+                zip_metadata_str(&normalize_line_endings(&std::fs::read_to_string(
+                    &u.source_path,
+                )?))?
             } else {
                 vec![]
             };
@@ -301,6 +322,50 @@ impl BuiltPackage {
         })
     }
 
+    /// Builds the package again from scratch, in a temporary directory, and fails if the metadata
+    /// or the code differ from this build's
+    ///
+    /// Others verifying the published package against its source rebuild it the same way.
+    pub fn check_reproducibility(&self) -> anyhow::Result<()> {
+        let install_dir = tempfile::tempdir()?;
+        let rebuilt = Self::build(
+            self.package_path.clone(),
+            BuildOptions {
+                install_dir: Some(install_dir.path().to_path_buf()),
+                with_docs: false,
+                docgen_options: None,
+                ..self.options.clone()
+            },
+        )?;
+
+        let mut differences = vec![];
+        if bcs::to_bytes(&self.extract_metadata()?)? != bcs::to_bytes(&rebuilt.extract_metadata()?)?
+        {
+            differences.push("metadata".to_string());
+        }
+        for (unit, rebuilt_unit) in self
+            .package
+            .root_modules()
+            .zip(rebuilt.package.root_modules())
+        {
+            if unit.unit.serialize(None) != rebuilt_unit.unit.serialize(None) {
+                differences.push(format!("module {}", unit.unit.name()));
+            }
+        }
+        if self.package.root_modules().count() != rebuilt.package.root_modules().count() {
+            differences.push("number of modules".to_string());
+        }
+        if differences.is_empty() {
+            Ok(())
+        } else {
+            bail!(
+                "Package {} isn't reproducible, rebuilding it changed its {}",
+                self.name(),
+                differences.join(", ")
+            )
+        }
+    }
+
     pub fn extract_metadata_and_save(&self) -> anyhow::Result<()> {
         let data = self.extract_metadata()?;
         let path = self.package_artifacts_path();
@@ -310,6 +375,56 @@ impl BuiltPackage {
     }
 }
 
+fn normalize_line_endings(text: &str) -> String {
+    text.replace("\r\n", "\n")
+}
+
+/// Normalizes the manifest to be the same wherever the package is built, with Unix line endings,
+/// and local dependencies at absolute paths made relative to the package
+fn normalize_manifest(manifest: &str, package_path: &Path) -> anyhow::Result<String> {
+    let manifest = normalize_line_endings(manifest);
+    let mut value: toml::Value = toml::from_str(&manifest)?;
+    let mut changed = false;
+    for section in ["dependencies", "dev-dependencies"] {
+        if let Some(toml::Value::Table(dependencies)) = value.get_mut(section) {
+            for dependency in dependencies.values_mut() {
+                if let Some(toml::Value::String(local)) = dependency.get_mut("local") {
+                    let path = Path::new(local.as_str());
+                    if path.is_absolute() {
+                        *local = relative_path(package_path, path).display().to_string();
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+    // The manifest is kept as written unless it has to change
+    if changed {
+        Ok(toml::to_string(&value)?)
+    } else {
+        Ok(manifest)
+    }
+}
+
+/// The path to `target` relative to `base`, both absolute
+fn relative_path(base: &Path, target: &Path) -> PathBuf {
+    let base: Vec<_> = base.components().collect();
+    let target: Vec<_> = target.components().collect();
+    let common = base
+        .iter()
+        .zip(&target)
+        .take_while(|(base, target)| base == target)
+        .count();
+    let mut path = PathBuf::new();
+    for _ in common..base.len() {
+        path.push("..");
+    }
+    for component in &target[common..] {
+        path.push(component);
+    }
+    path
+}
+
 fn extract_custom_fields(toml: &str) -> anyhow::Result<BTreeMap<String, String>> {
     let manifest = parse_source_manifest(parse_move_manifest_string(toml.to_owned())?)?;
     Ok(manifest
@@ -359,3 +474,41 @@ fn inject_module_metadata(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_manifest() {
+        let manifest = "[package]\r\nname = \"Example\"\r\nversion = \"0.0.1\"\r\n";
+        assert_eq!(
+            "[package]\nname = \"Example\"\nversion = \"0.0.1\"\n",
+            normalize_manifest(manifest, Path::new("/home/alice/example")).unwrap()
+        );
+
+        let manifest = r#"
+[package]
+name = "Example"
+version = "0.0.1"
+
+[dependencies]
+AptosFramework = { local = "/home/alice/aptos-core/aptos-move/framework/aptos-framework" }
+Other = { local = "../other" }
+"#;
+        let manifest: toml::Value = toml::from_str(
+            &normalize_manifest(manifest, Path::new("/home/alice/example")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            "../aptos-core/aptos-move/framework/aptos-framework",
+            manifest["dependencies"]["AptosFramework"]["local"]
+                .as_str()
+                .unwrap()
+        );
+        assert_eq!(
+            "../other",
+            manifest["dependencies"]["Other"]["local"].as_str().unwrap()
+        );
+    }
+}
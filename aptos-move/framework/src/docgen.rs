@@ -5,7 +5,8 @@ use anyhow::{anyhow, Context};
 use codespan_reporting::diagnostic::Severity;
 use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
 use move_model::model::GlobalEnv;
-use std::path::PathBuf;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 #[derive(Debug, Clone, clap::Parser, serde::Serialize, serde::Deserialize, Default)]
@@ -47,6 +48,15 @@ pub struct DocgenOptions {
     /// This can contain common markdown references fpr this package (e.g. `[move-book]: <url>`).
     #[clap(long)]
     pub references_file: Option<String>,
+
+    /// Package-relative directory to write the documentation to. Defaults to `doc`.
+    #[clap(long)]
+    pub output_directory: Option<String>,
+
+    /// Instead of writing the documentation, fail if it differs from what's already in the
+    /// output directory, e.g. to check in CI that it's up to date.
+    #[clap(long)]
+    pub check: bool,
 }
 
 impl DocgenOptions {
@@ -64,7 +74,7 @@ impl DocgenOptions {
         let _lock = MUTEX.lock();
         let current_dir = std::env::current_dir()?.canonicalize()?;
         std::env::set_current_dir(&package_path)?;
-        let output_directory = PathBuf::from("doc");
+        let output_directory = PathBuf::from(self.output_directory.as_deref().unwrap_or("doc"));
         let doc_path = doc_path
             .into_iter()
             .filter_map(|s| {
@@ -101,6 +111,8 @@ impl DocgenOptions {
         }
         let res = if model.has_errors() {
             Err(anyhow!("documentation generation failed"))
+        } else if self.check {
+            check_output(&output_directory, &output)
         } else {
             // Write the generated output files
             std::fs::create_dir_all(&output_directory)?;
@@ -115,3 +127,35 @@ impl DocgenOptions {
         res
     }
 }
+
+/// Fails naming each file in the output directory which is missing, differs from the generated
+/// documentation, or is no longer generated
+fn check_output(output_directory: &Path, output: &[(String, String)]) -> anyhow::Result<()> {
+    let mut stale = vec![];
+    for (file_name, content) in output {
+        if std::fs::read_to_string(file_name).ok().as_ref() != Some(content) {
+            stale.push(file_name.clone());
+        }
+    }
+    if output_directory.is_dir() {
+        let generated: BTreeSet<_> = output
+            .iter()
+            .map(|(file_name, _)| PathBuf::from(file_name))
+            .collect();
+        for entry in std::fs::read_dir(output_directory)? {
+            let path = entry?.path();
+            if path.extension().map_or(false, |ext| ext == "md") && !generated.contains(&path) {
+                stale.push(path.display().to_string());
+            }
+        }
+    }
+    if stale.is_empty() {
+        Ok(())
+    } else {
+        stale.sort();
+        Err(anyhow!(
+            "documentation is out of date, regenerate it without `--check`:\n{}",
+            stale.join("\n")
+        ))
+    }
+}
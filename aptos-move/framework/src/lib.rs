@@ -28,8 +28,8 @@ pub use released_framework::*;
 pub use release_bundle::*;
 
 use flate2::read::GzDecoder;
-use flate2::write::GzEncoder;
 use flate2::Compression;
+use flate2::GzBuilder;
 use std::path::PathBuf;
 
 pub fn path_in_crate<S>(relative: S) -> PathBuf
@@ -44,8 +44,15 @@ pub(crate) fn path_relative_to_crate(path: PathBuf) -> PathBuf {
     path.strip_prefix(crate_path).unwrap_or(&path).to_path_buf()
 }
 
+/// Operating system byte of the gzip header for an unknown system
+const GZIP_UNKNOWN_OS: u8 = 255;
+
+/// Compresses metadata, with a fixed gzip header so it's the same wherever it's compressed
 pub fn zip_metadata(data: &[u8]) -> anyhow::Result<Vec<u8>> {
-    let mut e = GzEncoder::new(Vec::new(), Compression::best());
+    let mut e = GzBuilder::new()
+        .mtime(0)
+        .operating_system(GZIP_UNKNOWN_OS)
+        .write(Vec::new(), Compression::best());
     e.write_all(data)?;
     Ok(e.finish()?)
 }
@@ -3,11 +3,13 @@
 
 use crate::build_model;
 use codespan_reporting::diagnostic::Severity;
-use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
+use codespan_reporting::term::termcolor::{ColorChoice, StandardStream, WriteColor};
 use log::LevelFilter;
 use move_core_types::account_address::AccountAddress;
+use move_model::model::VerificationScope;
+use serde::Serialize;
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use tempfile::TempDir;
 
@@ -22,6 +24,11 @@ pub struct ProverOptions {
     #[clap(long, short)]
     pub filter: Option<String>,
 
+    /// Scopes verification to the specified function, e.g. `coin::transfer`. Other functions
+    /// are still checked to type check, but their specs are not verified.
+    #[clap(long)]
+    pub only: Option<String>,
+
     /// Whether to display additional information in error reports. This may help
     /// debugging but also can make verification slower.
     #[clap(long, short)]
@@ -32,6 +39,22 @@ pub struct ProverOptions {
     #[clap(long)]
     pub cvc5: bool,
 
+    /// Path to the boogie executable, instead of the environment variable `BOOGIE_EXE`.
+    #[clap(long)]
+    pub boogie_exe: Option<String>,
+
+    /// Path to the z3 executable, instead of the environment variable `Z3_EXE`.
+    #[clap(long)]
+    pub z3_exe: Option<String>,
+
+    /// Path to the cvc5 executable, instead of the environment variable `CVC5_EXE`.
+    #[clap(long)]
+    pub cvc5_exe: Option<String>,
+
+    /// Additional flags passed on to boogie, e.g. `-proverOpt:O:smt.qi.eager_threshold=100`.
+    #[clap(long, multiple_values = true, allow_hyphen_values = true)]
+    pub boogie_flags: Vec<String>,
+
     /// The depth until which stratified functions are expanded.
     #[clap(long, default_value_t = 6)]
     pub stratification_depth: usize,
@@ -78,8 +101,13 @@ impl Default for ProverOptions {
         Self {
             verbosity: None,
             filter: None,
+            only: None,
             trace: false,
             cvc5: false,
+            boogie_exe: None,
+            z3_exe: None,
+            cvc5_exe: None,
+            boogie_flags: vec![],
             stratification_depth: 6,
             random_seed: 0,
             proc_cores: 4,
@@ -100,10 +128,32 @@ impl ProverOptions {
         self,
         package_path: &Path,
         named_addresses: BTreeMap<String, AccountAddress>,
+    ) -> anyhow::Result<()> {
+        self.prove_with_writer(
+            package_path,
+            named_addresses,
+            None,
+            &mut StandardStream::stderr(ColorChoice::Auto),
+        )
+    }
+
+    /// Runs the move prover on the package, resolved into `install_dir` as when building it,
+    /// and reports diagnostics to the writer.
+    pub fn prove_with_writer<W: WriteColor>(
+        self,
+        package_path: &Path,
+        named_addresses: BTreeMap<String, AccountAddress>,
+        install_dir: Option<PathBuf>,
+        writer: &mut W,
     ) -> anyhow::Result<()> {
         let now = Instant::now();
         let for_test = self.for_test;
-        let model = build_model(package_path, named_addresses, self.filter.clone())?;
+        let model = build_model(
+            package_path,
+            named_addresses,
+            self.filter.clone(),
+            install_dir,
+        )?;
         let mut options = self.convert_options();
         // Need to ensure a distinct output.bpl file for concurrent execution. In non-test
         // mode, we actually want to use the static output.bpl for debugging purposes
@@ -123,8 +173,7 @@ impl ProverOptions {
                 .to_string();
             None
         };
-        let mut writer = StandardStream::stderr(ColorChoice::Auto);
-        move_prover::run_move_prover_with_model(&model, &mut writer, options, Some(now))?;
+        move_prover::run_move_prover_with_model(&model, writer, options, Some(now))?;
         Ok(())
     }
 
@@ -136,7 +185,14 @@ impl ProverOptions {
         } else {
             LevelFilter::Info
         };
-        let opts = move_prover::cli::Options {
+        let default_backend = move_prover_boogie_backend::options::BoogieOptions::default();
+        let default_backend = move_prover_boogie_backend::options::BoogieOptions {
+            boogie_exe: self.boogie_exe.unwrap_or(default_backend.boogie_exe),
+            z3_exe: self.z3_exe.unwrap_or(default_backend.z3_exe),
+            cvc5_exe: self.cvc5_exe.unwrap_or(default_backend.cvc5_exe),
+            ..default_backend
+        };
+        let mut opts = move_prover::cli::Options {
             output_path: "".to_string(),
             verbosity_level,
             prover: move_stackless_bytecode::options::ProverOptions {
@@ -155,7 +211,7 @@ impl ProverOptions {
             },
             backend: move_prover_boogie_backend::options::BoogieOptions {
                 use_cvc5: self.cvc5,
-                boogie_flags: vec![],
+                boogie_flags: self.boogie_flags,
                 generate_smt: self.dump,
                 stratification_depth: self.stratification_depth,
                 proc_cores: self.proc_cores,
@@ -169,10 +225,13 @@ impl ProverOptions {
                 },
                 custom_natives: None,
                 loop_unroll: self.loop_unroll,
-                ..Default::default()
+                ..default_backend
             },
             ..Default::default()
         };
+        if let Some(function) = self.only {
+            opts.prover.verify_scope = VerificationScope::Only(function);
+        }
         if self.for_test {
             opts.setup_logging_for_test();
         } else {
@@ -188,3 +247,85 @@ impl ProverOptions {
         }
     }
 }
+
+/// A diagnostic of the prover, e.g. a spec that doesn't hold
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ProverDiagnostic {
+    /// `error`, `warning`, `bug`, `note` or `help`
+    pub severity: String,
+    pub message: String,
+    /// Primary location as `<file>:<line>:<column>`, if any
+    pub location: Option<String>,
+    /// The diagnostic as the prover prints it, with source excerpts and execution traces
+    pub text: String,
+}
+
+/// Splits the prover's uncolored output into its diagnostics
+pub fn parse_diagnostics(output: &str) -> Vec<ProverDiagnostic> {
+    const SEVERITIES: [&str; 5] = ["error", "warning", "bug", "note", "help"];
+
+    let mut diagnostics: Vec<ProverDiagnostic> = vec![];
+    for line in output.lines() {
+        let header = line.split_once(": ").filter(|(severity, _)| {
+            let severity = severity.split('[').next().unwrap_or_default();
+            SEVERITIES.contains(&severity)
+        });
+        match (header, diagnostics.last_mut()) {
+            (Some((severity, message)), _) => diagnostics.push(ProverDiagnostic {
+                severity: severity.split('[').next().unwrap_or_default().to_string(),
+                message: message.to_string(),
+                location: None,
+                text: line.to_string(),
+            }),
+            (None, Some(diagnostic)) => {
+                if diagnostic.location.is_none() {
+                    if let Some((_, location)) = line.split_once("┌─ ") {
+                        diagnostic.location = Some(location.trim().to_string());
+                    }
+                }
+                diagnostic.text.push('\n');
+                diagnostic.text.push_str(line);
+            }
+            // Progress output before the first diagnostic
+            (None, None) => {}
+        }
+    }
+    for diagnostic in &mut diagnostics {
+        diagnostic.text = diagnostic.text.trim_end().to_string();
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let output = "\
+[INFO] preparing module 0x1::m
+error: post-condition does not hold
+   ┌─ /tmp/m/sources/m.move:5:9
+   │
+ 5 │         ensures result == 1;
+   │         ^^^^^^^^^^^^^^^^^^^^
+
+warning[W09001]: unused variable
+   ┌─ /tmp/m/sources/m.move:8:13
+";
+        let diagnostics = parse_diagnostics(output);
+        assert_eq!(2, diagnostics.len());
+        assert_eq!("error", diagnostics[0].severity);
+        assert_eq!("post-condition does not hold", diagnostics[0].message);
+        assert_eq!(
+            Some("/tmp/m/sources/m.move:5:9".to_string()),
+            diagnostics[0].location
+        );
+        assert!(diagnostics[0].text.ends_with("^^^^"));
+        assert_eq!("warning", diagnostics[1].severity);
+        assert_eq!(
+            Some("/tmp/m/sources/m.move:8:13".to_string()),
+            diagnostics[1].location
+        );
+    }
+}
@@ -0,0 +1,49 @@
+/// Emits many events per call, so the transaction emitter can load the event store and the
+/// indexers reading it.
+module event_workload::event_workload {
+    use std::signer;
+    use std::vector;
+    use aptos_framework::account;
+    use aptos_framework::event::{Self, EventHandle};
+
+    struct LoadEvent has drop, store {
+        index: u64,
+        data: vector<u8>,
+    }
+
+    struct LoadEvents has key {
+        events: EventHandle<LoadEvent>,
+    }
+
+    /// Emits `count` events, each with `size` bytes of data
+    public entry fun emit(account: &signer, count: u64, size: u64) acquires LoadEvents {
+        let addr = signer::address_of(account);
+        if (!exists<LoadEvents>(addr)) {
+            move_to(account, LoadEvents {
+                events: account::new_event_handle<LoadEvent>(account),
+            });
+        };
+
+        let data = vector::empty<u8>();
+        let i = 0;
+        while (i < size) {
+            vector::push_back(&mut data, ((i % 256) as u8));
+            i = i + 1;
+        };
+
+        let events = &mut borrow_global_mut<LoadEvents>(addr).events;
+        let index = 0;
+        while (index < count) {
+            event::emit_event(events, LoadEvent { index, data: copy data });
+            index = index + 1;
+        };
+    }
+
+    #[test(account = @0x123)]
+    public entry fun test_emit(account: &signer) acquires LoadEvents {
+        account::create_account_for_test(signer::address_of(account));
+        emit(account, 3, 10);
+        emit(account, 2, 0);
+        assert!(event::counter(&borrow_global<LoadEvents>(@0x123).events) == 5, 0);
+    }
+}
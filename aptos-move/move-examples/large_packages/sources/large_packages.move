@@ -0,0 +1,92 @@
+/// This module publishes packages too large for one transaction, as used by `aptos move publish
+/// --chunked-publish`.
+///
+/// The package's metadata and code are staged in chunks over several transactions, in a staging
+/// area under the publisher's account.  The last transaction publishes the assembled package with
+/// `code::publish_package_txn`, so it is published atomically, as if in one transaction.
+module large_packages::large_packages {
+    use std::error;
+    use std::signer;
+    use std::vector;
+    use aptos_framework::code;
+
+    /// The number of code indices doesn't match the number of code chunks
+    const ECODE_MISMATCH: u64 = 1;
+    /// There is no code staged to clean up
+    const ENO_STAGING_AREA: u64 = 2;
+
+    /// The package staged so far
+    struct StagingArea has key {
+        metadata_serialized: vector<u8>,
+        code: vector<vector<u8>>,
+    }
+
+    /// Appends a chunk of the metadata, and chunks of modules by their index in the package, then
+    /// publishes the package if `publish`
+    public entry fun stage_code(
+        owner: &signer,
+        metadata_chunk: vector<u8>,
+        code_indices: vector<u16>,
+        code_chunks: vector<vector<u8>>,
+        publish: bool,
+    ) acquires StagingArea {
+        assert!(
+            vector::length(&code_indices) == vector::length(&code_chunks),
+            error::invalid_argument(ECODE_MISMATCH),
+        );
+
+        let owner_address = signer::address_of(owner);
+        if (!exists<StagingArea>(owner_address)) {
+            move_to(owner, StagingArea {
+                metadata_serialized: vector::empty(),
+                code: vector::empty(),
+            });
+        };
+
+        let staging_area = borrow_global_mut<StagingArea>(owner_address);
+        vector::append(&mut staging_area.metadata_serialized, metadata_chunk);
+        let i = 0;
+        while (i < vector::length(&code_chunks)) {
+            let index = (*vector::borrow(&code_indices, i) as u64);
+            while (vector::length(&staging_area.code) <= index) {
+                vector::push_back(&mut staging_area.code, vector::empty());
+            };
+            vector::append(
+                vector::borrow_mut(&mut staging_area.code, index),
+                *vector::borrow(&code_chunks, i),
+            );
+            i = i + 1;
+        };
+
+        if (publish) {
+            let StagingArea { metadata_serialized, code } = move_from<StagingArea>(owner_address);
+            code::publish_package_txn(owner, metadata_serialized, code);
+        }
+    }
+
+    /// Discards the staged package, e.g. after a chunked publish failed part way
+    public entry fun cleanup(owner: &signer) acquires StagingArea {
+        let owner_address = signer::address_of(owner);
+        assert!(exists<StagingArea>(owner_address), error::not_found(ENO_STAGING_AREA));
+        let StagingArea { metadata_serialized: _, code: _ } = move_from<StagingArea>(owner_address);
+    }
+
+    #[test(owner = @0x123)]
+    public entry fun test_stage_code(owner: signer) acquires StagingArea {
+        stage_code(&owner, x"0102", vector[0], vector[x"aa"], false);
+        stage_code(&owner, x"03", vector[0, 1], vector[x"bb", x"cc"], false);
+
+        let staging_area = borrow_global<StagingArea>(@0x123);
+        assert!(staging_area.metadata_serialized == x"010203", 0);
+        assert!(staging_area.code == vector[x"aabb", x"cc"], 1);
+
+        cleanup(&owner);
+        assert!(!exists<StagingArea>(@0x123), 2);
+    }
+
+    #[test(owner = @0x123)]
+    #[expected_failure(abort_code = 0x10001)]
+    public entry fun test_code_mismatch(owner: signer) acquires StagingArea {
+        stage_code(&owner, vector::empty(), vector[0, 1], vector[x"aa"], false);
+    }
+}
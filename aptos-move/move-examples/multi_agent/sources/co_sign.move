@@ -0,0 +1,42 @@
+/// An entry function needing a secondary signer, so it can only be called with a multi-agent
+/// transaction. The transaction emitter calls it to load the multi-agent paths.
+module multi_agent::co_sign {
+    use std::signer;
+
+    /// Number of transactions an account co-signed, as either signer
+    struct CoSignCount has key {
+        count: u64,
+    }
+
+    public fun count(addr: address): u64 acquires CoSignCount {
+        if (exists<CoSignCount>(addr)) {
+            borrow_global<CoSignCount>(addr).count
+        } else {
+            0
+        }
+    }
+
+    /// Counts a transaction signed by both `primary` and `secondary`
+    public entry fun co_sign(primary: &signer, secondary: &signer) acquires CoSignCount {
+        increment(primary);
+        increment(secondary);
+    }
+
+    fun increment(account: &signer) acquires CoSignCount {
+        let addr = signer::address_of(account);
+        if (!exists<CoSignCount>(addr)) {
+            move_to(account, CoSignCount { count: 0 });
+        };
+        let count = &mut borrow_global_mut<CoSignCount>(addr).count;
+        *count = *count + 1;
+    }
+
+    #[test(primary = @0x123, secondary = @0x456)]
+    public entry fun test_co_sign(primary: &signer, secondary: &signer) acquires CoSignCount {
+        co_sign(primary, secondary);
+        co_sign(secondary, primary);
+        assert!(count(@0x123) == 2, 0);
+        assert!(count(@0x456) == 2, 1);
+        assert!(count(@0x789) == 0, 2);
+    }
+}
@@ -63,6 +63,15 @@ fn test_data_structures() {
     run_tests_for_pkg("data_structures", named_address);
 }
 
+#[test]
+fn test_event_workload() {
+    let named_address = BTreeMap::from([(
+        String::from("event_workload"),
+        AccountAddress::from_hex_literal("0xcafe").unwrap(),
+    )]);
+    run_tests_for_pkg("event_workload", named_address);
+}
+
 #[test]
 fn test_hello_blockchain() {
     let named_address = BTreeMap::from([(
@@ -93,6 +102,15 @@ fn test_two_by_two_transfer() {
     run_tests_for_pkg("scripts/two_by_two_transfer", named_address);
 }
 
+#[test]
+fn test_multi_agent() {
+    let named_address = BTreeMap::from([(
+        String::from("multi_agent"),
+        AccountAddress::from_hex_literal("0x1").unwrap(),
+    )]);
+    run_tests_for_pkg("multi_agent", named_address);
+}
+
 #[test]
 fn test_shared_account() {
     let named_address = BTreeMap::from([(
@@ -134,3 +152,25 @@ fn test_resource_account() {
     )]);
     run_tests_for_pkg("resource_account", named_address);
 }
+
+#[test]
+fn test_table_workload() {
+    let named_address = BTreeMap::from([(
+        String::from("table_workload"),
+        AccountAddress::from_hex_literal("0xcafe").unwrap(),
+    )]);
+    // The emitter's package, tested here as its crate doesn't build Move
+    run_tests_for_pkg(
+        "../../crates/transaction-emitter-lib/module_publishing/table_workload",
+        named_address,
+    );
+}
+
+#[test]
+fn test_large_packages() {
+    let named_address = BTreeMap::from([(
+        String::from("large_packages"),
+        AccountAddress::from_hex_literal("0xcafe").unwrap(),
+    )]);
+    run_tests_for_pkg("large_packages", named_address);
+}
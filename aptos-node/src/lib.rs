@@ -133,6 +133,7 @@ impl AptosNodeArgs {
                 self.test_dir,
                 self.random_ports,
                 self.lazy,
+                None,
                 &genesis_framework,
                 rng,
             )
@@ -248,6 +249,7 @@ pub fn load_test_environment<R>(
     test_dir: Option<PathBuf>,
     random_ports: bool,
     lazy: bool,
+    indexer_postgres_uri: Option<String>,
     framework: &ReleaseBundle,
     rng: R,
 ) -> anyhow::Result<()>
@@ -272,7 +274,7 @@ where
     let aptos_root_key_path = test_dir.join("mint.key");
 
     // If there's already a config, use it
-    let config = if validator_config_path.exists() {
+    let mut config = if validator_config_path.exists() {
         NodeConfig::load(&validator_config_path)
             .map_err(|err| anyhow!("Unable to load config: {}", err))?
     } else {
@@ -341,6 +343,16 @@ where
         validators[0].config.clone()
     };
 
+    // Run the indexer in the node, against the given database
+    if let Some(postgres_uri) = indexer_postgres_uri {
+        config.indexer.enabled = true;
+        config.indexer.postgres_uri = Some(postgres_uri);
+        config
+            .indexer
+            .processor
+            .get_or_insert_with(|| "default_processor".to_string());
+    }
+
     // Prepare log file since we cannot automatically route logs to stderr
     let log_file = test_dir.join("validator.log");
 
@@ -362,6 +374,12 @@ where
     if lazy {
         println!("\tLazy mode is enabled");
     }
+    if config.indexer.enabled {
+        println!(
+            "\tIndexer processor: {}",
+            config.indexer.processor.as_deref().unwrap_or_default()
+        );
+    }
 
     println!("\nAptos is running, press ctrl-c to exit\n");
 
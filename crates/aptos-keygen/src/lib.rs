@@ -30,6 +30,19 @@ impl KeyGen {
         Self::from_seed(seed)
     }
 
+    /// Constructs another key generator seeded from this one, e.g. to generate keys on
+    /// another thread.
+    pub fn fork(&mut self) -> Self {
+        Self::from_seed(self.0.gen())
+    }
+
+    /// Generate random bytes, e.g. the entropy of a mnemonic phrase.
+    pub fn generate_bytes(&mut self, len: usize) -> Vec<u8> {
+        let mut bytes = vec![0u8; len];
+        self.0.fill(bytes.as_mut_slice());
+        bytes
+    }
+
     /// Generate an Ed25519 private key.
     pub fn generate_ed25519_private_key(&mut self) -> Ed25519PrivateKey {
         Ed25519PrivateKey::generate(&mut self.0)
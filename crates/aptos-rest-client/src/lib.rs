@@ -9,6 +9,8 @@ pub mod faucet;
 pub use faucet::FaucetClient;
 pub mod response;
 pub use response::Response;
+pub mod retry;
+pub use retry::RetryPolicy;
 pub mod state;
 pub mod types;
 
@@ -40,7 +42,7 @@ use futures::executor::block_on;
 use move_binary_format::CompiledModule;
 use move_core_types::language_storage::{ModuleId, StructTag};
 use reqwest::header::ACCEPT;
-use reqwest::{header::CONTENT_TYPE, Client as ReqwestClient, StatusCode};
+use reqwest::{header::CONTENT_TYPE, Client as ReqwestClient, ClientBuilder, StatusCode};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::BTreeMap;
@@ -67,12 +69,17 @@ pub struct Client {
     inner: ReqwestClient,
     base_url: Url,
     version_path_base: String,
+    retry_policy: RetryPolicy,
 }
 
 impl Client {
     pub fn new_with_timeout(base_url: Url, timeout: Duration) -> Self {
-        let inner = ReqwestClient::builder()
-            .timeout(timeout)
+        Self::new_with_builder(base_url, ReqwestClient::builder().timeout(timeout))
+    }
+
+    /// Creates a client from a builder with other settings, e.g. a proxy or root certificates
+    pub fn new_with_builder(base_url: Url, builder: ClientBuilder) -> Self {
+        let inner = builder
             .user_agent(USER_AGENT)
             .cookie_store(true)
             .build()
@@ -96,9 +103,16 @@ impl Client {
             inner,
             base_url,
             version_path_base,
+            retry_policy: RetryPolicy::default(),
         }
     }
 
+    /// Retries GET requests by the policy, other requests aren't retried
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
     pub fn new(base_url: Url) -> Self {
         Self::new_with_timeout(base_url, Duration::from_secs(10))
     }
@@ -771,7 +785,7 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send_idempotent(request).await?;
 
         self.json(response).await
     }
@@ -808,7 +822,9 @@ impl Client {
         hash: HashValue,
     ) -> AptosResult<reqwest::Response> {
         let url = self.build_path(&format!("transactions/by_hash/{}", hash.to_hex_literal()))?;
-        let response = self.inner.get(url).header(ACCEPT, BCS).send().await?;
+        let response = self
+            .send_idempotent(self.inner.get(url).header(ACCEPT, BCS))
+            .await?;
         Ok(response)
     }
 
@@ -817,7 +833,7 @@ impl Client {
         hash: HashValue,
     ) -> AptosResult<reqwest::Response> {
         let url = self.build_path(&format!("transactions/by_hash/{}", hash.to_hex_literal()))?;
-        Ok(self.inner.get(url).send().await?)
+        Ok(self.send_idempotent(self.inner.get(url)).await?)
     }
 
     pub async fn get_transaction_by_version(
@@ -842,7 +858,7 @@ impl Client {
         version: u64,
     ) -> AptosResult<reqwest::Response> {
         let url = self.build_path(&format!("transactions/by_version/{}", version))?;
-        Ok(self.inner.get(url).send().await?)
+        Ok(self.send_idempotent(self.inner.get(url)).await?)
     }
 
     pub async fn get_account_transactions(
@@ -862,7 +878,7 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send_idempotent(request).await?;
 
         self.json(response).await
     }
@@ -890,6 +906,19 @@ impl Client {
         .await
     }
 
+    /// Gets one page of the account's resources, starting at `cursor` if given
+    ///
+    /// The cursor of the next page, if any, is in the response's state
+    pub async fn get_account_resources_page(
+        &self,
+        address: AccountAddress,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> AptosResult<Response<Vec<Resource>>> {
+        self.get_page(&format!("accounts/{}/resources", address), limit, cursor)
+            .await
+    }
+
     pub async fn get_account_resources_bcs(
         &self,
         address: AccountAddress,
@@ -956,12 +985,7 @@ impl Client {
     ) -> AptosResult<Response<Option<Resource>>> {
         let url = self.build_path(&format!("accounts/{}/resource/{}", address, resource_type))?;
 
-        let response = self
-            .inner
-            .get(url)
-            .send()
-            .await
-            .map_err(anyhow::Error::from)?;
+        let response = self.send_idempotent(self.inner.get(url)).await?;
         self.json(response).await
     }
 
@@ -1016,7 +1040,7 @@ impl Client {
             address, resource_type, version
         ))?;
 
-        let response = self.inner.get(url).send().await?;
+        let response = self.send_idempotent(self.inner.get(url)).await?;
         self.json(response).await
     }
 
@@ -1032,6 +1056,19 @@ impl Client {
         .await
     }
 
+    /// Gets one page of the account's modules, starting at `cursor` if given
+    ///
+    /// The cursor of the next page, if any, is in the response's state
+    pub async fn get_account_modules_page(
+        &self,
+        address: AccountAddress,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> AptosResult<Response<Vec<MoveModuleBytecode>>> {
+        self.get_page(&format!("accounts/{}/modules", address), limit, cursor)
+            .await
+    }
+
     pub async fn get_account_modules_bcs(
         &self,
         address: AccountAddress,
@@ -1085,7 +1122,32 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send_idempotent(request).await?;
+        self.json(response).await
+    }
+
+    pub async fn get_account_events_by_creation_number(
+        &self,
+        address: AccountAddress,
+        creation_number: u64,
+        start: Option<u64>,
+        limit: Option<u16>,
+    ) -> AptosResult<Response<Vec<VersionedEvent>>> {
+        let url = self.build_path(&format!(
+            "accounts/{}/events/{}",
+            address.to_hex_literal(),
+            creation_number
+        ))?;
+        let mut request = self.inner.get(url);
+        if let Some(start) = start {
+            request = request.query(&[("start", start)])
+        }
+
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)])
+        }
+
+        let response = self.send_idempotent(request).await?;
         self.json(response).await
     }
 
@@ -1214,7 +1276,7 @@ impl Client {
 
     pub async fn get_account(&self, address: AccountAddress) -> AptosResult<Response<Account>> {
         let url = self.build_path(&format!("accounts/{}", address))?;
-        let response = self.inner.get(url).send().await?;
+        let response = self.send_idempotent(self.inner.get(url)).await?;
         self.json(response).await
     }
 
@@ -1229,7 +1291,7 @@ impl Client {
 
     pub async fn estimate_gas_price(&self) -> AptosResult<Response<GasEstimation>> {
         let url = self.build_path("estimate_gas_price")?;
-        let response = self.inner.get(url).send().await?;
+        let response = self.send_idempotent(self.inner.get(url)).await?;
         self.json(response).await
     }
 
@@ -1277,10 +1339,7 @@ impl Client {
     pub async fn health_check(&self, seconds: u64) -> AptosResult<()> {
         let url = self.build_path("-/healthy")?;
         let response = self
-            .inner
-            .get(url)
-            .query(&[("duration_secs", seconds)])
-            .send()
+            .send_idempotent(self.inner.get(url).query(&[("duration_secs", seconds)]))
             .await?;
 
         if !response.status().is_success() {
@@ -1291,11 +1350,14 @@ impl Client {
     }
 
     async fn get<T: DeserializeOwned>(&self, url: Url) -> AptosResult<Response<T>> {
-        self.json(self.inner.get(url).send().await?).await
+        self.json(self.send_idempotent(self.inner.get(url)).await?)
+            .await
     }
 
     async fn get_bcs(&self, url: Url) -> AptosResult<Response<bytes::Bytes>> {
-        let response = self.inner.get(url).header(ACCEPT, BCS).send().await?;
+        let response = self
+            .send_idempotent(self.inner.get(url).header(ACCEPT, BCS))
+            .await?;
         self.check_and_parse_bcs_response(response).await
     }
 
@@ -1329,7 +1391,7 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send_idempotent(request).await?;
         self.check_and_parse_bcs_response(response).await
     }
 
@@ -1341,6 +1403,47 @@ impl Client {
         Ok(Response::new(response.bytes().await?, state))
     }
 
+    /// Sends an idempotent request, retrying transient failures by the retry policy
+    async fn send_idempotent(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> AptosResult<reqwest::Response> {
+        let policy = self.retry_policy;
+        let start = Instant::now();
+        let mut retries = 0;
+        loop {
+            let attempt = request
+                .try_clone()
+                .expect("Requests without a streamed body can be cloned");
+            let (result, delay) = match attempt.send().await {
+                Ok(response) if RetryPolicy::is_retryable_status(response.status()) => {
+                    // Rate limited requests should wait as long as the server asks
+                    let delay = retry::retry_after(response.headers())
+                        .unwrap_or_else(|| policy.backoff(retries));
+                    (Ok(response), delay)
+                }
+                Err(error) if RetryPolicy::is_retryable_error(&error) => {
+                    (Err(error), policy.backoff(retries))
+                }
+                result => return Ok(result?),
+            };
+
+            if retries >= policy.max_retries || start.elapsed() + delay > policy.timeout {
+                return Ok(result?);
+            }
+            info!(
+                "Request failed with {}, retrying in {}ms",
+                match result {
+                    Ok(ref response) => response.status().to_string(),
+                    Err(ref error) => error.to_string(),
+                },
+                delay.as_millis()
+            );
+            tokio::time::sleep(delay).await;
+            retries += 1;
+        }
+    }
+
     pub async fn try_until_ok<F, Fut, RetryFun, T>(
         total_wait: Option<Duration>,
         initial_interval: Option<Duration>,
@@ -1412,6 +1515,19 @@ impl Client {
         self.build_path(&path)
     }
 
+    /// This function gets a single page from an endpoint that has pagination support.
+    /// The cursor for the next page, if there is one, is in the response's state.
+    pub async fn get_page<T: for<'a> Deserialize<'a>>(
+        &self,
+        base_path: &str,
+        limit: u64,
+        cursor: Option<String>,
+    ) -> AptosResult<Response<Vec<T>>> {
+        let url = self.build_url_for_pagination(base_path, limit, None, cursor)?;
+        let response = self.send_idempotent(self.inner.get(url)).await?;
+        self.json(response).await
+    }
+
     /// This function calls an endpoint that has pagination support and paginates
     /// using the cursor the API returns. It keeps paginating until the API doesn't
     /// return a cursor anymore. Since the functions calling this function are
@@ -1434,7 +1550,7 @@ impl Client {
                 ledger_version,
                 cursor,
             )?;
-            let raw_response = self.inner.get(url).send().await?;
+            let raw_response = self.send_idempotent(self.inner.get(url)).await?;
             let response: Response<Vec<T>> = self.json(raw_response).await?;
             cursor = response.state().cursor.clone();
             if cursor.is_none() {
@@ -0,0 +1,118 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Retrying of idempotent requests which fail transiently, e.g. when rate limited
+
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+/// How to retry idempotent requests, which are only GETs
+///
+/// A request is retried if it couldn't connect or timed out, or if the server is rate limiting
+/// or temporarily unavailable.  Delays double from `initial_delay` up to `max_delay`, with
+/// jitter, unless the server asks to wait with `Retry-After`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// Longest delay between retries
+    pub max_delay: Duration,
+    /// Total time to keep retrying for, a retry which would go past it isn't made
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether a response with this status is worth retrying
+    pub fn is_retryable_status(status: StatusCode) -> bool {
+        matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS
+                | StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT
+        )
+    }
+
+    /// Whether a failure to get a response is worth retrying
+    pub fn is_retryable_error(error: &reqwest::Error) -> bool {
+        error.is_connect() || error.is_timeout()
+    }
+
+    /// Delay before the retry after `retries` retries, from `initial_delay` with up to a
+    /// quarter of it added as jitter
+    pub fn backoff(&self, retries: u32) -> Duration {
+        let delay = self
+            .initial_delay
+            .saturating_mul(2u32.saturating_pow(retries))
+            .min(self.max_delay);
+        let jitter_ms = (delay.as_millis() / 4) as u64;
+        let jitter = if jitter_ms > 0 {
+            Duration::from_millis(rand::thread_rng().gen_range(0, jitter_ms))
+        } else {
+            Duration::ZERO
+        };
+        delay + jitter
+    }
+}
+
+/// Delay the server asked for in `Retry-After`, only the seconds form is supported
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(300),
+            timeout: Duration::from_secs(10),
+        };
+        let first = policy.backoff(0);
+        assert!(first >= Duration::from_millis(100) && first < Duration::from_millis(125));
+        let second = policy.backoff(1);
+        assert!(second >= Duration::from_millis(200) && second < Duration::from_millis(250));
+        let capped = policy.backoff(10);
+        assert!(capped >= Duration::from_millis(300) && capped < Duration::from_millis(375));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(None, retry_after(&headers));
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+        assert_eq!(Some(Duration::from_secs(3)), retry_after(&headers));
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(None, retry_after(&headers));
+    }
+}
@@ -15,6 +15,15 @@ const LATENCY: &str = "latency";
 const SUCCESS: &str = "success";
 const ERROR: &str = "error";
 
+/// Settings for where and as whom CLI telemetry is sent
+#[derive(Clone, Debug, Default)]
+pub struct CliTelemetryOptions {
+    /// Endpoint to send events to, instead of the default analytics endpoint
+    pub endpoint: Option<String>,
+    /// Stable anonymous identifier of the CLI user, random per event if not set
+    pub anonymous_id: Option<String>,
+}
+
 /// Collects and sends the build information via telemetry
 pub async fn send_cli_telemetry_event(
     mut build_information: BTreeMap<String, String>,
@@ -22,6 +31,7 @@ pub async fn send_cli_telemetry_event(
     latency: Duration,
     success: bool,
     error: Option<&str>,
+    options: CliTelemetryOptions,
 ) {
     // Collection information about the cli command
     collect_cli_info(command, latency, success, error, &mut build_information);
@@ -32,14 +42,25 @@ pub async fn send_cli_telemetry_event(
         params: build_information,
     };
 
-    // TODO(joshlind): can we find a better way of identifying each CLI user?
-    let user_id = uuid::Uuid::new_v4().to_string();
+    // Without an anonymous id, a random one is used so events can't be correlated
+    let user_id = options
+        .anonymous_id
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
     // Send the event (we block on the join handle to ensure the
     // event is processed before terminating the cli command).
-    let join_handle =
+    let join_handle = if let Some(endpoint) = options.endpoint {
+        service::send_telemetry_event_with_ip_to_endpoint(
+            endpoint,
+            user_id,
+            "NO_CHAIN".into(),
+            telemetry_event,
+        )
+        .await
+    } else {
         service::send_telemetry_event_with_ip(user_id, "NO_CHAIN".into(), None, telemetry_event)
-            .await;
+            .await
+    };
     if let Err(error) = join_handle.await {
         debug!(
             "Failed to send telemetry event with join error: {:?}",
@@ -424,15 +424,33 @@ pub(crate) async fn send_telemetry_event_with_ip(
     telemetry_sender: Option<TelemetrySender>,
     telemetry_event: TelemetryEvent,
 ) -> JoinHandle<()> {
-    // Update the telemetry event with the ip address and random token
+    let telemetry_event = add_ip_and_token(chain_id, telemetry_event).await;
+
+    // Send the telemetry event
+    send_telemetry_event(peer_id, telemetry_sender, telemetry_event).await
+}
+
+/// Fetches the IP address and sends the given telemetry event to a custom
+/// endpoint, instead of the default analytics endpoint.
+pub(crate) async fn send_telemetry_event_with_ip_to_endpoint(
+    endpoint: String,
+    peer_id: String,
+    chain_id: String,
+    telemetry_event: TelemetryEvent,
+) -> JoinHandle<()> {
+    let telemetry_event = add_ip_and_token(chain_id, telemetry_event).await;
+    let event_name = telemetry_event.name.clone();
+    let telemetry_dump = create_telemetry_dump(peer_id, telemetry_event);
+    spawn_telemetry_dump_sender(endpoint, event_name, telemetry_dump)
+}
+
+/// Updates the telemetry event with the ip address, random token and chain id
+async fn add_ip_and_token(chain_id: String, telemetry_event: TelemetryEvent) -> TelemetryEvent {
     let TelemetryEvent { name, mut params } = telemetry_event;
     params.insert(IP_ADDRESS_KEY.to_string(), get_origin_ip().await);
     params.insert(TELEMETRY_TOKEN_KEY.to_string(), TELEMETRY_TOKEN.clone());
     params.insert(CHAIN_ID_KEY.into(), chain_id);
-    let telemetry_event = TelemetryEvent { name, params };
-
-    // Send the telemetry event
-    send_telemetry_event(peer_id, telemetry_sender, telemetry_event).await
+    TelemetryEvent { name, params }
 }
 
 /// Gets the IP origin of the machine by pinging a url.
@@ -462,22 +480,27 @@ async fn send_telemetry_event(
 
     // Create and send the telemetry dump
     let event_name = telemetry_event.name.clone();
+    let telemetry_dump = create_telemetry_dump(peer_id, telemetry_event);
+    let _handle = spawn_telemetry_service_event_sender(
+        event_name.clone(),
+        telemetry_sender,
+        telemetry_dump.clone(),
+    );
+    spawn_telemetry_event_sender(api_secret, measurement_id, event_name, telemetry_dump)
+}
+
+/// Creates a telemetry dump holding the single given event
+fn create_telemetry_dump(peer_id: String, telemetry_event: TelemetryEvent) -> TelemetryDump {
     let timestamp_micros = match SystemTime::now().duration_since(UNIX_EPOCH) {
         Ok(duration) => duration.as_micros().to_string(),
         Err(_) => UNKNOWN_METRIC_VALUE.into(),
     };
-    let telemetry_dump = TelemetryDump {
+    TelemetryDump {
         client_id: Uuid::new_v4().to_string(), // We generate a random client id for each request
         user_id: peer_id,
         timestamp_micros,
         events: vec![telemetry_event],
-    };
-    let _handle = spawn_telemetry_service_event_sender(
-        event_name.clone(),
-        telemetry_sender,
-        telemetry_dump.clone(),
-    );
-    spawn_telemetry_event_sender(api_secret, measurement_id, event_name, telemetry_dump)
+    }
 }
 
 fn spawn_telemetry_service_event_sender(
@@ -502,6 +525,22 @@ fn spawn_telemetry_event_sender(
     measurement_id: String,
     event_name: String,
     telemetry_dump: TelemetryDump,
+) -> JoinHandle<()> {
+    spawn_telemetry_dump_sender(
+        format!(
+            "{}?&measurement_id={}&api_secret={}",
+            GA4_URL, measurement_id, api_secret
+        ),
+        event_name,
+        telemetry_dump,
+    )
+}
+
+/// Spawns a sender that posts the telemetry dump to the given url
+fn spawn_telemetry_dump_sender(
+    url: String,
+    event_name: String,
+    telemetry_dump: TelemetryDump,
 ) -> JoinHandle<()> {
     tokio::spawn(async move {
         // Create a request client
@@ -509,10 +548,7 @@ fn spawn_telemetry_event_sender(
 
         // Send the request and wait for a response
         let send_result = client
-            .post(format!(
-                "{}?&measurement_id={}&api_secret={}",
-                GA4_URL, measurement_id, api_secret
-            ))
+            .post(url)
             .json::<TelemetryDump>(&telemetry_dump)
             .send()
             .await;
@@ -0,0 +1,143 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::account::list::{coin_store_type, matches_type, parse_resource_type};
+use crate::common::types::{CliCommand, CliTypedResult, ProfileOptions, RestOptions};
+use aptos_rest_client::Client;
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::Parser;
+use move_core_types::language_storage::StructTag;
+use serde::{Deserialize, Serialize};
+
+/// Show the balances of all coins owned by an account
+///
+/// Every `0x1::coin::CoinStore` of the account is listed, with the coin's name, symbol and
+/// decimals from its `0x1::coin::CoinInfo`, and the balance in whole coins.
+#[derive(Debug, Parser)]
+pub struct AccountBalance {
+    /// Address of the account to show the balances of
+    ///
+    /// Defaults to the profile's account
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) account: Option<AccountAddress>,
+
+    /// Only show the balances of these coin types, e.g. `0x1::aptos_coin::AptosCoin`
+    #[clap(long, multiple_values = true, parse(try_from_str = parse_resource_type))]
+    pub(crate) coin_type: Vec<StructTag>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+/// Balance of one coin type
+#[derive(Debug, Serialize)]
+pub struct CoinBalance {
+    pub coin_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decimals: Option<u8>,
+    /// Balance in the coin's smallest unit, e.g. Octas
+    pub balance: u64,
+    /// Balance in whole coins with the symbol, e.g. `1.5 APT`
+    pub amount: String,
+}
+
+/// The fields of `0x1::coin::CoinInfo` shown with balances
+#[derive(Debug, Deserialize)]
+struct CoinInfo {
+    name: String,
+    symbol: String,
+    decimals: u8,
+}
+
+#[async_trait]
+impl CliCommand<Vec<CoinBalance>> for AccountBalance {
+    fn command_name(&self) -> &'static str {
+        "AccountBalance"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<CoinBalance>> {
+        let account = match self.account {
+            Some(account) => account,
+            None => self.profile_options.account_address()?,
+        };
+        let client = self.rest_options.client(&self.profile_options)?;
+
+        let mut balances = Vec::new();
+        for resource in client.get_account_resources(account).await?.into_inner() {
+            let coin_type = match coin_store_type(&resource.resource_type) {
+                Some(coin_type) if matches_type(&self.coin_type, coin_type) => coin_type,
+                _ => continue,
+            };
+            let balance: u64 = resource.data["coin"]["value"]
+                .as_str()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default();
+
+            // Coins without a readable `CoinInfo` are still listed, in their smallest unit
+            let coin_info = coin_info(&client, coin_type).await;
+            let amount = match coin_info {
+                Some(ref coin_info) => format!(
+                    "{} {}",
+                    format_amount(balance, coin_info.decimals),
+                    coin_info.symbol
+                ),
+                None => balance.to_string(),
+            };
+            balances.push(CoinBalance {
+                coin_type: coin_type.to_string(),
+                name: coin_info.as_ref().map(|coin_info| coin_info.name.clone()),
+                symbol: coin_info.as_ref().map(|coin_info| coin_info.symbol.clone()),
+                decimals: coin_info.as_ref().map(|coin_info| coin_info.decimals),
+                balance,
+                amount,
+            });
+        }
+        Ok(balances)
+    }
+}
+
+/// The coin's `CoinInfo`, which is stored at the address of the module defining the coin
+async fn coin_info(client: &Client, coin_type: &StructTag) -> Option<CoinInfo> {
+    let resource = client
+        .get_account_resource(
+            coin_type.address,
+            &format!("0x1::coin::CoinInfo<{}>", coin_type),
+        )
+        .await
+        .ok()?
+        .into_inner()?;
+    serde_json::from_value(resource.data).ok()
+}
+
+/// Formats an amount in a coin's smallest unit as whole coins, e.g. `150000000` with 8 decimals
+/// as `1.5`
+fn format_amount(amount: u64, decimals: u8) -> String {
+    let divisor = 10u128.pow(decimals as u32);
+    let whole = amount as u128 / divisor;
+    let fraction = amount as u128 % divisor;
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!("1.5", format_amount(150_000_000, 8));
+        assert_eq!("0.00000001", format_amount(1, 8));
+        assert_eq!("42", format_amount(4_200_000_000, 8));
+        assert_eq!("7", format_amount(7, 0));
+    }
+}
@@ -1,7 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{CliCommand, CliTypedResult, TransactionOptions, TransactionSummary};
+use crate::common::types::{
+    CliCommand, CliTypedResult, Submission, TransactionOptions, TransactionSummary,
+};
 use aptos_types::account_address::AccountAddress;
 use async_trait::async_trait;
 use cached_packages::aptos_stdlib;
@@ -26,16 +28,16 @@ pub struct CreateAccount {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for CreateAccount {
+impl CliCommand<Submission<TransactionSummary>> for CreateAccount {
     fn command_name(&self) -> &'static str {
         "CreateAccount"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
         let address = self.account;
         self.txn_options
             .submit_transaction(aptos_stdlib::aptos_account_create_account(address))
             .await
-            .map(TransactionSummary::from)
+            .map(|submission| submission.map(TransactionSummary::from))
     }
 }
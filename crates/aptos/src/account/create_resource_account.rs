@@ -1,35 +1,85 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{CliCommand, CliTypedResult, TransactionOptions, TransactionSummary};
+use crate::common::types::{
+    CliCommand, CliError, CliTypedResult, ProfileOptions, Submission, TransactionOptions,
+    TransactionSummary,
+};
 use aptos_rest_client::{
     aptos_api_types::{WriteResource, WriteSetChange},
     Transaction,
 };
-use aptos_types::{account_address::AccountAddress, transaction::authenticator::AuthenticationKey};
+use aptos_types::{
+    account_address::{create_resource_address, AccountAddress},
+    transaction::authenticator::AuthenticationKey,
+};
 use async_trait::async_trait;
-use cached_packages::aptos_stdlib::resource_account_create_resource_account;
-use clap::Parser;
+use cached_packages::aptos_stdlib::{
+    resource_account_create_resource_account, resource_account_create_resource_account_and_fund,
+};
+use clap::{ArgEnum, Parser};
 use serde::Serialize;
 use std::str::FromStr;
 
-/// Create a resource account on-chain
-///
-/// This will create a resource account which can be used as an autonomous account
-/// not controlled directly by one account.
+/// How a resource account seed is converted to bytes
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SeedEncoding {
+    /// BCS of the seed string, i.e. prefixed by its length
+    Bcs,
+    /// The UTF-8 bytes of the seed, the same as `b"seed"` in Move
+    Utf8,
+    /// The seed is hex encoded bytes, e.g. `0x0123`
+    Hex,
+}
+
+/// Seed of a resource account
 #[derive(Debug, Parser)]
-pub struct CreateResourceAccount {
+pub struct ResourceAccountSeed {
     /// Resource account seed
     ///
     /// Seed used in generation of the AccountId of the resource account
-    /// The seed will be converted to bytes using `BCS`
+    /// The seed will be converted to bytes using `--seed-encoding`
     #[clap(long)]
     pub(crate) seed: String,
 
+    /// Encoding of the seed: [bcs, utf8, hex]
+    #[clap(arg_enum, long, default_value = "bcs")]
+    pub(crate) seed_encoding: SeedEncoding,
+}
+
+impl ResourceAccountSeed {
+    pub fn seed(&self) -> CliTypedResult<Vec<u8>> {
+        match self.seed_encoding {
+            SeedEncoding::Bcs => Ok(bcs::to_bytes(&self.seed)?),
+            SeedEncoding::Utf8 => Ok(self.seed.as_bytes().to_vec()),
+            SeedEncoding::Hex => hex::decode(self.seed.strip_prefix("0x").unwrap_or(&self.seed))
+                .map_err(|err| CliError::UnableToParse("--seed", err.to_string())),
+        }
+    }
+
+    /// Address of the resource account created by `source` with this seed
+    pub fn resource_address(&self, source: AccountAddress) -> CliTypedResult<AccountAddress> {
+        Ok(create_resource_address(source, &self.seed()?))
+    }
+}
+
+/// Create a resource account on-chain
+///
+/// This will create a resource account which can be used as an autonomous account
+/// not controlled directly by one account.
+#[derive(Debug, Parser)]
+pub struct CreateResourceAccount {
+    #[clap(flatten)]
+    pub(crate) seed_args: ResourceAccountSeed,
+
     /// Optional Resource Account authentication key.
     #[clap(long, parse(try_from_str = AuthenticationKey::from_str))]
     pub(crate) authentication_key: Option<AuthenticationKey>,
 
+    /// Optional amount of Octas to transfer to the resource account when creating it
+    #[clap(long)]
+    pub(crate) fund_amount: Option<u64>,
+
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
 }
@@ -71,23 +121,63 @@ impl From<Transaction> for CreateResourceAccountSummary {
 }
 
 #[async_trait]
-impl CliCommand<CreateResourceAccountSummary> for CreateResourceAccount {
+impl CliCommand<Submission<CreateResourceAccountSummary>> for CreateResourceAccount {
     fn command_name(&self) -> &'static str {
         "CreateResourceAccount"
     }
 
-    async fn execute(self) -> CliTypedResult<CreateResourceAccountSummary> {
+    async fn execute(self) -> CliTypedResult<Submission<CreateResourceAccountSummary>> {
         let authentication_key: Vec<u8> = if let Some(key) = self.authentication_key {
             bcs::to_bytes(&key)?
         } else {
             vec![]
         };
-        self.txn_options
-            .submit_transaction(resource_account_create_resource_account(
-                bcs::to_bytes(&self.seed)?,
+        let seed = self.seed_args.seed()?;
+        let payload = match self.fund_amount {
+            Some(fund_amount) => resource_account_create_resource_account_and_fund(
+                seed,
                 authentication_key,
-            ))
+                fund_amount,
+            ),
+            None => resource_account_create_resource_account(seed, authentication_key),
+        };
+        self.txn_options
+            .submit_transaction(payload)
             .await
-            .map(CreateResourceAccountSummary::from)
+            .map(|submission| submission.map(CreateResourceAccountSummary::from))
+    }
+}
+
+/// Derive the address of a resource account
+///
+/// The address depends only on the source account and the seed, so it can be known before
+/// the resource account is created, e.g. for named addresses in a Move package.
+#[derive(Debug, Parser)]
+pub struct DeriveResourceAccountAddress {
+    #[clap(flatten)]
+    pub(crate) seed_args: ResourceAccountSeed,
+
+    /// Address of the account creating the resource account
+    ///
+    /// Defaults to the profile's account
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) address: Option<AccountAddress>,
+
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<AccountAddress> for DeriveResourceAccountAddress {
+    fn command_name(&self) -> &'static str {
+        "DeriveResourceAccountAddress"
+    }
+
+    async fn execute(self) -> CliTypedResult<AccountAddress> {
+        let source = match self.address {
+            Some(address) => address,
+            None => self.profile_options.account_address()?,
+        };
+        self.seed_args.resource_address(source)
     }
 }
@@ -0,0 +1,180 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::follow::{execute_follow, FollowOptions, FollowSource};
+use crate::common::types::{
+    CliCommand, CliError, CliResult, CliTypedResult, ProfileOptions, RestOptions,
+};
+use aptos_rest_client::aptos_api_types::VersionedEvent;
+use aptos_rest_client::Client;
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::Parser;
+use move_core_types::parser::parse_struct_tag;
+
+/// List the events of an account
+///
+/// Events are looked up either by the event handle field of a resource, e.g.
+/// `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>::deposit_events`, or by the creation number
+/// of the event handle.
+///
+/// With `--follow`, new events are printed as they're emitted, one JSON object per line.
+#[derive(Debug, Parser)]
+pub struct ListEvents {
+    /// Address of the account to list events of
+    ///
+    /// Defaults to the profile's account
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) account: Option<AccountAddress>,
+
+    /// Event handle as `<STRUCT_TAG>::<FIELD_NAME>`
+    ///
+    /// Example: `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>::withdraw_events`
+    #[clap(long, group = "event_source", parse(try_from_str = parse_event_handle))]
+    pub(crate) event_handle: Option<EventHandleId>,
+
+    /// Creation number of the event handle, as in the `guid` of the handle
+    #[clap(long, group = "event_source")]
+    pub(crate) creation_number: Option<u64>,
+
+    /// Sequence number of the first event to list
+    ///
+    /// Defaults to the latest events
+    #[clap(long)]
+    pub(crate) start: Option<u64>,
+
+    /// Maximum number of events to list
+    #[clap(long)]
+    pub(crate) limit: Option<u16>,
+
+    #[clap(flatten)]
+    pub(crate) follow_options: FollowOptions,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+/// A resource's event handle field
+#[derive(Clone, Debug)]
+pub struct EventHandleId {
+    pub struct_tag: String,
+    pub field_name: String,
+}
+
+fn parse_event_handle(str: &str) -> CliTypedResult<EventHandleId> {
+    let (struct_tag, field_name) = str.rsplit_once("::").ok_or_else(|| {
+        CliError::UnableToParse(
+            "--event-handle",
+            format!("{} isn't of the form <STRUCT_TAG>::<FIELD_NAME>", str),
+        )
+    })?;
+    let struct_tag = parse_struct_tag(struct_tag)
+        .map_err(|err| CliError::UnableToParse("--event-handle", err.to_string()))?;
+    Ok(EventHandleId {
+        struct_tag: struct_tag.to_string(),
+        field_name: field_name.to_string(),
+    })
+}
+
+impl ListEvents {
+    /// Prints new events as they're emitted, until interrupted
+    pub async fn execute_follow(mut self) -> CliResult {
+        let options = std::mem::take(&mut self.follow_options);
+        execute_follow(self.command_name(), &options, async move {
+            let account = self.account()?;
+            let client = self.rest_options.client(&self.profile_options)?;
+            let start = self.start;
+            Ok(FollowEvents {
+                command: self,
+                client,
+                account,
+                start,
+            })
+        })
+        .await
+    }
+
+    fn account(&self) -> CliTypedResult<AccountAddress> {
+        match self.account {
+            Some(account) => Ok(account),
+            None => self.profile_options.account_address(),
+        }
+    }
+
+    /// Events from the sequence number `start`, or the latest events without it
+    async fn fetch(
+        &self,
+        client: &Client,
+        account: AccountAddress,
+        start: Option<u64>,
+    ) -> CliTypedResult<Vec<VersionedEvent>> {
+        let events = match (&self.event_handle, self.creation_number) {
+            (Some(event_handle), None) => {
+                client
+                    .get_account_events(
+                        account,
+                        &event_handle.struct_tag,
+                        &event_handle.field_name,
+                        start,
+                        self.limit,
+                    )
+                    .await?
+            }
+            (None, Some(creation_number)) => {
+                client
+                    .get_account_events_by_creation_number(
+                        account,
+                        creation_number,
+                        start,
+                        self.limit,
+                    )
+                    .await?
+            }
+            _ => {
+                return Err(CliError::CommandArgumentError(
+                    "One of --event-handle or --creation-number must be given".to_string(),
+                ))
+            }
+        };
+        Ok(events.into_inner())
+    }
+}
+
+/// Events of a handle, polled from after the last one seen
+struct FollowEvents {
+    command: ListEvents,
+    client: Client,
+    account: AccountAddress,
+    /// Sequence number of the next event, `None` until an event is seen
+    start: Option<u64>,
+}
+
+#[async_trait]
+impl FollowSource for FollowEvents {
+    type Item = VersionedEvent;
+
+    async fn poll(&mut self) -> CliTypedResult<Vec<VersionedEvent>> {
+        let events = self
+            .command
+            .fetch(&self.client, self.account, self.start)
+            .await?;
+        if let Some(last) = events.last() {
+            self.start = Some(u64::from(last.sequence_number) + 1);
+        }
+        Ok(events)
+    }
+}
+
+#[async_trait]
+impl CliCommand<Vec<VersionedEvent>> for ListEvents {
+    fn command_name(&self) -> &'static str {
+        "ListEvents"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<VersionedEvent>> {
+        let account = self.account()?;
+        let client = self.rest_options.client(&self.profile_options)?;
+        self.fetch(&client, account, self.start).await
+    }
+}
@@ -6,10 +6,16 @@ use std::time::{Duration, SystemTime};
 use crate::{
     account::create::DEFAULT_FUNDED_COINS,
     common::{
+        http::HttpConfig,
         types::{CliCommand, CliError, CliTypedResult, FaucetOptions, ProfileOptions, RestOptions},
-        utils::fund_account,
+        utils::{fund_account_with_retries, DEFAULT_FAUCET_ATTEMPTS},
     },
 };
+use aptos_rest_client::{
+    aptos_api_types::{AptosError, AptosErrorCode},
+    error::{AptosErrorResponse, RestError},
+    Client,
+};
 use aptos_types::account_address::AccountAddress;
 use async_trait::async_trait;
 use clap::Parser;
@@ -33,6 +39,12 @@ pub struct FundWithFaucet {
     #[clap(long, default_value_t = DEFAULT_FUNDED_COINS)]
     pub(crate) amount: u64,
 
+    /// Number of attempts at each faucet before falling back to the next one
+    ///
+    /// Connection errors, rate limiting and server errors are retried with exponential backoff
+    #[clap(long, default_value_t = DEFAULT_FAUCET_ATTEMPTS)]
+    pub(crate) faucet_attempts: u32,
+
     #[clap(flatten)]
     pub(crate) faucet_options: FaucetOptions,
     #[clap(flatten)]
@@ -48,10 +60,15 @@ impl CliCommand<String> for FundWithFaucet {
     }
 
     async fn execute(self) -> CliTypedResult<String> {
-        let hashes = fund_account(
-            self.faucet_options.faucet_url(&self.profile_options)?,
+        let client = self.rest_options.client(&self.profile_options)?;
+        let balance_before = account_balance(&client, self.account).await?;
+
+        let hashes = fund_account_with_retries(
+            &self.faucet_options.faucet_urls(&self.profile_options)?,
             self.amount,
             self.account,
+            &HttpConfig::for_profile(&self.profile_options)?,
+            self.faucet_attempts,
         )
         .await?;
         let sys_time = SystemTime::now()
@@ -59,20 +76,59 @@ impl CliCommand<String> for FundWithFaucet {
             .map_err(|e| CliError::UnexpectedError(e.to_string()))?
             .as_secs()
             + 30;
-        let client = self.rest_options.client(&self.profile_options)?;
+        let mut failed = Vec::new();
         for hash in hashes {
-            client
+            if let Err(err) = client
                 .wait_for_transaction_by_hash(
                     hash.into(),
                     sys_time,
                     Some(Duration::from_secs(60)),
                     None,
                 )
-                .await?;
+                .await
+            {
+                failed.push(format!("{}: {}", hash, err));
+            }
         }
-        return Ok(format!(
-            "Added {} Octas to account {}",
-            self.amount, self.account
-        ));
+
+        // The faucet may give less than requested, or some of its transactions may fail, so
+        // report what actually arrived
+        let added = account_balance(&client, self.account)
+            .await?
+            .saturating_sub(balance_before);
+        let failures = if failed.is_empty() {
+            String::new()
+        } else {
+            format!(", failed faucet transactions: {}", failed.join(", "))
+        };
+        if added == 0 {
+            Err(CliError::UnexpectedError(format!(
+                "Faucet didn't add any Octas to account {}{}",
+                self.account, failures
+            )))
+        } else if added < self.amount || !failed.is_empty() {
+            Ok(format!(
+                "Partially funded, added {} of the requested {} Octas to account {}{}",
+                added, self.amount, self.account, failures
+            ))
+        } else {
+            Ok(format!("Added {} Octas to account {}", added, self.account))
+        }
+    }
+}
+
+/// Balance of the account in Octas, which is 0 if the account doesn't exist yet
+async fn account_balance(client: &Client, address: AccountAddress) -> CliTypedResult<u64> {
+    match client.get_account_balance(address).await {
+        Ok(balance) => Ok(balance.into_inner().get()),
+        Err(RestError::Api(AptosErrorResponse {
+            error:
+                AptosError {
+                    error_code: AptosErrorCode::AccountNotFound | AptosErrorCode::ResourceNotFound,
+                    ..
+                },
+            ..
+        })) => Ok(0),
+        Err(err) => Err(CliError::ApiError(err.to_string())),
     }
 }
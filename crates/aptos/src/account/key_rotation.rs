@@ -1,13 +1,15 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::utils::prompt_yes;
+use crate::common::key_encryption::EncryptedPrivateKey;
+use crate::common::key_store::{KeyBackend, OsKeychain};
+use crate::common::utils::{assume_yes, prompt_yes};
 use crate::common::{
     types::{
         CliCommand, CliConfig, CliError, CliTypedResult, ConfigSearchMode, EncodingOptions,
         EncodingType, ExtractPublicKey, ParsePrivateKey, ProfileConfig, ProfileOptions,
-        PublicKeyInputOptions, RestOptions, RotationProofChallenge, TransactionOptions,
-        TransactionSummary,
+        PublicKeyInputOptions, RestOptions, RngArgs, RotationProofChallenge, Submission,
+        TransactionOptions, TransactionSummary, WaitFor, REDACTED,
     },
     utils::{prompt_yes_with_override, read_line},
 };
@@ -31,11 +33,11 @@ use std::{collections::BTreeMap, path::PathBuf};
 /// Rotate an account's authentication key
 ///
 /// Rotating the account's authentication key allows you to use a new
-/// private key.  You must provide a new private key.  Once it is
+/// private key.  You must provide a new private key, or generate one.  Once it is
 /// rotated you will need to use the original account address, with the
-/// new private key.  There is an interactive prompt to help you add it
-/// to a new profile.
-#[derive(Debug, Parser)]
+/// new private key.  The new key can replace the key in the current profile, or
+/// there is an interactive prompt to help you add it to a new profile.
+#[derive(Parser)]
 pub struct RotateKey {
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
@@ -48,22 +50,58 @@ pub struct RotateKey {
     #[clap(long, group = "new_private_key")]
     pub(crate) new_private_key: Option<String>,
 
+    /// Generate a new private key, instead of providing one
+    ///
+    /// The generated key has to be saved, so either `--save-to-profile` with a new profile
+    /// name, or `--update-profile` is required
+    #[clap(long, group = "new_private_key")]
+    pub(crate) generate_new_key: bool,
+
+    #[clap(flatten)]
+    pub(crate) rng_args: RngArgs,
+
     /// Name of the profile to save the new private key
     ///
     /// If not provided, it will interactively have you save a profile,
-    /// unless `--skip_saving_profile` is provided
-    #[clap(long)]
+    /// unless `--skip_saving_profile` or `--update-profile` is provided
+    #[clap(long, group = "save_new_key")]
     pub(crate) save_to_profile: Option<String>,
 
+    /// Replace the key of the current profile with the new key
+    ///
+    /// The new key is stored the same way as the current one, in plaintext, encrypted
+    /// with the key passphrase, or in the OS keychain
+    #[clap(long, group = "save_new_key")]
+    pub(crate) update_profile: bool,
+
     /// Skip saving profile
     ///
     /// This skips the interactive profile saving after rotating the authentication key
-    #[clap(long)]
+    #[clap(long, group = "save_new_key")]
     pub(crate) skip_saving_profile: bool,
 }
 
 impl ParsePrivateKey for RotateKey {}
 
+/// Keeps the new private key out of debug output and logs
+impl std::fmt::Debug for RotateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RotateKey")
+            .field("txn_options", &self.txn_options)
+            .field("new_private_key_file", &self.new_private_key_file)
+            .field(
+                "new_private_key",
+                &self.new_private_key.as_ref().map(|_| REDACTED),
+            )
+            .field("generate_new_key", &self.generate_new_key)
+            .field("rng_args", &self.rng_args)
+            .field("save_to_profile", &self.save_to_profile)
+            .field("update_profile", &self.update_profile)
+            .field("skip_saving_profile", &self.skip_saving_profile)
+            .finish()
+    }
+}
+
 impl RotateKey {
     /// Extract private key from CLI args
     pub fn extract_private_key(
@@ -85,20 +123,60 @@ pub struct RotateSummary {
 }
 
 #[async_trait]
-impl CliCommand<RotateSummary> for RotateKey {
+impl CliCommand<Submission<RotateSummary>> for RotateKey {
     fn command_name(&self) -> &'static str {
         "RotateKey"
     }
 
-    async fn execute(self) -> CliTypedResult<RotateSummary> {
-        let new_private_key = self
-            .extract_private_key(self.txn_options.encoding_options.encoding)?
-            .ok_or_else(|| {
+    async fn execute(self) -> CliTypedResult<Submission<RotateSummary>> {
+        // Profiles are only updated with the new key once the rotation is known to have executed
+        if self.txn_options.wait_options.wait_for == WaitFor::Submitted {
+            return Err(CliError::CommandArgumentError(
+                "Rotating a key must wait for its transaction to be executed, '--wait-for submitted' isn't supported".to_string(),
+            ));
+        }
+
+        // Check where the new key will be saved before rotating, a generated key is lost otherwise
+        if self.update_profile {
+            let config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+            let profile_name =
+                config.active_profile_name(self.txn_options.profile_options.profile_name());
+            if !config.has_profile(&profile_name) {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Profile {} not found to update",
+                    profile_name
+                )));
+            }
+        } else if self.generate_new_key {
+            let profile_name = self.save_to_profile.as_ref().ok_or_else(|| {
                 CliError::CommandArgumentError(
-                    "One of ['--new-private-key', '--new-private-key-file'] must be used"
+                    "'--generate-new-key' requires '--save-to-profile' or '--update-profile'"
                         .to_string(),
                 )
             })?;
+            // The new profile is based on the current one, so it must exist too
+            self.txn_options.profile_options.profile()?;
+            if CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?.has_profile(profile_name) {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Profile {} already exists, use a new profile name or '--update-profile'",
+                    profile_name
+                )));
+            }
+        }
+
+        let new_private_key = if self.generate_new_key {
+            self.rng_args
+                .key_generator()?
+                .generate_ed25519_private_key()
+        } else {
+            self.extract_private_key(self.txn_options.encoding_options.encoding)?
+                .ok_or_else(|| {
+                    CliError::CommandArgumentError(
+                        "One of ['--new-private-key', '--new-private-key-file', '--generate-new-key'] must be used"
+                            .to_string(),
+                    )
+                })?
+        };
 
         let (current_private_key, sender_address) = self.txn_options.get_key_and_address()?;
 
@@ -126,7 +204,7 @@ impl CliCommand<RotateSummary> for RotateKey {
         let rotation_proof_signed_by_new_private_key =
             new_private_key.sign_arbitrary_message(&rotation_msg);
 
-        let txn_summary = self
+        let txn_summary = match self
             .txn_options
             .submit_transaction(aptos_stdlib::account_rotate_authentication_key(
                 0,
@@ -140,8 +218,13 @@ impl CliCommand<RotateSummary> for RotateKey {
                     .to_vec(),
                 rotation_proof_signed_by_new_private_key.to_bytes().to_vec(),
             ))
-            .await
-            .map(TransactionSummary::from)?;
+            .await?
+        {
+            Submission::Submitted(transaction) => TransactionSummary::from(transaction),
+            Submission::NotSubmitted(not_submitted) => {
+                return Ok(Submission::NotSubmitted(not_submitted))
+            }
+        };
 
         let string = serde_json::to_string_pretty(&txn_summary)
             .map_err(|err| CliError::UnableToParse("transaction summary", err.to_string()))?;
@@ -160,16 +243,48 @@ impl CliCommand<RotateSummary> for RotateKey {
             ));
         }
 
+        // The chain maps the new key's derived address to the account, check it's found by it
+        let rest_client = self
+            .txn_options
+            .rest_options
+            .client(&self.txn_options.profile_options)?;
+        let lookup_warning = match lookup_address(&rest_client, &new_private_key.public_key()).await
+        {
+            Ok(address) if address == sender_address => None,
+            Ok(address) => Some(format!(
+                "The new key's address lookup found {} instead of {}",
+                address, sender_address
+            )),
+            Err(err) => Some(format!("The new key's address lookup failed: {}", err)),
+        };
+        if let Some(ref warning) = lookup_warning {
+            eprintln!("Warning: {}", warning);
+        }
+
+        if self.update_profile {
+            let profile_name = self.update_current_profile(&new_private_key, sender_address)?;
+            eprintln!("Profile {} is updated with the new key.", profile_name);
+            return Ok(Submission::Submitted(RotateSummary {
+                transaction: txn_summary,
+                message: Some(with_warning(
+                    format!("Profile {} is updated with the new key.", profile_name),
+                    lookup_warning,
+                )),
+            }));
+        }
+
         let mut profile_name: String;
 
         if self.save_to_profile.is_none() {
+            // Without prompts there's no way to name the profile
             if self.skip_saving_profile
+                || assume_yes()
                 || !prompt_yes("Do you want to create a profile for the new key?")
             {
-                return Ok(RotateSummary {
+                return Ok(Submission::Submitted(RotateSummary {
                     transaction: txn_summary,
-                    message: None,
-                });
+                    message: lookup_warning,
+                }));
             }
 
             eprintln!("Enter the name for the profile");
@@ -194,10 +309,10 @@ impl CliCommand<RotateSummary> for RotateKey {
                 ) {
                     match cli_err {
                         CliError::AbortedError => {
-                            return Ok(RotateSummary {
+                            return Ok(Submission::Submitted(RotateSummary {
                                 transaction: txn_summary,
-                                message: None,
-                            });
+                                message: lookup_warning,
+                            }));
                         }
                         _ => {
                             return Err(cli_err);
@@ -216,6 +331,9 @@ impl CliCommand<RotateSummary> for RotateKey {
 
         let mut profile_config = ProfileConfig {
             private_key: Some(new_private_key.clone()),
+            encrypted_private_key: None,
+            keychain_entry: None,
+            ledger_derivation_path: None,
             public_key: Some(new_private_key.public_key()),
             account: Some(sender_address),
             ..self.txn_options.profile_options.profile()?
@@ -238,19 +356,76 @@ impl CliCommand<RotateSummary> for RotateKey {
 
         eprintln!("Profile {} is saved.", profile_name);
 
-        Ok(RotateSummary {
+        Ok(Submission::Submitted(RotateSummary {
             transaction: txn_summary,
-            message: Some(format!("Profile {} is saved.", profile_name)),
-        })
+            message: Some(with_warning(
+                format!("Profile {} is saved.", profile_name),
+                lookup_warning,
+            )),
+        }))
+    }
+}
+
+impl RotateKey {
+    /// Replaces the key of the profile in use, keeping how its key is stored
+    fn update_current_profile(
+        &self,
+        new_private_key: &Ed25519PrivateKey,
+        sender_address: AccountAddress,
+    ) -> CliTypedResult<String> {
+        let mut config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+        let profile_name =
+            config.active_profile_name(self.txn_options.profile_options.profile_name());
+        // It was checked to exist before rotating
+        let mut profile_config = config.remove_profile(&profile_name).ok_or_else(|| {
+            CliError::CommandArgumentError(format!("Profile {} not found", profile_name))
+        })?;
+
+        if let Some(ref keychain_entry) = profile_config.keychain_entry {
+            OsKeychain.store_private_key(keychain_entry, new_private_key)?;
+        } else if profile_config.encrypted_private_key.is_some() {
+            let passphrase = self
+                .txn_options
+                .private_key_options
+                .key_passphrase_options
+                .passphrase(false)?;
+            profile_config.encrypted_private_key =
+                Some(EncryptedPrivateKey::encrypt(new_private_key, &passphrase)?);
+        } else {
+            profile_config.private_key = Some(new_private_key.clone());
+        }
+        profile_config.ledger_derivation_path = None;
+        profile_config.public_key = Some(new_private_key.public_key());
+        profile_config.account = Some(sender_address);
+
+        config.insert_profile(&profile_name, profile_config);
+        config.save()?;
+        Ok(profile_name)
+    }
+}
+
+fn with_warning(message: String, warning: Option<String>) -> String {
+    match warning {
+        Some(warning) => format!("{} Warning: {}", message, warning),
+        None => message,
     }
 }
 
 /// Lookup the account address through the on-chain lookup table
 ///
 /// If the account is rotated, it will provide the address accordingly.  If the account was not
-/// rotated, it will provide the derived address only if the account exists onchain.
+/// rotated, it will provide the derived address only if the account exists onchain.  Either way,
+/// the account must still be controlled by the key, so a key that was rotated away from is an
+/// error rather than its old account.
 #[derive(Debug, Parser)]
 pub struct LookupAddress {
+    /// Authentication key to look up instead of a public key
+    ///
+    /// This is also the address originally derived from the key, so it can be given as an
+    /// address, and it works for multi-ed25519 keys
+    #[clap(long, group = "public_key_input", parse(try_from_str = parse_auth_key))]
+    pub(crate) auth_key: Option<AuthenticationKey>,
+
     #[clap(flatten)]
     pub(crate) encoding_options: EncodingOptions,
 
@@ -270,6 +445,14 @@ impl LookupAddress {
             .extract_public_key(self.encoding_options.encoding, &self.profile_options)
     }
 
+    /// The authentication key to look up, from `--auth-key` or the public key
+    fn auth_key(&self) -> CliTypedResult<AuthenticationKey> {
+        match self.auth_key {
+            Some(auth_key) => Ok(auth_key),
+            None => Ok(AuthenticationKey::ed25519(&self.public_key()?)),
+        }
+    }
+
     /// Builds a rest client
     fn rest_client(&self) -> CliTypedResult<Client> {
         self.rest_options.client(&self.profile_options)
@@ -283,44 +466,77 @@ impl CliCommand<AccountAddress> for LookupAddress {
     }
 
     async fn execute(self) -> CliTypedResult<AccountAddress> {
-        let rest_client = self.rest_client()?;
+        lookup_auth_key(&self.rest_client()?, self.auth_key()?).await
+    }
+}
 
-        let originating_resource: OriginatingResource = rest_client
-            .get_account_resource_bcs(CORE_CODE_ADDRESS, "0x1::account::OriginatingAddress")
-            .await?
-            .into_inner();
-
-        let table_handle = originating_resource.address_map.handle;
-
-        // The derived address that can be used to look up the original address
-        // TODO: This command needs to support multi-ed25519
-        let address_key = AuthenticationKey::ed25519(&self.public_key()?).derived_address();
-        match rest_client
-            .get_table_item_bcs(
-                table_handle,
-                "address",
-                "address",
-                address_key.to_hex_literal(),
-            )
-            .await
-        {
-            Ok(inner) => Ok(inner.into_inner()),
-            Err(RestError::Api(AptosErrorResponse {
-                error:
-                    AptosError {
-                        error_code: AptosErrorCode::TableItemNotFound,
-                        ..
-                    },
-                ..
-            })) => {
-                // If the table item wasn't found, let's at least check if the account exists
-                // It won't be in the table if it wasn't rotated, then return the derived account address
-                rest_client.get_account_bcs(address_key).await?;
-                Ok(address_key)
-            }
-            Err(err) => Err(err)?,
+fn parse_auth_key(str: &str) -> CliTypedResult<AuthenticationKey> {
+    AccountAddress::from_hex_literal(str)
+        .or_else(|_| AccountAddress::from_hex(str))
+        .map(|address| AuthenticationKey::new(address.into_bytes()))
+        .map_err(|err| CliError::UnableToParse("auth key", err.to_string()))
+}
+
+/// Looks up the account of a public key in the on-chain originating address table
+pub async fn lookup_address(
+    rest_client: &Client,
+    public_key: &Ed25519PublicKey,
+) -> CliTypedResult<AccountAddress> {
+    lookup_auth_key(rest_client, AuthenticationKey::ed25519(public_key)).await
+}
+
+/// Looks up the account of an authentication key in the on-chain originating address table,
+/// checking the account is still controlled by it
+pub async fn lookup_auth_key(
+    rest_client: &Client,
+    auth_key: AuthenticationKey,
+) -> CliTypedResult<AccountAddress> {
+    let originating_resource: OriginatingResource = rest_client
+        .get_account_resource_bcs(CORE_CODE_ADDRESS, "0x1::account::OriginatingAddress")
+        .await?
+        .into_inner();
+
+    let table_handle = originating_resource.address_map.handle;
+
+    // The derived address that can be used to look up the original address
+    let address_key = auth_key.derived_address();
+    let address = match rest_client
+        .get_table_item_bcs(
+            table_handle,
+            "address",
+            "address",
+            address_key.to_hex_literal(),
+        )
+        .await
+    {
+        Ok(inner) => inner.into_inner(),
+        Err(RestError::Api(AptosErrorResponse {
+            error:
+                AptosError {
+                    error_code: AptosErrorCode::TableItemNotFound,
+                    ..
+                },
+            ..
+        })) => {
+            // It won't be in the table if it wasn't rotated, then it's the derived account address
+            address_key
         }
+        Err(err) => Err(err)?,
+    };
+
+    // The table keeps entries of keys that were later rotated away from, and an account at the
+    // derived address may have rotated to another key, so check the key is still current
+    let account = rest_client.get_account_bcs(address).await?.into_inner();
+    if account.authentication_key() != auth_key.to_vec() {
+        return Err(CliError::UnexpectedError(format!(
+            "Account {} was found for authentication key {}, but it has since rotated to \
+            authentication key {}",
+            address,
+            auth_key,
+            hex::encode(account.authentication_key())
+        )));
     }
+    Ok(address)
 }
 
 #[derive(Deserialize)]
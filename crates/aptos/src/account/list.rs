@@ -4,9 +4,13 @@
 use crate::common::types::{
     CliCommand, CliConfig, CliError, CliTypedResult, ConfigSearchMode, ProfileOptions, RestOptions,
 };
+use aptos_rest_client::Resource;
 use aptos_types::account_address::AccountAddress;
 use async_trait::async_trait;
 use clap::{ArgEnum, Parser};
+use move_core_types::language_storage::{StructTag, TypeTag};
+use move_core_types::parser::parse_struct_tag;
+use serde::Serialize;
 use serde_json::json;
 use std::{
     fmt::{Display, Formatter},
@@ -16,6 +20,7 @@ use std::{
 #[derive(ArgEnum, Clone, Copy, Debug)]
 pub enum ListQuery {
     Balance,
+    Balances,
     Modules,
     Resources,
 }
@@ -24,6 +29,7 @@ impl Display for ListQuery {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let str = match self {
             ListQuery::Balance => "balance",
+            ListQuery::Balances => "balances",
             ListQuery::Modules => "modules",
             ListQuery::Resources => "resources",
         };
@@ -37,9 +43,10 @@ impl FromStr for ListQuery {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "balance" => Ok(ListQuery::Balance),
+            "balances" => Ok(ListQuery::Balances),
             "modules" => Ok(ListQuery::Modules),
             "resources" => Ok(ListQuery::Resources),
-            _ => Err("Invalid query. Valid values are balance, modules, resources"),
+            _ => Err("Invalid query. Valid values are balance, balances, modules, resources"),
         }
     }
 }
@@ -54,23 +61,65 @@ pub struct ListAccount {
     #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
     pub(crate) account: Option<AccountAddress>,
 
-    /// Type of items to list: [balance, resources, modules]
+    /// Type of items to list: [balance, balances, resources, modules]
+    ///
+    /// `balance` is the APT balance, and `balances` are the balances of all coins
     #[clap(long, default_value_t = ListQuery::Resources)]
     pub(crate) query: ListQuery,
 
+    /// Only list resources of these types, e.g. `0x1::coin::CoinStore`
+    ///
+    /// A type without type arguments matches all of its instantiations.  For `balances`, the
+    /// coin type is matched instead, e.g. `0x1::aptos_coin::AptosCoin`
+    #[clap(long, multiple_values = true, parse(try_from_str = parse_resource_type))]
+    pub(crate) resource_type: Vec<StructTag>,
+
+    /// Maximum number of resources or modules to fetch, to list a page at a time
+    ///
+    /// The output is then the page's `items`, and the `cursor` of the next page if there is
+    /// one.  Resource type filters apply within the page, so it may have fewer items.
+    #[clap(long)]
+    pub(crate) limit: Option<u16>,
+
+    /// Cursor of the page to list, from the output of the previous page
+    #[clap(long, requires = "limit")]
+    pub(crate) cursor: Option<String>,
+
     #[clap(flatten)]
     pub(crate) rest_options: RestOptions,
     #[clap(flatten)]
     pub(crate) profile_options: ProfileOptions,
 }
 
+/// A page of listed items, for listing with `--limit`
+#[derive(Debug, Serialize)]
+pub struct ListPage {
+    pub items: Vec<serde_json::Value>,
+    /// Cursor for `--cursor` to list the next page, if there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
 #[async_trait]
-impl CliCommand<Vec<serde_json::Value>> for ListAccount {
+impl CliCommand<serde_json::Value> for ListAccount {
     fn command_name(&self) -> &'static str {
         "ListAccount"
     }
 
-    async fn execute(self) -> CliTypedResult<Vec<serde_json::Value>> {
+    async fn execute(self) -> CliTypedResult<serde_json::Value> {
+        // Without pagination, the output stays a plain list
+        let paginated = self.limit.is_some();
+        let page = self.list().await?;
+        if paginated {
+            Ok(json!(page))
+        } else {
+            Ok(serde_json::Value::Array(page.items))
+        }
+    }
+}
+
+impl ListAccount {
+    pub(crate) async fn list(self) -> CliTypedResult<ListPage> {
         let account = if let Some(account) = self.account {
             account
         } else if let Some(Some(account)) = CliConfig::load_profile(
@@ -86,39 +135,158 @@ impl CliCommand<Vec<serde_json::Value>> for ListAccount {
             ));
         };
 
+        match self.query {
+            ListQuery::Balance if self.limit.is_some() || !self.resource_type.is_empty() => {
+                return Err(CliError::CommandArgumentError(
+                    "--limit and --resource-type can't be used with the balance query, use balances instead".to_string(),
+                ));
+            }
+            ListQuery::Modules if !self.resource_type.is_empty() => {
+                return Err(CliError::CommandArgumentError(
+                    "--resource-type can't be used with the modules query".to_string(),
+                ));
+            }
+            _ => {}
+        }
+
         let client = self.rest_options.client(&self.profile_options)?;
-        let response = match self.query {
-            ListQuery::Balance => vec![
-                client
-                    .get_account_resource(
-                        account,
-                        "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+        let page = match self.query {
+            ListQuery::Balance => ListPage {
+                items: vec![
+                    client
+                        .get_account_resource(
+                            account,
+                            "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+                        )
+                        .await?
+                        .into_inner()
+                        .unwrap()
+                        .data,
+                ],
+                cursor: None,
+            },
+            ListQuery::Modules => {
+                let (modules, cursor) = if let Some(limit) = self.limit {
+                    let response = client
+                        .get_account_modules_page(account, self.cursor, limit as u64)
+                        .await?;
+                    let cursor = response.state().cursor.clone();
+                    (response.into_inner(), cursor)
+                } else {
+                    (
+                        client.get_account_modules(account).await?.into_inner(),
+                        None,
+                    )
+                };
+                ListPage {
+                    items: modules
+                        .into_iter()
+                        .map(|module| json!(module.try_parse_abi().unwrap()))
+                        .collect(),
+                    cursor,
+                }
+            }
+            ListQuery::Balances | ListQuery::Resources => {
+                let (resources, cursor) = if let Some(limit) = self.limit {
+                    let response = client
+                        .get_account_resources_page(account, self.cursor, limit as u64)
+                        .await?;
+                    let cursor = response.state().cursor.clone();
+                    (response.into_inner(), cursor)
+                } else {
+                    (
+                        client.get_account_resources(account).await?.into_inner(),
+                        None,
                     )
-                    .await?
-                    .into_inner()
-                    .unwrap()
-                    .data,
-            ],
-            ListQuery::Modules => client
-                .get_account_modules(account)
-                .await?
-                .into_inner()
-                .into_iter()
-                .map(|module| json!(module.try_parse_abi().unwrap()))
-                .collect::<Vec<serde_json::Value>>(),
-            ListQuery::Resources => client
-                .get_account_resources(account)
-                .await?
-                .into_inner()
-                .into_iter()
-                .map(|resource| {
-                    let mut map = serde_json::Map::new();
-                    map.insert(resource.resource_type.to_string(), resource.data);
-                    serde_json::Value::Object(map)
-                })
-                .collect::<Vec<serde_json::Value>>(),
+                };
+                let items = if let ListQuery::Balances = self.query {
+                    coin_balances(resources, &self.resource_type)
+                } else {
+                    resources
+                        .into_iter()
+                        .filter(|resource| {
+                            matches_type(&self.resource_type, &resource.resource_type)
+                        })
+                        .map(|resource| {
+                            let mut map = serde_json::Map::new();
+                            map.insert(resource.resource_type.to_string(), resource.data);
+                            serde_json::Value::Object(map)
+                        })
+                        .collect()
+                };
+                ListPage { items, cursor }
+            }
         };
 
-        Ok(response)
+        Ok(page)
+    }
+}
+
+pub(crate) fn parse_resource_type(str: &str) -> CliTypedResult<StructTag> {
+    parse_struct_tag(str)
+        .map_err(|err| CliError::UnableToParse("resource type", format!("{}: {}", str, err)))
+}
+
+/// Whether the type matches any of the filters, or there are no filters
+pub(crate) fn matches_type(filters: &[StructTag], tag: &StructTag) -> bool {
+    filters.is_empty()
+        || filters.iter().any(|filter| {
+            filter.address == tag.address
+                && filter.module == tag.module
+                && filter.name == tag.name
+                && (filter.type_params.is_empty() || filter.type_params == tag.type_params)
+        })
+}
+
+/// Balances of the coin stores among the resources, with their coin type matching the filters
+fn coin_balances(resources: Vec<Resource>, filters: &[StructTag]) -> Vec<serde_json::Value> {
+    resources
+        .into_iter()
+        .filter_map(|resource| {
+            let coin_type = coin_store_type(&resource.resource_type)?;
+            if !matches_type(filters, coin_type) {
+                return None;
+            }
+            Some(json!({
+                "coin_type": coin_type.to_string(),
+                "balance": resource.data["coin"]["value"],
+            }))
+        })
+        .collect()
+}
+
+/// The coin type of a `0x1::coin::CoinStore`
+pub(crate) fn coin_store_type(tag: &StructTag) -> Option<&StructTag> {
+    if tag.address != AccountAddress::ONE
+        || tag.module.as_str() != "coin"
+        || tag.name.as_str() != "CoinStore"
+    {
+        return None;
+    }
+    match tag.type_params.first() {
+        Some(TypeTag::Struct(coin_type)) => Some(coin_type.as_ref()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_type() {
+        let coin_store =
+            parse_struct_tag("0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>").unwrap();
+        let any_coin_store = vec![parse_resource_type("0x1::coin::CoinStore").unwrap()];
+        let other_coin_store =
+            vec![parse_resource_type("0x1::coin::CoinStore<0x1::a::B>").unwrap()];
+
+        assert!(matches_type(&[], &coin_store));
+        assert!(matches_type(&any_coin_store, &coin_store));
+        assert!(!matches_type(&other_coin_store, &coin_store));
+        assert_eq!(
+            "0x1::aptos_coin::AptosCoin",
+            coin_store_type(&coin_store).unwrap().to_string()
+        );
     }
 }
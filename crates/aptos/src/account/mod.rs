@@ -4,11 +4,15 @@
 use crate::common::types::{CliCommand, CliResult};
 use clap::Subcommand;
 
+pub mod balance;
 pub mod create;
 pub mod create_resource_account;
+pub mod events;
 pub mod fund;
 pub mod key_rotation;
 pub mod list;
+pub mod multisig;
+pub mod transactions;
 pub mod transfer;
 
 /// Tool for interacting with accounts
@@ -17,24 +21,39 @@ pub mod transfer;
 /// account's resources, and transfer resources between accounts.
 #[derive(Debug, Subcommand)]
 pub enum AccountTool {
+    Balance(balance::AccountBalance),
     Create(create::CreateAccount),
     CreateResourceAccount(create_resource_account::CreateResourceAccount),
+    DeriveResourceAccountAddress(create_resource_account::DeriveResourceAccountAddress),
+    Events(events::ListEvents),
     FundWithFaucet(fund::FundWithFaucet),
     List(list::ListAccount),
     LookupAddress(key_rotation::LookupAddress),
+    #[clap(subcommand)]
+    Multisig(multisig::MultisigTool),
     RotateKey(key_rotation::RotateKey),
+    Transactions(transactions::ListTransactions),
     Transfer(transfer::TransferCoins),
 }
 
 impl AccountTool {
     pub async fn execute(self) -> CliResult {
         match self {
+            AccountTool::Balance(tool) => tool.execute_serialized().await,
             AccountTool::Create(tool) => tool.execute_serialized().await,
             AccountTool::CreateResourceAccount(tool) => tool.execute_serialized().await,
+            AccountTool::DeriveResourceAccountAddress(tool) => tool.execute_serialized().await,
+            AccountTool::Events(tool) if tool.follow_options.follow => tool.execute_follow().await,
+            AccountTool::Events(tool) => tool.execute_serialized().await,
             AccountTool::FundWithFaucet(tool) => tool.execute_serialized().await,
             AccountTool::List(tool) => tool.execute_serialized().await,
             AccountTool::LookupAddress(tool) => tool.execute_serialized().await,
+            AccountTool::Multisig(tool) => tool.execute().await,
             AccountTool::RotateKey(tool) => tool.execute_serialized().await,
+            AccountTool::Transactions(tool) if tool.follow_options.follow => {
+                tool.execute_follow().await
+            }
+            AccountTool::Transactions(tool) => tool.execute_serialized().await,
             AccountTool::Transfer(tool) => tool.execute_serialized().await,
         }
     }
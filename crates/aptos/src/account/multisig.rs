@@ -0,0 +1,519 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Multisig accounts, which are k-of-n MultiEd25519 accounts
+//!
+//! A transaction is proposed to a file, which each owner approves offline by signing it to a
+//! signature file.  Once enough owners have approved it, the signatures are assembled into the
+//! final transaction and submitted.
+
+use crate::common::{
+    types::{
+        CliCommand, CliError, CliResult, CliTypedResult, EncodingOptions, GasOptions,
+        PrivateKeyInputOptions, ProfileOptions, PromptOptions, RestOptions, SaveFile, Submission,
+        TransactionOptions, TransactionSummary, WaitOptions,
+    },
+    utils::{chain_id, get_sequence_number, prompt_yes_with_override, read_from_file},
+};
+use crate::move_tool::{entry_function_payload, ArgWithType, MemberId};
+use crate::transaction::describe_transaction;
+use aptos_crypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    hash::{CryptoHash, HashValue},
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    PrivateKey, Signature, SigningKey, ValidCryptoMaterialStringExt,
+};
+use aptos_global_constants::adjust_gas_headroom;
+use aptos_rest_client::aptos_api_types::{HexEncodedBytes, MoveType};
+use aptos_rest_client::Client;
+use aptos_sdk::transaction_builder::TransactionFactory;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
+};
+use async_trait::async_trait;
+use cached_packages::aptos_stdlib;
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Default time in seconds a proposed transaction stays valid, to leave time to gather approvals
+const DEFAULT_EXPIRATION_SECS: u64 = 24 * 60 * 60;
+
+/// Tool for multisig accounts
+///
+/// A multisig account is owned by a set of public keys, and a threshold of them must sign each
+/// transaction.  Transactions are proposed to a file, approved offline by each owner, and
+/// executed once enough owners have approved them.
+#[derive(Debug, Subcommand)]
+pub enum MultisigTool {
+    Create(CreateMultisig),
+    Propose(ProposeMultisig),
+    Approve(ApproveMultisig),
+    Execute(ExecuteMultisig),
+}
+
+impl MultisigTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            MultisigTool::Create(tool) => tool.execute_serialized().await,
+            MultisigTool::Propose(tool) => tool.execute_serialized().await,
+            MultisigTool::Approve(tool) => tool.execute_serialized().await,
+            MultisigTool::Execute(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// The owners of a multisig account
+#[derive(Debug, Parser)]
+pub struct MultisigAccountOptions {
+    /// Public keys of the owners, separated by spaces
+    ///
+    /// The order of the keys is part of the account, and must be the same every time
+    #[clap(long, multiple_values = true, parse(try_from_str = parse_public_key))]
+    pub(crate) public_keys: Vec<Ed25519PublicKey>,
+
+    /// Number of owners that have to approve each transaction
+    #[clap(long)]
+    pub(crate) threshold: u8,
+}
+
+impl MultisigAccountOptions {
+    pub fn public_key(&self) -> CliTypedResult<MultiEd25519PublicKey> {
+        MultiEd25519PublicKey::new(self.public_keys.clone(), self.threshold).map_err(|err| {
+            CliError::CommandArgumentError(format!(
+                "Invalid multisig of {} keys with threshold {}: {}",
+                self.public_keys.len(),
+                self.threshold,
+                err
+            ))
+        })
+    }
+}
+
+pub(crate) fn parse_public_key(str: &str) -> CliTypedResult<Ed25519PublicKey> {
+    Ed25519PublicKey::from_encoded_string(str)
+        .map_err(|err| CliError::UnableToParse("public key", format!("{}: {}", str, err)))
+}
+
+/// Address of the multisig account owned by the public key
+pub fn multisig_address(public_key: &MultiEd25519PublicKey) -> AccountAddress {
+    AuthenticationKey::multi_ed25519(public_key).derived_address()
+}
+
+/// A transaction proposed for a multisig account, to be approved by its owners
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MultisigProposal {
+    pub multisig_address: AccountAddress,
+    pub public_key: MultiEd25519PublicKey,
+    /// Hash of the transaction, which approvals refer to
+    pub transaction_hash: HashValue,
+    /// The BCS encoded `RawTransaction`
+    pub raw_transaction: HexEncodedBytes,
+}
+
+impl MultisigProposal {
+    fn new(
+        public_key: MultiEd25519PublicKey,
+        raw_transaction: &RawTransaction,
+    ) -> CliTypedResult<Self> {
+        Ok(MultisigProposal {
+            multisig_address: multisig_address(&public_key),
+            public_key,
+            transaction_hash: raw_transaction.hash(),
+            raw_transaction: HexEncodedBytes(bcs::to_bytes(raw_transaction)?),
+        })
+    }
+
+    /// Loads a proposal, checking that it's consistent with its transaction
+    fn load(path: &Path) -> CliTypedResult<(Self, RawTransaction)> {
+        let proposal: MultisigProposal = serde_json::from_slice(&read_from_file(path)?)
+            .map_err(|err| CliError::UnableToParse("--proposal-file", err.to_string()))?;
+        let raw_transaction: RawTransaction = bcs::from_bytes(&proposal.raw_transaction.0)?;
+
+        if raw_transaction.hash() != proposal.transaction_hash {
+            return Err(CliError::UnexpectedError(format!(
+                "Proposal {} doesn't match its transaction hash",
+                path.display()
+            )));
+        }
+        if raw_transaction.sender() != proposal.multisig_address
+            || multisig_address(&proposal.public_key) != proposal.multisig_address
+        {
+            return Err(CliError::UnexpectedError(format!(
+                "Proposal {} isn't sent from the multisig account {}",
+                path.display(),
+                proposal.multisig_address
+            )));
+        }
+        Ok((proposal, raw_transaction))
+    }
+
+    /// Index of an owner's public key in the multisig
+    fn owner_index(&self, public_key: &Ed25519PublicKey) -> CliTypedResult<u8> {
+        self.public_key
+            .public_keys()
+            .iter()
+            .position(|owner| owner == public_key)
+            .map(|index| index as u8)
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!(
+                    "Public key {} isn't an owner of multisig account {}",
+                    public_key, self.multisig_address
+                ))
+            })
+    }
+}
+
+/// An owner's approval of a proposed transaction
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MultisigApproval {
+    pub transaction_hash: HashValue,
+    pub public_key: Ed25519PublicKey,
+    pub signature: Ed25519Signature,
+}
+
+/// Create a multisig account on-chain
+///
+/// The account's address is derived from the owners' public keys and the threshold, and the
+/// account is created by the sender of the transaction.
+#[derive(Debug, Parser)]
+pub struct CreateMultisig {
+    #[clap(flatten)]
+    pub(crate) multisig_options: MultisigAccountOptions,
+
+    /// Amount of Octas to transfer to the new account, e.g. to pay for its transactions
+    #[clap(long, default_value_t = 0)]
+    pub(crate) initial_balance: u64,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateMultisigSummary {
+    pub multisig_address: AccountAddress,
+    pub threshold: u8,
+    pub public_keys: Vec<Ed25519PublicKey>,
+    #[serde(flatten)]
+    pub transaction_summary: TransactionSummary,
+}
+
+#[async_trait]
+impl CliCommand<Submission<CreateMultisigSummary>> for CreateMultisig {
+    fn command_name(&self) -> &'static str {
+        "CreateMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<Submission<CreateMultisigSummary>> {
+        let public_key = self.multisig_options.public_key()?;
+        let address = multisig_address(&public_key);
+
+        // Transferring creates the account if it doesn't exist yet
+        let payload = if self.initial_balance > 0 {
+            aptos_stdlib::aptos_account_transfer(address, self.initial_balance)
+        } else {
+            aptos_stdlib::aptos_account_create_account(address)
+        };
+        let submission = self.txn_options.submit_transaction(payload).await?;
+
+        Ok(submission.map(|transaction| CreateMultisigSummary {
+            multisig_address: address,
+            threshold: *public_key.threshold(),
+            public_keys: public_key.public_keys().clone(),
+            transaction_summary: TransactionSummary::from(&transaction),
+        }))
+    }
+}
+
+/// Propose a transaction for a multisig account
+///
+/// The unsigned transaction is written to a proposal file, to be approved by the owners with
+/// `aptos account multisig approve`.
+#[derive(Debug, Parser)]
+pub struct ProposeMultisig {
+    #[clap(flatten)]
+    pub(crate) multisig_options: MultisigAccountOptions,
+
+    /// Function name as `<ADDRESS>::<MODULE_ID>::<FUNCTION_NAME>`
+    ///
+    /// Example: `0x1::coin::transfer`
+    #[clap(long)]
+    pub(crate) function_id: MemberId,
+
+    /// Arguments combined with their type separated by spaces.
+    ///
+    /// Supported types [u8, u64, u128, bool, hex, string, address, raw]
+    ///
+    /// Example: `address:0x1 bool:true u8:0`
+    #[clap(long, multiple_values = true)]
+    pub(crate) args: Vec<ArgWithType>,
+
+    /// TypeTag arguments separated by spaces.
+    ///
+    /// Example: `u8 u64 u128 bool address vector signer`
+    #[clap(long, multiple_values = true)]
+    pub(crate) type_args: Vec<MoveType>,
+
+    /// Sequence number of the transaction
+    ///
+    /// Defaults to the multisig account's current sequence number
+    #[clap(long)]
+    pub(crate) sequence_number: Option<u64>,
+
+    /// Seconds from now until the transaction expires, which all approvals have to be gathered
+    /// and the transaction executed within
+    #[clap(long, default_value_t = DEFAULT_EXPIRATION_SECS)]
+    pub(crate) expiration_secs: u64,
+
+    #[clap(flatten)]
+    pub(crate) gas_options: GasOptions,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<MultisigProposal> for ProposeMultisig {
+    fn command_name(&self) -> &'static str {
+        "ProposeMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<MultisigProposal> {
+        self.save_file.check_file()?;
+        let public_key = self.multisig_options.public_key()?;
+        let address = multisig_address(&public_key);
+        let payload = entry_function_payload(self.function_id, self.type_args, self.args)?;
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        let sequence_number = match self.sequence_number {
+            Some(sequence_number) => sequence_number,
+            None => get_sequence_number(&client, address).await?,
+        };
+        let gas_unit_price = self.gas_options.gas_unit_price(&client).await?;
+        let transaction_factory = TransactionFactory::new(chain_id(&client).await?)
+            .with_gas_unit_price(gas_unit_price)
+            .with_transaction_expiration_time(self.expiration_secs);
+
+        let max_gas = match self.gas_options.max_gas {
+            Some(max_gas) => max_gas,
+            None => {
+                let raw_transaction = transaction_factory
+                    .payload(payload.clone())
+                    .sender(address)
+                    .sequence_number(sequence_number)
+                    .build();
+                simulate_max_gas(&client, raw_transaction, &public_key).await?
+            }
+        };
+
+        let raw_transaction = transaction_factory
+            .with_max_gas_amount(max_gas)
+            .payload(payload)
+            .sender(address)
+            .sequence_number(sequence_number)
+            .build();
+        let proposal = MultisigProposal::new(public_key, &raw_transaction)?;
+        self.save_file.save_to_file(
+            "Multisig proposal",
+            serde_json::to_string_pretty(&proposal)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+                .as_bytes(),
+        )?;
+        Ok(proposal)
+    }
+}
+
+/// Simulates the transaction with an unsigned threshold of signatures, to estimate its gas
+async fn simulate_max_gas(
+    client: &Client,
+    raw_transaction: RawTransaction,
+    public_key: &MultiEd25519PublicKey,
+) -> CliTypedResult<u64> {
+    let empty_signature = Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap();
+    let signature = MultiEd25519Signature::new(
+        (0..*public_key.threshold())
+            .map(|index| (empty_signature.clone(), index))
+            .collect(),
+    )
+    .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+    let signed_transaction =
+        SignedTransaction::new_multisig(raw_transaction, public_key.clone(), signature);
+
+    let simulated_txn = client
+        .simulate_bcs_with_gas_estimation(&signed_transaction, true, false)
+        .await?
+        .into_inner();
+    let status = simulated_txn.info.status();
+    if !status.is_success() {
+        let status = client.explain_vm_status(status);
+        return Err(CliError::SimulationError(status));
+    }
+
+    Ok(adjust_gas_headroom(
+        simulated_txn.info.gas_used(),
+        simulated_txn
+            .transaction
+            .as_signed_user_txn()
+            .expect("Should be signed user transaction")
+            .max_gas_amount(),
+    ))
+}
+
+/// Approve a proposed multisig transaction
+///
+/// Signs the transaction in the proposal file with an owner's key, and writes the signature to
+/// a file for `aptos account multisig execute`.  This doesn't need network access.
+#[derive(Debug, Parser)]
+pub struct ApproveMultisig {
+    /// Proposal file from `aptos account multisig propose`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) proposal_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<MultisigApproval> for ApproveMultisig {
+    fn command_name(&self) -> &'static str {
+        "ApproveMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<MultisigApproval> {
+        self.save_file.check_file()?;
+        let (proposal, raw_transaction) = MultisigProposal::load(&self.proposal_file)?;
+        let private_key = self
+            .private_key_options
+            .extract_private_key(self.encoding_options.encoding, &self.profile_options)?;
+        let public_key = private_key.public_key();
+        proposal.owner_index(&public_key)?;
+
+        eprintln!("{}", describe_transaction(&raw_transaction));
+        prompt_yes_with_override(
+            "Do you want to approve this transaction?",
+            self.save_file.prompt_options,
+        )?;
+
+        let signature = private_key
+            .sign(&raw_transaction)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        let approval = MultisigApproval {
+            transaction_hash: proposal.transaction_hash,
+            public_key,
+            signature,
+        };
+        self.save_file.save_to_file(
+            "Multisig approval",
+            serde_json::to_string_pretty(&approval)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+                .as_bytes(),
+        )?;
+        Ok(approval)
+    }
+}
+
+/// Execute an approved multisig transaction
+///
+/// Assembles the owners' signatures into the final transaction and submits it.  Anyone can
+/// submit it, as the transaction is paid for by the multisig account.
+#[derive(Debug, Parser)]
+pub struct ExecuteMultisig {
+    /// Proposal file from `aptos account multisig propose`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) proposal_file: PathBuf,
+
+    /// Approval files from `aptos account multisig approve`, separated by spaces
+    #[clap(long, multiple_values = true, parse(from_os_str))]
+    pub(crate) approval_files: Vec<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+    #[clap(flatten)]
+    pub(crate) wait_options: WaitOptions,
+}
+
+#[async_trait]
+impl CliCommand<Submission<TransactionSummary>> for ExecuteMultisig {
+    fn command_name(&self) -> &'static str {
+        "ExecuteMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        let (proposal, raw_transaction) = MultisigProposal::load(&self.proposal_file)?;
+
+        // Only one signature per owner counts towards the threshold
+        let mut signatures = BTreeMap::new();
+        for approval_file in &self.approval_files {
+            let approval: MultisigApproval =
+                serde_json::from_slice(&read_from_file(approval_file)?)
+                    .map_err(|err| CliError::UnableToParse("--approval-files", err.to_string()))?;
+            if approval.transaction_hash != proposal.transaction_hash {
+                return Err(CliError::CommandArgumentError(format!(
+                    "{} approves a different transaction {}",
+                    approval_file.display(),
+                    approval.transaction_hash
+                )));
+            }
+            let index = proposal.owner_index(&approval.public_key)?;
+            approval
+                .signature
+                .verify(&raw_transaction, &approval.public_key)
+                .map_err(|err| {
+                    CliError::CommandArgumentError(format!(
+                        "{} has an invalid signature: {}",
+                        approval_file.display(),
+                        err
+                    ))
+                })?;
+            signatures.insert(index, approval.signature);
+        }
+
+        let threshold = *proposal.public_key.threshold() as usize;
+        if signatures.len() < threshold {
+            return Err(CliError::CommandArgumentError(format!(
+                "Only {} of the {} required owners approved the transaction",
+                signatures.len(),
+                threshold
+            )));
+        }
+        let signature = MultiEd25519Signature::new(
+            signatures
+                .into_iter()
+                .map(|(index, signature)| (signature, index))
+                .collect(),
+        )
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+
+        let message = format!(
+            "Do you want to submit the transaction for a maximum of {} Octas at a gas unit price of {} Octas?",
+            raw_transaction.max_gas_amount() * raw_transaction.gas_unit_price(),
+            raw_transaction.gas_unit_price()
+        );
+        prompt_yes_with_override(&message, self.prompt_options)?;
+
+        let transaction =
+            SignedTransaction::new_multisig(raw_transaction, proposal.public_key, signature);
+        let client = self.rest_options.client(&self.profile_options)?;
+        self.wait_options
+            .submit_and_wait(&client, &transaction)
+            .await
+            .map(|submission| submission.map(TransactionSummary::from))
+    }
+}
@@ -0,0 +1,333 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    follow::{execute_follow, FollowOptions, FollowSource},
+    types::{CliCommand, CliResult, CliTypedResult, ProfileOptions, RestOptions},
+    utils::get_sequence_number,
+};
+use aptos_rest_client::aptos_api_types::{
+    EntryFunctionPayload, MoveFunction, MoveType, TransactionPayload, UserTransaction,
+};
+use aptos_rest_client::{Client, Transaction};
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Transactions fetched per request when paging back to `--since-version`
+const PAGE_SIZE: u64 = 100;
+
+/// List the transactions sent by an account
+///
+/// Entry function arguments are shown with their types from the on-chain ABI of the function,
+/// in the same `<TYPE>:<VALUE>` form as `--args` of `aptos move run`.  Use `--output csv` for
+/// a spreadsheet.
+///
+/// With `--follow`, new transactions are printed as they're committed, one JSON object per line.
+#[derive(Debug, Parser)]
+pub struct ListTransactions {
+    /// Address of the account
+    ///
+    /// Defaults to the profile's account
+    #[clap(parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) account: Option<AccountAddress>,
+
+    /// Only list transactions at or after this ledger version, oldest first
+    ///
+    /// Without it, the latest transactions are listed
+    #[clap(long)]
+    pub(crate) since_version: Option<u64>,
+
+    /// Maximum number of transactions to list
+    #[clap(long, default_value_t = 25)]
+    pub(crate) limit: u64,
+
+    #[clap(flatten)]
+    pub(crate) follow_options: FollowOptions,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+/// A transaction of the account's history
+#[derive(Debug, Serialize)]
+pub struct TransactionHistoryEntry {
+    pub version: u64,
+    pub hash: String,
+    pub sequence_number: u64,
+    /// Timestamp of the transaction in microseconds
+    pub timestamp: u64,
+    pub success: bool,
+    pub vm_status: String,
+    pub gas_used: u64,
+    /// The called function, or `script` or `module_bundle`
+    pub function: String,
+    pub type_arguments: Vec<String>,
+    /// Arguments as `<TYPE>:<VALUE>`, or just the value if the ABI isn't available
+    pub arguments: Vec<String>,
+}
+
+#[async_trait]
+impl CliCommand<Vec<TransactionHistoryEntry>> for ListTransactions {
+    fn command_name(&self) -> &'static str {
+        "ListTransactions"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<TransactionHistoryEntry>> {
+        let account = self.account()?;
+        let client = self.rest_options.client(&self.profile_options)?;
+        let transactions = self.initial_transactions(&client, account).await?;
+        Ok(history_entries(&client, &mut AbiCache::default(), transactions).await)
+    }
+}
+
+impl ListTransactions {
+    /// Prints new transactions as they're committed, after the ones listed without
+    /// `--follow`, until interrupted
+    pub async fn execute_follow(mut self) -> CliResult {
+        let options = std::mem::take(&mut self.follow_options);
+        execute_follow(self.command_name(), &options, async move {
+            let account = self.account()?;
+            let client = self.rest_options.client(&self.profile_options)?;
+            Ok(FollowTransactions {
+                command: self,
+                client,
+                account,
+                abis: AbiCache::default(),
+                next_sequence_number: None,
+            })
+        })
+        .await
+    }
+
+    fn account(&self) -> CliTypedResult<AccountAddress> {
+        match self.account {
+            Some(account) => Ok(account),
+            None => self.profile_options.account_address(),
+        }
+    }
+
+    /// Transactions listed before following any new ones
+    async fn initial_transactions(
+        &self,
+        client: &Client,
+        account: AccountAddress,
+    ) -> CliTypedResult<Vec<Transaction>> {
+        Ok(match self.since_version {
+            Some(since_version) => {
+                let mut transactions = transactions_since(client, account, since_version).await?;
+                transactions.truncate(self.limit as usize);
+                transactions
+            }
+            None => client
+                .get_account_transactions(account, None, Some(self.limit))
+                .await?
+                .into_inner(),
+        })
+    }
+}
+
+/// Transactions of an account, polled by sequence number from after the last one seen
+struct FollowTransactions {
+    command: ListTransactions,
+    client: Client,
+    account: AccountAddress,
+    abis: AbiCache,
+    /// Sequence number of the next transaction, `None` until the initial ones are listed
+    next_sequence_number: Option<u64>,
+}
+
+#[async_trait]
+impl FollowSource for FollowTransactions {
+    type Item = TransactionHistoryEntry;
+
+    async fn poll(&mut self) -> CliTypedResult<Vec<TransactionHistoryEntry>> {
+        let transactions = match self.next_sequence_number {
+            Some(next) => self
+                .client
+                .get_account_transactions(self.account, Some(next), Some(self.command.limit))
+                .await?
+                .into_inner(),
+            None => {
+                let transactions = self
+                    .command
+                    .initial_transactions(&self.client, self.account)
+                    .await?;
+                // Without any to start from, follow from the account's next transaction
+                if transactions.is_empty() {
+                    self.next_sequence_number =
+                        Some(get_sequence_number(&self.client, self.account).await?);
+                }
+                transactions
+            }
+        };
+
+        let entries = history_entries(&self.client, &mut self.abis, transactions).await;
+        if let Some(last) = entries.last() {
+            self.next_sequence_number = Some(last.sequence_number + 1);
+        }
+        Ok(entries)
+    }
+}
+
+/// History entries of the user transactions, skipping any other transactions
+async fn history_entries(
+    client: &Client,
+    abis: &mut AbiCache,
+    transactions: Vec<Transaction>,
+) -> Vec<TransactionHistoryEntry> {
+    let mut entries = Vec::new();
+    for transaction in transactions {
+        if let Transaction::UserTransaction(transaction) = transaction {
+            entries.push(history_entry(client, abis, *transaction).await);
+        }
+    }
+    entries
+}
+
+/// All transactions of the account at or after the version, oldest first
+///
+/// Pages backwards from the latest transaction, as versions only increase with sequence
+/// numbers, until a page reaches back before the version.
+async fn transactions_since(
+    client: &Client,
+    account: AccountAddress,
+    since_version: u64,
+) -> CliTypedResult<Vec<Transaction>> {
+    let mut end = get_sequence_number(client, account).await?;
+    let mut transactions = Vec::new();
+    while end > 0 {
+        let start = end.saturating_sub(PAGE_SIZE);
+        let page = client
+            .get_account_transactions(account, Some(start), Some(end - start))
+            .await?
+            .into_inner();
+        let reached_version = page
+            .first()
+            .map_or(true, |transaction| version(transaction) < since_version);
+        let page: Vec<_> = page
+            .into_iter()
+            .filter(|transaction| version(transaction) >= since_version)
+            .collect();
+        transactions.splice(0..0, page);
+        if reached_version {
+            break;
+        }
+        end = start;
+    }
+    Ok(transactions)
+}
+
+fn version(transaction: &Transaction) -> u64 {
+    transaction.version().unwrap_or_default()
+}
+
+/// Entry function ABIs by module, fetched once per module
+#[derive(Default)]
+struct AbiCache {
+    modules: BTreeMap<String, Option<Vec<MoveFunction>>>,
+}
+
+impl AbiCache {
+    /// Parameter types of the entry function, without its signers
+    async fn params(
+        &mut self,
+        client: &Client,
+        payload: &EntryFunctionPayload,
+    ) -> Option<Vec<MoveType>> {
+        let module = &payload.function.module;
+        let key = format!("{}::{}", module.address, module.name);
+        if !self.modules.contains_key(&key) {
+            let functions = match client
+                .get_account_module(AccountAddress::from(&module.address), module.name.as_str())
+                .await
+            {
+                Ok(response) => response
+                    .into_inner()
+                    .try_parse_abi()
+                    .ok()
+                    .and_then(|module| module.abi)
+                    .map(|abi| abi.exposed_functions),
+                Err(_) => None,
+            };
+            self.modules.insert(key.clone(), functions);
+        }
+
+        let function = self
+            .modules
+            .get(&key)?
+            .as_ref()?
+            .iter()
+            .find(|function| function.name.as_str() == payload.function.name.as_str())?;
+        Some(
+            function
+                .params
+                .iter()
+                .filter(|param| !is_signer(param))
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+fn is_signer(param: &MoveType) -> bool {
+    match param {
+        MoveType::Signer => true,
+        MoveType::Reference { to, .. } => is_signer(to),
+        _ => false,
+    }
+}
+
+async fn history_entry(
+    client: &Client,
+    abis: &mut AbiCache,
+    transaction: UserTransaction,
+) -> TransactionHistoryEntry {
+    let (function, type_arguments, arguments) = match transaction.request.payload {
+        TransactionPayload::EntryFunctionPayload(payload) => {
+            let params = abis.params(client, &payload).await;
+            let arguments = payload
+                .arguments
+                .iter()
+                .enumerate()
+                .map(|(index, argument)| {
+                    let value = match argument {
+                        serde_json::Value::String(value) => value.clone(),
+                        value => value.to_string(),
+                    };
+                    match params.as_ref().and_then(|params| params.get(index)) {
+                        Some(param) => format!("{}:{}", param, value),
+                        None => value,
+                    }
+                })
+                .collect();
+            (
+                payload.function.to_string(),
+                payload
+                    .type_arguments
+                    .iter()
+                    .map(|type_argument| type_argument.to_string())
+                    .collect(),
+                arguments,
+            )
+        }
+        TransactionPayload::ScriptPayload(_) => ("script".to_string(), vec![], vec![]),
+        TransactionPayload::ModuleBundlePayload(_) => ("module_bundle".to_string(), vec![], vec![]),
+    };
+
+    TransactionHistoryEntry {
+        version: transaction.info.version.into(),
+        hash: transaction.info.hash.to_string(),
+        sequence_number: transaction.request.sequence_number.into(),
+        timestamp: transaction.timestamp.into(),
+        success: transaction.info.success,
+        vm_status: transaction.info.vm_status,
+        gas_used: transaction.info.gas_used.into(),
+        function,
+        type_arguments,
+        arguments,
+    }
+}
@@ -1,110 +1,286 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{CliCommand, CliTypedResult, TransactionOptions};
-use aptos_rest_client::aptos_api_types::HashValue;
+use crate::common::types::{
+    CliCommand, CliConfig, CliError, CliTypedResult, ConfigSearchMode, GasOptions, ProfileOptions,
+    RestOptions, Submission, TransactionOptions,
+};
+use aptos_rest_client::aptos_api_types::{HashValue, MoveStructTag};
 use aptos_rest_client::{
     aptos_api_types::{WriteResource, WriteSetChange},
     Transaction,
 };
 use aptos_types::account_address::AccountAddress;
+use aptos_types::utility_coin::APTOS_COIN_TYPE;
 use async_trait::async_trait;
 use cached_packages::aptos_stdlib;
 use clap::Parser;
+use move_core_types::ident_str;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use move_core_types::parser::parse_struct_tag;
 use serde::Serialize;
 use std::collections::BTreeMap;
 
-// TODO: Add ability to transfer non-APT coins
 // TODO: Add ability to not create account by default
-/// Transfer APT between accounts
+/// Transfer APT, or any other coin, between accounts
 ///
 #[derive(Debug, Parser)]
 pub struct TransferCoins {
-    /// Address of account to send APT to
+    /// Address of account to send coins to
     #[clap(long, parse(try_from_str = crate::common::types::load_account_arg))]
     pub(crate) account: AccountAddress,
 
-    /// Amount of Octas (10^-8 APT) to transfer
+    /// Amount of coins to transfer, in the coin's smallest unit, e.g. Octas (10^-8 APT)
     #[clap(long)]
     pub(crate) amount: u64,
 
+    /// Type of the coin to transfer, e.g. `0x1::aptos_coin::AptosCoin`
+    ///
+    /// Defaults to APT.  Only APT transfers create the recipient's account if it doesn't exist,
+    /// and other coins need the recipient to be registered for the coin.
+    #[clap(long, parse(try_from_str = parse_coin_type))]
+    pub(crate) coin_type: Option<StructTag>,
+
+    /// Register the recipient for the coin first, if it isn't already
+    ///
+    /// Registering must be signed by the recipient, so the recipient must be the account of a
+    /// profile in the config, which pays the gas for registering
+    #[clap(long)]
+    pub(crate) register_recipient: bool,
+
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
 }
 
 #[async_trait]
-impl CliCommand<TransferSummary> for TransferCoins {
+impl CliCommand<Submission<TransferSummary>> for TransferCoins {
     fn command_name(&self) -> &'static str {
         "TransferCoins"
     }
 
-    async fn execute(self) -> CliTypedResult<TransferSummary> {
+    async fn execute(self) -> CliTypedResult<Submission<TransferSummary>> {
+        let coin_type = match self.coin_type {
+            Some(ref coin_type) => TypeTag::Struct(Box::new(coin_type.clone())),
+            None => APTOS_COIN_TYPE.clone(),
+        };
+        let coin_store = coin_store_type(coin_type.clone());
+
+        if self.register_recipient && self.txn_options.encode_only {
+            return Err(CliError::CommandArgumentError(
+                "--register-recipient submits a transaction, so it can't be used with --encode-only"
+                    .to_string(),
+            ));
+        }
+        if self.register_recipient {
+            self.register_recipient(&coin_type, &coin_store).await?;
+        }
+
+        let payload = if coin_type == *APTOS_COIN_TYPE {
+            aptos_stdlib::aptos_account_transfer(self.account, self.amount)
+        } else {
+            aptos_stdlib::coin_transfer(coin_type, self.account, self.amount)
+        };
         self.txn_options
-            .submit_transaction(aptos_stdlib::aptos_account_transfer(
-                self.account,
-                self.amount,
-            ))
+            .submit_transaction(payload)
             .await
-            .map(TransferSummary::from)
+            .map(|submission| {
+                submission.map(|transaction| TransferSummary::new(transaction, &coin_store))
+            })
+    }
+}
+
+impl TransferCoins {
+    /// Registers the recipient for the coin with the recipient's profile, if it isn't registered
+    async fn register_recipient(
+        &self,
+        coin_type: &TypeTag,
+        coin_store: &StructTag,
+    ) -> CliTypedResult<()> {
+        let client = self
+            .txn_options
+            .rest_options
+            .client(&self.txn_options.profile_options)?;
+        if client
+            .get_account_resource(self.account, &coin_store.to_string())
+            .await?
+            .into_inner()
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let profile = profile_for_account(self.account)?;
+        eprintln!(
+            "Registering account {} for {} with profile {}",
+            self.account, coin_type, profile
+        );
+        let recipient_options = TransactionOptions {
+            profile_options: ProfileOptions {
+                profile: Some(profile),
+            },
+            rest_options: RestOptions::new(
+                self.txn_options.rest_options.url.clone(),
+                Some(self.txn_options.rest_options.connection_timeout_secs),
+            ),
+            gas_options: GasOptions {
+                gas_unit_price: self.txn_options.gas_options.gas_unit_price,
+                max_gas: None,
+            },
+            prompt_options: self.txn_options.prompt_options,
+            ..Default::default()
+        };
+        recipient_options
+            .submit_transaction(aptos_stdlib::managed_coin_register(coin_type.clone()))
+            .await?;
+        Ok(())
+    }
+}
+
+fn parse_coin_type(str: &str) -> CliTypedResult<StructTag> {
+    parse_struct_tag(str)
+        .map_err(|err| CliError::UnableToParse("coin type", format!("{}: {}", str, err)))
+}
+
+/// The `0x1::coin::CoinStore` holding the coin
+fn coin_store_type(coin_type: TypeTag) -> StructTag {
+    StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("coin").to_owned(),
+        name: ident_str!("CoinStore").to_owned(),
+        type_params: vec![coin_type],
     }
 }
 
-const SUPPORTED_COINS: [&str; 1] = ["0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>"];
+/// Finds the profile with the account, to sign for it
+fn profile_for_account(account: AccountAddress) -> CliTypedResult<String> {
+    CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?
+        .profiles
+        .unwrap_or_default()
+        .into_iter()
+        .find(|(_, profile)| profile.account == Some(account))
+        .map(|(name, _)| name)
+        .ok_or_else(|| {
+            CliError::CommandArgumentError(format!(
+                "Account {} isn't registered for the coin, and there's no profile for it to register it with",
+                account
+            ))
+        })
+}
 
 /// A shortened transaction output
+///
+/// Only the sender, gas unit price and hash are known of a transfer that's still pending, with
+/// `--wait-for submitted`
 #[derive(Clone, Debug, Serialize)]
 pub struct TransferSummary {
     pub gas_unit_price: u64,
-    pub gas_used: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
     pub balance_changes: BTreeMap<AccountAddress, serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending: Option<bool>,
     pub sender: AccountAddress,
-    pub success: bool,
-    pub version: u64,
-    pub vm_status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vm_status: Option<String>,
     pub transaction_hash: HashValue,
 }
 
-impl From<Transaction> for TransferSummary {
-    fn from(transaction: Transaction) -> Self {
-        if let Transaction::UserTransaction(txn) = transaction {
-            let vm_status = txn.info.vm_status;
-            let success = txn.info.success;
-            let sender = *txn.request.sender.inner();
-            let gas_unit_price = txn.request.gas_unit_price.0;
-            let gas_used = txn.info.gas_used.0;
-            let transaction_hash = txn.info.hash;
-            let version = txn.info.version.0;
-            let balance_changes = txn
-                .info
-                .changes
-                .into_iter()
-                .filter_map(|change| match change {
-                    WriteSetChange::WriteResource(WriteResource { address, data, .. }) => {
-                        if SUPPORTED_COINS.contains(&data.typ.to_string().as_str()) {
-                            Some((
-                                *address.inner(),
-                                serde_json::to_value(data.data).unwrap_or_default(),
-                            ))
-                        } else {
-                            None
+impl TransferSummary {
+    /// Summarizes a transfer, with the balance changes of the coin store
+    pub fn new(transaction: Transaction, coin_store: &StructTag) -> Self {
+        let coin_store = MoveStructTag::from(coin_store.clone()).to_string();
+        match transaction {
+            Transaction::PendingTransaction(txn) => TransferSummary {
+                gas_unit_price: txn.request.gas_unit_price.0,
+                gas_used: None,
+                balance_changes: BTreeMap::new(),
+                pending: Some(true),
+                sender: *txn.request.sender.inner(),
+                success: None,
+                version: None,
+                vm_status: None,
+                transaction_hash: txn.hash,
+            },
+            Transaction::UserTransaction(txn) => {
+                let balance_changes = txn
+                    .info
+                    .changes
+                    .into_iter()
+                    .filter_map(|change| match change {
+                        WriteSetChange::WriteResource(WriteResource { address, data, .. }) => {
+                            if data.typ.to_string() == coin_store {
+                                Some((
+                                    *address.inner(),
+                                    serde_json::to_value(data.data).unwrap_or_default(),
+                                ))
+                            } else {
+                                None
+                            }
                         }
-                    }
-                    _ => None,
-                })
-                .collect();
-
-            TransferSummary {
-                gas_unit_price,
-                gas_used,
-                balance_changes,
-                sender,
-                success,
-                version,
-                vm_status,
-                transaction_hash,
+                        _ => None,
+                    })
+                    .collect();
+
+                TransferSummary {
+                    gas_unit_price: txn.request.gas_unit_price.0,
+                    gas_used: Some(txn.info.gas_used.0),
+                    balance_changes,
+                    pending: None,
+                    sender: *txn.request.sender.inner(),
+                    success: Some(txn.info.success),
+                    version: Some(txn.info.version.0),
+                    vm_status: Some(txn.info.vm_status),
+                    transaction_hash: txn.info.hash,
+                }
             }
-        } else {
-            panic!("Can't call From<Transaction> for a non UserTransaction")
+            _ => panic!("Can't summarize a non UserTransaction as a transfer"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_summarize_pending_transfer() {
+        let hash = format!("0x{}", "ab".repeat(32));
+        let transaction: Transaction = serde_json::from_value(json!({
+            "type": "pending_transaction",
+            "hash": hash,
+            "sender": "0x1234",
+            "sequence_number": "7",
+            "max_gas_amount": "2000",
+            "gas_unit_price": "150",
+            "expiration_timestamp_secs": "1700000000",
+            "payload": {
+                "type": "entry_function_payload",
+                "function": "0x1::coin::transfer",
+                "type_arguments": ["0x1::aptos_coin::AptosCoin"],
+                "arguments": ["0x5678", "100"]
+            }
+        }))
+        .unwrap();
+
+        let summary = TransferSummary::new(transaction, &coin_store_type(APTOS_COIN_TYPE.clone()));
+        assert_eq!(summary.pending, Some(true));
+        assert_eq!(
+            summary.sender,
+            AccountAddress::from_hex_literal("0x1234").unwrap()
+        );
+        assert_eq!(summary.gas_unit_price, 150);
+        assert_eq!(summary.transaction_hash.to_string(), hash);
+        assert!(summary.balance_changes.is_empty());
+        assert_eq!(summary.success, None);
+
+        let output = serde_json::to_value(&summary).unwrap();
+        assert_eq!(output["pending"], json!(true));
+        assert!(output.get("success").is_none());
+        assert!(output.get("version").is_none());
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Following of new results of a query, for watching an account or contract as it's used
+//!
+//! The REST API has no streaming endpoint, so followed queries are polled with a cursor past
+//! the last result.  Each result is printed as it arrives as a line of JSON (NDJSON), until the
+//! command is interrupted with Ctrl-C.
+
+use crate::common::types::{CliError, CliResult, CliTypedResult};
+use crate::common::utils::{start_logger, to_common_result};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Options for following the results of a query
+#[derive(Debug, Default, Parser)]
+pub struct FollowOptions {
+    /// Keep printing new results as they're committed, as a line of JSON each, until
+    /// interrupted
+    #[clap(long)]
+    pub follow: bool,
+
+    /// Milliseconds between polls for new results with `--follow`
+    #[clap(long, default_value_t = 1000, requires = "follow")]
+    pub poll_interval_ms: u64,
+}
+
+/// A query whose new results can be polled for
+#[async_trait]
+pub trait FollowSource: Send {
+    type Item: Serialize + Send;
+
+    /// Results after the ones already returned, oldest first
+    async fn poll(&mut self) -> CliTypedResult<Vec<Self::Item>>;
+}
+
+impl FollowOptions {
+    /// Prints the results of the source as they arrive, until interrupted with Ctrl-C
+    pub async fn follow<S: FollowSource>(&self, mut source: S) -> CliTypedResult<()> {
+        let interval = Duration::from_millis(self.poll_interval_ms);
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+                items = source.poll() => {
+                    for item in items? {
+                        println!(
+                            "{}",
+                            serde_json::to_string(&item)
+                                .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+                        );
+                    }
+                }
+            }
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+    }
+}
+
+/// Follows the results of a command's query from the source it opens
+///
+/// Failures are output like those of any other command, while once interrupted there's
+/// nothing more to output.
+pub async fn execute_follow<S: FollowSource>(
+    command_name: &str,
+    options: &FollowOptions,
+    source: impl Future<Output = CliTypedResult<S>> + Send,
+) -> CliResult {
+    start_logger();
+    let start_time = Instant::now();
+    let result = match source.await {
+        Ok(source) => options.follow(source).await,
+        Err(err) => Err(err),
+    };
+    to_common_result(command_name, start_time, result)
+        .await
+        .map(|_| String::new())
+}
@@ -0,0 +1,227 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! HTTP settings for the REST and faucet clients, for use behind proxies and TLS intercepting
+//! gateways, and for retrying requests to rate limited or flaky endpoints
+//!
+//! Settings can be in a profile or in the global config, and a profile's settings take
+//! precedence over the global ones.
+
+use crate::common::types::{CliConfig, CliError, CliTypedResult, ConfigSearchMode, ProfileOptions};
+use crate::common::utils::read_from_file;
+use crate::config::GlobalConfig;
+use aptos_rest_client::RetryPolicy;
+use reqwest::{Certificate, ClientBuilder, Proxy, Url};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const PEM_CERTIFICATE_END: &str = "-----END CERTIFICATE-----";
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+pub const DEFAULT_RETRY_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HttpConfig {
+    /// Proxy for all requests, e.g. `http://proxy.example.com:3128`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Comma separated hosts, domains and IP ranges to reach without the proxy,
+    /// e.g. `localhost,.corp.example.com,10.0.0.0/8`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+    /// PEM file of root certificates to trust in addition to the system ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+    /// Times to retry REST requests which can safely be repeated, after transient failures
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    /// Seconds to keep retrying a REST request for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_timeout_secs: Option<u64>,
+}
+
+impl HttpConfig {
+    /// Settings for the profile, falling back to the global config for any it doesn't set
+    pub fn for_profile(profile: &ProfileOptions) -> CliTypedResult<HttpConfig> {
+        let mode = ConfigSearchMode::CurrentDirAndParents;
+        let profile_config = if CliConfig::config_exists(mode) {
+            CliConfig::load_profile(profile.profile_name(), mode)?
+        } else {
+            None
+        };
+        Self::resolve(profile_config.and_then(|profile| profile.http).as_ref())
+    }
+
+    /// Layers the profile's settings, if any, on top of the global config's
+    pub fn resolve(profile_http: Option<&HttpConfig>) -> CliTypedResult<HttpConfig> {
+        let global_http = GlobalConfig::load()?.http.unwrap_or_default();
+        Ok(match profile_http {
+            Some(profile_http) => profile_http.clone().or(global_http),
+            None => global_http,
+        })
+    }
+
+    /// Fills in any settings not set here from `fallback`
+    pub fn or(self, fallback: HttpConfig) -> HttpConfig {
+        HttpConfig {
+            proxy: self.proxy.or(fallback.proxy),
+            no_proxy: self.no_proxy.or(fallback.no_proxy),
+            ca_bundle: self.ca_bundle.or(fallback.ca_bundle),
+            max_retries: self.max_retries.or(fallback.max_retries),
+            retry_timeout_secs: self.retry_timeout_secs.or(fallback.retry_timeout_secs),
+        }
+    }
+
+    /// How the REST client retries GET requests
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_retries: self.max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+            timeout: Duration::from_secs(
+                self.retry_timeout_secs
+                    .unwrap_or(DEFAULT_RETRY_TIMEOUT_SECS),
+            ),
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Applies the proxy and root certificates to a client builder
+    pub fn apply(&self, mut builder: ClientBuilder) -> CliTypedResult<ClientBuilder> {
+        if let Some(ref proxy_url) = self.proxy {
+            let proxy_url = Url::parse(proxy_url)
+                .map_err(|err| CliError::UnableToParse("proxy", err.to_string()))?;
+            let no_proxy: Vec<String> = self
+                .no_proxy
+                .iter()
+                .flat_map(|no_proxy| no_proxy.split(','))
+                .map(|entry| entry.trim().to_lowercase())
+                .filter(|entry| !entry.is_empty())
+                .collect();
+            builder = builder.proxy(Proxy::custom(move |url| {
+                if bypasses_proxy(&no_proxy, url) {
+                    None
+                } else {
+                    Some(proxy_url.clone())
+                }
+            }));
+        }
+        if let Some(ref ca_bundle) = self.ca_bundle {
+            for certificate in load_ca_bundle(ca_bundle)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        Ok(builder)
+    }
+
+    /// Builds a client with these settings, e.g. for the faucet
+    pub fn client(&self) -> CliTypedResult<reqwest::Client> {
+        self.apply(reqwest::Client::builder())?
+            .build()
+            .map_err(|err| CliError::UnexpectedError(format!("Failed to build client: {}", err)))
+    }
+
+    /// Builds a REST client for `url` with these settings
+    pub fn rest_client(
+        &self,
+        url: Url,
+        timeout: Duration,
+    ) -> CliTypedResult<aptos_rest_client::Client> {
+        Ok(aptos_rest_client::Client::new_with_builder(
+            url,
+            self.apply(reqwest::Client::builder().timeout(timeout))?,
+        )
+        .with_retry_policy(self.retry_policy()))
+    }
+}
+
+/// Whether the URL's host matches a `no_proxy` entry, which is `*`, a host, a domain and its
+/// subdomains (with or without a leading `.`), an IP or a CIDR range
+fn bypasses_proxy(no_proxy: &[String], url: &Url) -> bool {
+    let host = match url.host_str() {
+        Some(host) => host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_lowercase(),
+        None => return false,
+    };
+    let ip = host.parse::<IpAddr>().ok();
+
+    no_proxy.iter().any(|entry| {
+        if entry == "*" {
+            return true;
+        }
+        if let (Some(ip), Some((network, prefix_len))) = (ip, entry.split_once('/')) {
+            return match (network.parse::<IpAddr>(), prefix_len.parse::<u32>()) {
+                (Ok(network), Ok(prefix_len)) => in_cidr(ip, network, prefix_len),
+                _ => false,
+            };
+        }
+        let domain = entry.trim_start_matches('.');
+        host == domain || host.ends_with(&format!(".{}", domain))
+    })
+}
+
+fn in_cidr(ip: IpAddr, network: IpAddr, prefix_len: u32) -> bool {
+    let (ip, network, bits) = match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            (u32::from(ip) as u128, u32::from(network) as u128, 32)
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => (u128::from(ip), u128::from(network), 128),
+        _ => return false,
+    };
+    if prefix_len > bits {
+        return false;
+    }
+    let mask = if prefix_len == 0 {
+        0
+    } else {
+        u128::MAX << (128 - prefix_len) >> (128 - bits)
+    };
+    ip & mask == network & mask
+}
+
+/// Reads every certificate in a PEM bundle, as reqwest only reads the first of a PEM
+fn load_ca_bundle(path: &Path) -> CliTypedResult<Vec<Certificate>> {
+    let bundle = String::from_utf8(read_from_file(path)?)
+        .map_err(|err| CliError::UnableToParse("ca_bundle", err.to_string()))?;
+
+    let certificates = bundle
+        .split_inclusive(PEM_CERTIFICATE_END)
+        .filter(|pem| pem.contains(PEM_CERTIFICATE_END))
+        .map(|pem| {
+            Certificate::from_pem(pem.trim().as_bytes()).map_err(|err| {
+                CliError::UnableToParse("ca_bundle", format!("{}: {}", path.display(), err))
+            })
+        })
+        .collect::<CliTypedResult<Vec<_>>>()?;
+    if certificates.is_empty() {
+        return Err(CliError::UnableToParse(
+            "ca_bundle",
+            format!("{} has no PEM certificates", path.display()),
+        ));
+    }
+    Ok(certificates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bypasses_proxy() {
+        let no_proxy: Vec<String> = ["localhost", ".corp.example.com", "10.0.0.0/8", "::1"]
+            .iter()
+            .map(|entry| entry.to_string())
+            .collect();
+        let bypasses = |url: &str| bypasses_proxy(&no_proxy, &Url::parse(url).unwrap());
+
+        assert!(bypasses("http://localhost:8080"));
+        assert!(bypasses("https://node.corp.example.com/v1"));
+        assert!(bypasses("https://corp.example.com"));
+        assert!(bypasses("http://10.1.2.3:8080"));
+        assert!(bypasses("http://[::1]:8080"));
+        assert!(!bypasses("https://fullnode.devnet.aptoslabs.com"));
+        assert!(!bypasses("https://notcorp.example.com"));
+        assert!(!bypasses("http://11.1.2.3"));
+    }
+}
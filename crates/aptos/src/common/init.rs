@@ -1,6 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::common::http::HttpConfig;
+use crate::common::ledger::{Ledger, DEFAULT_DERIVATION_PATH};
+use crate::common::mnemonic::{derive_private_key, parse_mnemonic};
 use crate::common::types::{ConfigSearchMode, DEFAULT_PROFILE};
 use crate::common::{
     types::{
@@ -8,20 +11,25 @@ use crate::common::{
         EncodingOptions, PrivateKeyInputOptions, ProfileConfig, ProfileOptions, PromptOptions,
         RngArgs,
     },
-    utils::{fund_account, prompt_yes_with_override, read_line},
+    utils::{chain_id, fund_account, prompt_yes_with_override, read_line},
 };
+use crate::config::network::NetworkPreset;
 use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, ValidCryptoMaterialStringExt};
 use aptos_rest_client::aptos_api_types::{AptosError, AptosErrorCode};
 use aptos_rest_client::error::{AptosErrorResponse, RestError};
 use async_trait::async_trait;
 use clap::Parser;
+use move_core_types::account_address::AccountAddress;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::str::FromStr;
+use std::time::Duration;
 
 /// 1 APT (might not actually get that much, depending on the faucet)
 const NUM_DEFAULT_OCTAS: u64 = 100000000;
+/// Same as the REST client's default timeout
+const REST_TIMEOUT_SECS: u64 = 10;
 
 /// Tool to initialize current directory for the aptos tool
 ///
@@ -30,7 +38,8 @@ const NUM_DEFAULT_OCTAS: u64 = 100000000;
 pub struct InitTool {
     /// Network to use for default settings
     ///
-    /// If custom `rest_url` and `faucet_url` are wanted, use `custom`
+    /// The REST and faucet URLs of [devnet, testnet, mainnet, local] are set from built-in
+    /// presets.  If custom `rest_url` and `faucet_url` are wanted, use `custom`
     #[clap(long)]
     pub network: Option<Network>,
 
@@ -46,6 +55,26 @@ pub struct InitTool {
     #[clap(long)]
     pub skip_faucet: bool,
 
+    /// Store the private key encrypted with a passphrase instead of in plaintext
+    ///
+    /// The passphrase is read from `--key-passphrase-file` or prompted for
+    #[clap(long, group = "key_storage")]
+    pub encrypt_private_key: bool,
+
+    /// Sign with a Ledger instead of a private key, using the key at this derivation path
+    ///
+    /// Defaults to the path of the first account, m/44'/637'/0'/0'/0', if no path is given
+    #[clap(long, group = "key_storage", value_name = "DERIVATION_PATH", min_values = 0, default_missing_value = DEFAULT_DERIVATION_PATH)]
+    pub ledger: Option<String>,
+
+    /// Recover the private key from a BIP-39 mnemonic phrase, e.g. one exported from Petra,
+    /// using the key at this derivation path
+    ///
+    /// The phrase is prompted for.  Defaults to the path of the first account,
+    /// m/44'/637'/0'/0'/0', if no path is given
+    #[clap(long, conflicts_with = "ledger", value_name = "DERIVATION_PATH", min_values = 0, default_missing_value = DEFAULT_DERIVATION_PATH)]
+    pub from_mnemonic: Option<String>,
+
     #[clap(flatten)]
     pub rng_args: RngArgs,
     #[clap(flatten)]
@@ -104,65 +133,35 @@ impl CliCommand<()> for InitTool {
             }
         };
 
-        match network {
-            Network::Mainnet => {
-                profile_config.rest_url =
-                    Some("https://fullnode.mainnet.aptoslabs.com".to_string());
-                profile_config.faucet_url = None;
-            }
-            Network::Testnet => {
-                profile_config.rest_url =
-                    Some("https://fullnode.testnet.aptoslabs.com".to_string());
-                profile_config.faucet_url = None;
-            }
-            Network::Devnet => {
-                profile_config.rest_url = Some("https://fullnode.devnet.aptoslabs.com".to_string());
-                profile_config.faucet_url = Some("https://faucet.devnet.aptoslabs.com".to_string());
-            }
-            Network::Local => {
-                profile_config.rest_url = Some("http://localhost:8080".to_string());
-                profile_config.faucet_url = Some("http://localhost:8081".to_string());
-            }
-            Network::Custom => self.custom_network(&mut profile_config)?,
+        let preset = NetworkPreset::for_network(network);
+        if let Some(preset) = preset {
+            preset.apply(&mut profile_config);
+        } else {
+            profile_config.network = Some(Network::Custom);
+            self.custom_network(&mut profile_config)?;
         }
 
-        // Private key
-        let private_key = if let Some(private_key) = self
-            .private_key_options
-            .extract_private_key_cli(self.encoding_options.encoding)?
-        {
-            eprintln!("Using command line argument for private key");
-            private_key
+        // Private key, or the Ledger's public key
+        let address = if let Some(ref derivation_path) = self.ledger {
+            self.configure_ledger(derivation_path, &mut profile_config)?
         } else {
-            eprintln!("Enter your private key as a hex literal (0x...) [Current: {} | No input: Generate new key (or keep one if present)]", profile_config.private_key.as_ref().map(|_| "Redacted").unwrap_or("None"));
-            let input = read_line("Private key")?;
-            let input = input.trim();
-            if input.is_empty() {
-                if let Some(private_key) = profile_config.private_key {
-                    eprintln!("No key given, keeping existing key...");
-                    private_key
-                } else {
-                    eprintln!("No key given, generating key...");
-                    self.rng_args
-                        .key_generator()?
-                        .generate_ed25519_private_key()
-                }
-            } else {
-                Ed25519PrivateKey::from_encoded_string(input)
-                    .map_err(|err| CliError::UnableToParse("Ed25519PrivateKey", err.to_string()))?
-            }
+            self.configure_private_key(&mut profile_config)?
         };
-        let public_key = private_key.public_key();
-        let address = account_address_from_public_key(&public_key);
-        profile_config.private_key = Some(private_key);
-        profile_config.public_key = Some(public_key);
-        profile_config.account = Some(address);
 
         // Create account if it doesn't exist (and there's a faucet)
-        let client = aptos_rest_client::Client::new(
+        let http_config = HttpConfig::resolve(profile_config.http.as_ref())?;
+        let client = http_config.rest_client(
             Url::parse(profile_config.rest_url.as_ref().unwrap())
                 .map_err(|err| CliError::UnableToParse("rest_url", err.to_string()))?,
-        );
+            Duration::from_secs(REST_TIMEOUT_SECS),
+        )?;
+
+        // Catch endpoints that point at a different network than the one chosen
+        if let Some(preset) = preset {
+            if preset.chain_id.is_some() {
+                preset.check_chain_id(chain_id(&client).await?.id())?;
+            }
+        }
 
         // Check if account exists
         let account_exists = match client.get_account(address).await {
@@ -207,6 +206,7 @@ impl CliCommand<()> for InitTool {
                         .map_err(|err| CliError::UnableToParse("rest_url", err.to_string()))?,
                     NUM_DEFAULT_OCTAS,
                     address,
+                    &http_config,
                 )
                 .await
                 {
@@ -240,6 +240,83 @@ impl CliCommand<()> for InitTool {
 }
 
 impl InitTool {
+    /// Configures the profile to sign with a private key, returning its account address
+    fn configure_private_key(
+        &self,
+        profile_config: &mut ProfileConfig,
+    ) -> CliTypedResult<AccountAddress> {
+        let private_key = if let Some(ref derivation_path) = self.from_mnemonic {
+            eprintln!("Enter your mnemonic phrase");
+            let mnemonic = parse_mnemonic(&read_line("Mnemonic")?)?;
+            derive_private_key(&mnemonic, derivation_path)?
+        } else if let Some(private_key) = self
+            .private_key_options
+            .extract_private_key_cli(self.encoding_options.encoding)?
+        {
+            eprintln!("Using command line argument for private key");
+            private_key
+        } else {
+            eprintln!("Enter your private key as a hex literal (0x...) [Current: {} | No input: Generate new key (or keep one if present)]", if profile_config.has_private_key() { "Redacted" } else { "None" });
+            let input = read_line("Private key")?;
+            let input = input.trim();
+            if input.is_empty() {
+                if let Some(private_key) = profile_config
+                    .resolve_private_key(&self.private_key_options.key_passphrase_options)?
+                {
+                    eprintln!("No key given, keeping existing key...");
+                    private_key
+                } else {
+                    eprintln!("No key given, generating key...");
+                    self.rng_args
+                        .key_generator()?
+                        .generate_ed25519_private_key()
+                }
+            } else {
+                Ed25519PrivateKey::from_encoded_string(input)
+                    .map_err(|err| CliError::UnableToParse("Ed25519PrivateKey", err.to_string()))?
+            }
+        };
+        let public_key = private_key.public_key();
+        let address = account_address_from_public_key(&public_key);
+        profile_config.private_key = Some(private_key);
+        profile_config.encrypted_private_key = None;
+        profile_config.keychain_entry = None;
+        profile_config.ledger_derivation_path = None;
+        profile_config.public_key = Some(public_key);
+        profile_config.account = Some(address);
+
+        if self.encrypt_private_key {
+            let passphrase = self
+                .private_key_options
+                .key_passphrase_options
+                .passphrase(true)?;
+            profile_config.encrypt_private_key(&passphrase)?;
+            eprintln!("Private key will be stored encrypted");
+        }
+        Ok(address)
+    }
+
+    /// Configures the profile to sign with a Ledger, returning its account address
+    fn configure_ledger(
+        &self,
+        derivation_path: &str,
+        profile_config: &mut ProfileConfig,
+    ) -> CliTypedResult<AccountAddress> {
+        eprintln!(
+            "Reading the public key at {} from the Ledger",
+            derivation_path
+        );
+        let public_key = Ledger::open()?.public_key(derivation_path)?;
+        let address = account_address_from_public_key(&public_key);
+        profile_config.ledger_derivation_path = Some(derivation_path.to_string());
+        profile_config.private_key = None;
+        profile_config.encrypted_private_key = None;
+        profile_config.keychain_entry = None;
+        profile_config.public_key = Some(public_key);
+        profile_config.account = Some(address);
+        Ok(address)
+    }
+
     fn custom_network(&self, profile_config: &mut ProfileConfig) -> CliTypedResult<()> {
         // Rest Endpoint
         let rest_url = if let Some(ref rest_url) = self.rest_url {
@@ -0,0 +1,137 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliError, CliTypedResult};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterial};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::num::NonZeroU32;
+
+/// Key derivation function used to turn a passphrase into an encryption key
+pub const PBKDF2_SHA256: &str = "pbkdf2-sha256";
+/// Cipher used to encrypt the private key
+pub const AES_256_GCM_CIPHER: &str = "aes-256-gcm";
+
+const DEFAULT_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// An Ed25519 private key encrypted with a passphrase
+///
+/// The passphrase is stretched with PBKDF2-HMAC-SHA256 and the key is encrypted
+/// with AES-256-GCM.  All binary fields are hex encoded.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EncryptedPrivateKey {
+    pub kdf: String,
+    pub iterations: u32,
+    pub salt: String,
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+impl EncryptedPrivateKey {
+    /// Encrypts a private key with the passphrase, using a random salt and nonce
+    pub fn encrypt(private_key: &Ed25519PrivateKey, passphrase: &str) -> CliTypedResult<Self> {
+        let rng = SystemRandom::new();
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill(&mut salt)
+            .and_then(|_| rng.fill(&mut nonce))
+            .map_err(|_| {
+                CliError::UnexpectedError("Failed to generate random salt and nonce".to_string())
+            })?;
+
+        let key = cipher_key(passphrase, &salt, DEFAULT_ITERATIONS)?;
+        let mut in_out = private_key.to_bytes();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut in_out,
+        )
+        .map_err(|_| CliError::UnexpectedError("Failed to encrypt private key".to_string()))?;
+
+        Ok(EncryptedPrivateKey {
+            kdf: PBKDF2_SHA256.to_string(),
+            iterations: DEFAULT_ITERATIONS,
+            salt: hex::encode(salt),
+            cipher: AES_256_GCM_CIPHER.to_string(),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(in_out),
+        })
+    }
+
+    /// Decrypts the private key, failing if the passphrase is wrong
+    pub fn decrypt(&self, passphrase: &str) -> CliTypedResult<Ed25519PrivateKey> {
+        if self.kdf != PBKDF2_SHA256 || self.cipher != AES_256_GCM_CIPHER {
+            return Err(CliError::UnableToParse(
+                "encrypted_private_key",
+                format!(
+                    "Unsupported encryption {} with {}, must be {} with {}",
+                    self.kdf, self.cipher, PBKDF2_SHA256, AES_256_GCM_CIPHER
+                ),
+            ));
+        }
+
+        let salt = hex::decode(&self.salt)?;
+        let nonce = Nonce::try_assume_unique_for_key(&hex::decode(&self.nonce)?).map_err(|_| {
+            CliError::UnableToParse("encrypted_private_key", "Invalid nonce".to_string())
+        })?;
+        let mut in_out = hex::decode(&self.ciphertext)?;
+
+        let key = cipher_key(passphrase, &salt, self.iterations)?;
+        let plaintext = key
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| {
+                CliError::CommandArgumentError(
+                    "Failed to decrypt private key, is the passphrase correct?".to_string(),
+                )
+            })?;
+
+        Ed25519PrivateKey::try_from(&*plaintext)
+            .map_err(|err| CliError::UnableToParse("encrypted_private_key", err.to_string()))
+    }
+}
+
+/// Derives the AES-256-GCM key from the passphrase
+fn cipher_key(passphrase: &str, salt: &[u8], iterations: u32) -> CliTypedResult<LessSafeKey> {
+    let iterations = NonZeroU32::new(iterations).ok_or_else(|| {
+        CliError::UnableToParse(
+            "encrypted_private_key",
+            "Iterations must be greater than 0".to_string(),
+        )
+    })?;
+
+    let mut key_bytes = [0u8; KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key_bytes,
+    );
+    let key = UnboundKey::new(&AES_256_GCM, &key_bytes)
+        .map_err(|_| CliError::UnexpectedError("Failed to create cipher key".to_string()))?;
+    Ok(LessSafeKey::new(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_keygen::KeyGen;
+
+    #[test]
+    fn test_encrypt_decrypt_private_key() {
+        let private_key = KeyGen::from_seed([7; 32]).generate_ed25519_private_key();
+        let encrypted = EncryptedPrivateKey::encrypt(&private_key, "passphrase").unwrap();
+
+        let decrypted = encrypted.decrypt("passphrase").unwrap();
+        assert_eq!(private_key.to_bytes(), decrypted.to_bytes());
+        assert!(encrypted.decrypt("wrong passphrase").is_err());
+    }
+}
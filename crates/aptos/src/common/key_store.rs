@@ -0,0 +1,188 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliError, CliTypedResult};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterialStringExt};
+
+/// Service name used for all entries the CLI stores in the OS keychain
+pub const KEYCHAIN_SERVICE: &str = "aptos-cli";
+
+/// A storage backend for signing keys, referenced by entry name from a profile
+pub trait KeyBackend {
+    /// Name of the backend for error messages
+    fn name(&self) -> &'static str;
+
+    /// Loads the private key stored under `entry`
+    fn load_private_key(&self, entry: &str) -> CliTypedResult<Ed25519PrivateKey>;
+
+    /// Stores the private key under `entry`, replacing any existing key
+    fn store_private_key(&self, entry: &str, private_key: &Ed25519PrivateKey)
+        -> CliTypedResult<()>;
+
+    /// Removes the key stored under `entry`
+    fn delete_private_key(&self, entry: &str) -> CliTypedResult<()>;
+}
+
+/// The platform's credential store
+///
+/// This is the macOS Keychain, the Windows Credential Manager, or the Linux secret-service
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsKeychain;
+
+impl OsKeychain {
+    fn entry(&self, entry: &str) -> CliTypedResult<keyring::Entry> {
+        keyring::Entry::new(KEYCHAIN_SERVICE, entry)
+            .map_err(|err| keychain_error(self.name(), entry, err))
+    }
+}
+
+impl KeyBackend for OsKeychain {
+    fn name(&self) -> &'static str {
+        "OS keychain"
+    }
+
+    fn load_private_key(&self, entry: &str) -> CliTypedResult<Ed25519PrivateKey> {
+        let encoded = self
+            .entry(entry)?
+            .get_password()
+            .map_err(|err| keychain_error(self.name(), entry, err))?;
+        Ed25519PrivateKey::from_encoded_string(encoded.trim())
+            .map_err(|err| CliError::UnableToParse("keychain private key", err.to_string()))
+    }
+
+    fn store_private_key(
+        &self,
+        entry: &str,
+        private_key: &Ed25519PrivateKey,
+    ) -> CliTypedResult<()> {
+        let encoded = private_key.to_encoded_string()?;
+        self.entry(entry)?
+            .set_password(&encoded)
+            .map_err(|err| keychain_error(self.name(), entry, err))
+    }
+
+    fn delete_private_key(&self, entry: &str) -> CliTypedResult<()> {
+        self.entry(entry)?
+            .delete_password()
+            .map_err(|err| keychain_error(self.name(), entry, err))
+    }
+}
+
+fn keychain_error(backend: &str, entry: &str, err: keyring::Error) -> CliError {
+    CliError::UnexpectedError(format!(
+        "Failed to access {} entry '{}': {}",
+        backend, entry, err
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::{KeyPassphraseOptions, ProfileConfig};
+    use crate::common::utils::write_to_file;
+    use aptos_crypto::PrivateKey;
+    use aptos_keygen::KeyGen;
+    use aptos_temppath::TempPath;
+    use std::{cell::RefCell, collections::HashMap};
+
+    /// Keeps keys in memory, in place of the OS keychain
+    #[derive(Default)]
+    struct InMemoryBackend {
+        keys: RefCell<HashMap<String, String>>,
+    }
+
+    impl KeyBackend for InMemoryBackend {
+        fn name(&self) -> &'static str {
+            "in memory"
+        }
+
+        fn load_private_key(&self, entry: &str) -> CliTypedResult<Ed25519PrivateKey> {
+            let encoded = self.keys.borrow().get(entry).cloned().ok_or_else(|| {
+                CliError::UnexpectedError(format!("No entry '{}' in {}", entry, self.name()))
+            })?;
+            Ok(Ed25519PrivateKey::from_encoded_string(&encoded).unwrap())
+        }
+
+        fn store_private_key(
+            &self,
+            entry: &str,
+            private_key: &Ed25519PrivateKey,
+        ) -> CliTypedResult<()> {
+            self.keys
+                .borrow_mut()
+                .insert(entry.to_string(), private_key.to_encoded_string()?);
+            Ok(())
+        }
+
+        fn delete_private_key(&self, entry: &str) -> CliTypedResult<()> {
+            self.keys.borrow_mut().remove(entry);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_store_and_resolve_private_key() {
+        let backend = InMemoryBackend::default();
+        let mut keygen = KeyGen::from_seed([0; 32]);
+        let no_passphrase = KeyPassphraseOptions::default();
+
+        let private_key = keygen.generate_ed25519_private_key();
+        let mut profile = ProfileConfig {
+            private_key: Some(private_key.clone()),
+            ..Default::default()
+        };
+        profile
+            .store_private_key_in(&backend, "plaintext", &no_passphrase)
+            .unwrap();
+        assert!(profile.private_key.is_none());
+        assert_eq!(profile.keychain_entry.as_deref(), Some("plaintext"));
+        assert!(profile.has_private_key());
+        let resolved = profile
+            .resolve_private_key_from(&backend, &no_passphrase)
+            .unwrap()
+            .unwrap();
+        assert_eq!(resolved.public_key(), private_key.public_key());
+
+        // An encrypted key is decrypted before it's stored
+        let passphrase_file = TempPath::new();
+        write_to_file(passphrase_file.path(), "passphrase", b"hunter2\n").unwrap();
+        let passphrase = KeyPassphraseOptions {
+            key_passphrase_file: Some(passphrase_file.path().to_path_buf()),
+        };
+        let private_key = keygen.generate_ed25519_private_key();
+        let mut profile = ProfileConfig {
+            private_key: Some(private_key.clone()),
+            ..Default::default()
+        };
+        profile.encrypt_private_key("hunter2").unwrap();
+        profile
+            .store_private_key_in(&backend, "encrypted", &passphrase)
+            .unwrap();
+        assert!(profile.encrypted_private_key.is_none());
+        let resolved = profile
+            .resolve_private_key_from(&backend, &no_passphrase)
+            .unwrap()
+            .unwrap();
+        assert_eq!(resolved.public_key(), private_key.public_key());
+
+        // A key already in the backend is read from it, to move it to another entry
+        profile
+            .store_private_key_in(&backend, "moved", &no_passphrase)
+            .unwrap();
+        assert_eq!(profile.keychain_entry.as_deref(), Some("moved"));
+        let resolved = profile
+            .resolve_private_key_from(&backend, &no_passphrase)
+            .unwrap()
+            .unwrap();
+        assert_eq!(resolved.public_key(), private_key.public_key());
+        backend.delete_private_key("encrypted").unwrap();
+
+        // A profile without a key has nothing to store
+        let mut profile = ProfileConfig::default();
+        assert!(profile
+            .store_private_key_in(&backend, "empty", &no_passphrase)
+            .is_err());
+        backend.delete_private_key("plaintext").unwrap();
+        assert_eq!(backend.keys.borrow().len(), 1);
+    }
+}
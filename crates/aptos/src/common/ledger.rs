@@ -0,0 +1,248 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing with the Aptos app on a Ledger hardware wallet
+//!
+//! The device is reached over USB HID, with APDUs framed into 64 byte HID reports as the
+//! Ledger transport protocol requires.  Keys never leave the device, only public keys and
+//! signatures are read back.
+
+use crate::common::types::{CliError, CliTypedResult};
+use aptos_crypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+use aptos_types::transaction::{RawTransaction, SignedTransaction};
+use hidapi::{HidApi, HidDevice};
+use std::convert::TryFrom;
+
+/// Derivation path of the first Aptos account on a Ledger
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/637'/0'/0'/0'";
+
+const LEDGER_VENDOR_ID: u16 = 0x2c97;
+/// HID usage page of the Ledger APDU interface
+const LEDGER_USAGE_PAGE: u16 = 0xffa0;
+
+const HID_PACKET_SIZE: usize = 64;
+const HID_CHANNEL: u16 = 0x0101;
+const HID_TAG_APDU: u8 = 0x05;
+const HID_TIMEOUT_MS: i32 = -1;
+
+/// APDU class and instructions of the Aptos Ledger app
+const CLA_APTOS: u8 = 0x5B;
+const INS_GET_PUB_KEY: u8 = 0x05;
+const INS_SIGN_TX: u8 = 0x06;
+const P1_NON_CONFIRM: u8 = 0x00;
+const P1_START: u8 = 0x00;
+const P2_MORE: u8 = 0x80;
+const P2_LAST: u8 = 0x00;
+const MAX_APDU_DATA_LEN: usize = 255;
+const APDU_CODE_SUCCESS: u16 = 0x9000;
+
+pub const HARDENED: u32 = 0x8000_0000;
+
+/// A connected Ledger device
+pub struct Ledger {
+    device: HidDevice,
+}
+
+impl Ledger {
+    /// Opens the first connected Ledger, which must be unlocked with the Aptos app open
+    pub fn open() -> CliTypedResult<Ledger> {
+        let api = HidApi::new().map_err(ledger_error)?;
+        let device_info = api
+            .device_list()
+            .find(|device| {
+                device.vendor_id() == LEDGER_VENDOR_ID
+                    && (device.usage_page() == LEDGER_USAGE_PAGE || device.interface_number() == 0)
+            })
+            .ok_or_else(|| {
+                CliError::UnexpectedError(
+                    "No Ledger found, please connect and unlock it, and open the Aptos app"
+                        .to_string(),
+                )
+            })?;
+        let device = device_info.open_device(&api).map_err(ledger_error)?;
+        Ok(Ledger { device })
+    }
+
+    /// Reads the public key at the derivation path
+    pub fn public_key(&self, derivation_path: &str) -> CliTypedResult<Ed25519PublicKey> {
+        let path = serialize_derivation_path(derivation_path)?;
+        let response = self.exchange(INS_GET_PUB_KEY, P1_NON_CONFIRM, P2_LAST, &path)?;
+
+        // The response is the key length, including a 1 byte prefix, the prefix, then the key
+        let key_len = (*response.first().unwrap_or(&0) as usize).saturating_sub(1);
+        let key = response
+            .get(2..2 + key_len)
+            .ok_or_else(|| ledger_error("Malformed public key response"))?;
+        Ed25519PublicKey::try_from(key).map_err(ledger_error)
+    }
+
+    /// Signs a transaction with the key at the derivation path, after the user approves it
+    /// on the device
+    pub fn sign_transaction(
+        &self,
+        derivation_path: &str,
+        public_key: Ed25519PublicKey,
+        raw_transaction: RawTransaction,
+    ) -> CliTypedResult<SignedTransaction> {
+        let message = raw_transaction.signing_message().map_err(ledger_error)?;
+
+        // The derivation path goes first, then the message in as many chunks as needed
+        let path = serialize_derivation_path(derivation_path)?;
+        self.exchange(INS_SIGN_TX, P1_START, P2_MORE, &path)?;
+
+        eprintln!("Please review and approve the transaction on your Ledger");
+        let chunks: Vec<_> = message.chunks(MAX_APDU_DATA_LEN).collect();
+        let mut response = Vec::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let p2 = if i + 1 == chunks.len() {
+                P2_LAST
+            } else {
+                P2_MORE
+            };
+            response = self.exchange(INS_SIGN_TX, (i + 1) as u8, p2, chunk)?;
+        }
+
+        let signature_len = *response.first().unwrap_or(&0) as usize;
+        let signature = response
+            .get(1..1 + signature_len)
+            .ok_or_else(|| ledger_error("Malformed signature response"))?;
+        let signature = Ed25519Signature::try_from(signature).map_err(ledger_error)?;
+        Ok(SignedTransaction::new(
+            raw_transaction,
+            public_key,
+            signature,
+        ))
+    }
+
+    /// Sends an APDU and returns the response data, failing on any status but success
+    fn exchange(&self, ins: u8, p1: u8, p2: u8, data: &[u8]) -> CliTypedResult<Vec<u8>> {
+        let mut apdu = vec![CLA_APTOS, ins, p1, p2, data.len() as u8];
+        apdu.extend_from_slice(data);
+        self.write_apdu(&apdu)?;
+
+        let mut response = self.read_apdu()?;
+        if response.len() < 2 {
+            return Err(ledger_error("Response is missing its status code"));
+        }
+        let status_bytes = response.split_off(response.len() - 2);
+        let status = u16::from_be_bytes([status_bytes[0], status_bytes[1]]);
+        if status != APDU_CODE_SUCCESS {
+            return Err(ledger_error(format!(
+                "Ledger returned status {:#06x}, make sure the Aptos app is open and the request was approved",
+                status
+            )));
+        }
+        Ok(response)
+    }
+
+    fn write_apdu(&self, apdu: &[u8]) -> CliTypedResult<()> {
+        // The first packet has the APDU length before the APDU itself
+        let mut payload = (apdu.len() as u16).to_be_bytes().to_vec();
+        payload.extend_from_slice(apdu);
+
+        for (sequence, chunk) in payload.chunks(HID_PACKET_SIZE - 5).enumerate() {
+            // A leading 0 report ID, then the channel, tag and packet sequence number
+            let mut packet = vec![0u8];
+            packet.extend_from_slice(&HID_CHANNEL.to_be_bytes());
+            packet.push(HID_TAG_APDU);
+            packet.extend_from_slice(&(sequence as u16).to_be_bytes());
+            packet.extend_from_slice(chunk);
+            packet.resize(HID_PACKET_SIZE + 1, 0);
+            self.device.write(&packet).map_err(ledger_error)?;
+        }
+        Ok(())
+    }
+
+    fn read_apdu(&self) -> CliTypedResult<Vec<u8>> {
+        let mut response = Vec::new();
+        let mut expected_len = None;
+        let mut sequence: u16 = 0;
+
+        while expected_len.map_or(true, |len| response.len() < len) {
+            let mut packet = [0u8; HID_PACKET_SIZE];
+            let read = self
+                .device
+                .read_timeout(&mut packet, HID_TIMEOUT_MS)
+                .map_err(ledger_error)?;
+            if read < 5
+                || u16::from_be_bytes([packet[0], packet[1]]) != HID_CHANNEL
+                || packet[2] != HID_TAG_APDU
+                || u16::from_be_bytes([packet[3], packet[4]]) != sequence
+            {
+                return Err(ledger_error("Unexpected HID packet from Ledger"));
+            }
+
+            let data = if sequence == 0 {
+                expected_len = Some(u16::from_be_bytes([packet[5], packet[6]]) as usize);
+                &packet[7..read]
+            } else {
+                &packet[5..read]
+            };
+            response.extend_from_slice(data);
+            sequence += 1;
+        }
+
+        response.truncate(expected_len.unwrap_or_default());
+        Ok(response)
+    }
+}
+
+/// Serializes a BIP32 path like `m/44'/637'/0'/0'/0'` as its number of elements, followed by
+/// each element as a big endian u32
+pub fn serialize_derivation_path(derivation_path: &str) -> CliTypedResult<Vec<u8>> {
+    let indices = parse_derivation_path(derivation_path)?;
+    let mut serialized = vec![indices.len() as u8];
+    for index in indices {
+        serialized.extend_from_slice(&index.to_be_bytes());
+    }
+    Ok(serialized)
+}
+
+/// Parses a BIP32 path like `m/44'/637'/0'/0'/0'` into its child indices, with the hardened
+/// bit set for hardened children
+pub fn parse_derivation_path(derivation_path: &str) -> CliTypedResult<Vec<u32>> {
+    let invalid = || {
+        CliError::CommandArgumentError(format!(
+            "Invalid derivation path {}, expected a path like {}",
+            derivation_path, DEFAULT_DERIVATION_PATH
+        ))
+    };
+
+    derivation_path
+        .strip_prefix("m/")
+        .ok_or_else(invalid)?
+        .split('/')
+        .map(|element| {
+            let (index, hardened) = match element.strip_suffix('\'') {
+                Some(index) => (index, HARDENED),
+                None => (element, 0),
+            };
+            let index: u32 = index.parse().map_err(|_| invalid())?;
+            if index >= HARDENED {
+                return Err(invalid());
+            }
+            Ok(index | hardened)
+        })
+        .collect()
+}
+
+fn ledger_error<E: std::fmt::Display>(err: E) -> CliError {
+    CliError::UnexpectedError(format!("Ledger error: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_derivation_path() {
+        assert_eq!(
+            vec![
+                5, 0x80, 0, 0, 44, 0x80, 0, 0x02, 0x7d, 0x80, 0, 0, 0, 0x80, 0, 0, 0, 0x80, 0, 0, 0
+            ],
+            serialize_derivation_path(DEFAULT_DERIVATION_PATH).unwrap()
+        );
+        assert!(serialize_derivation_path("44'/637'").is_err());
+        assert!(serialize_derivation_path("m/44'/x").is_err());
+    }
+}
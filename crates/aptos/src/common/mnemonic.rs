@@ -0,0 +1,115 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! BIP-39 mnemonic phrases, and the SLIP-0010 derivation of ed25519 keys from them used by
+//! Aptos wallets such as Petra
+
+use crate::common::ledger::{parse_derivation_path, HARDENED};
+use crate::common::types::{CliError, CliTypedResult};
+use aptos_crypto::ed25519::Ed25519PrivateKey;
+use aptos_keygen::KeyGen;
+use bip39::{Language, Mnemonic, Seed};
+use ring::hmac;
+use std::convert::TryFrom;
+
+/// File extension of a saved mnemonic phrase
+pub const MNEMONIC_EXTENSION: &str = "mnemonic";
+
+/// HMAC key of the master key of an ed25519 SLIP-0010 tree
+const ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
+
+/// Generates an English mnemonic phrase of 12 or 24 words
+pub fn generate_mnemonic(keygen: &mut KeyGen, words: usize) -> CliTypedResult<Mnemonic> {
+    // Each word is 11 bits, of which the entropy is 32 bits for every 3 words
+    let entropy_len = match words {
+        12 => 16,
+        24 => 32,
+        _ => {
+            return Err(CliError::CommandArgumentError(format!(
+                "A mnemonic phrase has 12 or 24 words, not {}",
+                words
+            )))
+        }
+    };
+    Mnemonic::from_entropy(&keygen.generate_bytes(entropy_len), Language::English)
+        .map_err(|err| CliError::UnexpectedError(format!("Failed to generate mnemonic: {}", err)))
+}
+
+/// Parses an English mnemonic phrase, checking its checksum
+pub fn parse_mnemonic(phrase: &str) -> CliTypedResult<Mnemonic> {
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    Mnemonic::from_phrase(&phrase, Language::English)
+        .map_err(|err| CliError::UnableToParse("mnemonic", err.to_string()))
+}
+
+/// Derives the ed25519 private key at the derivation path, e.g. `m/44'/637'/0'/0'/0'`
+///
+/// Ed25519 only has hardened children, so every element of the path must be hardened.
+pub fn derive_private_key(
+    mnemonic: &Mnemonic,
+    derivation_path: &str,
+) -> CliTypedResult<Ed25519PrivateKey> {
+    let indices = parse_derivation_path(derivation_path)?;
+    if indices.iter().any(|index| index & HARDENED == 0) {
+        return Err(CliError::CommandArgumentError(format!(
+            "Every element of derivation path {} must be hardened, e.g. 0'",
+            derivation_path
+        )));
+    }
+
+    let seed = Seed::new(mnemonic, "");
+    let (mut key, mut chain_code) = split_hmac(ED25519_SEED_KEY, seed.as_bytes());
+    for index in indices {
+        let mut data = Vec::with_capacity(37);
+        data.push(0);
+        data.extend_from_slice(&key);
+        data.extend_from_slice(&index.to_be_bytes());
+        (key, chain_code) = split_hmac(&chain_code, &data);
+    }
+
+    Ed25519PrivateKey::try_from(key.as_slice())
+        .map_err(|err| CliError::UnexpectedError(format!("Invalid derived key: {}", err)))
+}
+
+/// HMAC-SHA512 split into the key and chain code halves
+fn split_hmac(key: &[u8], data: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let tag = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA512, key), data);
+    let (key, chain_code) = tag.as_ref().split_at(32);
+    (key.to_vec(), chain_code.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::ledger::DEFAULT_DERIVATION_PATH;
+    use aptos_crypto::ValidCryptoMaterial;
+
+    #[test]
+    fn test_derive_private_key() {
+        let mnemonic = parse_mnemonic(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+            abandon about",
+        )
+        .unwrap();
+        assert_eq!(
+            "cc92c0eaf80206d817f150e21917f797e49cf644a33ac514de3c316baa2f1bf5",
+            hex::encode(
+                derive_private_key(&mnemonic, DEFAULT_DERIVATION_PATH)
+                    .unwrap()
+                    .to_bytes()
+            )
+        );
+        assert!(derive_private_key(&mnemonic, "m/44'/637'/0'/0/0").is_err());
+
+        let mut keygen = KeyGen::from_seed([0; 32]);
+        assert_eq!(
+            24,
+            generate_mnemonic(&mut keygen, 24)
+                .unwrap()
+                .phrase()
+                .split(' ')
+                .count()
+        );
+        assert!(parse_mnemonic("abandon abandon").is_err());
+    }
+}
@@ -1,6 +1,15 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod follow;
+pub mod http;
 pub mod init;
+pub mod key_encryption;
+pub mod key_store;
+pub mod ledger;
+pub mod mnemonic;
+pub mod output;
+pub mod progress;
+pub mod sequencer;
 pub mod types;
 pub mod utils;
@@ -0,0 +1,466 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Formatting of command results
+//!
+//! JSON is the default and stays stable for scripts, while YAML and tables are meant for
+//! humans, and CSV for spreadsheets.  The format is chosen once per invocation with
+//! `--output`, falling back to the `output_format` in the global config.
+//!
+//! JSON and YAML wrap each command's output as `{"Result": ...}`, or on failure as
+//! `{"Error": "<message>", "ErrorType": "<kind of error>"}`.  The wrapper only changes with
+//! `OUTPUT_SCHEMA_VERSION`, which `aptos info` shows.
+//!
+//! Only tables are colored and have hashes shortened, so the other formats stay the same on any
+//! terminal.  Color follows the `color_theme` in the global config, and is off with `--no-color`,
+//! the `NO_COLOR` environment variable, or when stdout isn't a terminal.
+
+use crate::common::types::CliError;
+use clap::ArgEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::Formatter;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Version of the wrapper around command output, bumped on any incompatible change to it
+pub const OUTPUT_SCHEMA_VERSION: u64 = 1;
+
+/// Format selected for this invocation, `None` until set by the top level tool
+static OUTPUT_FORMAT: Mutex<Option<OutputFormat>> = Mutex::new(None);
+
+/// Theme of colored output for this invocation, `None` when output isn't colored
+static COLOR_THEME: Mutex<Option<ColorTheme>> = Mutex::new(None);
+
+/// Whether tables show hashes in full, rather than shortened
+static FULL_HASHES: AtomicBool = AtomicBool::new(false);
+
+/// Hex digits kept at each end of a shortened hash
+const SHORT_HASH_DIGITS: usize = 4;
+
+/// Format of the output of commands
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+    Table,
+    Csv,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Json
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Table => "table",
+            OutputFormat::Csv => "csv",
+        })
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().trim() {
+            "json" => Ok(OutputFormat::Json),
+            "yaml" | "yml" => Ok(OutputFormat::Yaml),
+            "table" => Ok(OutputFormat::Table),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(CliError::CommandArgumentError(
+                "Invalid output format, must be one of [json, yaml, table, csv]".to_string(),
+            )),
+        }
+    }
+}
+
+/// Colors of tables shown on a terminal
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ColorTheme {
+    Default,
+    /// Bold and bright colors, for dark or low contrast terminals
+    Bright,
+    Off,
+}
+
+impl Default for ColorTheme {
+    fn default() -> Self {
+        ColorTheme::Default
+    }
+}
+
+impl std::fmt::Display for ColorTheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ColorTheme::Default => "default",
+            ColorTheme::Bright => "bright",
+            ColorTheme::Off => "off",
+        })
+    }
+}
+
+impl FromStr for ColorTheme {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().trim() {
+            "default" => Ok(ColorTheme::Default),
+            "bright" => Ok(ColorTheme::Bright),
+            "off" | "none" => Ok(ColorTheme::Off),
+            _ => Err(CliError::CommandArgumentError(
+                "Invalid color theme, must be one of [default, bright, off]".to_string(),
+            )),
+        }
+    }
+}
+
+impl ColorTheme {
+    /// ANSI SGR parameters of a style
+    fn sgr(self, style: Style) -> &'static str {
+        match (self, style) {
+            (ColorTheme::Bright, Style::Key) => "1;96",
+            (ColorTheme::Bright, Style::Success) => "1;92",
+            (ColorTheme::Bright, Style::Warning) => "1;93",
+            (ColorTheme::Bright, Style::Error) => "1;91",
+            (_, Style::Key) => "36",
+            (_, Style::Success) => "32",
+            (_, Style::Warning) => "33",
+            (_, Style::Error) => "31",
+        }
+    }
+}
+
+/// What a piece of a table stands for, which decides its color
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Style {
+    Key,
+    Success,
+    Warning,
+    Error,
+}
+
+/// Sets how tables are rendered for all commands run afterwards
+pub fn set_render_options(theme: ColorTheme, no_color: bool, full_hashes: bool) {
+    let no_color_env = std::env::var_os("NO_COLOR").map_or(false, |value| !value.is_empty());
+    let dumb_terminal = std::env::var("TERM").map_or(false, |term| term == "dumb");
+    let colored = !no_color
+        && !no_color_env
+        && !dumb_terminal
+        && theme != ColorTheme::Off
+        && atty::is(atty::Stream::Stdout);
+    *COLOR_THEME.lock().unwrap() = if colored { Some(theme) } else { None };
+    FULL_HASHES.store(full_hashes, Ordering::Relaxed);
+}
+
+/// Sets the output format for all commands run afterwards
+pub fn set_output_format(format: OutputFormat) {
+    *OUTPUT_FORMAT.lock().unwrap() = Some(format);
+}
+
+/// The output format for this invocation
+pub fn output_format() -> OutputFormat {
+    OUTPUT_FORMAT.lock().unwrap().unwrap_or_default()
+}
+
+/// Formats a command result in the selected output format
+pub fn format_output<T: Serialize>(output: &T) -> String {
+    match output_format() {
+        OutputFormat::Json => serde_json::to_string_pretty(output).unwrap(),
+        OutputFormat::Yaml => serde_yaml::to_string(output).unwrap(),
+        OutputFormat::Table => format_table(&serde_json::to_value(output).unwrap()),
+        OutputFormat::Csv => format_csv(&serde_json::to_value(output).unwrap()),
+    }
+}
+
+/// Renders a value as a table
+///
+/// A list of objects has a row per object and a column per field, any other object is shown
+/// as a block of aligned fields and values.  Nested values are shown as compact JSON in a
+/// single cell.
+fn format_table(value: &Value) -> String {
+    let value = match unwrap_result(value) {
+        Ok(value) => value,
+        Err(error) => return paint(&format!("Error: {}", error), Style::Error),
+    };
+    match value {
+        Value::Object(map) => render_fields(
+            map.iter()
+                .map(|(key, value)| (key.as_str(), cell(value)))
+                .collect(),
+        ),
+        value => match tabulate(value) {
+            Ok((headers, rows)) => render_table(headers, rows),
+            Err(text) => text,
+        },
+    }
+}
+
+/// Renders a value as CSV, with the same rows and columns as a table
+fn format_csv(value: &Value) -> String {
+    match tabulate(value) {
+        Ok((headers, rows)) => std::iter::once(headers)
+            .chain(rows)
+            .map(|row| {
+                row.iter()
+                    .map(|field| csv_field(field))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Err(text) => text,
+    }
+}
+
+/// The headers and rows of a value, or the text for a value that isn't tabular
+fn tabulate(value: &Value) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let value = unwrap_result(value).map_err(|error| format!("Error: {}", error))?;
+
+    match value {
+        Value::Array(items) if items.iter().all(Value::is_object) && !items.is_empty() => {
+            let mut headers: Vec<String> = Vec::new();
+            for item in items {
+                for key in item.as_object().unwrap().keys() {
+                    if !headers.contains(key) {
+                        headers.push(key.clone());
+                    }
+                }
+            }
+            let rows = items
+                .iter()
+                .map(|item| {
+                    headers
+                        .iter()
+                        .map(|header| item.get(header).map(cell).unwrap_or_default())
+                        .collect()
+                })
+                .collect();
+            Ok((headers, rows))
+        }
+        Value::Array(items) => Ok((
+            vec!["value".to_string()],
+            items.iter().map(|item| vec![cell(item)]).collect(),
+        )),
+        Value::Object(map) => Ok((
+            vec!["field".to_string(), "value".to_string()],
+            map.iter()
+                .map(|(key, value)| vec![key.clone(), cell(value)])
+                .collect(),
+        )),
+        value => Err(cell(value)),
+    }
+}
+
+/// The value inside the `Result` wrapper, or the message of the `Error` wrapper, as the
+/// wrappers only add noise to a table
+fn unwrap_result(value: &Value) -> Result<&Value, &str> {
+    match value {
+        Value::Object(map) => match (map.get("Result"), map.get("Error")) {
+            (Some(inner), None) if map.len() == 1 => Ok(inner),
+            (None, Some(Value::String(error))) => Err(error),
+            _ => Ok(value),
+        },
+        _ => Ok(value),
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Quotes a CSV field if it has a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains(|c| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn render_table(headers: Vec<String>, rows: Vec<Vec<String>>) -> String {
+    let rows: Vec<Vec<String>> = rows
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .zip(&headers)
+                .map(|(cell, header)| shorten_hash(header, cell))
+                .collect()
+        })
+        .collect();
+
+    // Widths are of the text without color, so colored tables are still aligned
+    let mut widths: Vec<usize> = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let render_row = |row: &[String], style: &dyn Fn(usize, &str) -> Option<Style>| {
+        let last = row.len().saturating_sub(1);
+        row.iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, width))| {
+                let padding = if i < last {
+                    " ".repeat(width - cell.chars().count())
+                } else {
+                    String::new()
+                };
+                let cell = match style(i, cell) {
+                    Some(style) => paint(cell, style),
+                    None => cell.clone(),
+                };
+                format!("{}{}", cell, padding)
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![
+        render_row(&headers, &|_, _| Some(Style::Key)),
+        render_row(
+            &widths
+                .iter()
+                .map(|width| "-".repeat(*width))
+                .collect::<Vec<_>>(),
+            &|_, _| None,
+        ),
+    ];
+    lines.extend(
+        rows.iter()
+            .map(|row| render_row(row, &|i, cell| value_style(&headers[i], cell))),
+    );
+    lines.join("\n")
+}
+
+/// Renders fields and their values as aligned lines
+fn render_fields(fields: Vec<(&str, String)>) -> String {
+    let width = fields
+        .iter()
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or_default();
+    fields
+        .into_iter()
+        .map(|(key, value)| {
+            let value = shorten_hash(key, value);
+            if value.is_empty() {
+                return paint(key, Style::Key);
+            }
+            let padding = " ".repeat(width - key.chars().count());
+            let value = match value_style(key, &value) {
+                Some(style) => paint(&value, style),
+                None => value,
+            };
+            format!("{}{}  {}", paint(key, Style::Key), padding, value)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Style of a value which shows whether something succeeded
+fn value_style(key: &str, value: &str) -> Option<Style> {
+    match (key, value) {
+        ("success", "true") => Some(Style::Success),
+        ("success", "false") => Some(Style::Error),
+        ("vm_status", "Executed successfully") => Some(Style::Success),
+        ("vm_status", _) => Some(Style::Warning),
+        ("error" | "network_error", value) if !value.is_empty() => Some(Style::Error),
+        _ => None,
+    }
+}
+
+/// Shortens a hash in a field named like one to its first and last digits, e.g. `0x1a2b…9f8e`,
+/// unless `--full-hashes` is set
+fn shorten_hash(key: &str, value: String) -> String {
+    let is_hash = key.contains("hash")
+        && value.len() == 66
+        && value.starts_with("0x")
+        && value[2..].chars().all(|c| c.is_ascii_hexdigit());
+    if !is_hash || FULL_HASHES.load(Ordering::Relaxed) {
+        return value;
+    }
+    format!(
+        "{}…{}",
+        &value[..2 + SHORT_HASH_DIGITS],
+        &value[value.len() - SHORT_HASH_DIGITS..]
+    )
+}
+
+/// Wraps text in the color of a style, if output is colored
+fn paint(text: &str, style: Style) -> String {
+    match *COLOR_THEME.lock().unwrap() {
+        Some(theme) => format!("\x1b[{}m{}\x1b[0m", theme.sgr(style), text),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_format_table() {
+        let table = format_table(&json!({
+            "Result": [
+                {"name": "default", "account": "0x1"},
+                {"name": "testnet", "account": "0x42"},
+            ]
+        }));
+        assert_eq!(
+            "name     account\n-------  -------\ndefault  0x1\ntestnet  0x42",
+            table
+        );
+        assert_eq!(
+            "Error: Failure",
+            format_table(&json!({"Error": "Failure", "ErrorType": "UnexpectedError"}))
+        );
+    }
+
+    #[test]
+    fn test_format_fields() {
+        let hash = format!("0x{}", "ab".repeat(32));
+        assert_eq!(
+            "hash       0xabab…abab\nsuccess    true\nvm_status  Executed successfully",
+            format_table(&json!({
+                "Result": {
+                    "hash": hash,
+                    "success": true,
+                    "vm_status": "Executed successfully",
+                }
+            }))
+        );
+    }
+
+    #[test]
+    fn test_format_csv() {
+        let csv = format_csv(&json!({
+            "Result": [
+                {"function": "0x1::coin::transfer", "arguments": ["0x1", "100"]},
+                {"function": "0x1::code::publish", "arguments": []},
+            ]
+        }));
+        assert_eq!(
+            "function,arguments\n0x1::coin::transfer,\"[\"\"0x1\"\",\"\"100\"\"]\"\n0x1::code::publish,[]",
+            csv
+        );
+    }
+}
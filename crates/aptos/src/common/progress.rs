@@ -0,0 +1,80 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Progress of long running operations, so slow ones can be told apart from hung ones
+//!
+//! Progress is drawn on stderr, and only when it's a terminal.  It's also turned off when JSON
+//! output is chosen explicitly, as the CLI is then likely driven by a script.
+
+use indicatif::{ProgressBar, ProgressStyle};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether progress is shown for this invocation
+static PROGRESS_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Milliseconds between redraws of a spinner
+const TICK_MS: u64 = 100;
+
+/// Shows or hides progress for all commands run afterwards
+pub fn set_progress_enabled(enabled: bool) {
+    PROGRESS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Progress of an operation, cleared when it's finished or dropped
+pub struct Progress {
+    bar: ProgressBar,
+}
+
+impl Progress {
+    /// A spinner for an operation of unknown length
+    pub fn spinner(message: &str) -> Progress {
+        let bar = Self::new_bar(ProgressBar::new_spinner);
+        bar.set_style(ProgressStyle::default_spinner().template("{spinner} {msg} [{elapsed}]"));
+        bar.set_message(message);
+        bar.enable_steady_tick(TICK_MS);
+        Progress { bar }
+    }
+
+    /// A bar for an operation of `total` steps
+    pub fn bar(message: &str, total: u64) -> Progress {
+        let bar = Self::new_bar(|| ProgressBar::new(total));
+        bar.set_style(
+            ProgressStyle::default_bar().template("{msg} [{bar:30}] {pos}/{len} [{elapsed}]"),
+        );
+        bar.set_message(message);
+        bar.enable_steady_tick(TICK_MS);
+        Progress { bar }
+    }
+
+    fn new_bar(new: impl FnOnce() -> ProgressBar) -> ProgressBar {
+        if PROGRESS_ENABLED.load(Ordering::Relaxed) {
+            new()
+        } else {
+            ProgressBar::hidden()
+        }
+    }
+
+    pub fn set_message(&self, message: &str) {
+        self.bar.set_message(message);
+    }
+
+    /// Completes `steps` more steps of a bar
+    pub fn inc(&self, steps: u64) {
+        self.bar.inc(steps);
+    }
+
+    /// Prints a line above the progress, or to stderr if progress isn't shown
+    pub fn println(&self, line: &str) {
+        if self.bar.is_hidden() {
+            eprintln!("{}", line);
+        } else {
+            self.bar.println(line);
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.bar.finish_and_clear();
+    }
+}
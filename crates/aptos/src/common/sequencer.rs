@@ -0,0 +1,96 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sequence numbers for the transactions one process sends from an account
+//!
+//! Fetching the sequence number from the fullnode for each transaction only works when the
+//! previous one is already committed.  Commands sending several transactions, possibly
+//! concurrently, instead share an `AccountSequencer`, which fetches the account's sequence
+//! number once and hands out the following ones.
+
+use crate::common::types::CliTypedResult;
+use crate::common::utils::get_sequence_number;
+use aptos_rest_client::aptos_api_types::AptosErrorCode;
+use aptos_rest_client::error::RestError;
+use aptos_rest_client::Client;
+use aptos_types::account_address::AccountAddress;
+use move_core_types::vm_status::StatusCode;
+use std::sync::{Arc, Mutex};
+
+/// Sequencers of this process, for each endpoint and account
+static SEQUENCERS: Mutex<Vec<Arc<AccountSequencer>>> = Mutex::new(Vec::new());
+
+/// Hands out the sequence numbers of an account, to transactions sent through one endpoint
+pub struct AccountSequencer {
+    client: Client,
+    endpoint: String,
+    address: AccountAddress,
+    /// Next sequence number to hand out, `None` until fetched, or when it has to be fetched
+    /// again
+    next: tokio::sync::Mutex<Option<u64>>,
+}
+
+impl AccountSequencer {
+    /// The sequencer shared by this process for the account on the client's endpoint
+    pub fn for_account(client: &Client, address: AccountAddress) -> Arc<AccountSequencer> {
+        let endpoint = client.path_prefix_string();
+        let mut sequencers = SEQUENCERS.lock().unwrap();
+        if let Some(sequencer) = sequencers
+            .iter()
+            .find(|sequencer| sequencer.address == address && sequencer.endpoint == endpoint)
+        {
+            return sequencer.clone();
+        }
+
+        let sequencer = Arc::new(AccountSequencer {
+            client: client.clone(),
+            endpoint,
+            address,
+            next: tokio::sync::Mutex::new(None),
+        });
+        sequencers.push(sequencer.clone());
+        sequencer
+    }
+
+    /// Takes the next sequence number, fetching the account's if it isn't known
+    pub async fn next(&self) -> CliTypedResult<u64> {
+        let mut next = self.next.lock().await;
+        let sequence_number = match *next {
+            Some(sequence_number) => sequence_number,
+            None => get_sequence_number(&self.client, self.address).await?,
+        };
+        *next = Some(sequence_number + 1);
+        Ok(sequence_number)
+    }
+
+    /// Gives back a sequence number which wasn't used, as its transaction wasn't submitted
+    ///
+    /// Only the last number handed out can be taken again, otherwise the gap it leaves is
+    /// fixed by fetching the account's sequence number again.
+    pub async fn release(&self, sequence_number: u64) {
+        let mut next = self.next.lock().await;
+        *next = match *next {
+            Some(next) if next == sequence_number + 1 => Some(sequence_number),
+            _ => None,
+        };
+    }
+
+    /// Fetches the account's sequence number again for the next transaction, after it was
+    /// found to be out of date, e.g. when another process sent from the account
+    pub async fn repair(&self) {
+        *self.next.lock().await = None;
+    }
+}
+
+/// Whether the transaction was rejected for a sequence number which was already used
+pub fn is_sequence_number_too_old(error: &RestError) -> bool {
+    match error {
+        RestError::Api(response) => {
+            matches!(
+                response.error.error_code,
+                AptosErrorCode::SequenceNumberTooOld
+            ) || response.error.vm_error_code == Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD as u64)
+        }
+        _ => false,
+    }
+}
@@ -1,16 +1,28 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::common::http::HttpConfig;
 use crate::common::init::Network;
-use crate::common::utils::prompt_yes_with_override;
+use crate::common::key_encryption::EncryptedPrivateKey;
+use crate::common::key_store::{KeyBackend, OsKeychain};
+use crate::common::ledger::Ledger;
+use crate::common::progress::Progress;
+use crate::common::sequencer::{is_sequence_number_too_old, AccountSequencer};
+use crate::common::utils::{dry_run, prompt_yes_with_override};
 use crate::{
     common::utils::{
         chain_id, check_if_file_exists, create_dir_if_not_exist, dir_default_to_current,
-        get_auth_key, get_sequence_number, read_from_file, start_logger, to_common_result,
-        to_common_success_result, write_to_file, write_to_file_with_opts, write_to_user_only_file,
+        estimate_gas_unit_price, get_auth_key, get_sequence_number, read_from_file, read_hidden,
+        start_logger, to_common_result, to_common_success_result, write_to_file,
+        write_to_file_with_opts,
     },
-    config::GlobalConfig,
-    genesis::git::from_yaml,
+    config::{
+        audit_log, env, format,
+        lock::{write_config_file, ConfigLock},
+        migration, network, ConfigFormat, GlobalConfig,
+    },
+    move_tool::package_hooks,
+    transaction::{UnsignedTransaction, DEFAULT_UNSIGNED_EXPIRATION_SECS},
 };
 use aptos_crypto::ed25519::Ed25519Signature;
 use aptos_crypto::{
@@ -21,22 +33,25 @@ use aptos_global_constants::adjust_gas_headroom;
 use aptos_keygen::KeyGen;
 use aptos_rest_client::aptos_api_types::{ExplainVMStatus, HashValue, UserTransaction};
 use aptos_rest_client::error::RestError;
-use aptos_rest_client::{Client, Transaction};
-use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
+use aptos_rest_client::{Client, PendingTransaction, Transaction};
+use aptos_sdk::transaction_builder::TransactionFactory;
 use aptos_types::transaction::{
-    authenticator::AuthenticationKey, SignedTransaction, TransactionPayload,
+    authenticator::AuthenticationKey, RawTransaction, SignedTransaction, TransactionPayload,
 };
 use async_trait::async_trait;
 use clap::{ArgEnum, Parser};
 use hex::FromHexError;
 use move_core_types::account_address::AccountAddress;
+use move_package::source_package::{
+    layout::SourcePackageLayout, manifest_parser::parse_move_manifest_from_file,
+};
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 #[cfg(unix)]
 use std::os::unix::fs::OpenOptionsExt;
 use std::time::Duration;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt::{Debug, Display, Formatter},
     fs::OpenOptions,
     path::{Path, PathBuf},
@@ -47,6 +62,12 @@ use thiserror::Error;
 
 const MAX_POSSIBLE_GAS_UNITS: u64 = 1_000_000;
 pub const DEFAULT_PROFILE: &str = "default";
+/// Time to wait for a lagging fullnode to catch up, before deciding a transaction expired
+const MAX_SERVER_LAG_WAIT: Duration = Duration::from_secs(60);
+/// Prefix of account args taken from a profile, e.g. `profile:alice`
+pub const PROFILE_ADDRESS_PREFIX: &str = "profile:";
+/// Default file, in a Move package, of named addresses for each network
+pub const NETWORK_ADDRESSES_FILE: &str = "NetworkAddresses.toml";
 
 /// A common result to be returned to users
 pub type CliResult = Result<String, String>;
@@ -85,6 +106,12 @@ pub enum CliError {
     UnexpectedError(String),
     #[error("Simulation failed with status: {0}")]
     SimulationError(String),
+    #[error("Transaction rejected: {0}")]
+    TransactionRejected(String),
+    #[error("Transaction failed: {0}")]
+    TransactionFailed(String),
+    #[error("Timed out waiting for transaction: {0}")]
+    TransactionTimeout(String),
 }
 
 impl CliError {
@@ -104,10 +131,82 @@ impl CliError {
             CliError::UnableToReadFile(_, _) => "UnableToReadFile",
             CliError::UnexpectedError(_) => "UnexpectedError",
             CliError::SimulationError(_) => "SimulationError",
+            CliError::TransactionRejected(_) => "TransactionRejected",
+            CliError::TransactionFailed(_) => "TransactionFailed",
+            CliError::TransactionTimeout(_) => "TransactionTimeout",
         }
     }
 }
 
+impl CliError {
+    /// The exit code of a command failing with this error
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            CliError::AbortedError
+            | CliError::CommandArgumentError(_)
+            | CliError::ConfigLoadError(_, _)
+            | CliError::ConfigNotFoundError(_)
+            | CliError::MoveCompilationError(_)
+            | CliError::MoveTestError
+            | CliError::MoveProverError(_)
+            | CliError::UnableToParse(_, _)
+            | CliError::UnableToReadFile(_, _) => ExitCode::UserError,
+            CliError::ApiError(_) => ExitCode::NetworkError,
+            CliError::SimulationError(_) | CliError::TransactionRejected(_) => {
+                ExitCode::TransactionRejected
+            }
+            CliError::TransactionFailed(_) => ExitCode::TransactionFailed,
+            CliError::TransactionTimeout(_) => ExitCode::Timeout,
+            CliError::BCS(_, _) | CliError::IO(_, _) | CliError::UnexpectedError(_) => {
+                ExitCode::Unexpected
+            }
+        }
+    }
+}
+
+/// Exit codes of the CLI, so scripts can tell kinds of failures apart
+///
+/// These are stable, new kinds of failures get new codes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExitCode {
+    Success = 0,
+    /// An unexpected failure, e.g. a file couldn't be written
+    Unexpected = 1,
+    /// Invalid arguments, config, or Move code, which the user has to fix
+    UserError = 2,
+    /// The REST endpoint couldn't be reached, or returned an error
+    NetworkError = 3,
+    /// The transaction wasn't committed, as it failed simulation or the node rejected it
+    TransactionRejected = 4,
+    /// The transaction was committed, but failed execution on-chain
+    TransactionFailed = 5,
+    /// The transaction wasn't seen committed in time, it may still be committed
+    Timeout = 6,
+}
+
+/// Converts the error of submitting and waiting for a transaction by what became of it
+pub fn submission_error(error: RestError) -> CliError {
+    let message = error.to_string();
+    match error {
+        // The node refused the transaction e.g. for a bad sequence number or signature
+        RestError::Api(ref response) if response.status_code.is_client_error() => {
+            CliError::TransactionRejected(message)
+        }
+        RestError::Timeout(_) => CliError::TransactionTimeout(message),
+        // The client only describes these outcomes in its messages
+        RestError::Unknown(_) if message.contains("failed execution") => {
+            CliError::TransactionFailed(message)
+        }
+        RestError::Unknown(_) if message.contains("expired") => {
+            CliError::TransactionRejected(message)
+        }
+        RestError::Unknown(_) if message.contains("Timeout") || message.contains("timing out") => {
+            CliError::TransactionTimeout(message)
+        }
+        _ => CliError::ApiError(message),
+    }
+}
+
 impl From<RestError> for CliError {
     fn from(e: RestError) -> Self {
         CliError::ApiError(e.to_string())
@@ -171,23 +270,52 @@ impl From<bcs::Error> for CliError {
 /// Config saved to `.aptos/config.yaml`
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CliConfig {
+    /// Schema version of the config, see [`migration`]
+    #[serde(default)]
+    pub version: u64,
     /// Map of profile configs
     #[serde(skip_serializing_if = "Option::is_none")]
     pub profiles: Option<BTreeMap<String, ProfileConfig>>,
+    /// Profile to use when no `--profile` is given, defaults to `default`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    /// The `.aptos` folder the config was loaded from, where it will be saved back to
+    #[serde(skip)]
+    folder: Option<PathBuf>,
 }
 
-const CONFIG_FILE: &str = "config.yaml";
+/// Name of the config file, without the extension of its format
+const CONFIG_FILE: &str = "config";
 const LEGACY_CONFIG_FILE: &str = "config.yml";
 pub const CONFIG_FOLDER: &str = ".aptos";
 
 /// An individual profile
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ProfileConfig {
+    /// Base profile to inherit the network, REST URL, faucet URL, HTTP settings and named
+    /// addresses from, if not set here
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network: Option<Network>,
     /// Private key for commands.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub private_key: Option<Ed25519PrivateKey>,
+    /// Private key for commands, encrypted with a passphrase
+    ///
+    /// Used in place of `private_key`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypted_private_key: Option<EncryptedPrivateKey>,
+    /// Name of the OS keychain entry holding the private key for commands
+    ///
+    /// Used in place of `private_key`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keychain_entry: Option<String>,
+    /// BIP32 derivation path of a key on a Ledger, which signs transactions for commands
+    ///
+    /// Used in place of `private_key`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_derivation_path: Option<String>,
     /// Public key for commands
     #[serde(skip_serializing_if = "Option::is_none")]
     pub public_key: Option<Ed25519PublicKey>,
@@ -200,12 +328,30 @@ pub struct ProfileConfig {
     /// URL for the Faucet endpoint (if applicable)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub faucet_url: Option<String>,
+    /// Proxy and root certificates for the REST and faucet endpoints
+    ///
+    /// Settings not given here are taken from the global config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpConfig>,
+    /// Named addresses for Move builds, merged with `--named-addresses`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub named_addresses: Option<BTreeMap<String, AccountAddress>>,
 }
 
+/// Placeholder for key material in any output other than `aptos config show-private-key`
+pub const REDACTED: &str = "<redacted>";
+
 /// ProfileConfig but without the private parts
 #[derive(Debug, Serialize)]
 pub struct ProfileSummary {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
     pub has_private_key: bool,
+    pub private_key_encrypted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keychain_entry: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_derivation_path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub public_key: Option<Ed25519PublicKey>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -214,16 +360,101 @@ pub struct ProfileSummary {
     pub rest_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub faucet_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub named_addresses: Option<BTreeMap<String, AccountAddress>>,
 }
 
 impl From<&ProfileConfig> for ProfileSummary {
     fn from(config: &ProfileConfig) -> Self {
         ProfileSummary {
-            has_private_key: config.private_key.is_some(),
+            extends: config.extends.clone(),
+            has_private_key: config.has_private_key(),
+            private_key_encrypted: config.encrypted_private_key.is_some(),
+            keychain_entry: config.keychain_entry.clone(),
+            ledger_derivation_path: config.ledger_derivation_path.clone(),
             public_key: config.public_key.clone(),
             account: config.account,
             rest_url: config.rest_url.clone(),
             faucet_url: config.faucet_url.clone(),
+            http: config.http.clone(),
+            named_addresses: config.named_addresses.clone(),
+        }
+    }
+}
+
+impl ProfileConfig {
+    /// Whether the profile has a private key in any form of storage
+    pub fn has_private_key(&self) -> bool {
+        self.private_key.is_some()
+            || self.encrypted_private_key.is_some()
+            || self.keychain_entry.is_some()
+    }
+
+    /// Retrieves the private key from wherever it's stored, decrypting it if necessary
+    pub fn resolve_private_key(
+        &self,
+        passphrase_options: &KeyPassphraseOptions,
+    ) -> CliTypedResult<Option<Ed25519PrivateKey>> {
+        self.resolve_private_key_from(&OsKeychain, passphrase_options)
+    }
+
+    /// Retrieves the private key, loading a keychain entry from `backend`
+    pub fn resolve_private_key_from(
+        &self,
+        backend: &dyn KeyBackend,
+        passphrase_options: &KeyPassphraseOptions,
+    ) -> CliTypedResult<Option<Ed25519PrivateKey>> {
+        if let Some(ref private_key) = self.private_key {
+            Ok(Some(private_key.clone()))
+        } else if let Some(ref encrypted_private_key) = self.encrypted_private_key {
+            let passphrase = passphrase_options.passphrase(false)?;
+            Ok(Some(encrypted_private_key.decrypt(&passphrase)?))
+        } else if let Some(ref keychain_entry) = self.keychain_entry {
+            Ok(Some(backend.load_private_key(keychain_entry)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Moves the private key into a key backend, removing it from the profile
+    pub fn store_private_key_in(
+        &mut self,
+        backend: &dyn KeyBackend,
+        entry: &str,
+        passphrase_options: &KeyPassphraseOptions,
+    ) -> CliTypedResult<()> {
+        let private_key = self
+            .resolve_private_key_from(backend, passphrase_options)?
+            .ok_or_else(|| {
+                CliError::CommandArgumentError("Profile has no private key to store".to_string())
+            })?;
+        backend.store_private_key(entry, &private_key)?;
+
+        self.private_key = None;
+        self.encrypted_private_key = None;
+        self.keychain_entry = Some(entry.to_string());
+        Ok(())
+    }
+
+    /// Encrypts the plaintext private key in place, removing the plaintext key
+    pub fn encrypt_private_key(&mut self, passphrase: &str) -> CliTypedResult<()> {
+        if let Some(private_key) = self.private_key.take() {
+            self.encrypted_private_key =
+                Some(EncryptedPrivateKey::encrypt(&private_key, passphrase)?);
+        }
+        Ok(())
+    }
+
+    /// The account address derived from the profile's keys
+    pub fn derived_address(&self) -> Option<AccountAddress> {
+        if let Some(ref private_key) = self.private_key {
+            Some(account_address_from_public_key(&private_key.public_key()))
+        } else {
+            self.public_key
+                .as_ref()
+                .map(account_address_from_public_key)
         }
     }
 }
@@ -231,7 +462,10 @@ impl From<&ProfileConfig> for ProfileSummary {
 impl Default for CliConfig {
     fn default() -> Self {
         CliConfig {
+            version: migration::CLI_CONFIG_VERSION,
             profiles: Some(BTreeMap::new()),
+            default_profile: None,
+            folder: None,
         }
     }
 }
@@ -246,9 +480,9 @@ impl CliConfig {
     /// Checks if the config exists in the current working directory
     pub fn config_exists(mode: ConfigSearchMode) -> bool {
         if let Ok(folder) = Self::aptos_folder(mode) {
-            let config_file = folder.join(CONFIG_FILE);
             let old_config_file = folder.join(LEGACY_CONFIG_FILE);
-            config_file.exists() || old_config_file.exists()
+            format::find_config_file(folder.as_path(), CONFIG_FILE).is_some()
+                || old_config_file.exists()
         } else {
             false
         }
@@ -258,45 +492,231 @@ impl CliConfig {
     pub fn load(mode: ConfigSearchMode) -> CliTypedResult<Self> {
         let folder = Self::aptos_folder(mode)?;
 
-        let config_file = folder.join(CONFIG_FILE);
         let old_config_file = folder.join(LEGACY_CONFIG_FILE);
-        if config_file.exists() {
-            from_yaml(
+        let (config, migrated): (CliConfig, bool) = if let Some((config_file, config_format)) =
+            format::find_config_file(folder.as_path(), CONFIG_FILE)
+        {
+            migration::load_cli_config(
+                config_format,
                 &String::from_utf8(read_from_file(config_file.as_path())?)
                     .map_err(CliError::from)?,
-            )
+            )?
         } else if old_config_file.exists() {
-            from_yaml(
+            migration::load_cli_config(
+                ConfigFormat::Yaml,
                 &String::from_utf8(read_from_file(old_config_file.as_path())?)
                     .map_err(CliError::from)?,
-            )
+            )?
         } else {
-            Err(CliError::ConfigNotFoundError(format!(
+            return Err(CliError::ConfigNotFoundError(format!(
                 "{}",
-                config_file.display()
-            )))
+                folder
+                    .join(format!(
+                        "{}.{}",
+                        CONFIG_FILE,
+                        ConfigFormat::Yaml.extension()
+                    ))
+                    .display()
+            )));
+        };
+
+        let config = CliConfig {
+            folder: Some(folder),
+            ..config
+        };
+
+        // Write back the upgraded schema to where it was loaded from
+        if migrated {
+            config.save()?;
         }
+        Ok(config)
     }
 
     pub fn load_profile(
         profile: Option<&str>,
         mode: ConfigSearchMode,
     ) -> CliTypedResult<Option<ProfileConfig>> {
-        let mut config = Self::load(mode)?;
+        let env_profile = env::profile_override();
+        let profile = profile.or(env_profile.as_deref());
 
-        // If no profile was given, use `default`
-        if let Some(profile) = profile {
-            if let Some(account_profile) = config.remove_profile(profile) {
-                Ok(Some(account_profile))
+        let profile_config = if !Self::config_exists(mode) && env::has_profile_overrides() {
+            // Without a config file, the profile comes entirely from the environment
+            None
+        } else {
+            let mut config = Self::load(mode)?;
+
+            // If no profile was given, use the configured default profile
+            let profile_name = if let Some(profile) = profile {
+                if !config.has_profile(profile) {
+                    return Err(CliError::CommandArgumentError(format!(
+                        "Profile {} not found",
+                        profile
+                    )));
+                }
+                profile.to_string()
             } else {
-                Err(CliError::CommandArgumentError(format!(
-                    "Profile {} not found",
-                    profile
-                )))
+                config.default_profile_name().to_string()
+            };
+
+            let mut profile_config = config.remove_profile(&profile_name);
+            if let Some(ref mut profile_config) = profile_config {
+                config.inherit_from_base(&profile_name, profile_config)?;
+                network::resolve_endpoints(profile_config);
             }
-        } else {
-            Ok(config.remove_profile(DEFAULT_PROFILE))
+            profile_config
+        };
+
+        // Layer the environment overrides on top of the profile
+        match profile_config {
+            Some(mut profile_config) => {
+                env::apply_profile_overrides(&mut profile_config)?;
+                Ok(Some(profile_config))
+            }
+            None if env::has_profile_overrides() => {
+                let mut profile_config = ProfileConfig::default();
+                env::apply_profile_overrides(&mut profile_config)?;
+                Ok(Some(profile_config))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Name of the profile used when no profile is given, falling back to `default`
+    pub fn default_profile_name(&self) -> &str {
+        self.default_profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// Name of the profile in use, from the argument, then `APTOS_PROFILE`, then the default
+    pub fn active_profile_name(&self, profile: Option<&str>) -> String {
+        profile
+            .map(str::to_string)
+            .or_else(env::profile_override)
+            .unwrap_or_else(|| self.default_profile_name().to_string())
+    }
+
+    pub fn has_profile(&self, profile: &str) -> bool {
+        self.profiles
+            .as_ref()
+            .map(|profiles| profiles.contains_key(profile))
+            .unwrap_or(false)
+    }
+
+    /// Fills in the settings a profile doesn't set from its chain of `extends` base profiles
+    ///
+    /// Key material is never inherited
+    pub fn inherit_from_base(
+        &self,
+        profile: &str,
+        profile_config: &mut ProfileConfig,
+    ) -> CliTypedResult<()> {
+        let mut visited = BTreeSet::new();
+        visited.insert(profile.to_string());
+
+        let mut next_base = profile_config.extends.clone();
+        while let Some(base) = next_base {
+            if !visited.insert(base.clone()) {
+                return Err(CliError::ConfigLoadError(
+                    profile.to_string(),
+                    format!("profile inheritance has a cycle through {}", base),
+                ));
+            }
+
+            let base_config = self
+                .profiles
+                .as_ref()
+                .and_then(|profiles| profiles.get(&base))
+                .ok_or_else(|| {
+                    CliError::ConfigLoadError(
+                        profile.to_string(),
+                        format!("base profile {} not found", base),
+                    )
+                })?;
+
+            if profile_config.network.is_none() {
+                profile_config.network = base_config.network;
+            }
+            if profile_config.rest_url.is_none() {
+                profile_config.rest_url = base_config.rest_url.clone();
+            }
+            if profile_config.faucet_url.is_none() {
+                profile_config.faucet_url = base_config.faucet_url.clone();
+            }
+            if profile_config.http.is_none() {
+                profile_config.http = base_config.http.clone();
+            }
+            // Named addresses are merged, so a profile only needs to list the ones it changes
+            if let Some(ref base_named_addresses) = base_config.named_addresses {
+                let named_addresses = profile_config
+                    .named_addresses
+                    .get_or_insert_with(BTreeMap::new);
+                for (name, address) in base_named_addresses {
+                    named_addresses.entry(name.clone()).or_insert(*address);
+                }
+            }
+            next_base = base_config.extends.clone();
+        }
+
+        Ok(())
+    }
+
+    /// Names of the profiles that directly extend `profile`
+    pub fn profiles_extending(&self, profile: &str) -> Vec<String> {
+        self.profiles
+            .iter()
+            .flatten()
+            .filter(|(_, profile_config)| profile_config.extends.as_deref() == Some(profile))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Renames a profile, keeping the default profile and the profiles extending it pointing at it
+    pub fn rename_profile(&mut self, profile: &str, new_name: &str) -> CliTypedResult<()> {
+        let profile_config = self.remove_profile(profile).ok_or_else(|| {
+            CliError::CommandArgumentError(format!("Profile {} not found", profile))
+        })?;
+        self.insert_profile(new_name, profile_config);
+
+        if self.default_profile.as_deref() == Some(profile) {
+            self.default_profile = Some(new_name.to_string());
+        }
+        for profile_config in self
+            .profiles
+            .iter_mut()
+            .flat_map(|profiles| profiles.values_mut())
+        {
+            if profile_config.extends.as_deref() == Some(profile) {
+                profile_config.extends = Some(new_name.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that a profile exists, and that no other profile extends it
+    pub fn check_deletable(&self, profile: &str) -> CliTypedResult<()> {
+        if !self.has_profile(profile) {
+            return Err(CliError::CommandArgumentError(format!(
+                "Profile {} not found",
+                profile
+            )));
+        }
+
+        let extending_profiles = self.profiles_extending(profile);
+        if !extending_profiles.is_empty() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Profile {} is extended by profiles [{}], remove those first",
+                profile,
+                extending_profiles.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Removes a profile, falling back to `default` if it was the default profile
+    pub fn delete_profile(&mut self, profile: &str) -> Option<ProfileConfig> {
+        if self.default_profile.as_deref() == Some(profile) {
+            self.default_profile = None;
         }
+        self.remove_profile(profile)
     }
 
     pub fn remove_profile(&mut self, profile: &str) -> Option<ProfileConfig> {
@@ -307,19 +727,66 @@ impl CliConfig {
         }
     }
 
-    /// Saves the config to ./.aptos/config.yaml
+    pub fn insert_profile(&mut self, profile: &str, profile_config: ProfileConfig) {
+        self.profiles
+            .get_or_insert_with(BTreeMap::new)
+            .insert(profile.to_string(), profile_config);
+    }
+
+    /// Replaces the config's values with a raw config, keeping where it's saved
+    pub fn update_from_value(&mut self, value: serde_yaml::Value) -> CliTypedResult<()> {
+        let updated: CliConfig = serde_yaml::from_value(value)
+            .map_err(|err| CliError::UnableToParse("config", err.to_string()))?;
+        self.version = updated.version;
+        self.profiles = updated.profiles;
+        self.default_profile = updated.default_profile;
+        Ok(())
+    }
+
+    /// Saves the config to the `.aptos` folder it was loaded from, or ./.aptos if it's new
+    ///
+    /// The file is `config.yaml`, or has the extension of the configured format
     pub fn save(&self) -> CliTypedResult<()> {
-        let aptos_folder = Self::aptos_folder(ConfigSearchMode::CurrentDir)?;
+        if let Some(ref folder) = self.folder {
+            self.save_in(folder.as_path())
+        } else {
+            self.save_in(Self::aptos_folder(ConfigSearchMode::CurrentDir)?.as_path())
+        }
+    }
 
+    /// Finds the `.aptos` folder to use, and the config file in it if there is one
+    pub fn locate(mode: ConfigSearchMode) -> CliTypedResult<(PathBuf, Option<PathBuf>)> {
+        let folder = Self::aptos_folder(mode)?;
+        let config_file = format::find_config_file(folder.as_path(), CONFIG_FILE)
+            .map(|(config_file, _)| config_file)
+            .or_else(|| {
+                let old_config_file = folder.join(LEGACY_CONFIG_FILE);
+                if old_config_file.exists() {
+                    Some(old_config_file)
+                } else {
+                    None
+                }
+            });
+        Ok((folder, config_file))
+    }
+
+    /// Saves the config to `<aptos_folder>/config.<extension>`
+    fn save_in(&self, aptos_folder: &Path) -> CliTypedResult<()> {
         // Create if it doesn't exist
-        create_dir_if_not_exist(aptos_folder.as_path())?;
+        create_dir_if_not_exist(aptos_folder)?;
+
+        // Resolve the format before locking, as the global config takes its own lock
+        let config_format = GlobalConfig::load()?.config_format.unwrap_or_default();
 
-        // Save over previous config file
-        let config_file = aptos_folder.join(CONFIG_FILE);
-        let config_bytes = serde_yaml::to_string(&self).map_err(|err| {
+        // Save over previous config file, serializing with any other CLI processes
+        let _lock = ConfigLock::acquire(aptos_folder)?;
+        let config_file_name = format!("{}.{}", CONFIG_FILE, config_format.extension());
+        let config_file = aptos_folder.join(&config_file_name);
+        let config_bytes = config_format.serialize(&self).map_err(|err| {
             CliError::UnexpectedError(format!("Failed to serialize config {}", err))
         })?;
-        write_to_user_only_file(&config_file, CONFIG_FILE, config_bytes.as_bytes())?;
+        write_config_file(&config_file, &config_file_name, config_bytes.as_bytes())?;
+        format::remove_other_formats(aptos_folder, CONFIG_FILE, config_format);
 
         // As a cleanup, delete the old if it exists
         let legacy_config_file = aptos_folder.join(LEGACY_CONFIG_FILE);
@@ -346,6 +813,8 @@ pub enum KeyType {
     X25519,
     /// A BLS12381 key for consensus
     Bls12381,
+    /// A secp256k1 ECDSA key, as used by Ethereum and other chains
+    Secp256k1,
 }
 
 impl Display for KeyType {
@@ -354,6 +823,7 @@ impl Display for KeyType {
             KeyType::Ed25519 => "ed25519",
             KeyType::X25519 => "x25519",
             KeyType::Bls12381 => "bls12381",
+            KeyType::Secp256k1 => "secp256k1",
         };
         write!(f, "{}", str)
     }
@@ -367,7 +837,8 @@ impl FromStr for KeyType {
             "ed25519" => Ok(KeyType::Ed25519),
             "x25519" => Ok(KeyType::X25519),
             "bls12381" => Ok(KeyType::Bls12381),
-            _ => Err("Invalid key type: Must be one of [ed25519, x25519]"),
+            "secp256k1" => Ok(KeyType::Secp256k1),
+            _ => Err("Invalid key type: Must be one of [ed25519, x25519, bls12381, secp256k1]"),
         }
     }
 }
@@ -379,7 +850,8 @@ pub struct ProfileOptions {
     /// This will be used to override associated settings such as
     /// the REST URL, the Faucet URL, and the private key arguments.
     ///
-    /// Defaults to "default"
+    /// Defaults to `APTOS_PROFILE` if set, then the profile set with
+    /// `aptos config set-default-profile`, or "default"
     #[clap(long)]
     pub profile: Option<String>,
 }
@@ -415,6 +887,26 @@ impl ProfileOptions {
         self.profile.as_ref().map(|inner| inner.trim())
     }
 
+    /// Network of the profile, if there's a config and it has one
+    pub fn network(&self) -> CliTypedResult<Option<Network>> {
+        let mode = ConfigSearchMode::CurrentDirAndParents;
+        if !CliConfig::config_exists(mode) {
+            return Ok(None);
+        }
+        Ok(CliConfig::load_profile(self.profile_name(), mode)?.and_then(|profile| profile.network))
+    }
+
+    /// Named addresses of the profile for Move builds, empty if there's no config
+    pub fn named_addresses(&self) -> CliTypedResult<BTreeMap<String, AccountAddress>> {
+        let mode = ConfigSearchMode::CurrentDirAndParents;
+        if !CliConfig::config_exists(mode) {
+            return Ok(BTreeMap::new());
+        }
+        Ok(CliConfig::load_profile(self.profile_name(), mode)?
+            .and_then(|profile| profile.named_addresses)
+            .unwrap_or_default())
+    }
+
     pub fn profile(&self) -> CliTypedResult<ProfileConfig> {
         if let Some(profile) =
             CliConfig::load_profile(self.profile_name(), ConfigSearchMode::CurrentDirAndParents)?
@@ -462,6 +954,39 @@ impl EncodingType {
         path: &Path,
     ) -> CliTypedResult<Key> {
         self.decode_key(name, read_from_file(path)?)
+            .map_err(|err| load_error(name, path, *self, err))
+    }
+
+    /// Encodes raw key bytes, for keys without a crypto type such as secp256k1 keys
+    pub fn encode_bytes(&self, name: &'static str, bytes: &[u8]) -> CliTypedResult<Vec<u8>> {
+        Ok(match self {
+            EncodingType::Hex => hex::encode_upper(bytes).into_bytes(),
+            EncodingType::BCS => bcs::to_bytes(bytes).map_err(|err| CliError::BCS(name, err))?,
+            EncodingType::Base64 => base64::encode(bytes).into_bytes(),
+        })
+    }
+
+    /// Decodes raw key bytes encoded with [`EncodingType::encode_bytes`]
+    pub fn decode_bytes(&self, name: &'static str, data: Vec<u8>) -> CliTypedResult<Vec<u8>> {
+        match self {
+            EncodingType::BCS => bcs::from_bytes(&data).map_err(|err| CliError::BCS(name, err)),
+            EncodingType::Hex => {
+                let hex_string = String::from_utf8(data)?;
+                hex::decode(hex_string.trim().trim_start_matches("0x"))
+                    .map_err(|err| CliError::UnableToParse(name, err.to_string()))
+            }
+            EncodingType::Base64 => {
+                let string = String::from_utf8(data)?;
+                base64::decode(string.trim())
+                    .map_err(|err| CliError::UnableToParse(name, err.to_string()))
+            }
+        }
+    }
+
+    /// Loads raw key bytes from a file
+    pub fn load_bytes(&self, name: &'static str, path: &Path) -> CliTypedResult<Vec<u8>> {
+        self.decode_bytes(name, read_from_file(path)?)
+            .map_err(|err| load_error(name, path, *self, err))
     }
 
     /// Decodes an encoded key given the known encoding
@@ -489,7 +1014,20 @@ impl EncodingType {
     }
 }
 
-#[derive(Clone, Debug, Parser)]
+/// Points at the file and its expected encoding, as the wrong `--encoding` is the usual cause
+fn load_error(name: &'static str, path: &Path, encoding: EncodingType, err: CliError) -> CliError {
+    CliError::UnableToParse(
+        name,
+        format!(
+            "{} is not a valid {} encoded key, check --encoding: {}",
+            path.display(),
+            encoding,
+            err
+        ),
+    )
+}
+
+#[derive(Clone, Debug, Default, Parser)]
 pub struct RngArgs {
     /// The seed used for key generation, should be a 64 character hex string and only used for testing
     ///
@@ -566,6 +1104,8 @@ impl FromStr for EncodingType {
 }
 
 /// An insertable option for use with prompts.
+///
+/// Prompts can also be disabled for all commands with the `assume_yes` global config
 #[derive(Clone, Copy, Debug, Default, Parser)]
 pub struct PromptOptions {
     /// Assume yes for all yes/no prompts
@@ -670,7 +1210,7 @@ pub trait ParsePrivateKey {
     }
 }
 
-#[derive(Debug, Default, Parser)]
+#[derive(Default, Parser)]
 pub struct PrivateKeyInputOptions {
     /// Signing Ed25519 private key file path
     ///
@@ -684,10 +1224,24 @@ pub struct PrivateKeyInputOptions {
     /// Mutually exclusive with `--private-key-file`
     #[clap(long, group = "private_key_input")]
     private_key: Option<String>,
+
+    #[clap(flatten)]
+    pub(crate) key_passphrase_options: KeyPassphraseOptions,
 }
 
 impl ParsePrivateKey for PrivateKeyInputOptions {}
 
+/// Keeps private keys given on the command line out of debug output and logs
+impl std::fmt::Debug for PrivateKeyInputOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrivateKeyInputOptions")
+            .field("private_key_file", &self.private_key_file)
+            .field("private_key", &self.private_key.as_ref().map(|_| REDACTED))
+            .field("key_passphrase_options", &self.key_passphrase_options)
+            .finish()
+    }
+}
+
 impl PrivateKeyInputOptions {
     pub fn from_private_key(private_key: &Ed25519PrivateKey) -> CliTypedResult<Self> {
         Ok(PrivateKeyInputOptions {
@@ -697,6 +1251,7 @@ impl PrivateKeyInputOptions {
                     .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
             ),
             private_key_file: None,
+            key_passphrase_options: KeyPassphraseOptions::default(),
         })
     }
 
@@ -708,6 +1263,7 @@ impl PrivateKeyInputOptions {
                     .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
             ),
             private_key_file: None,
+            key_passphrase_options: KeyPassphraseOptions::default(),
         })
     }
 
@@ -715,6 +1271,7 @@ impl PrivateKeyInputOptions {
         PrivateKeyInputOptions {
             private_key: None,
             private_key_file: Some(file),
+            key_passphrase_options: KeyPassphraseOptions::default(),
         }
     }
 
@@ -737,11 +1294,8 @@ impl PrivateKeyInputOptions {
                 let address = account_address_from_public_key(&key.public_key());
                 Ok((key, address))
             }
-        } else if let Some((Some(key), maybe_config_address)) = CliConfig::load_profile(
-            profile.profile_name(),
-            ConfigSearchMode::CurrentDirAndParents,
-        )?
-        .map(|p| (p.private_key, p.account))
+        } else if let Some((key, maybe_config_address)) =
+            self.extract_private_key_profile(profile)?
         {
             match (maybe_address, maybe_config_address) {
                 (Some(address), _) => Ok((key, address)),
@@ -766,12 +1320,7 @@ impl PrivateKeyInputOptions {
     ) -> CliTypedResult<Ed25519PrivateKey> {
         if let Some(key) = self.extract_private_key_cli(encoding)? {
             Ok(key)
-        } else if let Some(Some(private_key)) = CliConfig::load_profile(
-            profile.profile_name(),
-            ConfigSearchMode::CurrentDirAndParents,
-        )?
-        .map(|p| p.private_key)
-        {
+        } else if let Some((private_key, _)) = self.extract_private_key_profile(profile)? {
             Ok(private_key)
         } else {
             Err(CliError::CommandArgumentError(
@@ -780,6 +1329,23 @@ impl PrivateKeyInputOptions {
         }
     }
 
+    /// Extract private key and account from the profile, decrypting the key if necessary
+    fn extract_private_key_profile(
+        &self,
+        profile: &ProfileOptions,
+    ) -> CliTypedResult<Option<(Ed25519PrivateKey, Option<AccountAddress>)>> {
+        if let Some(profile_config) = CliConfig::load_profile(
+            profile.profile_name(),
+            ConfigSearchMode::CurrentDirAndParents,
+        )? {
+            Ok(profile_config
+                .resolve_private_key(&self.key_passphrase_options)?
+                .map(|private_key| (private_key, profile_config.account)))
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Extract private key from CLI args
     pub fn extract_private_key_cli(
         &self,
@@ -804,6 +1370,57 @@ impl ExtractPublicKey for PrivateKeyInputOptions {
     }
 }
 
+/// Options for unlocking a private key stored encrypted in a profile
+#[derive(Debug, Default, Parser)]
+pub struct KeyPassphraseOptions {
+    /// File containing the passphrase for a profile's encrypted private key
+    ///
+    /// If not given, `APTOS_KEY_PASSPHRASE_FILE` is used, otherwise the passphrase is prompted for
+    #[clap(long, parse(from_os_str))]
+    pub key_passphrase_file: Option<PathBuf>,
+}
+
+impl KeyPassphraseOptions {
+    /// Retrieves the passphrase, prompting for it twice if `confirm` is set and it isn't in a file
+    pub fn passphrase(&self, confirm: bool) -> CliTypedResult<String> {
+        let passphrase_file = self
+            .key_passphrase_file
+            .clone()
+            .or_else(|| env::env_var(env::APTOS_KEY_PASSPHRASE_FILE).map(PathBuf::from));
+
+        let passphrase = if let Some(file) = passphrase_file {
+            String::from_utf8(read_from_file(file.as_path())?)?
+                .trim_end_matches(&['\r', '\n'][..])
+                .to_string()
+        } else {
+            let passphrase = read_hidden(
+                "Key passphrase",
+                "Enter the passphrase for the private key: ",
+            )?
+            .trim()
+            .to_string();
+            if confirm {
+                let confirmation =
+                    read_hidden("Key passphrase", "Enter the passphrase again to confirm: ")?;
+                if confirmation.trim() != passphrase {
+                    return Err(CliError::CommandArgumentError(
+                        "Passphrases do not match".to_string(),
+                    ));
+                }
+            }
+            passphrase
+        };
+
+        if passphrase.is_empty() {
+            Err(CliError::CommandArgumentError(
+                "Key passphrase must not be empty".to_string(),
+            ))
+        } else {
+            Ok(passphrase)
+        }
+    }
+}
+
 pub trait ExtractPublicKey {
     fn extract_public_key(
         &self,
@@ -859,6 +1476,20 @@ pub struct RestOptions {
     /// Connection timeout in seconds, used for the REST endpoint of the fullnode
     #[clap(long, default_value = "30", alias = "connection-timeout-s")]
     pub connection_timeout_secs: u64,
+
+    /// Times to retry requests which only read from the fullnode, after connection failures,
+    /// timeouts, or rate limiting
+    ///
+    /// Rate limited requests wait as long as the fullnode asks with `Retry-After`.  Defaults to
+    /// `max_retries` in the profile's or global `http` settings, or 3
+    #[clap(long)]
+    pub max_retries: Option<u32>,
+
+    /// Seconds to keep retrying a request for
+    ///
+    /// Defaults to `retry_timeout_secs` in the profile's or global `http` settings, or 60
+    #[clap(long, alias = "retry-timeout")]
+    pub retry_timeout_secs: Option<u64>,
 }
 
 impl RestOptions {
@@ -866,6 +1497,8 @@ impl RestOptions {
         RestOptions {
             url,
             connection_timeout_secs: connection_timeout_secs.unwrap_or(30),
+            max_retries: None,
+            retry_timeout_secs: None,
         }
     }
 
@@ -887,10 +1520,17 @@ impl RestOptions {
     }
 
     pub fn client(&self, profile: &ProfileOptions) -> CliTypedResult<Client> {
-        Ok(Client::new_with_timeout(
-            self.url(profile)?,
-            Duration::from_secs(self.connection_timeout_secs),
-        ))
+        let http_config = HttpConfig {
+            max_retries: self.max_retries,
+            retry_timeout_secs: self.retry_timeout_secs,
+            ..HttpConfig::default()
+        };
+        http_config
+            .or(HttpConfig::for_profile(profile)?)
+            .rest_client(
+                self.url(profile)?,
+                Duration::from_secs(self.connection_timeout_secs),
+            )
     }
 }
 
@@ -907,11 +1547,23 @@ pub struct MovePackageDir {
     pub output_dir: Option<PathBuf>,
     /// Named addresses for the move binary
     ///
-    /// Example: alice=0x1234, bob=0x5678
+    /// Addresses can also be a profile's account, as `profile:<name>`
+    ///
+    /// Example: alice=0x1234, bob=0x5678, carol=profile:carol
     ///
     /// Note: This will fail if there are duplicates in the Move.toml file remove those first.
     #[clap(long, parse(try_from_str = crate::common::utils::parse_map), default_value = "")]
     pub(crate) named_addresses: BTreeMap<String, AccountAddressWrapper>,
+    /// File of named addresses for each network, used for the profile's network
+    ///
+    /// A TOML table for each network, e.g. `[devnet]` followed by `alice = "0x1234"` or
+    /// `alice = "profile:alice"`, so one package builds for each network without editing
+    /// Move.toml.  Defaults to `NetworkAddresses.toml` in the package, if it exists.
+    #[clap(long, parse(from_os_str))]
+    pub named_addresses_file: Option<PathBuf>,
+    /// Only use dependencies already in the cache, without fetching git or on-chain dependencies
+    #[clap(long)]
+    pub offline: bool,
 }
 
 impl MovePackageDir {
@@ -920,20 +1572,89 @@ impl MovePackageDir {
             package_dir: Some(package_dir),
             output_dir: None,
             named_addresses: Default::default(),
+            named_addresses_file: None,
+            offline: false,
         }
     }
 
+    /// Applies `--offline` to the on-chain dependencies of the builds that follow
+    pub fn apply_offline(&self) {
+        package_hooks::set_offline(self.offline);
+    }
+
     pub fn get_package_path(&self) -> CliTypedResult<PathBuf> {
         dir_default_to_current(self.package_dir.clone())
     }
 
     /// Retrieve the NamedAddresses, resolving all the account addresses accordingly
-    pub fn named_addresses(&self) -> BTreeMap<String, AccountAddress> {
-        self.named_addresses
-            .clone()
-            .into_iter()
-            .map(|(key, value)| (key, value.account_address))
-            .collect()
+    ///
+    /// The profile's `named_addresses` are included, then those for the profile's network from
+    /// the named addresses file, except for any the package's Move.toml assigns.
+    /// `--named-addresses` take precedence over them all.
+    pub fn named_addresses(
+        &self,
+        profile: &ProfileOptions,
+    ) -> CliTypedResult<BTreeMap<String, AccountAddress>> {
+        let mut named_addresses = profile.named_addresses()?;
+        named_addresses.extend(self.network_named_addresses(profile)?);
+        if !named_addresses.is_empty() {
+            let manifest_path = self
+                .get_package_path()?
+                .join(SourcePackageLayout::Manifest.path());
+            let manifest_addresses = parse_move_manifest_from_file(&manifest_path)
+                .ok()
+                .and_then(|manifest| manifest.addresses);
+            if let Some(addresses) = manifest_addresses {
+                for (name, address) in addresses {
+                    if address.is_some() {
+                        named_addresses.remove(name.as_str());
+                    }
+                }
+            }
+        }
+
+        named_addresses.extend(
+            self.named_addresses
+                .iter()
+                .map(|(key, value)| (key.clone(), value.account_address)),
+        );
+        Ok(named_addresses)
+    }
+
+    /// Named addresses for the profile's network, from the named addresses file
+    fn network_named_addresses(
+        &self,
+        profile: &ProfileOptions,
+    ) -> CliTypedResult<BTreeMap<String, AccountAddress>> {
+        let path = match self.named_addresses_file {
+            Some(ref path) => path.clone(),
+            None => {
+                let path = self.get_package_path()?.join(NETWORK_ADDRESSES_FILE);
+                if !path.exists() {
+                    return Ok(BTreeMap::new());
+                }
+                path
+            }
+        };
+        let network = match profile.network()? {
+            Some(network) => network,
+            None => return Ok(BTreeMap::new()),
+        };
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|err| CliError::IO(path.display().to_string(), err))?;
+        let networks: BTreeMap<String, BTreeMap<String, String>> = toml::from_str(&contents)
+            .map_err(|err| CliError::UnableToParse("named addresses file", err.to_string()))?;
+        let mut named_addresses = BTreeMap::new();
+        for (name, addresses) in networks {
+            if Network::from_str(&name)? != network {
+                continue;
+            }
+            for (name, address) in addresses {
+                named_addresses.insert(name, load_account_arg(&address)?);
+            }
+        }
+        Ok(named_addresses)
     }
 
     pub fn add_named_address(&mut self, key: String, value: String) {
@@ -960,18 +1681,19 @@ impl FromStr for AccountAddressWrapper {
 
 /// Loads an account arg and allows for naming based on profiles
 pub fn load_account_arg(str: &str) -> Result<AccountAddress, CliError> {
-    if str.starts_with("0x") {
+    if let Some(profile) = str.strip_prefix(PROFILE_ADDRESS_PREFIX) {
+        load_profile_address(profile)
+    } else if str.starts_with("0x") {
         AccountAddress::from_hex_literal(str).map_err(|err| {
             CliError::CommandArgumentError(format!("Failed to parse AccountAddress {}", err))
         })
     } else if let Ok(account_address) = AccountAddress::from_str(str) {
         Ok(account_address)
-    } else if let Some(Some(private_key)) =
+    } else if let Some(Some(account_address)) =
         CliConfig::load_profile(Some(str), ConfigSearchMode::CurrentDirAndParents)?
-            .map(|p| p.private_key)
+            .map(|p| p.derived_address())
     {
-        let public_key = private_key.public_key();
-        Ok(account_address_from_public_key(&public_key))
+        Ok(account_address)
     } else {
         Err(CliError::CommandArgumentError(
             "'--account' or '--profile' after using aptos init must be provided".to_string(),
@@ -979,6 +1701,18 @@ pub fn load_account_arg(str: &str) -> Result<AccountAddress, CliError> {
     }
 }
 
+/// Loads the account of a profile, for `profile:<name>` account args
+fn load_profile_address(profile: &str) -> CliTypedResult<AccountAddress> {
+    let config = CliConfig::load_profile(Some(profile), ConfigSearchMode::CurrentDirAndParents)?
+        .ok_or_else(|| CliError::ConfigNotFoundError(profile.to_string()))?;
+    config
+        .account
+        .or_else(|| config.derived_address())
+        .ok_or_else(|| {
+            CliError::CommandArgumentError(format!("Profile {} has no account", profile))
+        })
+}
+
 /// A wrapper around `AccountAddress` to allow for "_"
 #[derive(Clone, Copy, Debug)]
 pub struct MoveManifestAccountWrapper {
@@ -999,6 +1733,8 @@ impl FromStr for MoveManifestAccountWrapper {
 pub fn load_manifest_account_arg(str: &str) -> Result<Option<AccountAddress>, CliError> {
     if str == "_" {
         Ok(None)
+    } else if let Some(profile) = str.strip_prefix(PROFILE_ADDRESS_PREFIX) {
+        load_profile_address(profile).map(Some)
     } else if str.starts_with("0x") {
         AccountAddress::from_hex_literal(str)
             .map(Some)
@@ -1007,12 +1743,11 @@ pub fn load_manifest_account_arg(str: &str) -> Result<Option<AccountAddress>, Cl
             })
     } else if let Ok(account_address) = AccountAddress::from_str(str) {
         Ok(Some(account_address))
-    } else if let Some(Some(private_key)) =
+    } else if let Some(Some(account_address)) =
         CliConfig::load_profile(Some(str), ConfigSearchMode::CurrentDirAndParents)?
-            .map(|p| p.private_key)
+            .map(|p| p.derived_address())
     {
-        let public_key = private_key.public_key();
-        Ok(Some(account_address_from_public_key(&public_key)))
+        Ok(Some(account_address))
     } else {
         Err(CliError::CommandArgumentError(
             "Invalid Move manifest account address".to_string(),
@@ -1053,6 +1788,124 @@ pub trait CliCommand<T: Serialize + Send>: Sized + Send {
     }
 }
 
+/// The result of a command which submits a transaction
+///
+/// `--encode-only`, `--save-unsigned` and `--dry-run` stop the command before it submits its
+/// transaction, which is the command's successful result too.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Submission<T> {
+    Submitted(T),
+    NotSubmitted(NotSubmitted),
+}
+
+impl<T> Submission<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Submission<U> {
+        match self {
+            Submission::Submitted(inner) => Submission::Submitted(f(inner)),
+            Submission::NotSubmitted(not_submitted) => Submission::NotSubmitted(not_submitted),
+        }
+    }
+
+    /// The submitted transaction's result, failing if the command stopped before submitting it
+    pub fn submitted(self) -> CliTypedResult<T> {
+        match self {
+            Submission::Submitted(inner) => Ok(inner),
+            Submission::NotSubmitted(_) => Err(CliError::UnexpectedError(
+                "Transaction was not submitted".to_string(),
+            )),
+        }
+    }
+}
+
+/// What a command output instead of submitting its transaction
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum NotSubmitted {
+    /// `--save-unsigned`, with where the unsigned transaction was saved
+    UnsignedTransactionSaved(String),
+    /// `--encode-only`, with the encoding or the file it was written to
+    TransactionEncoded(String),
+    /// `--dry-run`, with the transaction that would have been sent
+    DryRun(Box<DryRunTransaction>),
+}
+
+/// The transaction a command would have submitted, output with `--dry-run`
+#[derive(Clone, Debug, Serialize)]
+pub struct DryRunTransaction {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
+    pub expiration_timestamp_secs: u64,
+    pub chain_id: u8,
+    /// The payload as shown by the REST API if simulated, or else as hex of its BCS
+    pub payload: serde_json::Value,
+    /// Outcome of simulating the transaction, if it was simulated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub simulation: Option<SimulationOutcome>,
+}
+
+/// Outcome of simulating a transaction
+#[derive(Clone, Debug, Serialize)]
+pub struct SimulationOutcome {
+    pub success: bool,
+    pub vm_status: String,
+    pub gas_used: u64,
+}
+
+impl DryRunTransaction {
+    /// The transaction, without simulating it, e.g. as it's already signed
+    pub fn new(transaction: &SignedTransaction) -> CliTypedResult<DryRunTransaction> {
+        Ok(DryRunTransaction {
+            sender: transaction.sender(),
+            sequence_number: transaction.sequence_number(),
+            max_gas_amount: transaction.max_gas_amount(),
+            gas_unit_price: transaction.gas_unit_price(),
+            expiration_timestamp_secs: transaction.expiration_timestamp_secs(),
+            chain_id: transaction.chain_id().id(),
+            payload: serde_json::Value::String(format!(
+                "0x{}",
+                hex::encode(bcs::to_bytes(transaction.payload())?)
+            )),
+            simulation: None,
+        })
+    }
+
+    /// The transaction, with the outcome of simulating it
+    ///
+    /// The transaction is signed with an empty signature, which simulation requires.
+    pub async fn simulate(
+        client: &Client,
+        raw_transaction: RawTransaction,
+        public_key: Ed25519PublicKey,
+    ) -> CliTypedResult<DryRunTransaction> {
+        let transaction = SignedTransaction::new(
+            raw_transaction,
+            public_key,
+            Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+        );
+        let mut dry_run = DryRunTransaction::new(&transaction)?;
+        let simulated = client
+            .simulate(&transaction)
+            .await?
+            .into_inner()
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                CliError::UnexpectedError("Simulation returned no transaction".to_string())
+            })?;
+        dry_run.payload = serde_json::to_value(&simulated.request.payload)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        dry_run.simulation = Some(SimulationOutcome {
+            success: simulated.info.success,
+            vm_status: simulated.info.vm_status,
+            gas_used: simulated.info.gas_used.into(),
+        });
+        Ok(dry_run)
+    }
+}
+
 /// A shortened transaction output
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TransactionSummary {
@@ -1172,18 +2025,23 @@ pub struct ChangeSummary {
 #[derive(Debug, Default, Parser)]
 pub struct FaucetOptions {
     /// URL for the faucet endpoint e.g. https://faucet.devnet.aptoslabs.com
-    #[clap(long)]
-    faucet_url: Option<reqwest::Url>,
+    ///
+    /// Multiple faucets can be given separated by spaces, and each is tried in order until
+    /// one succeeds
+    #[clap(long = "faucet-url", multiple_values = true)]
+    faucet_urls: Vec<reqwest::Url>,
 }
 
 impl FaucetOptions {
     pub fn new(faucet_url: Option<reqwest::Url>) -> Self {
-        FaucetOptions { faucet_url }
+        FaucetOptions {
+            faucet_urls: faucet_url.into_iter().collect(),
+        }
     }
 
-    pub fn faucet_url(&self, profile: &ProfileOptions) -> CliTypedResult<reqwest::Url> {
-        if let Some(ref faucet_url) = self.faucet_url {
-            Ok(faucet_url.clone())
+    pub fn faucet_urls(&self, profile: &ProfileOptions) -> CliTypedResult<Vec<reqwest::Url>> {
+        if !self.faucet_urls.is_empty() {
+            Ok(self.faucet_urls.clone())
         } else if let Some(Some(url)) = CliConfig::load_profile(
             profile.profile_name(),
             ConfigSearchMode::CurrentDirAndParents,
@@ -1191,6 +2049,7 @@ impl FaucetOptions {
         .map(|profile| profile.faucet_url)
         {
             reqwest::Url::parse(&url)
+                .map(|url| vec![url])
                 .map_err(|err| CliError::UnableToParse("config faucet_url", err.to_string()))
         } else {
             Err(CliError::CommandArgumentError("No faucet given.  Please add --faucet-url or add a faucet URL to the .aptos/config.yaml for the current profile".to_string()))
@@ -1198,6 +2057,39 @@ impl FaucetOptions {
     }
 }
 
+/// A gas unit price, or `auto` to choose one from the network
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GasUnitPrice {
+    Fixed(u64),
+    /// Estimated, from the percentile of recent transactions' prices if given
+    Auto(Option<u8>),
+}
+
+impl FromStr for GasUnitPrice {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            CliError::UnableToParse(
+                "gas unit price",
+                format!(
+                    "{} is not a number of Octas, `auto`, or `auto:<PERCENTILE>` from 0 to 100",
+                    s
+                ),
+            )
+        };
+        match s.split_once(':') {
+            Some(("auto", percentile)) => match percentile.parse::<u8>() {
+                Ok(percentile) if percentile <= 100 => Ok(GasUnitPrice::Auto(Some(percentile))),
+                _ => Err(invalid()),
+            },
+            None if s == "auto" => Ok(GasUnitPrice::Auto(None)),
+            None => s.parse().map(GasUnitPrice::Fixed).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+}
+
 /// Gas price options for manipulating how to prioritize transactions
 #[derive(Debug, Default, Eq, Parser, PartialEq)]
 pub struct GasOptions {
@@ -1209,9 +2101,11 @@ pub struct GasOptions {
     /// to be paid for a transaction.  This will prioritize the
     /// transaction with a higher gas unit price.
     ///
-    /// Without a value, it will determine the price based on the current estimated price
+    /// Without a value, or with `auto`, it will determine the price based on the current
+    /// estimated price.  With `auto:<PERCENTILE>`, e.g. `auto:90`, it will use that percentile of
+    /// the prices of recent transactions, to get in faster during congestion
     #[clap(long)]
-    pub gas_unit_price: Option<u64>,
+    pub gas_unit_price: Option<GasUnitPrice>,
     /// Maximum amount of gas units to be used to send this transaction
     ///
     /// The maximum amount of gas units willing to pay for the transaction.
@@ -1227,6 +2121,19 @@ pub struct GasOptions {
     pub max_gas: Option<u64>,
 }
 
+impl GasOptions {
+    /// The gas unit price to use, estimated from the network unless a fixed price is given
+    pub async fn gas_unit_price(&self, client: &Client) -> CliTypedResult<u64> {
+        match self.gas_unit_price {
+            Some(GasUnitPrice::Fixed(gas_unit_price)) => Ok(gas_unit_price),
+            Some(GasUnitPrice::Auto(percentile)) => {
+                estimate_gas_unit_price(client, percentile).await
+            }
+            None => estimate_gas_unit_price(client, None).await,
+        }
+    }
+}
+
 /// Common options for interacting with an account for a validator
 #[derive(Debug, Default, Parser)]
 pub struct TransactionOptions {
@@ -1249,11 +2156,148 @@ pub struct TransactionOptions {
     pub(crate) gas_options: GasOptions,
     #[clap(flatten)]
     pub(crate) prompt_options: PromptOptions,
+
+    /// Save the transaction unsigned to this file instead of submitting it
+    ///
+    /// The transaction can then be signed with `aptos transaction sign`, e.g. on an offline
+    /// machine, and submitted with `aptos transaction submit`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) save_unsigned: Option<PathBuf>,
+
+    /// Encode the transaction's payload as BCS instead of submitting it
+    ///
+    /// For external signers and multisig tooling.  Prints the encoding as hex, or writes the
+    /// bytes to `--encode-output-file`
+    #[clap(long)]
+    pub(crate) encode_only: bool,
+
+    /// With `--encode-only`, encode the whole unsigned `RawTransaction` instead of its payload
+    ///
+    /// The sender, sequence number, and gas are filled in as for `--save-unsigned`
+    #[clap(long)]
+    pub(crate) encode_raw_transaction: bool,
+
+    /// With `--encode-only`, write the BCS bytes to this file instead of printing them as hex
+    #[clap(long, parse(from_os_str))]
+    pub(crate) encode_output_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) wait_options: WaitOptions,
+}
+
+/// How long to wait for a transaction after submitting it
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WaitFor {
+    /// Until the fullnode accepts the transaction into its mempool
+    Submitted,
+    /// Until the transaction is committed, which is final as Aptos doesn't fork
+    Executed,
+}
+
+impl Default for WaitFor {
+    fn default() -> Self {
+        WaitFor::Executed
+    }
+}
+
+impl Display for WaitFor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            WaitFor::Submitted => "submitted",
+            WaitFor::Executed => "executed",
+        })
+    }
+}
+
+impl FromStr for WaitFor {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().trim() {
+            "submitted" => Ok(WaitFor::Submitted),
+            // Committed transactions are final, there's nothing more to wait for
+            "executed" | "finality" => Ok(WaitFor::Executed),
+            _ => Err(CliError::CommandArgumentError(
+                "Invalid wait, must be one of [submitted, executed, finality]".to_string(),
+            )),
+        }
+    }
+}
+
+/// Options for waiting on a submitted transaction
+#[derive(Debug, Default, Parser)]
+pub struct WaitOptions {
+    /// What to wait for after submitting the transaction, one of [submitted, executed, finality]
+    ///
+    /// `submitted` returns once the fullnode accepts the transaction, with it pending.
+    /// `executed` waits for it to be committed, and fails if it failed execution.  Committed
+    /// transactions are final, so `finality` is the same as `executed`
+    #[clap(long, default_value_t = WaitFor::Executed)]
+    pub(crate) wait_for: WaitFor,
+
+    /// Seconds to wait for the transaction to be committed
+    ///
+    /// Defaults to waiting until the transaction expires.  The transaction may still be committed
+    /// after timing out
+    #[clap(long)]
+    pub(crate) timeout_secs: Option<u64>,
+}
+
+impl WaitOptions {
+    /// Submits the signed transaction, and waits for it by `--wait-for`
+    ///
+    /// The transaction is polled for until it's committed, it expires, or `--timeout-secs`.
+    pub async fn submit_and_wait(
+        &self,
+        client: &Client,
+        transaction: &SignedTransaction,
+    ) -> CliTypedResult<Submission<Transaction>> {
+        if dry_run() {
+            return Ok(Submission::NotSubmitted(NotSubmitted::DryRun(Box::new(
+                DryRunTransaction::new(transaction)?,
+            ))));
+        }
+        let pending_transaction = client
+            .submit(transaction)
+            .await
+            .map_err(submission_error)?
+            .into_inner();
+        self.wait(client, transaction, pending_transaction)
+            .await
+            .map(Submission::Submitted)
+    }
+
+    /// Waits for the submitted transaction by `--wait-for`
+    pub async fn wait(
+        &self,
+        client: &Client,
+        transaction: &SignedTransaction,
+        pending_transaction: PendingTransaction,
+    ) -> CliTypedResult<Transaction> {
+        if self.wait_for == WaitFor::Submitted {
+            return Ok(Transaction::PendingTransaction(pending_transaction));
+        }
+
+        let _progress = Progress::spinner(&format!(
+            "Waiting for transaction {} to be committed",
+            pending_transaction.hash
+        ));
+        client
+            .wait_for_transaction_by_hash(
+                transaction.clone().committed_hash(),
+                transaction.expiration_timestamp_secs(),
+                Some(MAX_SERVER_LAG_WAIT),
+                self.timeout_secs.map(Duration::from_secs),
+            )
+            .await
+            .map(|response| response.into_inner())
+            .map_err(submission_error)
+    }
 }
 
 impl TransactionOptions {
     /// Builds a rest client
-    fn rest_client(&self) -> CliTypedResult<Client> {
+    pub(crate) fn rest_client(&self) -> CliTypedResult<Client> {
         self.rest_options.client(&self.profile_options)
     }
 
@@ -1267,8 +2311,45 @@ impl TransactionOptions {
         )
     }
 
+    /// Retrieves the signer of transactions and the associated address
+    ///
+    /// A private key on the command line takes precedence over a Ledger in the profile
+    pub fn get_signer_and_address(&self) -> CliTypedResult<(TransactionSigner, AccountAddress)> {
+        if self
+            .private_key_options
+            .extract_private_key_cli(self.encoding_options.encoding)?
+            .is_none()
+        {
+            if let Some(profile_config) = CliConfig::load_profile(
+                self.profile_options.profile_name(),
+                ConfigSearchMode::CurrentDirAndParents,
+            )? {
+                if let Some(derivation_path) = profile_config.ledger_derivation_path {
+                    let public_key = match profile_config.public_key {
+                        Some(public_key) => public_key,
+                        None => Ledger::open()?.public_key(&derivation_path)?,
+                    };
+                    let address = self
+                        .sender_account
+                        .or(profile_config.account)
+                        .unwrap_or_else(|| account_address_from_public_key(&public_key));
+                    return Ok((
+                        TransactionSigner::Ledger {
+                            derivation_path,
+                            public_key,
+                        },
+                        address,
+                    ));
+                }
+            }
+        }
+
+        let (private_key, address) = self.get_key_and_address()?;
+        Ok((TransactionSigner::PrivateKey(private_key), address))
+    }
+
     pub fn sender_address(&self) -> CliTypedResult<AccountAddress> {
-        Ok(self.get_key_and_address()?.1)
+        Ok(self.get_signer_and_address()?.1)
     }
 
     /// Gets the auth key by account address. We need to fetch the auth key from Rest API rather than creating an
@@ -1290,35 +2371,135 @@ impl TransactionOptions {
     pub async fn submit_transaction(
         &self,
         payload: TransactionPayload,
-    ) -> CliTypedResult<Transaction> {
+    ) -> CliTypedResult<Submission<Transaction>> {
+        if self.encode_only {
+            return Ok(Submission::NotSubmitted(self.encode(payload).await?));
+        }
+        if let Some(ref unsigned_file) = self.save_unsigned {
+            let raw_transaction = self
+                .build_unsigned_transaction(payload, DEFAULT_UNSIGNED_EXPIRATION_SECS)
+                .await?;
+            UnsignedTransaction::new(&raw_transaction)?.save(unsigned_file)?;
+            return Ok(Submission::NotSubmitted(
+                NotSubmitted::UnsignedTransactionSaved(format!(
+                    "Unsigned transaction saved to {}",
+                    unsigned_file.display()
+                )),
+            ));
+        }
+
         let client = self.rest_client()?;
-        let (sender_key, sender_address) = self.get_key_and_address()?;
+        let (signer, sender_address) = self.get_signer_and_address()?;
+        // Without a config the key came from the command line, so there's no profile to record
+        if let Ok(config) = CliConfig::load(ConfigSearchMode::CurrentDirAndParents) {
+            audit_log::record_profile(
+                &config.active_profile_name(self.profile_options.profile_name()),
+            );
+        }
 
-        // Get sequence number for account
-        let sequence_number = self.sequence_number(sender_address).await?;
+        // Sequence numbers are shared by all transactions this process sends from the account,
+        // so commands can send several without waiting for each to be committed
+        let sequencer = AccountSequencer::for_account(&client, sender_address);
+        let mut sequence_number = sequencer.next().await?;
+        let transaction_factory = match self
+            .transaction_factory(&client, &payload, &signer, sender_address, sequence_number)
+            .await
+        {
+            Ok(transaction_factory) => transaction_factory,
+            Err(err) => {
+                sequencer.release(sequence_number).await;
+                return Err(err);
+            }
+        };
 
+        // Stop with the transaction as it would be sent, without signing it
+        if dry_run() {
+            sequencer.release(sequence_number).await;
+            let raw_transaction = transaction_factory
+                .payload(payload)
+                .sender(sender_address)
+                .sequence_number(sequence_number)
+                .build();
+            return Ok(Submission::NotSubmitted(NotSubmitted::DryRun(Box::new(
+                DryRunTransaction::simulate(&client, raw_transaction, signer.public_key()).await?,
+            ))));
+        }
+
+        let mut repaired = false;
+        loop {
+            let transaction = match signer.sign(
+                transaction_factory
+                    .payload(payload.clone())
+                    .sender(sender_address)
+                    .sequence_number(sequence_number)
+                    .build(),
+            ) {
+                Ok(transaction) => transaction,
+                Err(err) => {
+                    sequencer.release(sequence_number).await;
+                    return Err(err);
+                }
+            };
+            match client.submit(&transaction).await {
+                Ok(pending_transaction) => {
+                    audit_log::record_transaction(transaction.clone().committed_hash());
+                    return self
+                        .wait_options
+                        .wait(&client, &transaction, pending_transaction.into_inner())
+                        .await
+                        .map(Submission::Submitted);
+                }
+                // Something else sent from the account, so sign again with its sequence number
+                Err(err) if !repaired && is_sequence_number_too_old(&err) => {
+                    sequencer.repair().await;
+                    sequence_number = sequencer.next().await?;
+                    repaired = true;
+                }
+                Err(err) => {
+                    sequencer.release(sequence_number).await;
+                    return Err(submission_error(err));
+                }
+            }
+        }
+    }
+
+    /// Builds the factory of the transaction, with its gas unit price and maximum gas
+    ///
+    /// Without `--max-gas`, the gas is estimated by simulating the transaction.  Either way the
+    /// cost is confirmed, unless the gas unit price was given or it's a dry run.
+    async fn transaction_factory(
+        &self,
+        client: &Client,
+        payload: &TransactionPayload,
+        signer: &TransactionSigner,
+        sender_address: AccountAddress,
+        sequence_number: u64,
+    ) -> CliTypedResult<TransactionFactory> {
         // Ask to confirm price if the gas unit price is estimated above the lowest value when
         // it is automatically estimated
-        let ask_to_confirm_price;
-        let gas_unit_price = if let Some(gas_unit_price) = self.gas_options.gas_unit_price {
-            ask_to_confirm_price = false;
-            gas_unit_price
-        } else {
-            let gas_unit_price = client.estimate_gas_price().await?.into_inner().gas_estimate;
-
-            ask_to_confirm_price = true;
-            gas_unit_price
+        let ask_to_confirm_price = !matches!(
+            self.gas_options.gas_unit_price,
+            Some(GasUnitPrice::Fixed(_))
+        );
+        let gas_unit_price = self.gas_options.gas_unit_price(client).await?;
+        // There's nothing to confirm when the transaction won't be submitted
+        let confirm = |message: &str| {
+            if dry_run() {
+                Ok(())
+            } else {
+                prompt_yes_with_override(message, self.prompt_options)
+            }
         };
 
         let max_gas = if let Some(max_gas) = self.gas_options.max_gas {
             // If the gas unit price was estimated ask, but otherwise you've chosen hwo much you want to spend
             if ask_to_confirm_price {
                 let message = format!("Do you want to submit transaction for a maximum of {} Octas at a gas unit price of {} Octas?",  max_gas * gas_unit_price, gas_unit_price);
-                prompt_yes_with_override(&message, self.prompt_options)?;
+                confirm(&message)?;
             }
             max_gas
         } else {
-            let transaction_factory = TransactionFactory::new(chain_id(&client).await?)
+            let transaction_factory = TransactionFactory::new(chain_id(client).await?)
                 .with_gas_unit_price(gas_unit_price);
 
             let unsigned_transaction = transaction_factory
@@ -1327,60 +2508,106 @@ impl TransactionOptions {
                 .sequence_number(sequence_number)
                 .build();
 
-            let signed_transaction = SignedTransaction::new(
-                unsigned_transaction,
-                sender_key.public_key(),
-                Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
-            );
-            // TODO: Cleanup to use the gas price estimation here
-            let simulated_txn = client
-                .simulate_bcs_with_gas_estimation(&signed_transaction, true, false)
-                .await?
-                .into_inner();
-
-            // Check if the transaction will pass, if it doesn't then fail
-            // TODO: Add move resolver so we can explain the VM status with a proper error map
-            let status = simulated_txn.info.status();
-            if !status.is_success() {
-                let status = client.explain_vm_status(status);
-                return Err(CliError::SimulationError(status));
-            }
-
-            // Take the gas used and use a headroom factor on it
-            let adjusted_max_gas = adjust_gas_headroom(
-                simulated_txn.info.gas_used(),
-                simulated_txn
-                    .transaction
-                    .as_signed_user_txn()
-                    .expect("Should be signed user transaction")
-                    .max_gas_amount(),
-            );
+            let (gas_used, adjusted_max_gas) =
+                simulate_gas(client, unsigned_transaction, signer.public_key()).await?;
 
             // Ask if you want to accept the estimate amount
             let upper_cost_bound = adjusted_max_gas * gas_unit_price;
-            let lower_cost_bound = simulated_txn.info.gas_used() * gas_unit_price;
+            let lower_cost_bound = gas_used * gas_unit_price;
             let message = format!(
                     "Do you want to submit a transaction for a range of [{} - {}] Octas at a gas unit price of {} Octas?",
                     lower_cost_bound,
                     upper_cost_bound,
                     gas_unit_price);
-            prompt_yes_with_override(&message, self.prompt_options)?;
+            confirm(&message)?;
             adjusted_max_gas
         };
 
-        // Sign and submit transaction
+        Ok(TransactionFactory::new(chain_id(client).await?)
+            .with_gas_unit_price(gas_unit_price)
+            .with_max_gas_amount(max_gas))
+    }
+
+    /// Encodes the payload, or the unsigned transaction, for `--encode-only`
+    ///
+    /// Returns the hex encoding, or the file written to, as the command's result.
+    async fn encode(&self, payload: TransactionPayload) -> CliTypedResult<NotSubmitted> {
+        let bytes = if self.encode_raw_transaction {
+            let raw_transaction = self
+                .build_unsigned_transaction(payload, DEFAULT_UNSIGNED_EXPIRATION_SECS)
+                .await?;
+            bcs::to_bytes(&raw_transaction)?
+        } else {
+            bcs::to_bytes(&payload)?
+        };
+        Ok(NotSubmitted::TransactionEncoded(
+            match self.encode_output_file {
+                Some(ref output_file) => {
+                    write_to_file(output_file, "Encoded transaction", &bytes)?;
+                    format!("BCS written to {}", output_file.display())
+                }
+                None => format!("0x{}", hex::encode(bytes)),
+            },
+        ))
+    }
+
+    /// Builds the transaction without signing it, for `--save-unsigned` and
+    /// `aptos transaction build`
+    ///
+    /// The sender is `--sender-account`, or the profile's account, so the private key isn't
+    /// needed.  Without `--max-gas`, the gas is estimated by simulating with the signer's or
+    /// profile's public key.
+    pub async fn build_unsigned_transaction(
+        &self,
+        payload: TransactionPayload,
+        expiration_secs: u64,
+    ) -> CliTypedResult<RawTransaction> {
+        let client = self.rest_client()?;
+        let public_key = self
+            .get_signer_and_address()
+            .map(|(signer, _)| signer.public_key())
+            .or_else(|_| self.profile_options.public_key());
+        let sender_address = match self.sender_account {
+            Some(sender_address) => sender_address,
+            None => self.profile_options.account_address().or_else(|err| {
+                public_key
+                    .as_ref()
+                    .map(account_address_from_public_key)
+                    .map_err(|_| err)
+            })?,
+        };
+
+        let sequence_number = self.sequence_number(sender_address).await?;
+        let gas_unit_price = self.gas_options.gas_unit_price(&client).await?;
         let transaction_factory = TransactionFactory::new(chain_id(&client).await?)
             .with_gas_unit_price(gas_unit_price)
-            .with_max_gas_amount(max_gas);
-        let sender_account = &mut LocalAccount::new(sender_address, sender_key, sequence_number);
-        let transaction =
-            sender_account.sign_with_transaction_builder(transaction_factory.payload(payload));
-        let response = client
-            .submit_and_wait(&transaction)
-            .await
-            .map_err(|err| CliError::ApiError(err.to_string()))?;
+            .with_transaction_expiration_time(expiration_secs);
+
+        let max_gas = match (self.gas_options.max_gas, public_key) {
+            (Some(max_gas), _) => max_gas,
+            (None, Ok(public_key)) => {
+                let unsigned_transaction = transaction_factory
+                    .payload(payload.clone())
+                    .sender(sender_address)
+                    .sequence_number(sequence_number)
+                    .build();
+                simulate_gas(&client, unsigned_transaction, public_key)
+                    .await?
+                    .1
+            }
+            (None, Err(_)) => {
+                return Err(CliError::CommandArgumentError(
+                    "--max-gas is required without a public key to estimate gas with".to_string(),
+                ))
+            }
+        };
 
-        Ok(response.into_inner())
+        Ok(transaction_factory
+            .with_max_gas_amount(max_gas)
+            .payload(payload)
+            .sender(sender_address)
+            .sequence_number(sequence_number)
+            .build())
     }
 
     pub async fn simulate_transaction(
@@ -1390,7 +2617,7 @@ impl TransactionOptions {
         amount_transfer: Option<u64>,
     ) -> CliTypedResult<UserTransaction> {
         let client = self.rest_client()?;
-        let (sender_key, sender_address) = self.get_key_and_address()?;
+        let (signer, sender_address) = self.get_signer_and_address()?;
 
         // Get sequence number for account
         let sequence_number = get_sequence_number(&client, sender_address).await?;
@@ -1439,25 +2666,90 @@ impl TransactionOptions {
 
         let signed_transaction = SignedTransaction::new(
             unsigned_transaction,
-            sender_key.public_key(),
+            signer.public_key(),
             Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
         );
         let txns = client.simulate(&signed_transaction).await?.into_inner();
         Ok(txns.first().unwrap().clone())
     }
 
+    /// The gas unit price from `--gas-unit-price`, estimated from the network unless fixed
     pub async fn estimate_gas_price(&self) -> CliTypedResult<u64> {
-        let client = self.rest_client()?;
-        client
-            .estimate_gas_price()
-            .await
-            .map(|inner| inner.into_inner().gas_estimate)
-            .map_err(|err| {
-                CliError::UnexpectedError(format!(
-                    "Failed to retrieve gas price estimate {:?}",
-                    err
-                ))
-            })
+        self.gas_options.gas_unit_price(&self.rest_client()?).await
+    }
+}
+
+/// Simulates the transaction with an empty signature, returning the gas used and the max gas to
+/// submit it with
+async fn simulate_gas(
+    client: &Client,
+    unsigned_transaction: RawTransaction,
+    public_key: Ed25519PublicKey,
+) -> CliTypedResult<(u64, u64)> {
+    let signed_transaction = SignedTransaction::new(
+        unsigned_transaction,
+        public_key,
+        Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+    );
+    // TODO: Cleanup to use the gas price estimation here
+    let simulated_txn = client
+        .simulate_bcs_with_gas_estimation(&signed_transaction, true, false)
+        .await?
+        .into_inner();
+
+    // Check if the transaction will pass, if it doesn't then fail
+    // TODO: Add move resolver so we can explain the VM status with a proper error map
+    let status = simulated_txn.info.status();
+    if !status.is_success() {
+        let status = client.explain_vm_status(status);
+        return Err(CliError::SimulationError(status));
+    }
+
+    // Take the gas used and use a headroom factor on it
+    let adjusted_max_gas = adjust_gas_headroom(
+        simulated_txn.info.gas_used(),
+        simulated_txn
+            .transaction
+            .as_signed_user_txn()
+            .expect("Should be signed user transaction")
+            .max_gas_amount(),
+    );
+    Ok((simulated_txn.info.gas_used(), adjusted_max_gas))
+}
+
+/// Signs transactions for the sender of a command
+pub enum TransactionSigner {
+    PrivateKey(Ed25519PrivateKey),
+    /// A key on a Ledger, where the user approves each transaction
+    Ledger {
+        derivation_path: String,
+        public_key: Ed25519PublicKey,
+    },
+}
+
+impl TransactionSigner {
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        match self {
+            TransactionSigner::PrivateKey(private_key) => private_key.public_key(),
+            TransactionSigner::Ledger { public_key, .. } => public_key.clone(),
+        }
+    }
+
+    pub fn sign(&self, raw_transaction: RawTransaction) -> CliTypedResult<SignedTransaction> {
+        match self {
+            TransactionSigner::PrivateKey(private_key) => raw_transaction
+                .sign(private_key, private_key.public_key())
+                .map(|transaction| transaction.into_inner())
+                .map_err(|err| CliError::UnexpectedError(err.to_string())),
+            TransactionSigner::Ledger {
+                derivation_path,
+                public_key,
+            } => Ledger::open()?.sign_transaction(
+                derivation_path,
+                public_key.clone(),
+                raw_transaction,
+            ),
+        }
     }
 }
 
@@ -1495,3 +2787,132 @@ pub struct RotationProofChallenge {
     pub current_auth_key: AccountAddress,
     pub new_public_key: Vec<u8>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_keygen::KeyGen;
+
+    fn extending(base: Option<&str>) -> ProfileConfig {
+        ProfileConfig {
+            extends: base.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_inherit_from_multiple_bases() {
+        let two = AccountAddress::from_hex_literal("0x2").unwrap();
+        let mut config = CliConfig::default();
+        config.insert_profile(
+            "root",
+            ProfileConfig {
+                network: Some(Network::Testnet),
+                rest_url: Some("https://fullnode.testnet.aptoslabs.com/".to_string()),
+                faucet_url: Some("https://faucet.testnet.aptoslabs.com/".to_string()),
+                named_addresses: Some(BTreeMap::from([
+                    ("a".to_string(), AccountAddress::ONE),
+                    ("b".to_string(), AccountAddress::ONE),
+                ])),
+                ..Default::default()
+            },
+        );
+        config.insert_profile(
+            "middle",
+            ProfileConfig {
+                rest_url: Some("http://localhost:8080/".to_string()),
+                named_addresses: Some(BTreeMap::from([("b".to_string(), two)])),
+                private_key: Some(KeyGen::from_seed([0; 32]).generate_ed25519_private_key()),
+                ..extending(Some("root"))
+            },
+        );
+
+        let mut profile = extending(Some("middle"));
+        config.inherit_from_base("leaf", &mut profile).unwrap();
+        assert_eq!(profile.network, Some(Network::Testnet));
+        assert_eq!(profile.rest_url.as_deref(), Some("http://localhost:8080/"));
+        assert_eq!(
+            profile.faucet_url.as_deref(),
+            Some("https://faucet.testnet.aptoslabs.com/")
+        );
+        // The closest base's named addresses win, and keys are never inherited
+        assert_eq!(
+            profile.named_addresses,
+            Some(BTreeMap::from([
+                ("a".to_string(), AccountAddress::ONE),
+                ("b".to_string(), two),
+            ]))
+        );
+        assert!(profile.private_key.is_none());
+    }
+
+    #[test]
+    fn test_inherit_rejects_cycles_and_missing_bases() {
+        let mut config = CliConfig::default();
+        config.insert_profile("a", extending(Some("b")));
+        config.insert_profile("b", extending(Some("a")));
+        config.insert_profile("orphan", extending(Some("missing")));
+
+        let mut profile = extending(Some("b"));
+        assert!(matches!(
+            config.inherit_from_base("a", &mut profile),
+            Err(CliError::ConfigLoadError(name, reason)) if name == "a" && reason.contains("cycle")
+        ));
+
+        let mut profile = extending(Some("a"));
+        assert!(matches!(
+            config.inherit_from_base("c", &mut profile),
+            Err(CliError::ConfigLoadError(_, reason)) if reason.contains("cycle")
+        ));
+
+        let mut profile = extending(Some("missing"));
+        assert!(matches!(
+            config.inherit_from_base("orphan", &mut profile),
+            Err(CliError::ConfigLoadError(_, reason)) if reason.contains("missing not found")
+        ));
+    }
+
+    #[test]
+    fn test_rename_profile() {
+        let mut config = CliConfig::default();
+        config.insert_profile("base", extending(None));
+        config.insert_profile("child", extending(Some("base")));
+        config.insert_profile("other", extending(None));
+        config.default_profile = Some("base".to_string());
+
+        config.rename_profile("base", "renamed").unwrap();
+        assert!(!config.has_profile("base"));
+        assert!(config.has_profile("renamed"));
+        assert_eq!(config.default_profile.as_deref(), Some("renamed"));
+        assert_eq!(
+            config.profiles_extending("renamed"),
+            vec!["child".to_string()]
+        );
+
+        // Renaming another profile leaves the default alone
+        config.rename_profile("other", "another").unwrap();
+        assert_eq!(config.default_profile.as_deref(), Some("renamed"));
+        assert!(config.rename_profile("missing", "found").is_err());
+    }
+
+    #[test]
+    fn test_delete_profile() {
+        let mut config = CliConfig::default();
+        config.insert_profile("base", extending(None));
+        config.insert_profile("child", extending(Some("base")));
+        config.default_profile = Some("base".to_string());
+
+        assert!(matches!(
+            config.check_deletable("base"),
+            Err(CliError::CommandArgumentError(reason)) if reason.contains("[child]")
+        ));
+        assert!(config.check_deletable("missing").is_err());
+
+        config.check_deletable("child").unwrap();
+        assert!(config.delete_profile("child").is_some());
+        config.check_deletable("base").unwrap();
+        assert!(config.delete_profile("base").is_some());
+        assert_eq!(config.default_profile, None);
+        assert!(config.profiles.unwrap().is_empty());
+    }
+}
@@ -2,13 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    common::types::{CliError, CliTypedResult, PromptOptions},
+    common::{
+        http::HttpConfig,
+        output,
+        progress::Progress,
+        types::{CliError, CliTypedResult, ExitCode, PromptOptions},
+    },
+    config::{audit_log, GlobalConfig},
     CliResult,
 };
 use aptos_build_info::build_information;
 use aptos_logger::{debug, Level};
 use aptos_rest_client::aptos_api_types::HashValue;
-use aptos_rest_client::{Account, Client};
+use aptos_rest_client::{Account, Client, Transaction};
 use aptos_telemetry::service::telemetry_is_disabled;
 use aptos_types::{chain_id::ChainId, transaction::authenticator::AuthenticationKey};
 use itertools::Itertools;
@@ -24,9 +30,44 @@ use std::{
     io::Write,
     path::{Path, PathBuf},
     str::FromStr,
+    sync::atomic::{AtomicBool, AtomicI32, Ordering},
     time::{Duration, Instant},
 };
 
+/// Whether prompts are disabled for this invocation, see `GlobalConfig::assume_yes`
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Disables all prompts for commands run afterwards
+pub fn set_assume_yes(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+/// Whether confirmations are assumed to be yes, and any other input fails instead of prompting
+pub fn assume_yes() -> bool {
+    ASSUME_YES.load(Ordering::Relaxed)
+}
+
+/// Whether commands stop before submitting their transactions, see `--dry-run`
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Makes commands run afterwards stop before submitting any transaction
+pub fn set_dry_run(dry_run: bool) {
+    DRY_RUN.store(dry_run, Ordering::Relaxed);
+}
+
+/// Whether transactions are only built and simulated, rather than submitted
+pub fn dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Exit code of the last command to fail, for `main` to exit with
+static EXIT_CODE: AtomicI32 = AtomicI32::new(ExitCode::Unexpected as i32);
+
+/// The code to exit with when a command fails, see `ExitCode`
+pub fn exit_code() -> i32 {
+    EXIT_CODE.load(Ordering::Relaxed)
+}
+
 /// Prompts for confirmation until a yes or no is given explicitly
 pub fn prompt_yes(prompt: &str) -> bool {
     let mut result: Result<bool, ()> = Err(());
@@ -75,8 +116,16 @@ pub async fn to_common_result<T: Serialize>(
         send_telemetry_event(command, latency, !is_err, error).await;
     }
 
+    if let Err(ref error) = result {
+        EXIT_CODE.store(error.exit_code() as i32, Ordering::Relaxed);
+    }
+    audit_log::log_command(
+        command,
+        result.as_ref().err().map(|error| error.to_string()),
+    );
+
     let result: ResultWrapper<T> = result.into();
-    let string = serde_json::to_string_pretty(&result).unwrap();
+    let string = output::format_output(&result);
     if is_err {
         Err(string)
     } else {
@@ -95,6 +144,15 @@ async fn send_telemetry_event(
     success: bool,
     error: Option<&str>,
 ) {
+    // Organizations can disable or redirect telemetry in the global config
+    let telemetry_config = GlobalConfig::load()
+        .ok()
+        .and_then(|config| config.telemetry)
+        .unwrap_or_default();
+    if !telemetry_config.is_enabled() {
+        return;
+    }
+
     // Collect the build information
     let build_information = cli_build_information();
 
@@ -105,6 +163,7 @@ async fn send_telemetry_event(
         latency,
         success,
         error,
+        telemetry_config.options(),
     )
     .await;
 }
@@ -120,20 +179,35 @@ async fn send_telemetry_event(
 /// }
 ///
 /// {
-///   "Error":"Failed to run command"
+///   "Error":"Failed to run command",
+///   "ErrorType":"CommandArgumentError"
 /// }
 ///
+/// This is the schema at `OUTPUT_SCHEMA_VERSION`, scripts can match on `ErrorType` rather than
+/// the message, which may change.
 #[derive(Debug, Serialize)]
+#[serde(untagged)]
 enum ResultWrapper<T> {
-    Result(T),
-    Error(String),
+    Result {
+        #[serde(rename = "Result")]
+        result: T,
+    },
+    Error {
+        #[serde(rename = "Error")]
+        error: String,
+        #[serde(rename = "ErrorType")]
+        error_type: &'static str,
+    },
 }
 
 impl<T> From<CliTypedResult<T>> for ResultWrapper<T> {
     fn from(result: CliTypedResult<T>) -> Self {
         match result {
-            Ok(inner) => ResultWrapper::Result(inner),
-            Err(inner) => ResultWrapper::Error(inner.to_string()),
+            Ok(result) => ResultWrapper::Result { result },
+            Err(error) => ResultWrapper::Error {
+                error_type: error.to_str(),
+                error: error.to_string(),
+            },
         }
     }
 }
@@ -154,7 +228,9 @@ pub fn check_if_file_exists(file: &Path, prompt_options: PromptOptions) -> CliTy
 }
 
 pub fn prompt_yes_with_override(prompt: &str, prompt_options: PromptOptions) -> CliTypedResult<()> {
-    if prompt_options.assume_no || (!prompt_options.assume_yes && !prompt_yes(prompt)) {
+    if prompt_options.assume_no
+        || (!prompt_options.assume_yes && !assume_yes() && !prompt_yes(prompt))
+    {
         Err(CliError::AbortedError)
     } else {
         Ok(())
@@ -240,6 +316,65 @@ pub async fn get_auth_key(
     Ok(get_account(client, address).await?.authentication_key)
 }
 
+/// Number of the latest transactions whose prices are used for `--gas-unit-price auto:<PERCENTILE>`
+const RECENT_TRANSACTIONS: u16 = 100;
+
+/// Estimates a gas unit price, from the percentile of recent transactions' prices if given
+///
+/// The percentile price is never below the node's lowest estimate, so that an idle network with
+/// few recent transactions doesn't lead to a price too low to be accepted.
+pub async fn estimate_gas_unit_price(
+    client: &Client,
+    percentile: Option<u8>,
+) -> CliTypedResult<u64> {
+    let estimation = client
+        .estimate_gas_price()
+        .await
+        .map_err(|err| {
+            CliError::ApiError(format!("Failed to retrieve gas price estimate: {}", err))
+        })?
+        .into_inner();
+    let percentile = match percentile {
+        Some(percentile) => percentile,
+        None => return Ok(estimation.gas_estimate),
+    };
+
+    let floor = estimation
+        .deprioritized_gas_estimate
+        .unwrap_or(estimation.gas_estimate);
+    let prices = recent_gas_unit_prices(client).await?;
+    Ok(percentile_of(&prices, percentile)
+        .map(|price| price.max(floor))
+        .unwrap_or(estimation.gas_estimate))
+}
+
+/// Gas unit prices of the latest user transactions, sorted
+pub async fn recent_gas_unit_prices(client: &Client) -> CliTypedResult<Vec<u64>> {
+    let version = client.get_ledger_information().await?.into_inner().version;
+    let start = (version + 1).saturating_sub(RECENT_TRANSACTIONS as u64);
+    let mut prices: Vec<u64> = client
+        .get_transactions(Some(start), Some(RECENT_TRANSACTIONS))
+        .await?
+        .into_inner()
+        .into_iter()
+        .filter_map(|transaction| match transaction {
+            Transaction::UserTransaction(transaction) => Some(transaction.request.gas_unit_price.0),
+            _ => None,
+        })
+        .collect();
+    prices.sort_unstable();
+    Ok(prices)
+}
+
+/// The nearest-rank percentile of sorted values
+pub fn percentile_of(sorted: &[u64], percentile: u8) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percentile as usize * sorted.len() + 99) / 100;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
 /// Retrieves the chain id from the rest client
 pub async fn chain_id(rest_client: &Client) -> CliTypedResult<ChainId> {
     let state = rest_client
@@ -310,6 +445,13 @@ pub fn create_dir_if_not_exist(dir: &Path) -> CliTypedResult<()> {
 
 /// Reads a line from input
 pub fn read_line(input_name: &'static str) -> CliTypedResult<String> {
+    if assume_yes() {
+        return Err(CliError::CommandArgumentError(format!(
+            "{} is required, but prompts are disabled by the assume_yes global config",
+            input_name
+        )));
+    }
+
     let mut input_buf = String::new();
     let _ = std::io::stdin()
         .read_line(&mut input_buf)
@@ -318,13 +460,100 @@ pub fn read_line(input_name: &'static str) -> CliTypedResult<String> {
     Ok(input_buf)
 }
 
-/// Fund account (and possibly create it) from a faucet
+/// Reads a secret from the terminal without echoing it
+pub fn read_hidden(input_name: &'static str, prompt: &str) -> CliTypedResult<String> {
+    if assume_yes() {
+        return Err(CliError::CommandArgumentError(format!(
+            "{} is required, but prompts are disabled by the assume_yes global config",
+            input_name
+        )));
+    }
+
+    rpassword::prompt_password(prompt).map_err(|err| CliError::IO(input_name.to_string(), err))
+}
+
+/// Attempts at each faucet, including the first, before falling back to the next faucet
+pub const DEFAULT_FAUCET_ATTEMPTS: u32 = 4;
+/// Wait before the first retry of a faucet, doubling for each retry after it
+const FAUCET_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// Longest wait between retries, however long the faucet asks to wait
+const FAUCET_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Fund account (and possibly create it) from a faucet, retrying if it fails temporarily
 pub async fn fund_account(
     faucet_url: Url,
     num_octas: u64,
     address: AccountAddress,
+    http_config: &HttpConfig,
+) -> CliTypedResult<Vec<HashValue>> {
+    fund_account_with_retries(
+        &[faucet_url],
+        num_octas,
+        address,
+        http_config,
+        DEFAULT_FAUCET_ATTEMPTS,
+    )
+    .await
+}
+
+/// Fund account (and possibly create it) from the first faucet that succeeds
+///
+/// Each faucet is retried with exponential backoff on connection errors, rate limiting (429)
+/// and server errors (5xx), before falling back to the next one.
+pub async fn fund_account_with_retries(
+    faucet_urls: &[Url],
+    num_octas: u64,
+    address: AccountAddress,
+    http_config: &HttpConfig,
+    attempts: u32,
 ) -> CliTypedResult<Vec<HashValue>> {
-    let response = reqwest::Client::new()
+    let client = http_config.client()?;
+    let mut errors = Vec::new();
+    let progress = Progress::spinner(&format!("Funding {} from the faucet", address));
+
+    for faucet_url in faucet_urls {
+        let mut backoff = FAUCET_INITIAL_BACKOFF;
+        for attempt in 1..=attempts.max(1) {
+            match request_faucet_funds(&client, faucet_url, num_octas, address).await {
+                Ok(hashes) => return Ok(hashes),
+                Err(FaucetError::Retryable(err, retry_after)) if attempt < attempts => {
+                    let wait = retry_after.unwrap_or(backoff).min(FAUCET_MAX_BACKOFF);
+                    progress.println(&format!(
+                        "Faucet {} failed ({}), retrying in {} ms",
+                        faucet_url,
+                        err,
+                        wait.as_millis()
+                    ));
+                    tokio::time::sleep(wait).await;
+                    backoff = backoff.saturating_mul(2);
+                }
+                Err(FaucetError::Retryable(err, _)) | Err(FaucetError::Fatal(err)) => {
+                    errors.push(format!("{}: {}", faucet_url, err));
+                    break;
+                }
+            }
+        }
+    }
+
+    Err(CliError::ApiError(format!(
+        "Faucet issue: {}",
+        errors.join(", ")
+    )))
+}
+
+enum FaucetError {
+    /// The request can succeed if tried again, after the faucet's `Retry-After` if it gave one
+    Retryable(String, Option<Duration>),
+    Fatal(String),
+}
+
+async fn request_faucet_funds(
+    client: &reqwest::Client,
+    faucet_url: &Url,
+    num_octas: u64,
+    address: AccountAddress,
+) -> Result<Vec<HashValue>, FaucetError> {
+    let response = client
         .post(format!(
             "{}mint?amount={}&auth_key={}",
             faucet_url, num_octas, address
@@ -332,23 +561,97 @@ pub async fn fund_account(
         .body("{}")
         .send()
         .await
-        .map_err(|err| CliError::ApiError(err.to_string()))?;
-    if response.status() == 200 {
-        let hashes: Vec<HashValue> = response
+        .map_err(|err| FaucetError::Retryable(err.to_string(), None))?;
+
+    let status = response.status();
+    if status.is_success() {
+        response
             .json()
             .await
-            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
-        Ok(hashes)
+            .map_err(|err| FaucetError::Fatal(err.to_string()))
+    } else if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+            .map(Duration::from_secs);
+        Err(FaucetError::Retryable(status.to_string(), retry_after))
     } else {
-        Err(CliError::ApiError(format!(
-            "Faucet issue: {}",
-            response.status()
-        )))
+        Err(FaucetError::Fatal(status.to_string()))
     }
 }
 
+/// Octas in one APT
+pub const OCTAS_PER_APT: u64 = 100_000_000;
+
+/// Parses an amount of coins as Octas, or as APT with an `APT` suffix e.g. `1.5APT`
+pub fn parse_octas(str: &str) -> CliTypedResult<u64> {
+    let invalid = || {
+        CliError::UnableToParse(
+            "amount",
+            format!("{} is not a number of Octas, or of APT e.g. 1.5APT", str),
+        )
+    };
+    let str = str.trim();
+    let apt = match str.strip_suffix("APT").or_else(|| str.strip_suffix("apt")) {
+        Some(apt) => apt.trim(),
+        None => return str.parse().map_err(|_| invalid()),
+    };
+
+    let (whole, fraction) = apt.split_once('.').unwrap_or((apt, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || fraction.len() > 8
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| invalid())?
+    };
+    let fraction: u64 = format!("{:0<8}", fraction).parse().map_err(|_| invalid())?;
+    whole
+        .checked_mul(OCTAS_PER_APT)
+        .and_then(|octas| octas.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
 pub fn start_logger() {
     let mut logger = aptos_logger::Logger::new();
     logger.channel_size(1000).is_async(false).level(Level::Warn);
     logger.build();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_of() {
+        assert_eq!(None, percentile_of(&[], 50));
+        let prices = [100, 100, 150, 200, 1000];
+        assert_eq!(Some(100), percentile_of(&prices, 0));
+        assert_eq!(Some(150), percentile_of(&prices, 50));
+        assert_eq!(Some(1000), percentile_of(&prices, 90));
+        assert_eq!(Some(1000), percentile_of(&prices, 100));
+    }
+
+    #[test]
+    fn test_parse_octas() {
+        assert_eq!(100, parse_octas("100").unwrap());
+        assert_eq!(OCTAS_PER_APT, parse_octas("1APT").unwrap());
+        assert_eq!(150_000_000, parse_octas("1.5 APT").unwrap());
+        assert_eq!(1, parse_octas("0.00000001apt").unwrap());
+        assert_eq!(50_000_000, parse_octas(".5APT").unwrap());
+        assert!(parse_octas("1.5").is_err());
+        assert!(parse_octas("0.000000001APT").is_err());
+        assert!(parse_octas("-1APT").is_err());
+        assert!(parse_octas("APT").is_err());
+        assert!(parse_octas("200000000000APT").is_err());
+    }
+}
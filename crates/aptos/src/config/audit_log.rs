@@ -0,0 +1,313 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Local log of the commands run, for reconstructing what was executed
+//!
+//! When enabled, each command appends a JSON line to `audit_log.jsonl` in the global config
+//! folder, with its arguments, the profile it used, the transactions it submitted, and whether
+//! it succeeded.  Secrets in the arguments are redacted.
+
+use crate::common::types::{CliCommand, CliError, CliTypedResult, REDACTED};
+use crate::common::utils::{create_dir_if_not_exist, read_from_file};
+use crate::config::{global_folder, GlobalConfig};
+use aptos_logger::debug;
+use aptos_rest_client::aptos_api_types::HashValue;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const AUDIT_LOG_FILE: &str = "audit_log.jsonl";
+
+/// Arguments whose values are redacted, matched as parts of the argument's name
+const SECRET_ARGS: [&str; 4] = ["private-key", "password", "mnemonic", "passphrase"];
+
+/// Audit log settings in the global config
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AuditLogConfig {
+    /// Whether to log commands, defaults to false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Days to keep entries for, forever if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention_days: Option<u64>,
+}
+
+impl AuditLogConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+}
+
+/// A command run, as logged
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub command: String,
+    /// Arguments of the command, with secrets redacted
+    pub args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    pub transactions: Vec<HashValue>,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// What the running command did, collected for its entry
+struct Recorded {
+    profile: Option<String>,
+    transactions: Vec<HashValue>,
+}
+
+static RECORDED: Mutex<Recorded> = Mutex::new(Recorded {
+    profile: None,
+    transactions: Vec::new(),
+});
+
+/// Records the profile the command used
+pub fn record_profile(profile: &str) {
+    RECORDED.lock().unwrap().profile = Some(profile.to_string());
+}
+
+/// Records a transaction the command submitted
+pub fn record_transaction(hash: HashValue) {
+    RECORDED.lock().unwrap().transactions.push(hash);
+}
+
+/// Appends the command's entry to the audit log, if it's enabled
+///
+/// Failing to write the log doesn't fail the command.
+pub fn log_command(command: &str, error: Option<String>) {
+    let config = GlobalConfig::load()
+        .ok()
+        .and_then(|config| config.audit_log)
+        .unwrap_or_default();
+    if !config.is_enabled() {
+        return;
+    }
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let recorded = std::mem::replace(
+        &mut *RECORDED.lock().unwrap(),
+        Recorded {
+            profile: None,
+            transactions: Vec::new(),
+        },
+    );
+    let entry = AuditEntry {
+        timestamp: Utc::now(),
+        command: command.to_string(),
+        profile: recorded.profile.or_else(|| profile_arg(&args)),
+        args: redact_args(&args),
+        transactions: recorded.transactions,
+        success: error.is_none(),
+        error,
+    };
+    if let Err(err) = append_entry(&entry, config.retention_days) {
+        debug!("Failed to write to the audit log: {}", err);
+    }
+}
+
+fn audit_log_path() -> CliTypedResult<PathBuf> {
+    Ok(global_folder()?.join(AUDIT_LOG_FILE))
+}
+
+fn append_entry(entry: &AuditEntry, retention_days: Option<u64>) -> CliTypedResult<()> {
+    let path = audit_log_path()?;
+    create_dir_if_not_exist(global_folder()?.as_path())?;
+
+    // Drop entries past retention, rewriting the log without them
+    if let Some(retention_days) = retention_days {
+        let cutoff = Utc::now() - Duration::days(retention_days as i64);
+        let entries = read_entries()?;
+        if entries.iter().any(|entry| entry.timestamp < cutoff) {
+            let kept: Vec<String> = entries
+                .iter()
+                .filter(|entry| entry.timestamp >= cutoff)
+                .map(|entry| serde_json::to_string(entry).unwrap())
+                .collect();
+            let mut contents = kept.join("\n");
+            if !contents.is_empty() {
+                contents.push('\n');
+            }
+            std::fs::write(&path, contents)
+                .map_err(|err| CliError::IO(path.display().to_string(), err))?;
+        }
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|err| CliError::IO(path.display().to_string(), err))?;
+    writeln!(file, "{}", serde_json::to_string(entry).unwrap())
+        .map_err(|err| CliError::IO(path.display().to_string(), err))
+}
+
+/// Entries of the audit log, oldest first, skipping any lines which can't be read
+fn read_entries() -> CliTypedResult<Vec<AuditEntry>> {
+    let path = audit_log_path()?;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    Ok(String::from_utf8(read_from_file(&path)?)?
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// The profile given with `--profile`, if any
+fn profile_arg(args: &[String]) -> Option<String> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == "--profile" {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix("--profile=").map(str::to_string)
+        }
+    })
+}
+
+/// Redacts the values of arguments which are secrets, given either as `--arg value` or
+/// `--arg=value`
+fn redact_args(args: &[String]) -> Vec<String> {
+    let is_secret = |name: &str| {
+        name.starts_with("--") && SECRET_ARGS.iter().any(|secret| name.contains(secret))
+    };
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut redact_next = false;
+    for arg in args {
+        if redact_next {
+            redacted.push(REDACTED.to_string());
+            redact_next = false;
+        } else if let Some((name, _)) = arg.split_once('=').filter(|(name, _)| is_secret(name)) {
+            redacted.push(format!("{}={}", name, REDACTED));
+        } else {
+            // Flags pointing at files of secrets are fine to log, it's their contents that aren't
+            redact_next = is_secret(arg) && !arg.ends_with("-file");
+            redacted.push(arg.clone());
+        }
+    }
+    redacted
+}
+
+/// Set audit log settings in the global config
+///
+/// Any settings that are not provided will not be changed
+#[derive(Parser, Debug)]
+pub struct SetAuditLog {
+    /// Whether to log the commands run
+    #[clap(long)]
+    enabled: Option<bool>,
+
+    /// Days to keep entries for, older entries are removed as new ones are logged
+    #[clap(long, group = "audit_log_retention")]
+    retention_days: Option<u64>,
+
+    /// Keep entries forever
+    #[clap(long, group = "audit_log_retention")]
+    keep_forever: bool,
+}
+
+#[async_trait]
+impl CliCommand<AuditLogConfig> for SetAuditLog {
+    fn command_name(&self) -> &'static str {
+        "SetAuditLog"
+    }
+
+    async fn execute(self) -> CliTypedResult<AuditLogConfig> {
+        // Load the global config, without environment overrides so they aren't persisted
+        let mut config = GlobalConfig::load_from_file()?;
+        let mut audit_log = config.audit_log.take().unwrap_or_default();
+
+        if let Some(enabled) = self.enabled {
+            audit_log.enabled = Some(enabled);
+        }
+        if let Some(retention_days) = self.retention_days {
+            audit_log.retention_days = Some(retention_days);
+        } else if self.keep_forever {
+            audit_log.retention_days = None;
+        }
+
+        config.audit_log = Some(audit_log.clone());
+        config.save()?;
+        Ok(audit_log)
+    }
+}
+
+/// Show the commands in the audit log, most recent first
+///
+/// Commands are only logged once the audit log is enabled with `aptos config set-audit-log
+/// --enabled true`
+#[derive(Parser, Debug)]
+pub struct ShowAuditLog {
+    /// Number of the most recent entries to show
+    #[clap(long, default_value_t = 20)]
+    limit: usize,
+
+    /// Only show entries of this command, e.g. `PublishPackage`
+    #[clap(long)]
+    command: Option<String>,
+}
+
+#[async_trait]
+impl CliCommand<Vec<AuditEntry>> for ShowAuditLog {
+    fn command_name(&self) -> &'static str {
+        "ShowAuditLog"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<AuditEntry>> {
+        Ok(read_entries()?
+            .into_iter()
+            .rev()
+            .filter(|entry| {
+                self.command
+                    .as_ref()
+                    .map_or(true, |command| &entry.command == command)
+            })
+            .take(self.limit)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_args() {
+        let args: Vec<String> = [
+            "account",
+            "transfer",
+            "--private-key",
+            "0xdead",
+            "--private-key-file",
+            "key.txt",
+            "--encryption-password=hunter2",
+            "--profile",
+            "treasury",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        assert_eq!(
+            vec![
+                "account",
+                "transfer",
+                "--private-key",
+                REDACTED,
+                "--private-key-file",
+                "key.txt",
+                "--encryption-password=<redacted>",
+                "--profile",
+                "treasury",
+            ],
+            redact_args(&args)
+        );
+        assert_eq!(Some("treasury".to_string()), profile_arg(&args));
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    CliCommand, CliConfig, CliError, CliTypedResult, ConfigSearchMode, ProfileConfig,
+};
+use aptos_crypto::{ed25519::Ed25519PublicKey, HashValue, PrivateKey, ValidCryptoMaterial};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Shows the differences between two profiles
+///
+/// Profiles are compared after resolving `extends`, network presets and `APTOS_*` environment
+/// overrides, so the diff shows the settings commands actually use.  Keys are shown as
+/// fingerprints, never in full.
+#[derive(Parser, Debug)]
+pub struct DiffProfiles {
+    /// First profile to compare
+    #[clap(long)]
+    profile: String,
+
+    /// Second profile to compare
+    #[clap(long)]
+    other_profile: String,
+}
+
+/// A setting that differs between the two profiles
+#[derive(Debug, Serialize)]
+pub struct SettingDiff {
+    pub profile: Option<String>,
+    pub other_profile: Option<String>,
+}
+
+#[async_trait]
+impl CliCommand<BTreeMap<String, SettingDiff>> for DiffProfiles {
+    fn command_name(&self) -> &'static str {
+        "DiffProfiles"
+    }
+
+    async fn execute(self) -> CliTypedResult<BTreeMap<String, SettingDiff>> {
+        let first = comparable_settings(&load_profile(&self.profile)?);
+        let mut second = comparable_settings(&load_profile(&self.other_profile)?);
+
+        let mut diff = BTreeMap::new();
+        for (setting, first_value) in first {
+            let second_value = second.remove(setting).flatten();
+            if first_value != second_value {
+                diff.insert(
+                    setting.to_string(),
+                    SettingDiff {
+                        profile: first_value,
+                        other_profile: second_value,
+                    },
+                );
+            }
+        }
+        Ok(diff)
+    }
+}
+
+fn load_profile(profile: &str) -> CliTypedResult<ProfileConfig> {
+    CliConfig::load_profile(Some(profile), ConfigSearchMode::CurrentDirAndParents)?
+        .ok_or_else(|| CliError::CommandArgumentError(format!("Profile {} not found", profile)))
+}
+
+/// The settings of a profile that are safe to show, by name
+fn comparable_settings(profile: &ProfileConfig) -> BTreeMap<&'static str, Option<String>> {
+    let private_key_storage = if profile.private_key.is_some() {
+        Some("plaintext".to_string())
+    } else if profile.encrypted_private_key.is_some() {
+        Some("encrypted".to_string())
+    } else if let Some(ref derivation_path) = profile.ledger_derivation_path {
+        Some(format!("ledger ({})", derivation_path))
+    } else {
+        profile
+            .keychain_entry
+            .as_ref()
+            .map(|entry| format!("keychain ({})", entry))
+    };
+
+    let mut settings = BTreeMap::new();
+    settings.insert("extends", profile.extends.clone());
+    settings.insert(
+        "network",
+        profile.network.map(|network| format!("{:?}", network)),
+    );
+    settings.insert("rest_url", profile.rest_url.clone());
+    settings.insert("faucet_url", profile.faucet_url.clone());
+    settings.insert(
+        "account",
+        profile.account.map(|account| account.to_hex_literal()),
+    );
+    settings.insert("private_key_storage", private_key_storage);
+    // Only a plaintext private key can be fingerprinted without unlocking it
+    settings.insert(
+        "private_key_fingerprint",
+        profile
+            .private_key
+            .as_ref()
+            .map(|private_key| fingerprint(&private_key.public_key())),
+    );
+    settings.insert(
+        "public_key_fingerprint",
+        profile.public_key.as_ref().map(fingerprint),
+    );
+    settings
+}
+
+/// A short hash identifying a key, derived from its public key so nothing secret is shown
+fn fingerprint(public_key: &Ed25519PublicKey) -> String {
+    HashValue::sha3_256_of(&public_key.to_bytes()).to_hex()[..16].to_string()
+}
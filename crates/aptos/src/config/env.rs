@@ -0,0 +1,444 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Environment variable overrides for the CLI configuration
+//!
+//! Values are resolved in layers, with later layers taking precedence:
+//! 1. The config files in `.aptos/`
+//! 2. `APTOS_*` environment variables
+//! 3. Command line arguments
+//!
+//! This allows for running the CLI in CI pipelines without writing config files to disk.
+
+use crate::common::http::HttpConfig;
+use crate::common::init::Network;
+use crate::common::output::{ColorTheme, OutputFormat};
+use crate::common::types::{CliError, CliTypedResult, EncodingType, ProfileConfig};
+use crate::config::audit_log::AuditLogConfig;
+use crate::config::telemetry::TelemetryConfig;
+use crate::config::{ConfigFormat, ConfigType, GlobalConfig};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+    ValidCryptoMaterialStringExt,
+};
+use move_core_types::account_address::AccountAddress;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Profile to use when `--profile` is not given
+pub const APTOS_PROFILE: &str = "APTOS_PROFILE";
+/// Overrides the folder of the global config
+pub const APTOS_GLOBAL_CONFIG_FOLDER: &str = "APTOS_GLOBAL_CONFIG_FOLDER";
+/// Overrides `config_type` in the global config
+pub const APTOS_CONFIG_TYPE: &str = "APTOS_CONFIG_TYPE";
+/// Overrides `config_format` in the global config
+pub const APTOS_CONFIG_FORMAT: &str = "APTOS_CONFIG_FORMAT";
+/// Overrides `output_format` in the global config
+pub const APTOS_OUTPUT_FORMAT: &str = "APTOS_OUTPUT_FORMAT";
+/// Overrides `color_theme` in the global config
+pub const APTOS_COLOR_THEME: &str = "APTOS_COLOR_THEME";
+/// Overrides `assume_yes` in the global config
+pub const APTOS_ASSUME_YES: &str = "APTOS_ASSUME_YES";
+/// Overrides `telemetry.enabled` in the global config
+pub const APTOS_TELEMETRY: &str = "APTOS_TELEMETRY";
+/// Overrides `telemetry.endpoint` in the global config
+pub const APTOS_TELEMETRY_ENDPOINT: &str = "APTOS_TELEMETRY_ENDPOINT";
+/// Overrides `telemetry.anonymous_id` in the global config
+pub const APTOS_TELEMETRY_ANONYMOUS_ID: &str = "APTOS_TELEMETRY_ANONYMOUS_ID";
+/// Overrides `http.proxy` in the global config
+pub const APTOS_HTTP_PROXY: &str = "APTOS_HTTP_PROXY";
+/// Overrides `http.no_proxy` in the global config
+pub const APTOS_HTTP_NO_PROXY: &str = "APTOS_HTTP_NO_PROXY";
+/// Overrides `http.ca_bundle` in the global config
+pub const APTOS_HTTP_CA_BUNDLE: &str = "APTOS_HTTP_CA_BUNDLE";
+/// Overrides `http.max_retries` in the global config
+pub const APTOS_HTTP_MAX_RETRIES: &str = "APTOS_HTTP_MAX_RETRIES";
+/// Overrides `http.retry_timeout_secs` in the global config
+pub const APTOS_HTTP_RETRY_TIMEOUT_SECS: &str = "APTOS_HTTP_RETRY_TIMEOUT_SECS";
+/// Overrides `audit_log.enabled` in the global config
+pub const APTOS_AUDIT_LOG: &str = "APTOS_AUDIT_LOG";
+/// Overrides `audit_log.retention_days` in the global config
+pub const APTOS_AUDIT_LOG_RETENTION_DAYS: &str = "APTOS_AUDIT_LOG_RETENTION_DAYS";
+/// Overrides `network` in the profile
+pub const APTOS_NETWORK: &str = "APTOS_NETWORK";
+/// Overrides `rest_url` in the profile
+pub const APTOS_REST_URL: &str = "APTOS_REST_URL";
+/// Overrides `faucet_url` in the profile
+pub const APTOS_FAUCET_URL: &str = "APTOS_FAUCET_URL";
+/// Overrides `private_key` in the profile with a hex encoded key
+pub const APTOS_PRIVATE_KEY: &str = "APTOS_PRIVATE_KEY";
+/// Overrides `private_key` in the profile with a hex encoded key file
+pub const APTOS_PRIVATE_KEY_FILE: &str = "APTOS_PRIVATE_KEY_FILE";
+/// File containing the passphrase for an encrypted private key in the profile
+pub const APTOS_KEY_PASSPHRASE_FILE: &str = "APTOS_KEY_PASSPHRASE_FILE";
+/// Overrides `public_key` in the profile
+pub const APTOS_PUBLIC_KEY: &str = "APTOS_PUBLIC_KEY";
+/// Overrides `account` in the profile
+pub const APTOS_ACCOUNT: &str = "APTOS_ACCOUNT";
+
+/// All environment variables that override a value in a profile
+const PROFILE_ENV_VARS: [&str; 7] = [
+    APTOS_NETWORK,
+    APTOS_REST_URL,
+    APTOS_FAUCET_URL,
+    APTOS_PRIVATE_KEY,
+    APTOS_PRIVATE_KEY_FILE,
+    APTOS_PUBLIC_KEY,
+    APTOS_ACCOUNT,
+];
+
+/// Reads an environment variable, treating empty values as unset
+pub fn env_var(name: &str) -> Option<String> {
+    non_empty(process_env(name))
+}
+
+/// The profile name given by `APTOS_PROFILE`
+pub fn profile_override() -> Option<String> {
+    env_var(APTOS_PROFILE)
+}
+
+/// The global config folder given by `APTOS_GLOBAL_CONFIG_FOLDER`
+pub fn global_folder_override() -> Option<PathBuf> {
+    env_var(APTOS_GLOBAL_CONFIG_FOLDER).map(PathBuf::from)
+}
+
+/// Whether any environment variable overrides a profile value
+pub fn has_profile_overrides() -> bool {
+    has_profile_overrides_with(process_env)
+}
+
+/// Applies the `APTOS_*` overrides on top of the global config
+pub fn apply_global_overrides(config: &mut GlobalConfig) -> CliTypedResult<()> {
+    apply_global_overrides_with(config, process_env)
+}
+
+/// Applies the `APTOS_*` overrides on top of a profile
+pub fn apply_profile_overrides(profile: &mut ProfileConfig) -> CliTypedResult<()> {
+    apply_profile_overrides_with(profile, process_env)
+}
+
+fn process_env(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// The overrides read variables through `lookup`, so tests don't have to change the environment
+// shared by the whole process
+
+fn has_profile_overrides_with(lookup: impl Fn(&str) -> Option<String>) -> bool {
+    PROFILE_ENV_VARS
+        .iter()
+        .any(|name| non_empty(lookup(name)).is_some())
+}
+
+fn apply_global_overrides_with(
+    config: &mut GlobalConfig,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> CliTypedResult<()> {
+    let var = |name: &str| non_empty(lookup(name));
+
+    if let Some(config_type) = var(APTOS_CONFIG_TYPE) {
+        config.config_type = Some(ConfigType::from_str(&config_type)?);
+    }
+    if let Some(config_format) = var(APTOS_CONFIG_FORMAT) {
+        config.config_format = Some(ConfigFormat::from_str(&config_format)?);
+    }
+    if let Some(output_format) = var(APTOS_OUTPUT_FORMAT) {
+        config.output_format = Some(OutputFormat::from_str(&output_format)?);
+    }
+    if let Some(color_theme) = var(APTOS_COLOR_THEME) {
+        config.color_theme = Some(ColorTheme::from_str(&color_theme)?);
+    }
+    if let Some(assume_yes) = var(APTOS_ASSUME_YES) {
+        config.assume_yes = Some(parse_bool(APTOS_ASSUME_YES, &assume_yes)?);
+    }
+
+    // Nested settings are only added when a variable overrides one of them
+    if let Some(enabled) = var(APTOS_TELEMETRY) {
+        config
+            .telemetry
+            .get_or_insert_with(TelemetryConfig::default)
+            .enabled = Some(parse_bool(APTOS_TELEMETRY, &enabled)?);
+    }
+    if let Some(endpoint) = var(APTOS_TELEMETRY_ENDPOINT) {
+        config
+            .telemetry
+            .get_or_insert_with(TelemetryConfig::default)
+            .endpoint = Some(parse_url(APTOS_TELEMETRY_ENDPOINT, &endpoint)?);
+    }
+    if let Some(anonymous_id) = var(APTOS_TELEMETRY_ANONYMOUS_ID) {
+        config
+            .telemetry
+            .get_or_insert_with(TelemetryConfig::default)
+            .anonymous_id = Some(anonymous_id);
+    }
+
+    if let Some(proxy) = var(APTOS_HTTP_PROXY) {
+        config.http.get_or_insert_with(HttpConfig::default).proxy =
+            Some(parse_url(APTOS_HTTP_PROXY, &proxy)?);
+    }
+    if let Some(no_proxy) = var(APTOS_HTTP_NO_PROXY) {
+        config.http.get_or_insert_with(HttpConfig::default).no_proxy = Some(no_proxy);
+    }
+    if let Some(ca_bundle) = var(APTOS_HTTP_CA_BUNDLE) {
+        config
+            .http
+            .get_or_insert_with(HttpConfig::default)
+            .ca_bundle = Some(PathBuf::from(ca_bundle));
+    }
+    if let Some(max_retries) = var(APTOS_HTTP_MAX_RETRIES) {
+        config
+            .http
+            .get_or_insert_with(HttpConfig::default)
+            .max_retries = Some(parse_number(APTOS_HTTP_MAX_RETRIES, &max_retries)?);
+    }
+    if let Some(retry_timeout_secs) = var(APTOS_HTTP_RETRY_TIMEOUT_SECS) {
+        config
+            .http
+            .get_or_insert_with(HttpConfig::default)
+            .retry_timeout_secs = Some(parse_number(
+            APTOS_HTTP_RETRY_TIMEOUT_SECS,
+            &retry_timeout_secs,
+        )?);
+    }
+
+    if let Some(enabled) = var(APTOS_AUDIT_LOG) {
+        config
+            .audit_log
+            .get_or_insert_with(AuditLogConfig::default)
+            .enabled = Some(parse_bool(APTOS_AUDIT_LOG, &enabled)?);
+    }
+    if let Some(retention_days) = var(APTOS_AUDIT_LOG_RETENTION_DAYS) {
+        config
+            .audit_log
+            .get_or_insert_with(AuditLogConfig::default)
+            .retention_days = Some(parse_number(
+            APTOS_AUDIT_LOG_RETENTION_DAYS,
+            &retention_days,
+        )?);
+    }
+
+    Ok(())
+}
+
+fn apply_profile_overrides_with(
+    profile: &mut ProfileConfig,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> CliTypedResult<()> {
+    let var = |name: &str| non_empty(lookup(name));
+
+    if let Some(network) = var(APTOS_NETWORK) {
+        profile.network = Some(Network::from_str(&network)?);
+    }
+    if let Some(rest_url) = var(APTOS_REST_URL) {
+        profile.rest_url = Some(parse_url(APTOS_REST_URL, &rest_url)?);
+    }
+    if let Some(faucet_url) = var(APTOS_FAUCET_URL) {
+        profile.faucet_url = Some(parse_url(APTOS_FAUCET_URL, &faucet_url)?);
+    }
+
+    // An inline key takes precedence over a key file, same as on the command line
+    if let Some(private_key) = var(APTOS_PRIVATE_KEY) {
+        profile.private_key = Some(
+            Ed25519PrivateKey::from_encoded_string(&private_key)
+                .map_err(|err| CliError::UnableToParse(APTOS_PRIVATE_KEY, err.to_string()))?,
+        );
+    } else if let Some(private_key_file) = var(APTOS_PRIVATE_KEY_FILE) {
+        profile.private_key = Some(EncodingType::Hex.load_key(
+            APTOS_PRIVATE_KEY_FILE,
+            PathBuf::from(private_key_file).as_path(),
+        )?);
+    }
+
+    if let Some(public_key) = var(APTOS_PUBLIC_KEY) {
+        profile.public_key = Some(
+            Ed25519PublicKey::from_encoded_string(&public_key)
+                .map_err(|err| CliError::UnableToParse(APTOS_PUBLIC_KEY, err.to_string()))?,
+        );
+    }
+    if let Some(account) = var(APTOS_ACCOUNT) {
+        profile.account = Some(
+            AccountAddress::from_hex_literal(&account)
+                .or_else(|_| AccountAddress::from_str(&account))
+                .map_err(|err| CliError::UnableToParse(APTOS_ACCOUNT, err.to_string()))?,
+        );
+    }
+
+    Ok(())
+}
+
+fn parse_url(name: &'static str, url: &str) -> CliTypedResult<String> {
+    reqwest::Url::parse(url)
+        .map(|url| url.to_string())
+        .map_err(|err| CliError::UnableToParse(name, err.to_string()))
+}
+
+fn parse_bool(name: &'static str, value: &str) -> CliTypedResult<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" | "yes" => Ok(true),
+        "false" | "0" | "no" => Ok(false),
+        _ => Err(CliError::UnableToParse(
+            name,
+            format!("{} is not one of [true, false]", value),
+        )),
+    }
+}
+
+fn parse_number<T: FromStr>(name: &'static str, value: &str) -> CliTypedResult<T>
+where
+    T::Err: Display,
+{
+    T::from_str(value).map_err(|err| CliError::UnableToParse(name, err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::utils::write_to_file;
+    use aptos_crypto::PrivateKey;
+    use aptos_keygen::KeyGen;
+    use aptos_temppath::TempPath;
+
+    /// Looks up variables in `vars` instead of the environment
+    fn vars<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn test_profile_overrides() {
+        let mut keygen = KeyGen::from_seed([0; 32]);
+        let inline_key = keygen.generate_ed25519_private_key();
+        let file_key = keygen.generate_ed25519_private_key();
+        let key_file = TempPath::new();
+        write_to_file(
+            key_file.path(),
+            APTOS_PRIVATE_KEY_FILE,
+            &EncodingType::Hex
+                .encode_key(APTOS_PRIVATE_KEY_FILE, &file_key)
+                .unwrap(),
+        )
+        .unwrap();
+        let key_file = key_file.path().to_str().unwrap();
+        let inline_key_str = inline_key.to_encoded_string().unwrap();
+
+        // An inline key takes precedence over a key file
+        let mut profile = ProfileConfig::default();
+        apply_profile_overrides_with(
+            &mut profile,
+            vars(&[
+                (APTOS_PRIVATE_KEY, &inline_key_str),
+                (APTOS_PRIVATE_KEY_FILE, key_file),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(
+            profile.private_key.unwrap().public_key(),
+            inline_key.public_key()
+        );
+
+        // Empty values are unset, so the key file is used
+        let env = [
+            (APTOS_PRIVATE_KEY, "  "),
+            (APTOS_PRIVATE_KEY_FILE, key_file),
+            (APTOS_REST_URL, ""),
+        ];
+        assert!(has_profile_overrides_with(vars(&env)));
+        let mut profile = ProfileConfig {
+            rest_url: Some("http://localhost:8080/".to_string()),
+            ..Default::default()
+        };
+        apply_profile_overrides_with(&mut profile, vars(&env)).unwrap();
+        assert_eq!(
+            profile.private_key.unwrap().public_key(),
+            file_key.public_key()
+        );
+        assert_eq!(profile.rest_url.as_deref(), Some("http://localhost:8080/"));
+
+        assert!(!has_profile_overrides_with(vars(&[(APTOS_NETWORK, "")])));
+
+        // Invalid values fail to parse, with the name of the variable
+        assert!(matches!(
+            apply_profile_overrides_with(
+                &mut ProfileConfig::default(),
+                vars(&[(APTOS_REST_URL, "not a url")])
+            ),
+            Err(CliError::UnableToParse(APTOS_REST_URL, _))
+        ));
+        assert!(matches!(
+            apply_profile_overrides_with(
+                &mut ProfileConfig::default(),
+                vars(&[(APTOS_PRIVATE_KEY, "0xnotakey")])
+            ),
+            Err(CliError::UnableToParse(APTOS_PRIVATE_KEY, _))
+        ));
+    }
+
+    #[test]
+    fn test_global_overrides() {
+        let mut config = GlobalConfig::default();
+        apply_global_overrides_with(
+            &mut config,
+            vars(&[
+                (APTOS_CONFIG_TYPE, "global"),
+                (APTOS_CONFIG_FORMAT, "toml"),
+                (APTOS_OUTPUT_FORMAT, "table"),
+                (APTOS_COLOR_THEME, "off"),
+                (APTOS_ASSUME_YES, "true"),
+                (APTOS_TELEMETRY, "0"),
+                (APTOS_HTTP_MAX_RETRIES, "5"),
+                (APTOS_AUDIT_LOG, "yes"),
+                (APTOS_AUDIT_LOG_RETENTION_DAYS, "30"),
+            ]),
+        )
+        .unwrap();
+        assert!(matches!(config.config_type, Some(ConfigType::Global)));
+        assert_eq!(config.config_format, Some(ConfigFormat::Toml));
+        assert_eq!(config.output_format, Some(OutputFormat::Table));
+        assert_eq!(config.color_theme, Some(ColorTheme::Off));
+        assert_eq!(config.assume_yes, Some(true));
+        assert!(!config.telemetry.unwrap().is_enabled());
+        assert_eq!(config.http.unwrap().max_retries, Some(5));
+        let audit_log = config.audit_log.unwrap();
+        assert!(audit_log.is_enabled());
+        assert_eq!(audit_log.retention_days, Some(30));
+
+        // Nested settings which aren't overridden are left out
+        let mut config = GlobalConfig::default();
+        apply_global_overrides_with(&mut config, vars(&[(APTOS_ASSUME_YES, " ")])).unwrap();
+        assert!(config.assume_yes.is_none());
+        assert!(config.telemetry.is_none());
+        assert!(config.http.is_none());
+        assert!(config.audit_log.is_none());
+
+        // Invalid values fail
+        assert!(apply_global_overrides_with(
+            &mut GlobalConfig::default(),
+            vars(&[(APTOS_CONFIG_TYPE, "nowhere")])
+        )
+        .is_err());
+        assert!(matches!(
+            apply_global_overrides_with(
+                &mut GlobalConfig::default(),
+                vars(&[(APTOS_ASSUME_YES, "maybe")])
+            ),
+            Err(CliError::UnableToParse(APTOS_ASSUME_YES, _))
+        ));
+        assert!(matches!(
+            apply_global_overrides_with(
+                &mut GlobalConfig::default(),
+                vars(&[(APTOS_HTTP_MAX_RETRIES, "-1")])
+            ),
+            Err(CliError::UnableToParse(APTOS_HTTP_MAX_RETRIES, _))
+        ));
+    }
+}
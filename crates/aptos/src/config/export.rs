@@ -0,0 +1,205 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    CliCommand, CliConfig, CliError, CliTypedResult, ConfigSearchMode, KeyPassphraseOptions,
+    ProfileConfig, PromptOptions,
+};
+use crate::common::utils::{
+    check_if_file_exists, prompt_yes_with_override, read_from_file, write_to_user_only_file,
+};
+use crate::config::ConfigFormat;
+use async_trait::async_trait;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// A portable file of exported profiles
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExportedProfiles {
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+/// Exports profiles to a single portable file
+///
+/// The format of the file is determined by its extension, one of [yaml, json, toml].
+/// Private keys stored in the OS keychain are exported with the rest of the profile, as the
+/// keychain entry isn't portable.
+#[derive(Parser, Debug)]
+pub struct ExportProfiles {
+    /// Profiles to export, defaults to all profiles
+    ///
+    /// Example: default testnet
+    #[clap(long, multiple_values = true)]
+    profiles: Vec<String>,
+
+    /// File to export the profiles to
+    #[clap(long, parse(from_os_str))]
+    output_file: PathBuf,
+
+    /// Don't export any private keys
+    #[clap(long, group = "private_key_export")]
+    exclude_private_keys: bool,
+
+    /// Encrypt plaintext private keys with a passphrase in the exported file
+    ///
+    /// Private keys that are already encrypted are exported as is
+    #[clap(long, group = "private_key_export")]
+    encrypt_private_keys: bool,
+
+    #[clap(flatten)]
+    key_passphrase_options: KeyPassphraseOptions,
+    #[clap(flatten)]
+    prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<Vec<String>> for ExportProfiles {
+    fn command_name(&self) -> &'static str {
+        "ExportProfiles"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<String>> {
+        let format = ConfigFormat::from_path(self.output_file.as_path())?;
+        check_if_file_exists(self.output_file.as_path(), self.prompt_options)?;
+
+        let mut profiles = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?
+            .profiles
+            .unwrap_or_default();
+        for profile in &self.profiles {
+            if !profiles.contains_key(profile) {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Profile {} not found",
+                    profile
+                )));
+            }
+        }
+        if !self.profiles.is_empty() {
+            profiles.retain(|name, _| self.profiles.contains(name));
+        }
+
+        // Only ask for the passphrase once for all profiles
+        let passphrase = if self.encrypt_private_keys {
+            Some(self.key_passphrase_options.passphrase(true)?)
+        } else {
+            None
+        };
+
+        for profile_config in profiles.values_mut() {
+            if self.exclude_private_keys {
+                profile_config.private_key = None;
+                profile_config.encrypted_private_key = None;
+            } else if let Some(keychain_entry) = profile_config.keychain_entry.as_deref() {
+                eprintln!(
+                    "Exporting private key from OS keychain entry {}",
+                    keychain_entry
+                );
+                profile_config.private_key =
+                    profile_config.resolve_private_key(&self.key_passphrase_options)?;
+            }
+            profile_config.keychain_entry = None;
+
+            if let Some(ref passphrase) = passphrase {
+                profile_config.encrypt_private_key(passphrase)?;
+            }
+        }
+
+        let names = profiles.keys().cloned().collect();
+        let exported = ExportedProfiles { profiles };
+        write_to_user_only_file(
+            self.output_file.as_path(),
+            "Exported profiles",
+            format.serialize(&exported)?.as_bytes(),
+        )?;
+        Ok(names)
+    }
+}
+
+/// Imports profiles from a file created with `aptos config export`
+///
+/// Imported profiles are merged with the existing profiles.  Profiles that already exist
+/// with different settings are conflicts, which are only overwritten after confirmation.
+#[derive(Parser, Debug)]
+pub struct ImportProfiles {
+    /// File to import profiles from
+    #[clap(long, parse(from_os_str))]
+    input_file: PathBuf,
+
+    /// Profiles to import, defaults to all profiles in the file
+    ///
+    /// Example: default testnet
+    #[clap(long, multiple_values = true)]
+    profiles: Vec<String>,
+
+    #[clap(flatten)]
+    prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<Vec<String>> for ImportProfiles {
+    fn command_name(&self) -> &'static str {
+        "ImportProfiles"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<String>> {
+        let format = ConfigFormat::from_path(self.input_file.as_path())?;
+        let mut imported: ExportedProfiles = format.deserialize(&String::from_utf8(
+            read_from_file(self.input_file.as_path())?,
+        )?)?;
+        for profile in &self.profiles {
+            if !imported.profiles.contains_key(profile) {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Profile {} not found in {}",
+                    profile,
+                    self.input_file.display()
+                )));
+            }
+        }
+        if !self.profiles.is_empty() {
+            imported
+                .profiles
+                .retain(|name, _| self.profiles.contains(name));
+        }
+
+        let mut config = if CliConfig::config_exists(ConfigSearchMode::CurrentDirAndParents) {
+            CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?
+        } else {
+            CliConfig::default()
+        };
+
+        // Profiles that exist with the same settings don't need to be imported again
+        let mut conflicts = Vec::new();
+        let mut unchanged = Vec::new();
+        for (name, profile_config) in &imported.profiles {
+            if let Some(existing) = config.profiles.as_ref().and_then(|p| p.get(name)) {
+                if serde_yaml::to_string(existing)? == serde_yaml::to_string(profile_config)? {
+                    unchanged.push(name.clone());
+                } else {
+                    conflicts.push(name.clone());
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            prompt_yes_with_override(
+                &format!(
+                    "Profiles [{}] already exist with different settings, do you want to overwrite them?",
+                    conflicts.join(", ")
+                ),
+                self.prompt_options,
+            )?;
+        }
+
+        let mut names = Vec::new();
+        for (name, profile_config) in imported.profiles {
+            if !unchanged.contains(&name) {
+                config.insert_profile(&name, profile_config);
+                names.push(name);
+            }
+        }
+
+        config.save()?;
+        Ok(names)
+    }
+}
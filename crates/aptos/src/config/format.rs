@@ -0,0 +1,168 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliError, CliTypedResult};
+use clap::ArgEnum;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt::Formatter;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const YAML: &str = "yaml";
+const JSON: &str = "json";
+const TOML: &str = "toml";
+
+/// File format of the config files
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize, ArgEnum)]
+pub enum ConfigFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// All formats, in the order they're searched for when loading
+    pub const ALL: [ConfigFormat; 3] = [ConfigFormat::Yaml, ConfigFormat::Json, ConfigFormat::Toml];
+
+    /// File extension used for the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ConfigFormat::Yaml => YAML,
+            ConfigFormat::Json => JSON,
+            ConfigFormat::Toml => TOML,
+        }
+    }
+
+    /// Detects the format from a file's extension
+    pub fn from_path(path: &Path) -> CliTypedResult<Self> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("yaml") | Some("yml") => Ok(ConfigFormat::Yaml),
+            Some(JSON) => Ok(ConfigFormat::Json),
+            Some(TOML) => Ok(ConfigFormat::Toml),
+            _ => Err(CliError::UnableToParse(
+                "config format",
+                format!("Unknown config file extension for {}", path.display()),
+            )),
+        }
+    }
+
+    /// Serializes the config in the format
+    pub fn serialize<T: Serialize + ?Sized>(&self, input: &T) -> CliTypedResult<String> {
+        match self {
+            ConfigFormat::Yaml => Ok(serde_yaml::to_string(input)?),
+            ConfigFormat::Json => serde_json::to_string_pretty(input)
+                .map_err(|err| CliError::UnexpectedError(err.to_string())),
+            // Converting to a TOML value first orders plain values before tables, which
+            // TOML requires
+            ConfigFormat::Toml => toml::Value::try_from(input)
+                .and_then(|value| toml::to_string_pretty(&value))
+                .map_err(|err| CliError::UnexpectedError(err.to_string())),
+        }
+    }
+
+    /// Deserializes the config from the format
+    pub fn deserialize<T: DeserializeOwned>(&self, input: &str) -> CliTypedResult<T> {
+        match self {
+            ConfigFormat::Yaml => Ok(serde_yaml::from_str(input)?),
+            ConfigFormat::Json => serde_json::from_str(input)
+                .map_err(|err| CliError::UnableToParse("JSON config", err.to_string())),
+            ConfigFormat::Toml => toml::from_str(input)
+                .map_err(|err| CliError::UnableToParse("TOML config", err.to_string())),
+        }
+    }
+}
+
+/// Finds the config file named `file_stem` in `folder`, in any of the formats
+pub fn find_config_file(folder: &Path, file_stem: &str) -> Option<(PathBuf, ConfigFormat)> {
+    ConfigFormat::ALL.iter().find_map(|format| {
+        let path = folder.join(format!("{}.{}", file_stem, format.extension()));
+        if path.exists() {
+            Some((path, *format))
+        } else {
+            None
+        }
+    })
+}
+
+/// Removes copies of the config file named `file_stem` in formats other than `format`
+///
+/// This keeps a single config file after switching formats
+pub fn remove_other_formats(folder: &Path, file_stem: &str, format: ConfigFormat) {
+    for other_format in ConfigFormat::ALL.iter().filter(|other| **other != format) {
+        let path = folder.join(format!("{}.{}", file_stem, other_format.extension()));
+        if path.exists() {
+            eprintln!(
+                "Removing config file {} replaced by {} format",
+                path.display(),
+                format
+            );
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+impl Default for ConfigFormat {
+    fn default() -> Self {
+        ConfigFormat::Yaml
+    }
+}
+
+impl std::fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl FromStr for ConfigFormat {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().trim() {
+            YAML | "yml" => Ok(ConfigFormat::Yaml),
+            JSON => Ok(ConfigFormat::Json),
+            TOML => Ok(ConfigFormat::Toml),
+            _ => Err(CliError::CommandArgumentError(
+                "Invalid config format, must be one of [yaml, json, toml]".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::{CliConfig, ProfileConfig};
+
+    #[test]
+    fn test_config_round_trip_in_all_formats() {
+        let mut config = CliConfig::default();
+        config.insert_profile(
+            "default",
+            ProfileConfig {
+                rest_url: Some("http://localhost:8080/".to_string()),
+                ..Default::default()
+            },
+        );
+        config.default_profile = Some("default".to_string());
+
+        for format in ConfigFormat::ALL {
+            let serialized = format.serialize(&config).unwrap();
+            let deserialized: CliConfig = format.deserialize(&serialized).unwrap();
+            assert_eq!(
+                config.default_profile, deserialized.default_profile,
+                "{}",
+                format
+            );
+            assert_eq!(
+                Some("http://localhost:8080/"),
+                deserialized
+                    .profiles
+                    .unwrap()
+                    .get("default")
+                    .unwrap()
+                    .rest_url
+                    .as_deref()
+            );
+        }
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Access to single config values by a dotted key path
+//!
+//! Paths starting with `global.` address the global config, e.g. `global.config_type`, and
+//! all other paths address the workspace config, e.g. `profiles.default.rest_url`.
+
+use crate::common::types::{
+    CliCommand, CliConfig, CliError, CliTypedResult, ConfigSearchMode, REDACTED,
+};
+use crate::config::GlobalConfig;
+use async_trait::async_trait;
+use clap::Parser;
+use serde_yaml::{Mapping, Value};
+
+const GLOBAL_PREFIX: &str = "global";
+const PRIVATE_KEY: &str = "private_key";
+
+/// Gets a single config value by its dotted key path
+///
+/// Example: `aptos config get profiles.default.rest_url`
+#[derive(Parser, Debug)]
+pub struct GetConfigValue {
+    /// Dotted path of the value, e.g. `profiles.default.rest_url` or `global.config_type`
+    key: String,
+}
+
+#[async_trait]
+impl CliCommand<Value> for GetConfigValue {
+    fn command_name(&self) -> &'static str {
+        "GetConfigValue"
+    }
+
+    async fn execute(self) -> CliTypedResult<Value> {
+        let (is_global, path) = parse_key(&self.key)?;
+        let root = if is_global {
+            serde_yaml::to_value(GlobalConfig::load()?)?
+        } else {
+            serde_yaml::to_value(CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?)?
+        };
+
+        let mut value = path
+            .iter()
+            .try_fold(&root, |value, key| value.get(*key))
+            .cloned()
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!("No config value at {}", self.key))
+            })?;
+        if path.last() == Some(&PRIVATE_KEY) {
+            value = Value::String(REDACTED.to_string());
+        } else {
+            redact_private_keys(&mut value);
+        }
+        Ok(value)
+    }
+}
+
+/// Redacts private keys anywhere in the value, they're only shown by `aptos config show-private-key`
+fn redact_private_keys(value: &mut Value) {
+    if let Some(mapping) = value.as_mapping_mut() {
+        for (key, value) in mapping.iter_mut() {
+            if key.as_str() == Some(PRIVATE_KEY) {
+                *value = Value::String(REDACTED.to_string());
+            } else {
+                redact_private_keys(value);
+            }
+        }
+    }
+}
+
+/// Sets a single config value by its dotted key path
+///
+/// The value is parsed as YAML, so numbers and booleans keep their types, and `null` unsets
+/// the value.  The config is validated before it's saved.
+///
+/// Example: `aptos config set profiles.default.rest_url https://fullnode.devnet.aptoslabs.com`
+#[derive(Parser, Debug)]
+pub struct SetConfigValue {
+    /// Dotted path of the value, e.g. `profiles.default.rest_url` or `global.config_type`
+    key: String,
+    /// New value
+    value: String,
+}
+
+#[async_trait]
+impl CliCommand<()> for SetConfigValue {
+    fn command_name(&self) -> &'static str {
+        "SetConfigValue"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let (is_global, path) = parse_key(&self.key)?;
+        let value: Value = serde_yaml::from_str(&self.value)?;
+
+        if is_global {
+            // Environment overrides aren't persisted
+            let mut root = serde_yaml::to_value(GlobalConfig::load_from_file()?)?;
+            set_value(&mut root, &path, value)?;
+            let config: GlobalConfig = serde_yaml::from_value(root)
+                .map_err(|err| CliError::UnableToParse("global config", err.to_string()))?;
+            config.save()
+        } else {
+            let mut config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+            let mut root = serde_yaml::to_value(&config)?;
+            set_value(&mut root, &path, value)?;
+            config.update_from_value(root)?;
+            config.save()
+        }
+    }
+}
+
+/// Splits a key into whether it's in the global config, and its path within that config
+fn parse_key(key: &str) -> CliTypedResult<(bool, Vec<&str>)> {
+    let mut path: Vec<&str> = key.split('.').collect();
+    if path.iter().any(|part| part.is_empty()) {
+        return Err(CliError::CommandArgumentError(format!(
+            "Invalid key {}, must be a dotted path like profiles.default.rest_url",
+            key
+        )));
+    }
+
+    if path[0] == GLOBAL_PREFIX {
+        path.remove(0);
+        if path.is_empty() {
+            return Err(CliError::CommandArgumentError(
+                "Missing global config key, e.g. global.config_type".to_string(),
+            ));
+        }
+        Ok((true, path))
+    } else {
+        Ok((false, path))
+    }
+}
+
+/// Sets the value at `path`, creating any missing maps along the way
+fn set_value(root: &mut Value, path: &[&str], value: Value) -> CliTypedResult<()> {
+    let (last, parents) = path.split_last().unwrap();
+    let mut current = root;
+    for key in parents {
+        if current.is_null() {
+            *current = Value::Mapping(Mapping::new());
+        }
+        let mapping = current.as_mapping_mut().ok_or_else(|| not_a_map(key))?;
+        let key = Value::String(key.to_string());
+        if !mapping.contains_key(&key) {
+            mapping.insert(key.clone(), Value::Null);
+        }
+        current = mapping.get_mut(&key).unwrap();
+    }
+
+    if current.is_null() {
+        *current = Value::Mapping(Mapping::new());
+    }
+    let mapping = current.as_mapping_mut().ok_or_else(|| not_a_map(last))?;
+    if value.is_null() {
+        mapping.remove(&Value::String(last.to_string()));
+    } else {
+        mapping.insert(Value::String(last.to_string()), value);
+    }
+    Ok(())
+}
+
+fn not_a_map(key: &str) -> CliError {
+    CliError::CommandArgumentError(format!("Unable to set {}, its parent isn't a map", key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_nested_value() {
+        let mut root: Value =
+            serde_yaml::from_str("profiles:\n  default:\n    rest_url: a\n").unwrap();
+        set_value(
+            &mut root,
+            &["profiles", "testnet", "rest_url"],
+            Value::String("b".to_string()),
+        )
+        .unwrap();
+        set_value(&mut root, &["profiles", "default", "rest_url"], Value::Null).unwrap();
+
+        assert_eq!(
+            Some(&Value::String("b".to_string())),
+            root["profiles"]["testnet"].get("rest_url")
+        );
+        assert_eq!(None, root["profiles"]["default"].get("rest_url"));
+        assert!(set_value(
+            &mut root,
+            &["profiles", "testnet", "rest_url", "x"],
+            Value::Null
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,100 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Safe writes of config files when several CLI processes run at once
+//!
+//! Writers take an advisory lock on the config folder, so concurrent saves don't interleave,
+//! and replace files with an atomic rename, so readers never see a partially written file.
+
+use crate::common::types::{CliError, CliTypedResult};
+use crate::common::utils::write_to_user_only_file;
+use fs2::FileExt;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+/// Lock file created in each config folder
+const LOCK_FILE: &str = ".lock";
+
+/// An exclusive advisory lock on a config folder, released when dropped
+pub struct ConfigLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl ConfigLock {
+    /// Blocks until the lock on `folder` is acquired
+    pub fn acquire(folder: &Path) -> CliTypedResult<Self> {
+        let path = folder.join(LOCK_FILE);
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(&path)
+            .map_err(|err| CliError::IO(path.display().to_string(), err))?;
+        file.lock_exclusive()
+            .map_err(|err| CliError::IO(path.display().to_string(), err))?;
+        Ok(ConfigLock { file, path })
+    }
+}
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        if let Err(err) = self.file.unlock() {
+            eprintln!("Failed to unlock {}: {}", self.path.display(), err);
+        }
+    }
+}
+
+/// Writes a user only config file by writing a temporary file and renaming it into place
+///
+/// The caller should hold the [`ConfigLock`] of the file's folder.
+pub fn write_config_file(path: &Path, name: &str, bytes: &[u8]) -> CliTypedResult<()> {
+    // The temporary file has to be in the same folder for the rename to be atomic
+    let file_name = path
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .ok_or_else(|| {
+            CliError::UnexpectedError(format!("Invalid config file path {}", path.display()))
+        })?;
+    let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+
+    write_to_user_only_file(&temp_path, name, bytes)?;
+    let result = File::open(&temp_path)
+        .and_then(|file| file.sync_all())
+        .and_then(|_| std::fs::rename(&temp_path, path));
+    if let Err(err) = result {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(CliError::IO(name.to_string(), err));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrent_config_writes() {
+        let folder = tempfile::tempdir().unwrap();
+        let path = folder.path().join("config.yaml");
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let folder = folder.path().to_path_buf();
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let contents = format!("value: {}\n", i).repeat(1000);
+                    let _lock = ConfigLock::acquire(&folder).unwrap();
+                    write_config_file(&path, "config", contents.as_bytes()).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // The file is entirely from one of the writers
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let first_line = contents.lines().next().unwrap();
+        assert!(contents.lines().all(|line| line == first_line));
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Schema versioning for the CLI config files
+//!
+//! Each config file has a `version` field, which is missing (version 0) for files written
+//! before versioning was added.  On load, the raw config is upgraded one version at a time
+//! before being deserialized, and the caller writes back the upgraded file.
+
+use crate::common::types::{CliError, CliTypedResult};
+use crate::config::{ConfigFormat, ConfigType};
+use serde::de::DeserializeOwned;
+use serde_yaml::{Mapping, Value};
+
+/// Current schema version of `global_config.yaml`
+pub const GLOBAL_CONFIG_VERSION: u64 = 1;
+/// Current schema version of `config.yaml`
+pub const CLI_CONFIG_VERSION: u64 = 1;
+
+const VERSION_KEY: &str = "version";
+
+/// Upgrades a config from the previous version, in place
+type Migration = fn(&mut Mapping) -> CliTypedResult<()>;
+
+/// Migrations for `global_config.yaml`, the migration at index `i` upgrades version `i` to `i + 1`
+const GLOBAL_CONFIG_MIGRATIONS: [Migration; GLOBAL_CONFIG_VERSION as usize] =
+    [global_config_v0_to_v1];
+/// Migrations for `config.yaml`, the migration at index `i` upgrades version `i` to `i + 1`
+const CLI_CONFIG_MIGRATIONS: [Migration; CLI_CONFIG_VERSION as usize] = [cli_config_v0_to_v1];
+
+/// Loads `global_config.yaml`, returning whether it was migrated and needs to be saved
+pub fn load_global_config<T: DeserializeOwned>(
+    format: ConfigFormat,
+    input: &str,
+) -> CliTypedResult<(T, bool)> {
+    load_migrated("global config", format, input, &GLOBAL_CONFIG_MIGRATIONS)
+}
+
+/// Loads `config.yaml`, returning whether it was migrated and needs to be saved
+pub fn load_cli_config<T: DeserializeOwned>(
+    format: ConfigFormat,
+    input: &str,
+) -> CliTypedResult<(T, bool)> {
+    load_migrated("config", format, input, &CLI_CONFIG_MIGRATIONS)
+}
+
+fn load_migrated<T: DeserializeOwned>(
+    name: &str,
+    format: ConfigFormat,
+    input: &str,
+    migrations: &[Migration],
+) -> CliTypedResult<(T, bool)> {
+    // Every format is migrated as a YAML value, which can represent all of them
+    let mut mapping = match format.deserialize(input)? {
+        Value::Mapping(mapping) => mapping,
+        // An empty file is an empty config
+        Value::Null => Mapping::new(),
+        _ => {
+            return Err(CliError::ConfigLoadError(
+                name.to_string(),
+                "expected a map of config values".to_string(),
+            ))
+        }
+    };
+
+    let migrated = migrate(name, &mut mapping, migrations)?;
+    Ok((serde_yaml::from_value(Value::Mapping(mapping))?, migrated))
+}
+
+/// Applies all migrations after the config's version, returning whether any were applied
+fn migrate(name: &str, mapping: &mut Mapping, migrations: &[Migration]) -> CliTypedResult<bool> {
+    let latest_version = migrations.len() as u64;
+    let version = match mapping.get(&version_key()) {
+        None => 0,
+        Some(value) => value.as_u64().ok_or_else(|| {
+            CliError::ConfigLoadError(name.to_string(), format!("invalid version {:?}", value))
+        })?,
+    };
+
+    if version > latest_version {
+        return Err(CliError::ConfigLoadError(
+            name.to_string(),
+            format!(
+                "version {} is newer than the latest supported version {}, please upgrade the CLI",
+                version, latest_version
+            ),
+        ));
+    }
+
+    for migration in &migrations[version as usize..] {
+        migration(mapping)?;
+    }
+    mapping.insert(version_key(), Value::Number(latest_version.into()));
+
+    Ok(version != latest_version)
+}
+
+fn version_key() -> Value {
+    Value::String(VERSION_KEY.to_string())
+}
+
+/// Version 0 implicitly used `Workspace` when no `config_type` was set, make it explicit
+/// so the default can change without moving existing users' configs
+fn global_config_v0_to_v1(mapping: &mut Mapping) -> CliTypedResult<()> {
+    let key = Value::String("config_type".to_string());
+    if !mapping.contains_key(&key) {
+        mapping.insert(key, serde_yaml::to_value(ConfigType::Workspace)?);
+    }
+    Ok(())
+}
+
+/// Version 1 only adds the version, so there's nothing to change in the profiles
+fn cli_config_v0_to_v1(_mapping: &mut Mapping) -> CliTypedResult<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GlobalConfig;
+
+    #[test]
+    fn test_migrate_unversioned_global_config() {
+        let (config, migrated): (GlobalConfig, bool) =
+            load_global_config(ConfigFormat::Yaml, "---\n{}\n").unwrap();
+        assert!(migrated);
+        assert_eq!(GLOBAL_CONFIG_VERSION, config.version);
+        assert!(matches!(config.config_type, Some(ConfigType::Workspace)));
+
+        let (_, migrated): (GlobalConfig, bool) =
+            load_global_config(ConfigFormat::Yaml, &serde_yaml::to_string(&config).unwrap())
+                .unwrap();
+        assert!(!migrated);
+    }
+
+    #[test]
+    fn test_reject_newer_config_version() {
+        let result: CliTypedResult<(GlobalConfig, bool)> =
+            load_global_config(ConfigFormat::Yaml, "version: 1000\n");
+        assert!(result.is_err());
+    }
+}
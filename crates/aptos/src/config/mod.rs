@@ -1,20 +1,24 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::common::http::HttpConfig;
+use crate::common::key_store::{KeyBackend, OsKeychain};
+use crate::common::output::{ColorTheme, OutputFormat};
 use crate::common::types::{
-    CliCommand, CliConfig, CliError, CliResult, CliTypedResult, ConfigSearchMode, ProfileSummary,
-    CONFIG_FOLDER,
+    CliCommand, CliConfig, CliError, CliResult, CliTypedResult, ConfigSearchMode,
+    KeyPassphraseOptions, ProfileSummary, PromptOptions, CONFIG_FOLDER, DEFAULT_PROFILE,
 };
 use crate::common::utils::{
-    create_dir_if_not_exist, current_dir, read_from_file, write_to_user_only_file,
+    assume_yes, create_dir_if_not_exist, current_dir, prompt_yes_with_override, read_from_file,
 };
-use crate::genesis::git::{from_yaml, to_yaml};
 use crate::Tool;
+use aptos_crypto::{ed25519::Ed25519PublicKey, PrivateKey, ValidCryptoMaterialStringExt};
 use async_trait::async_trait;
 use clap::ArgEnum;
 use clap::CommandFactory;
 use clap::Parser;
 use clap_complete::{generate, Shell};
+use move_core_types::account_address::AccountAddress;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::BTreeMap;
@@ -22,6 +26,23 @@ use std::fmt::Formatter;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+use audit_log::AuditLogConfig;
+pub use format::ConfigFormat;
+use lock::{write_config_file, ConfigLock};
+use telemetry::TelemetryConfig;
+
+pub mod audit_log;
+pub mod diff;
+pub mod env;
+pub mod export;
+pub mod format;
+pub mod key_path;
+pub mod lock;
+pub mod migration;
+pub mod network;
+pub mod telemetry;
+pub mod validate;
+
 /// Tool for interacting with configuration of the Aptos CLI tool
 ///
 /// This tool handles the global configuration of the CLI tool for
@@ -33,47 +54,176 @@ pub enum ConfigTool {
     SetGlobalConfig(SetGlobalConfig),
     ShowGlobalConfig(ShowGlobalConfig),
     ShowProfiles(ShowProfiles),
+    ListProfiles(ListProfiles),
+    RenameProfile(RenameProfile),
+    DeleteProfile(DeleteProfile),
+    SetDefaultProfile(SetDefaultProfile),
+    EncryptPrivateKey(EncryptPrivateKey),
+    StoreKeyInKeychain(StoreKeyInKeychain),
+    ShowPrivateKey(ShowPrivateKey),
+    Validate(validate::ValidateConfig),
+    MigrateGlobalFolder(MigrateGlobalFolder),
+    Locate(LocateConfig),
+    Export(export::ExportProfiles),
+    Import(export::ImportProfiles),
+    Diff(diff::DiffProfiles),
+    Get(key_path::GetConfigValue),
+    Set(key_path::SetConfigValue),
+    SetTelemetry(telemetry::SetTelemetry),
+    SetAuditLog(audit_log::SetAuditLog),
+    ShowAuditLog(audit_log::ShowAuditLog),
 }
 
 impl ConfigTool {
     pub async fn execute(self) -> CliResult {
         match self {
             ConfigTool::Init(tool) => tool.execute_serialized_success().await,
-            ConfigTool::GenerateShellCompletions(tool) => tool.execute_serialized_success().await,
+            ConfigTool::GenerateShellCompletions(tool) => tool.execute_completions().await,
             ConfigTool::SetGlobalConfig(tool) => tool.execute_serialized().await,
             ConfigTool::ShowGlobalConfig(tool) => tool.execute_serialized().await,
             ConfigTool::ShowProfiles(tool) => tool.execute_serialized().await,
+            ConfigTool::ListProfiles(tool) => tool.execute_serialized().await,
+            ConfigTool::RenameProfile(tool) => tool.execute_serialized_success().await,
+            ConfigTool::DeleteProfile(tool) => tool.execute_serialized_success().await,
+            ConfigTool::SetDefaultProfile(tool) => tool.execute_serialized_success().await,
+            ConfigTool::EncryptPrivateKey(tool) => tool.execute_serialized_success().await,
+            ConfigTool::StoreKeyInKeychain(tool) => tool.execute_serialized_success().await,
+            ConfigTool::ShowPrivateKey(tool) => tool.execute_serialized().await,
+            ConfigTool::Validate(tool) => tool.execute_serialized().await,
+            ConfigTool::MigrateGlobalFolder(tool) => tool.execute_serialized().await,
+            ConfigTool::Locate(tool) => tool.execute_serialized().await,
+            ConfigTool::Export(tool) => tool.execute_serialized().await,
+            ConfigTool::Import(tool) => tool.execute_serialized().await,
+            ConfigTool::Diff(tool) => tool.execute_serialized().await,
+            ConfigTool::Get(tool) => tool.execute_serialized().await,
+            ConfigTool::Set(tool) => tool.execute_serialized_success().await,
+            ConfigTool::SetTelemetry(tool) => tool.execute_serialized().await,
+            ConfigTool::SetAuditLog(tool) => tool.execute_serialized().await,
+            ConfigTool::ShowAuditLog(tool) => tool.execute_serialized().await,
         }
     }
 }
 
 /// Generate shell completion files
 ///
-/// First generate the completion file, then follow the shell specific directions on how
-/// to install the completion file.
+/// Completions are written to stdout unless an output file is given, e.g.
+/// `source <(aptos config generate-shell-completions --shell bash)`.  With `--install`, the
+/// completions are written to the conventional completion directory of the shell.
 #[derive(Parser)]
 pub struct GenerateShellCompletions {
-    /// Shell to generate completions for one of [bash, elvish, powershell, zsh]
+    /// Shell to generate completions for one of [bash, elvish, fish, powershell, zsh]
+    ///
+    /// Defaults to the shell in `$SHELL`
     #[clap(long)]
-    shell: Shell,
+    shell: Option<Shell>,
 
-    /// File to output shell completions to
-    #[clap(long, parse(from_os_str))]
-    output_file: PathBuf,
+    /// File to output shell completions to, defaults to stdout
+    #[clap(long, parse(from_os_str), group = "completions_output")]
+    output_file: Option<PathBuf>,
+
+    /// Install the completions in the shell's completion directory
+    #[clap(long, group = "completions_output")]
+    install: bool,
+}
+
+impl GenerateShellCompletions {
+    /// Completions going to stdout are returned as is, since any other output would
+    /// break sourcing them
+    pub async fn execute_completions(self) -> CliResult {
+        if self.output_file.is_none() && !self.install {
+            let shell = self.shell().map_err(|err| err.to_string())?;
+            let mut completions = Vec::new();
+            generate(shell, &mut Tool::command(), "aptos", &mut completions);
+            String::from_utf8(completions).map_err(|err| err.to_string())
+        } else {
+            self.execute_serialized().await
+        }
+    }
+
+    fn shell(&self) -> CliTypedResult<Shell> {
+        if let Some(shell) = self.shell {
+            return Ok(shell);
+        }
+
+        let shell_path = std::env::var_os("SHELL").map(PathBuf::from);
+        shell_path
+            .as_ref()
+            .and_then(|path| path.file_stem())
+            .and_then(|name| name.to_str())
+            .and_then(|name| <Shell as ArgEnum>::from_str(name, true).ok())
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(
+                    "Unable to detect the shell from $SHELL, please provide --shell".to_string(),
+                )
+            })
+    }
 }
 
 #[async_trait]
-impl CliCommand<()> for GenerateShellCompletions {
+impl CliCommand<PathBuf> for GenerateShellCompletions {
     fn command_name(&self) -> &'static str {
         "GenerateShellCompletions"
     }
 
-    async fn execute(self) -> CliTypedResult<()> {
+    async fn execute(self) -> CliTypedResult<PathBuf> {
+        let shell = self.shell()?;
+        let output_file = match self.output_file {
+            Some(output_file) => output_file,
+            None => {
+                let output_file = completions_install_path(shell)?;
+                if let Some(parent) = output_file.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|err| CliError::IO(parent.display().to_string(), err))?;
+                }
+                output_file
+            }
+        };
+
         let mut command = Tool::command();
-        let mut file = std::fs::File::create(self.output_file.as_path())
-            .map_err(|err| CliError::IO(self.output_file.display().to_string(), err))?;
-        generate(self.shell, &mut command, "aptos".to_string(), &mut file);
-        Ok(())
+        let mut file = std::fs::File::create(output_file.as_path())
+            .map_err(|err| CliError::IO(output_file.display().to_string(), err))?;
+        generate(shell, &mut command, "aptos".to_string(), &mut file);
+
+        if self.install && shell == Shell::Zsh {
+            eprintln!(
+                "Add `fpath=({} $fpath)` to your .zshrc before `compinit` to load the completions",
+                output_file.parent().unwrap().display()
+            );
+        }
+        Ok(output_file)
+    }
+}
+
+/// Conventional location of user installed completions for each shell
+fn completions_install_path(shell: Shell) -> CliTypedResult<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| {
+        CliError::UnexpectedError("Unable to retrieve home directory".to_string())
+    })?;
+    let xdg_dir = |var: &str, default: &str| {
+        std::env::var_os(var)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(default))
+    };
+
+    match shell {
+        Shell::Bash => Ok(xdg_dir("XDG_DATA_HOME", ".local/share")
+            .join("bash-completion")
+            .join("completions")
+            .join("aptos")),
+        Shell::Fish => Ok(xdg_dir("XDG_CONFIG_HOME", ".config")
+            .join("fish")
+            .join("completions")
+            .join("aptos.fish")),
+        Shell::Elvish => Ok(xdg_dir("XDG_CONFIG_HOME", ".config")
+            .join("elvish")
+            .join("lib")
+            .join("aptos.elv")),
+        Shell::Zsh => Ok(home.join(".zfunc").join("_aptos")),
+        _ => Err(CliError::CommandArgumentError(format!(
+            "No conventional completion directory for {}, please use --output-file and source it from your profile",
+            shell
+        ))),
     }
 }
 
@@ -85,9 +235,56 @@ pub struct SetGlobalConfig {
     /// A configuration for where to place and use the config
     ///
     /// `Workspace` will put the `.aptos/` folder in the current directory, where
-    /// `Global` will put the `.aptos/` folder in your home directory
+    /// `Global` will put the config in `$XDG_CONFIG_HOME/aptos` if set, or the `.aptos/`
+    /// folder in your home directory
     #[clap(long)]
     config_type: Option<ConfigType>,
+
+    /// File format to save the config files in, one of [yaml, json, toml]
+    ///
+    /// Existing config files are converted the next time they're saved
+    #[clap(long)]
+    config_format: Option<ConfigFormat>,
+
+    /// Default format of command output, one of [json, yaml, table, csv]
+    ///
+    /// Can be overridden per command with `--output`
+    #[clap(long)]
+    output_format: Option<OutputFormat>,
+
+    /// Colors of tables on a terminal, one of [default, bright, off]
+    ///
+    /// Color is also turned off per command with `--no-color`, or by setting `NO_COLOR`
+    #[clap(long)]
+    color_theme: Option<ColorTheme>,
+
+    /// Answer yes to all confirmation prompts, and fail instead of prompting for other input
+    ///
+    /// Useful for running the CLI in automation, where there's nobody to answer prompts
+    #[clap(long)]
+    assume_yes: Option<bool>,
+
+    /// Proxy for the REST and faucet endpoints of all profiles
+    ///
+    /// e.g. `http://proxy.example.com:3128`, profiles can override it in their `http` settings
+    #[clap(long)]
+    proxy: Option<reqwest::Url>,
+
+    /// Comma separated hosts, domains and IP ranges to reach without the proxy
+    #[clap(long)]
+    no_proxy: Option<String>,
+
+    /// PEM file of extra root certificates to trust, e.g. of a TLS intercepting gateway
+    #[clap(long, parse(from_os_str))]
+    ca_bundle: Option<PathBuf>,
+
+    /// Times to retry REST requests which only read, after transient failures
+    #[clap(long)]
+    max_retries: Option<u32>,
+
+    /// Seconds to keep retrying a REST request for
+    #[clap(long)]
+    retry_timeout_secs: Option<u64>,
 }
 
 #[async_trait]
@@ -97,13 +294,55 @@ impl CliCommand<GlobalConfig> for SetGlobalConfig {
     }
 
     async fn execute(self) -> CliTypedResult<GlobalConfig> {
-        // Load the global config
-        let mut config = GlobalConfig::load()?;
+        // Load the global config, without environment overrides so they aren't persisted
+        let mut config = GlobalConfig::load_from_file()?;
 
         // Enable all features that are actually listed
         if let Some(config_type) = self.config_type {
             config.config_type = Some(config_type);
         }
+        if let Some(config_format) = self.config_format {
+            config.config_format = Some(config_format);
+        }
+        if let Some(output_format) = self.output_format {
+            config.output_format = Some(output_format);
+        }
+        if let Some(color_theme) = self.color_theme {
+            config.color_theme = Some(color_theme);
+        }
+        if let Some(assume_yes) = self.assume_yes {
+            config.assume_yes = Some(assume_yes);
+        }
+        if self.proxy.is_some()
+            || self.no_proxy.is_some()
+            || self.ca_bundle.is_some()
+            || self.max_retries.is_some()
+            || self.retry_timeout_secs.is_some()
+        {
+            let mut http = config.http.take().unwrap_or_default();
+            if let Some(proxy) = self.proxy {
+                http.proxy = Some(proxy.to_string());
+            }
+            if let Some(no_proxy) = self.no_proxy {
+                http.no_proxy = Some(no_proxy);
+            }
+            if let Some(ca_bundle) = self.ca_bundle {
+                if !ca_bundle.is_file() {
+                    return Err(CliError::CommandArgumentError(format!(
+                        "CA bundle {} doesn't exist",
+                        ca_bundle.display()
+                    )));
+                }
+                http.ca_bundle = Some(ca_bundle);
+            }
+            if let Some(max_retries) = self.max_retries {
+                http.max_retries = Some(max_retries);
+            }
+            if let Some(retry_timeout_secs) = self.retry_timeout_secs {
+                http.retry_timeout_secs = Some(retry_timeout_secs);
+            }
+            config.http = Some(http);
+        }
 
         config.save()?;
         config.display()
@@ -131,7 +370,7 @@ impl CliCommand<BTreeMap<String, ProfileSummary>> for ShowProfiles {
 
     async fn execute(self) -> CliTypedResult<BTreeMap<String, ProfileSummary>> {
         // Load the profile config
-        let config = CliConfig::load(ConfigSearchMode::CurrentDir)?;
+        let config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
         Ok(config
             .profiles
             .unwrap_or_default()
@@ -148,6 +387,412 @@ impl CliCommand<BTreeMap<String, ProfileSummary>> for ShowProfiles {
     }
 }
 
+/// Lists the names of the profiles available
+///
+/// The profile used when `--profile` is not given is shown as `default_profile`
+#[derive(Parser, Debug)]
+pub struct ListProfiles {}
+
+/// Names of all profiles in the config
+#[derive(Debug, Serialize)]
+pub struct ProfileList {
+    pub default_profile: String,
+    pub profiles: Vec<String>,
+}
+
+#[async_trait]
+impl CliCommand<ProfileList> for ListProfiles {
+    fn command_name(&self) -> &'static str {
+        "ListProfiles"
+    }
+
+    async fn execute(self) -> CliTypedResult<ProfileList> {
+        let config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+        Ok(ProfileList {
+            default_profile: config.default_profile_name().to_string(),
+            profiles: config.profiles.unwrap_or_default().into_keys().collect(),
+        })
+    }
+}
+
+/// Renames an existing profile
+///
+/// If the renamed profile is the default profile, the default will follow the new name
+#[derive(Parser, Debug)]
+pub struct RenameProfile {
+    /// Name of the existing profile
+    #[clap(long)]
+    profile: String,
+    /// New name for the profile
+    #[clap(long)]
+    new_name: String,
+    #[clap(flatten)]
+    prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<()> for RenameProfile {
+    fn command_name(&self) -> &'static str {
+        "RenameProfile"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let new_name = self.new_name.trim();
+        if new_name.is_empty() {
+            return Err(CliError::CommandArgumentError(
+                "New profile name must not be empty".to_string(),
+            ));
+        }
+
+        let mut config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+        if config.has_profile(new_name) {
+            prompt_yes_with_override(
+                &format!(
+                    "Profile {} already exists, do you want to overwrite it?",
+                    new_name
+                ),
+                self.prompt_options,
+            )?;
+        }
+
+        config.rename_profile(&self.profile, new_name)?;
+
+        config.save()
+    }
+}
+
+/// Deletes an existing profile
+#[derive(Parser, Debug)]
+pub struct DeleteProfile {
+    /// Name of the profile to delete
+    #[clap(long)]
+    profile: String,
+    #[clap(flatten)]
+    prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<()> for DeleteProfile {
+    fn command_name(&self) -> &'static str {
+        "DeleteProfile"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+        config.check_deletable(&self.profile)?;
+
+        prompt_yes_with_override(
+            &format!(
+                "Are you sure you want to delete profile {}?  Any private key stored in it will be lost",
+                self.profile
+            ),
+            self.prompt_options,
+        )?;
+        if let Some(keychain_entry) = config
+            .delete_profile(&self.profile)
+            .and_then(|profile_config| profile_config.keychain_entry)
+        {
+            if let Err(err) = OsKeychain.delete_private_key(&keychain_entry) {
+                eprintln!("Unable to remove key from the OS keychain: {}", err);
+            }
+        }
+
+        config.save()
+    }
+}
+
+/// Sets the profile to use when `--profile` is not given
+#[derive(Parser, Debug)]
+pub struct SetDefaultProfile {
+    /// Name of the profile to use as the default
+    #[clap(long)]
+    profile: String,
+}
+
+#[async_trait]
+impl CliCommand<()> for SetDefaultProfile {
+    fn command_name(&self) -> &'static str {
+        "SetDefaultProfile"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+        if !config.has_profile(&self.profile) {
+            return Err(CliError::CommandArgumentError(format!(
+                "Profile {} not found",
+                self.profile
+            )));
+        }
+
+        config.default_profile = Some(self.profile);
+        config.save()
+    }
+}
+
+/// Encrypts the plaintext private key stored in a profile
+///
+/// The passphrase will be needed whenever the key is used to sign
+#[derive(Parser, Debug)]
+pub struct EncryptPrivateKey {
+    /// Profile whose private key should be encrypted
+    ///
+    /// Defaults to the profile in use, from `APTOS_PROFILE` or the default profile
+    #[clap(long)]
+    profile: Option<String>,
+    #[clap(flatten)]
+    key_passphrase_options: KeyPassphraseOptions,
+}
+
+#[async_trait]
+impl CliCommand<()> for EncryptPrivateKey {
+    fn command_name(&self) -> &'static str {
+        "EncryptPrivateKey"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+        let profile = config.active_profile_name(self.profile.as_deref());
+        let mut profile_config = config.remove_profile(&profile).ok_or_else(|| {
+            CliError::CommandArgumentError(format!("Profile {} not found", profile))
+        })?;
+
+        if profile_config.private_key.is_none() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Profile {} has no plaintext private key to encrypt",
+                profile
+            )));
+        }
+
+        let passphrase = self.key_passphrase_options.passphrase(true)?;
+        profile_config.encrypt_private_key(&passphrase)?;
+        config.insert_profile(&profile, profile_config);
+        config.save()
+    }
+}
+
+/// Moves the private key of a profile into the OS keychain
+///
+/// The profile will only keep a reference to the keychain entry, and the key will be
+/// retrieved from the keychain whenever it's used to sign
+#[derive(Parser, Debug)]
+pub struct StoreKeyInKeychain {
+    /// Profile whose private key should be moved
+    ///
+    /// Defaults to the profile in use, from `APTOS_PROFILE` or the default profile
+    #[clap(long)]
+    profile: Option<String>,
+    /// Name of the keychain entry
+    ///
+    /// Defaults to the profile name
+    #[clap(long)]
+    entry: Option<String>,
+    #[clap(flatten)]
+    key_passphrase_options: KeyPassphraseOptions,
+}
+
+#[async_trait]
+impl CliCommand<()> for StoreKeyInKeychain {
+    fn command_name(&self) -> &'static str {
+        "StoreKeyInKeychain"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+        let profile = config.active_profile_name(self.profile.as_deref());
+        let entry = self.entry.as_deref().unwrap_or(&profile);
+        let mut profile_config = config.remove_profile(&profile).ok_or_else(|| {
+            CliError::CommandArgumentError(format!("Profile {} not found", profile))
+        })?;
+
+        profile_config.store_private_key_in(&OsKeychain, entry, &self.key_passphrase_options)?;
+        config.insert_profile(&profile, profile_config);
+        config.save()
+    }
+}
+
+/// Reveals the private key of a profile
+///
+/// Private keys are redacted from all other output.  Anyone who sees the key can take over
+/// the account, so this asks for confirmation unless `--force` is given.  Keys on a Ledger
+/// never leave the device, and can't be shown.
+#[derive(Parser, Debug)]
+pub struct ShowPrivateKey {
+    /// Profile whose private key should be shown
+    ///
+    /// Defaults to the profile in use, from `APTOS_PROFILE` or the default profile
+    #[clap(long)]
+    profile: Option<String>,
+    /// Show the key without asking for confirmation
+    ///
+    /// Required when prompts are disabled by the `assume_yes` global config
+    #[clap(long)]
+    force: bool,
+    #[clap(flatten)]
+    key_passphrase_options: KeyPassphraseOptions,
+}
+
+/// A private key revealed by `aptos config show-private-key`
+#[derive(Debug, Serialize)]
+pub struct RevealedPrivateKey {
+    pub private_key: String,
+    pub public_key: Ed25519PublicKey,
+    pub account: Option<AccountAddress>,
+}
+
+#[async_trait]
+impl CliCommand<RevealedPrivateKey> for ShowPrivateKey {
+    fn command_name(&self) -> &'static str {
+        "ShowPrivateKey"
+    }
+
+    async fn execute(self) -> CliTypedResult<RevealedPrivateKey> {
+        let profile = if CliConfig::config_exists(ConfigSearchMode::CurrentDirAndParents) {
+            CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?
+                .active_profile_name(self.profile.as_deref())
+        } else {
+            // Without a config the key can only come from the environment
+            self.profile
+                .clone()
+                .or_else(env::profile_override)
+                .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+        };
+        let profile_config =
+            CliConfig::load_profile(Some(&profile), ConfigSearchMode::CurrentDirAndParents)?
+                .ok_or_else(|| {
+                    CliError::CommandArgumentError(format!("Profile {} not found", profile))
+                })?;
+
+        if profile_config.ledger_derivation_path.is_some() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Profile {} signs with a Ledger, its private key can't leave the device",
+                profile
+            )));
+        }
+
+        // Assuming yes for every prompt isn't enough to reveal a key
+        if !self.force {
+            if assume_yes() {
+                return Err(CliError::CommandArgumentError(
+                    "Prompts are disabled, use --force to show the private key".to_string(),
+                ));
+            }
+            prompt_yes_with_override(
+                &format!(
+                    "Anyone who sees the private key of profile {} can take over its account, are you sure you want to show it?",
+                    profile
+                ),
+                PromptOptions::default(),
+            )?;
+        }
+
+        let private_key = profile_config
+            .resolve_private_key(&self.key_passphrase_options)?
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!("Profile {} has no private key", profile))
+            })?;
+        Ok(RevealedPrivateKey {
+            private_key: private_key
+                .to_encoded_string()
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
+            public_key: private_key.public_key(),
+            account: profile_config.account,
+        })
+    }
+}
+
+/// Moves the global config from `~/.aptos` to the platform's standard config directory
+///
+/// The standard directory is `$XDG_CONFIG_HOME/aptos`, or `%APPDATA%\aptos` on Windows.
+#[derive(Parser, Debug)]
+pub struct MigrateGlobalFolder {
+    #[clap(flatten)]
+    prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<String> for MigrateGlobalFolder {
+    fn command_name(&self) -> &'static str {
+        "MigrateGlobalFolder"
+    }
+
+    async fn execute(self) -> CliTypedResult<String> {
+        let legacy_folder = legacy_global_folder()?;
+        let standard_folder = standard_global_folder().ok_or_else(|| {
+            CliError::CommandArgumentError(
+                "No standard config directory set, set $XDG_CONFIG_HOME (or %APPDATA% on Windows)"
+                    .to_string(),
+            )
+        })?;
+
+        if !legacy_folder.is_dir() {
+            return Err(CliError::CommandArgumentError(format!(
+                "No global config found at {}",
+                legacy_folder.display()
+            )));
+        }
+        if standard_folder.exists() {
+            return Err(CliError::CommandArgumentError(format!(
+                "{} already exists, remove it before migrating",
+                standard_folder.display()
+            )));
+        }
+
+        prompt_yes_with_override(
+            &format!(
+                "Move the global config from {} to {}?",
+                legacy_folder.display(),
+                standard_folder.display()
+            ),
+            self.prompt_options,
+        )?;
+
+        if let Some(parent) = standard_folder.parent() {
+            create_dir_if_not_exist(parent)?;
+        }
+        std::fs::rename(&legacy_folder, &standard_folder)
+            .map_err(|err| CliError::IO(legacy_folder.display().to_string(), err))?;
+
+        Ok(standard_folder.display().to_string())
+    }
+}
+
+/// Shows which config files would be used from the current directory
+///
+/// In `Workspace` mode, the nearest `.aptos/` folder in the current directory or its parents
+/// is used, in `Global` mode, the global config folder is used
+#[derive(Parser, Debug)]
+pub struct LocateConfig {}
+
+/// Locations of the config files in use
+#[derive(Debug, Serialize)]
+pub struct ConfigLocation {
+    pub config_type: ConfigType,
+    pub global_config_folder: PathBuf,
+    pub config_folder: PathBuf,
+    /// The config file in `config_folder`, if one has been created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_file: Option<PathBuf>,
+}
+
+#[async_trait]
+impl CliCommand<ConfigLocation> for LocateConfig {
+    fn command_name(&self) -> &'static str {
+        "LocateConfig"
+    }
+
+    async fn execute(self) -> CliTypedResult<ConfigLocation> {
+        let global_config = GlobalConfig::load()?;
+        let (config_folder, config_file) =
+            CliConfig::locate(ConfigSearchMode::CurrentDirAndParents)?;
+        Ok(ConfigLocation {
+            config_type: global_config.config_type.unwrap_or_default(),
+            global_config_folder: global_folder()?,
+            config_folder,
+            config_file,
+        })
+    }
+}
+
 /// Shows the properties in the global config
 #[derive(Parser, Debug)]
 pub struct ShowGlobalConfig {}
@@ -166,14 +811,54 @@ impl CliCommand<GlobalConfig> for ShowGlobalConfig {
     }
 }
 
-const GLOBAL_CONFIG_FILE: &str = "global_config.yaml";
+const GLOBAL_CONFIG_FILE: &str = "global_config";
 
 /// A global configuration for global settings related to a user
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct GlobalConfig {
+    /// Schema version of the config, see [`migration`]
+    #[serde(default)]
+    pub version: u64,
     /// Whether to be using Global or Workspace mode
     #[serde(skip_serializing_if = "Option::is_none")]
     pub config_type: Option<ConfigType>,
+    /// File format of the config files, defaults to YAML
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_format: Option<ConfigFormat>,
+    /// Default format of command output, defaults to JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<OutputFormat>,
+    /// Colors of tables on a terminal, defaults to the default theme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_theme: Option<ColorTheme>,
+    /// Whether to answer yes to all prompts, and fail on any other input, defaults to false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assume_yes: Option<bool>,
+    /// Telemetry settings, see `aptos config set-telemetry`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub telemetry: Option<TelemetryConfig>,
+    /// Proxy and root certificates for all profiles, see [`HttpConfig`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpConfig>,
+    /// Audit log settings, see `aptos config set-audit-log`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit_log: Option<AuditLogConfig>,
+}
+
+impl Default for GlobalConfig {
+    fn default() -> Self {
+        GlobalConfig {
+            version: migration::GLOBAL_CONFIG_VERSION,
+            config_type: None,
+            config_format: None,
+            output_format: None,
+            color_theme: None,
+            assume_yes: None,
+            telemetry: None,
+            http: None,
+            audit_log: None,
+        }
+    }
 }
 
 impl GlobalConfig {
@@ -182,14 +867,44 @@ impl GlobalConfig {
         if self.config_type.is_none() {
             self.config_type = Some(ConfigType::default());
         }
+        if self.config_format.is_none() {
+            self.config_format = Some(ConfigFormat::default());
+        }
+        if self.output_format.is_none() {
+            self.output_format = Some(OutputFormat::default());
+        }
+        if self.color_theme.is_none() {
+            self.color_theme = Some(ColorTheme::default());
+        }
+        if self.assume_yes.is_none() {
+            self.assume_yes = Some(false);
+        }
 
         Ok(self)
     }
 
+    /// Loads the global config, with any `APTOS_*` environment overrides applied
     pub fn load() -> CliTypedResult<Self> {
-        let path = global_folder()?.join(GLOBAL_CONFIG_FILE);
-        if path.exists() {
-            from_yaml(&String::from_utf8(read_from_file(path.as_path())?)?)
+        let mut config = Self::load_from_file()?;
+        env::apply_global_overrides(&mut config)?;
+        Ok(config)
+    }
+
+    /// Loads the global config as it is on disk
+    pub fn load_from_file() -> CliTypedResult<Self> {
+        if let Some((path, format)) =
+            format::find_config_file(global_folder()?.as_path(), GLOBAL_CONFIG_FILE)
+        {
+            let (config, migrated): (GlobalConfig, bool) = migration::load_global_config(
+                format,
+                &String::from_utf8(read_from_file(path.as_path())?)?,
+            )?;
+
+            // Write back the upgraded schema, so it only has to be migrated once
+            if migrated {
+                config.save()?;
+            }
+            Ok(config)
         } else {
             // If we don't have a config, let's load the default
             Ok(GlobalConfig::default())
@@ -208,15 +923,43 @@ impl GlobalConfig {
         let global_folder = global_folder()?;
         create_dir_if_not_exist(global_folder.as_path())?;
 
-        write_to_user_only_file(
-            global_folder.join(GLOBAL_CONFIG_FILE).as_path(),
+        let _lock = ConfigLock::acquire(global_folder.as_path())?;
+        let format = self.config_format.unwrap_or_default();
+        write_config_file(
+            global_folder
+                .join(format!("{}.{}", GLOBAL_CONFIG_FILE, format.extension()))
+                .as_path(),
             "Global Config",
-            &to_yaml(&self)?.into_bytes(),
-        )
+            &format.serialize(&self)?.into_bytes(),
+        )?;
+        format::remove_other_formats(global_folder.as_path(), GLOBAL_CONFIG_FILE, format);
+        Ok(())
     }
 }
 
-fn global_folder() -> CliTypedResult<PathBuf> {
+/// Folder name for the global config inside a standard config directory
+const STANDARD_CONFIG_FOLDER: &str = "aptos";
+
+/// Finds the folder for the global config
+///
+/// Prefers the platform's standard config directory, `$XDG_CONFIG_HOME/aptos` or
+/// `%APPDATA%\aptos` on Windows, but keeps using an existing `~/.aptos` until it's moved with
+/// `aptos config migrate-global-folder`.  `APTOS_GLOBAL_CONFIG_FOLDER` overrides both.
+pub(crate) fn global_folder() -> CliTypedResult<PathBuf> {
+    if let Some(folder) = env::global_folder_override() {
+        return Ok(folder);
+    }
+    let legacy_folder = legacy_global_folder()?;
+    match standard_global_folder() {
+        Some(standard_folder) if standard_folder.is_dir() || !legacy_folder.is_dir() => {
+            Ok(standard_folder)
+        }
+        _ => Ok(legacy_folder),
+    }
+}
+
+/// The global config folder in the home directory, `~/.aptos`
+fn legacy_global_folder() -> CliTypedResult<PathBuf> {
     if let Some(dir) = dirs::home_dir() {
         Ok(dir.join(CONFIG_FOLDER))
     } else {
@@ -226,6 +969,18 @@ fn global_folder() -> CliTypedResult<PathBuf> {
     }
 }
 
+/// The global config folder in the platform's standard config directory, if one is set
+fn standard_global_folder() -> Option<PathBuf> {
+    let config_dir_var = if cfg!(windows) {
+        "APPDATA"
+    } else {
+        "XDG_CONFIG_HOME"
+    };
+    std::env::var_os(config_dir_var)
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join(STANDARD_CONFIG_FOLDER))
+}
+
 fn find_workspace_config(
     starting_path: PathBuf,
     mode: ConfigSearchMode,
@@ -256,7 +1011,7 @@ const WORKSPACE: &str = "workspace";
 /// Global allows for one config for every part of the code
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, ArgEnum)]
 pub enum ConfigType {
-    /// Per system user configuration put in `<HOME>/.aptos`
+    /// Per system user configuration put in `$XDG_CONFIG_HOME/aptos` or `<HOME>/.aptos`
     Global,
     /// Per directory configuration put in `<CURRENT_DIR>/.aptos`
     Workspace,
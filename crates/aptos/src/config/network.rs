@@ -0,0 +1,98 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Built-in presets for the well known networks
+//!
+//! A profile with a preset network doesn't need its endpoints to be configured by hand, and
+//! devnet profiles always follow the preset, so they keep working after devnet is reset.
+
+use crate::common::init::Network;
+use crate::common::types::{CliError, CliTypedResult, ProfileConfig};
+use aptos_types::chain_id::NamedChain;
+
+/// Endpoints and chain ID of a well known network
+#[derive(Debug)]
+pub struct NetworkPreset {
+    pub network: Network,
+    pub rest_url: &'static str,
+    pub faucet_url: Option<&'static str>,
+    /// Chain ID of the network, `None` if it changes, e.g. when devnet is reset
+    pub chain_id: Option<NamedChain>,
+}
+
+/// Presets for all networks except `custom`
+pub const NETWORK_PRESETS: [NetworkPreset; 4] = [
+    NetworkPreset {
+        network: Network::Mainnet,
+        rest_url: "https://fullnode.mainnet.aptoslabs.com",
+        faucet_url: None,
+        chain_id: Some(NamedChain::MAINNET),
+    },
+    NetworkPreset {
+        network: Network::Testnet,
+        rest_url: "https://fullnode.testnet.aptoslabs.com",
+        faucet_url: None,
+        chain_id: Some(NamedChain::TESTNET),
+    },
+    NetworkPreset {
+        network: Network::Devnet,
+        rest_url: "https://fullnode.devnet.aptoslabs.com",
+        faucet_url: Some("https://faucet.devnet.aptoslabs.com"),
+        chain_id: None,
+    },
+    NetworkPreset {
+        network: Network::Local,
+        rest_url: "http://localhost:8080",
+        faucet_url: Some("http://localhost:8081"),
+        chain_id: None,
+    },
+];
+
+impl NetworkPreset {
+    /// Looks up the preset for a network, `custom` has none
+    pub fn for_network(network: Network) -> Option<&'static NetworkPreset> {
+        NETWORK_PRESETS
+            .iter()
+            .find(|preset| preset.network == network)
+    }
+
+    /// Sets the endpoints of the profile to the preset's
+    pub fn apply(&self, profile_config: &mut ProfileConfig) {
+        profile_config.network = Some(self.network);
+        profile_config.rest_url = Some(self.rest_url.to_string());
+        profile_config.faucet_url = self.faucet_url.map(|url| url.to_string());
+    }
+
+    /// Checks the chain ID reported by the REST endpoint against the preset's
+    pub fn check_chain_id(&self, chain_id: u8) -> CliTypedResult<()> {
+        match self.chain_id {
+            Some(expected) if expected.id() != chain_id => Err(CliError::UnexpectedError(format!(
+                "REST endpoint has chain ID {}, but the network {:?} has chain ID {}",
+                chain_id,
+                self.network,
+                expected.id()
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Resolves the endpoints of a profile from its network's preset
+///
+/// Missing endpoints are filled in for all preset networks.  Devnet endpoints are always
+/// re-resolved, since a stale devnet profile is useless after a reset.
+pub fn resolve_endpoints(profile_config: &mut ProfileConfig) {
+    let preset = match profile_config.network.and_then(NetworkPreset::for_network) {
+        Some(preset) => preset,
+        None => return,
+    };
+
+    if preset.network == Network::Devnet {
+        preset.apply(profile_config);
+    } else if profile_config.rest_url.is_none() {
+        profile_config.rest_url = Some(preset.rest_url.to_string());
+        if profile_config.faucet_url.is_none() {
+            profile_config.faucet_url = preset.faucet_url.map(|url| url.to_string());
+        }
+    }
+}
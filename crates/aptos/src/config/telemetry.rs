@@ -0,0 +1,102 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliError, CliTypedResult};
+use crate::config::GlobalConfig;
+use aptos_telemetry::cli_metrics::CliTelemetryOptions;
+use async_trait::async_trait;
+use clap::Parser;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+/// Telemetry settings in the global config
+///
+/// `APTOS_DISABLE_TELEMETRY` still disables telemetry regardless of these settings
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// Whether to send telemetry, defaults to true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Endpoint to send telemetry to, instead of the default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Stable anonymous identifier sent with telemetry, random per event if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anonymous_id: Option<String>,
+}
+
+impl TelemetryConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn options(&self) -> CliTelemetryOptions {
+        CliTelemetryOptions {
+            endpoint: self.endpoint.clone(),
+            anonymous_id: self.anonymous_id.clone(),
+        }
+    }
+}
+
+/// Set telemetry settings in the global config
+///
+/// Any settings that are not provided will not be changed
+#[derive(Parser, Debug)]
+pub struct SetTelemetry {
+    /// Whether to send telemetry
+    #[clap(long)]
+    enabled: Option<bool>,
+
+    /// Endpoint to send telemetry to, e.g. an organization's own collector
+    #[clap(long, group = "telemetry_endpoint")]
+    endpoint: Option<Url>,
+
+    /// Send telemetry to the default endpoint
+    #[clap(long, group = "telemetry_endpoint")]
+    default_endpoint: bool,
+
+    /// Generate a new stable anonymous identifier, to correlate telemetry across commands
+    #[clap(long, group = "telemetry_anonymous_id")]
+    generate_anonymous_id: bool,
+
+    /// Remove the anonymous identifier, so every event gets a random one
+    #[clap(long, group = "telemetry_anonymous_id")]
+    clear_anonymous_id: bool,
+}
+
+#[async_trait]
+impl CliCommand<TelemetryConfig> for SetTelemetry {
+    fn command_name(&self) -> &'static str {
+        "SetTelemetry"
+    }
+
+    async fn execute(self) -> CliTypedResult<TelemetryConfig> {
+        // Load the global config, without environment overrides so they aren't persisted
+        let mut config = GlobalConfig::load_from_file()?;
+        let mut telemetry = config.telemetry.take().unwrap_or_default();
+
+        if let Some(enabled) = self.enabled {
+            telemetry.enabled = Some(enabled);
+        }
+        if let Some(endpoint) = self.endpoint {
+            if !matches!(endpoint.scheme(), "http" | "https") {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Telemetry endpoint {} must be an http(s) URL",
+                    endpoint
+                )));
+            }
+            telemetry.endpoint = Some(endpoint.to_string());
+        } else if self.default_endpoint {
+            telemetry.endpoint = None;
+        }
+        if self.generate_anonymous_id {
+            telemetry.anonymous_id = Some(uuid::Uuid::new_v4().to_string());
+        } else if self.clear_anonymous_id {
+            telemetry.anonymous_id = None;
+        }
+
+        config.telemetry = Some(telemetry.clone());
+        config.save()?;
+        Ok(telemetry)
+    }
+}
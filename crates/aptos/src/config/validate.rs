@@ -0,0 +1,294 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::http::HttpConfig;
+use crate::common::init::Network;
+use crate::common::types::{
+    account_address_from_public_key, CliCommand, CliConfig, CliError, CliTypedResult,
+    ConfigSearchMode, ProfileConfig,
+};
+use crate::config::network::NetworkPreset;
+use aptos_crypto::PrivateKey;
+
+use async_trait::async_trait;
+use clap::Parser;
+use reqwest::Url;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Validates the config and its profiles
+///
+/// Checks that each profile's keys and account are consistent, that the REST and faucet
+/// endpoints are reachable, and that the chain ID of the REST endpoint matches the network.
+/// Encrypted and keychain private keys are not unlocked for validation.
+#[derive(Parser, Debug)]
+pub struct ValidateConfig {
+    /// Profile to validate
+    ///
+    /// If not provided, all profiles are validated
+    #[clap(long)]
+    profile: Option<String>,
+
+    /// Skip probing the REST and faucet endpoints
+    #[clap(long)]
+    offline: bool,
+
+    /// Timeout in seconds for probing each endpoint
+    #[clap(long, default_value = "10")]
+    timeout_secs: u64,
+}
+
+/// Results of validating a single profile
+#[derive(Debug, Default, Serialize)]
+pub struct ProfileValidation {
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u8>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+#[async_trait]
+impl CliCommand<BTreeMap<String, ProfileValidation>> for ValidateConfig {
+    fn command_name(&self) -> &'static str {
+        "ValidateConfig"
+    }
+
+    async fn execute(self) -> CliTypedResult<BTreeMap<String, ProfileValidation>> {
+        let config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+        let profiles = config.profiles.clone().unwrap_or_default();
+        if let Some(ref profile) = self.profile {
+            if !profiles.contains_key(profile) {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Profile {} not found",
+                    profile
+                )));
+            }
+        }
+
+        let mut results = BTreeMap::new();
+        for (name, mut profile_config) in profiles {
+            if self
+                .profile
+                .as_ref()
+                .map_or(false, |profile| profile != &name)
+            {
+                continue;
+            }
+
+            let mut validation = ProfileValidation::default();
+            if let Err(err) = config.inherit_from_base(&name, &mut profile_config) {
+                validation.errors.push(err.to_string());
+            }
+            check_keys(&profile_config, &mut validation);
+            if !self.offline {
+                self.check_endpoints(&profile_config, &mut validation).await;
+            }
+
+            validation.valid = validation.errors.is_empty();
+            results.insert(name, validation);
+        }
+
+        Ok(results)
+    }
+}
+
+impl ValidateConfig {
+    async fn check_endpoints(
+        &self,
+        profile_config: &ProfileConfig,
+        validation: &mut ProfileValidation,
+    ) {
+        let timeout = Duration::from_secs(self.timeout_secs);
+        let http_config = match HttpConfig::resolve(profile_config.http.as_ref()) {
+            Ok(http_config) => http_config,
+            Err(err) => {
+                validation
+                    .errors
+                    .push(format!("Invalid HTTP settings: {}", err));
+                return;
+            }
+        };
+
+        match profile_config.rest_url.as_deref().map(Url::parse) {
+            None => validation.errors.push("No REST URL configured".to_string()),
+            Some(Err(err)) => validation.errors.push(format!("Invalid REST URL: {}", err)),
+            Some(Ok(rest_url)) => {
+                let result = match http_config.rest_client(rest_url.clone(), timeout) {
+                    Ok(client) => client
+                        .get_ledger_information()
+                        .await
+                        .map_err(|err| err.to_string()),
+                    Err(err) => Err(err.to_string()),
+                };
+                match result {
+                    Ok(state) => {
+                        let chain_id = state.into_inner().chain_id;
+                        validation.chain_id = Some(chain_id);
+                        check_chain_id(profile_config.network, chain_id, validation);
+                    }
+                    Err(err) => validation.errors.push(format!(
+                        "REST endpoint {} is unreachable: {}",
+                        rest_url, err
+                    )),
+                }
+            }
+        }
+
+        if let Some(ref faucet_url) = profile_config.faucet_url {
+            match Url::parse(faucet_url).and_then(|url| url.join("health")) {
+                Err(err) => validation
+                    .errors
+                    .push(format!("Invalid faucet URL: {}", err)),
+                Ok(health_url) => {
+                    let response = match http_config.client() {
+                        Ok(client) => client.get(health_url).timeout(timeout).send().await,
+                        Err(err) => {
+                            validation.errors.push(err.to_string());
+                            return;
+                        }
+                    };
+                    match response {
+                        Ok(response) if response.status().is_success() => {}
+                        Ok(response) => validation.errors.push(format!(
+                            "Faucet {} is unhealthy: {}",
+                            faucet_url,
+                            response.status()
+                        )),
+                        Err(err) => validation
+                            .errors
+                            .push(format!("Faucet {} is unreachable: {}", faucet_url, err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Checks that the keys and account of a profile agree with each other
+fn check_keys(profile_config: &ProfileConfig, validation: &mut ProfileValidation) {
+    if !profile_config.has_private_key() && profile_config.ledger_derivation_path.is_none() {
+        validation
+            .warnings
+            .push("No private key, the profile can't sign transactions".to_string());
+    }
+
+    let public_key = match (&profile_config.private_key, &profile_config.public_key) {
+        (Some(private_key), Some(public_key)) => {
+            if &private_key.public_key() != public_key {
+                validation
+                    .errors
+                    .push("Public key doesn't match the private key".to_string());
+            }
+            Some(public_key.clone())
+        }
+        (Some(private_key), None) => Some(private_key.public_key()),
+        (None, public_key) => public_key.clone(),
+    };
+
+    match (public_key, profile_config.account) {
+        (Some(public_key), Some(account)) => {
+            // A mismatch is expected after a key rotation, so it isn't an error
+            if account_address_from_public_key(&public_key) != account {
+                validation.warnings.push(format!(
+                    "Account {} isn't derived from the public key, this is expected only if the key was rotated",
+                    account
+                ));
+            }
+        }
+        (None, None) => validation
+            .errors
+            .push("No account or key to derive an account from".to_string()),
+        _ => {}
+    }
+}
+
+/// Checks that the chain ID is the one expected for the profile's network
+fn check_chain_id(network: Option<Network>, chain_id: u8, validation: &mut ProfileValidation) {
+    // Devnet is reset regularly, and local and custom networks can use any chain ID
+    if let Some(preset) = network.and_then(NetworkPreset::for_network) {
+        if let Err(err) = preset.check_chain_id(chain_id) {
+            validation.errors.push(err.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_keygen::KeyGen;
+    use aptos_types::chain_id::NamedChain;
+
+    #[test]
+    fn test_check_keys() {
+        let mut keygen = KeyGen::from_seed([0; 32]);
+        let private_key = keygen.generate_ed25519_private_key();
+        let other_key = keygen.generate_ed25519_private_key();
+
+        let mut validation = ProfileValidation::default();
+        check_keys(
+            &ProfileConfig {
+                private_key: Some(private_key.clone()),
+                public_key: Some(private_key.public_key()),
+                account: Some(account_address_from_public_key(&private_key.public_key())),
+                ..Default::default()
+            },
+            &mut validation,
+        );
+        assert!(validation.errors.is_empty());
+        assert!(validation.warnings.is_empty());
+
+        let mut validation = ProfileValidation::default();
+        check_keys(
+            &ProfileConfig {
+                private_key: Some(private_key.clone()),
+                public_key: Some(other_key.public_key()),
+                ..Default::default()
+            },
+            &mut validation,
+        );
+        assert_eq!(
+            validation.errors,
+            vec!["Public key doesn't match the private key".to_string()]
+        );
+
+        // A rotated account keeps its address, which is only warned about
+        let mut validation = ProfileValidation::default();
+        check_keys(
+            &ProfileConfig {
+                private_key: Some(private_key),
+                account: Some(account_address_from_public_key(&other_key.public_key())),
+                ..Default::default()
+            },
+            &mut validation,
+        );
+        assert!(validation.errors.is_empty());
+        assert_eq!(validation.warnings.len(), 1);
+        assert!(validation.warnings[0].contains("rotated"));
+    }
+
+    #[test]
+    fn test_check_chain_id() {
+        let mainnet = NamedChain::MAINNET.id();
+        let testnet = NamedChain::TESTNET.id();
+
+        let mut validation = ProfileValidation::default();
+        check_chain_id(Some(Network::Mainnet), mainnet, &mut validation);
+        check_chain_id(Some(Network::Testnet), testnet, &mut validation);
+        // Devnet, local and custom networks, or none, can have any chain ID
+        check_chain_id(Some(Network::Devnet), 57, &mut validation);
+        check_chain_id(Some(Network::Local), 4, &mut validation);
+        check_chain_id(Some(Network::Custom), mainnet, &mut validation);
+        check_chain_id(None, testnet, &mut validation);
+        assert!(validation.errors.is_empty());
+
+        check_chain_id(Some(Network::Mainnet), testnet, &mut validation);
+        check_chain_id(Some(Network::Testnet), mainnet, &mut validation);
+        assert_eq!(validation.errors.len(), 2);
+        assert!(validation.errors[0].contains("Mainnet"));
+        assert!(validation.errors[1].contains("Testnet"));
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    types::{CliCommand, CliError, CliResult, CliTypedResult, ProfileOptions, RestOptions},
+    utils::{percentile_of, recent_gas_unit_prices},
+};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+
+pub mod report;
+
+/// Tool for choosing gas unit prices
+#[derive(Debug, Subcommand)]
+pub enum GasTool {
+    Estimate(EstimateGasPrice),
+}
+
+impl GasTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            GasTool::Estimate(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// Estimate the gas unit price to get a transaction in
+///
+/// Shows the node's estimates along with the prices of recent transactions.  The chosen price is
+/// what `--gas-unit-price auto`, or `auto:<PERCENTILE>` with `--percentile`, would use.
+#[derive(Debug, Parser)]
+pub struct EstimateGasPrice {
+    /// Percentile of recent transactions' gas unit prices to choose, from 0 to 100
+    #[clap(long)]
+    pub(crate) percentile: Option<u8>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+/// Gas unit price estimates, in Octas
+#[derive(Debug, Serialize)]
+pub struct GasPriceEstimate {
+    pub deprioritized_gas_estimate: Option<u64>,
+    pub gas_estimate: u64,
+    pub prioritized_gas_estimate: Option<u64>,
+    /// Number of recent user transactions the percentiles are from
+    pub recent_transactions: usize,
+    pub recent_p10: Option<u64>,
+    pub recent_p50: Option<u64>,
+    pub recent_p90: Option<u64>,
+    pub chosen_gas_unit_price: u64,
+}
+
+#[async_trait]
+impl CliCommand<GasPriceEstimate> for EstimateGasPrice {
+    fn command_name(&self) -> &'static str {
+        "EstimateGasPrice"
+    }
+
+    async fn execute(self) -> CliTypedResult<GasPriceEstimate> {
+        if matches!(self.percentile, Some(percentile) if percentile > 100) {
+            return Err(CliError::CommandArgumentError(
+                "--percentile must be from 0 to 100".to_string(),
+            ));
+        }
+        let client = self.rest_options.client(&self.profile_options)?;
+        let estimation = client.estimate_gas_price().await?.into_inner();
+        let prices = recent_gas_unit_prices(&client).await?;
+
+        // Matches `estimate_gas_unit_price`, without querying the node again
+        let floor = estimation
+            .deprioritized_gas_estimate
+            .unwrap_or(estimation.gas_estimate);
+        let chosen_gas_unit_price = self
+            .percentile
+            .and_then(|percentile| percentile_of(&prices, percentile))
+            .map(|price| price.max(floor))
+            .unwrap_or(estimation.gas_estimate);
+
+        Ok(GasPriceEstimate {
+            deprioritized_gas_estimate: estimation.deprioritized_gas_estimate,
+            gas_estimate: estimation.gas_estimate,
+            prioritized_gas_estimate: estimation.prioritized_gas_estimate,
+            recent_transactions: prices.len(),
+            recent_p10: percentile_of(&prices, 10),
+            recent_p50: percentile_of(&prices, 50),
+            recent_p90: percentile_of(&prices, 90),
+            chosen_gas_unit_price,
+        })
+    }
+}
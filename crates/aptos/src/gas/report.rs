@@ -0,0 +1,162 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Gas reports of Move unit tests and simulated transactions, with baselines to catch regressions
+
+use crate::common::{
+    types::{CliError, CliTypedResult},
+    utils::{read_from_file, write_to_file},
+};
+use clap::Parser;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Options to report the gas used, e.g. by each test
+#[derive(Debug, Default, Parser)]
+pub struct GasReportOptions {
+    /// Print a table of the gas used, most expensive first
+    #[clap(long)]
+    pub gas_report: bool,
+
+    /// Save the gas used as a JSON baseline, to compare later runs with `--gas-baseline`
+    #[clap(long, parse(from_os_str))]
+    pub save_gas_baseline: Option<PathBuf>,
+
+    /// Fail if anything uses more gas than in this JSON baseline, beyond the threshold
+    #[clap(long, parse(from_os_str))]
+    pub gas_baseline: Option<PathBuf>,
+
+    /// Percentage of gas over the baseline allowed before failing
+    #[clap(long, default_value_t = 5.0)]
+    pub gas_regression_threshold: f64,
+}
+
+impl GasReportOptions {
+    /// Whether the gas used needs to be measured
+    pub fn enabled(&self) -> bool {
+        self.gas_report || self.save_gas_baseline.is_some() || self.gas_baseline.is_some()
+    }
+
+    /// Reports the gas used by each name, and checks it against the baseline before saving it
+    pub fn report(&self, gas_used: &BTreeMap<String, u64>) -> CliTypedResult<()> {
+        if self.gas_report {
+            eprintln!("{}", format_table(gas_used));
+        }
+        if let Some(path) = &self.gas_baseline {
+            let baseline: BTreeMap<String, u64> = serde_json::from_slice(&read_from_file(path)?)
+                .map_err(|err| CliError::UnableToParse("gas baseline", err.to_string()))?;
+            let regressions = regressions(&baseline, gas_used, self.gas_regression_threshold);
+            if !regressions.is_empty() {
+                return Err(CliError::UnexpectedError(format!(
+                    "Gas regressed more than {}% over the baseline:\n{}",
+                    self.gas_regression_threshold,
+                    regressions.join("\n")
+                )));
+            }
+        }
+        if let Some(path) = &self.save_gas_baseline {
+            let baseline = serde_json::to_string_pretty(gas_used)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            write_to_file(path, "Gas baseline", baseline.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the gas used as a table, most expensive first
+fn format_table(gas_used: &BTreeMap<String, u64>) -> String {
+    let mut rows: Vec<_> = gas_used.iter().collect();
+    rows.sort_by(|(name_a, gas_a), (name_b, gas_b)| gas_b.cmp(gas_a).then(name_a.cmp(name_b)));
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.len())
+        .chain(std::iter::once("Name".len()))
+        .max()
+        .unwrap_or_default();
+
+    let mut table = format!("{:<width$}  {:>12}\n", "Name", "Gas", width = width);
+    for (name, gas) in rows {
+        table.push_str(&format!("{:<width$}  {:>12}\n", name, gas, width = width));
+    }
+    table
+}
+
+/// Lines describing everything in the baseline that now uses more gas than the threshold allows
+fn regressions(
+    baseline: &BTreeMap<String, u64>,
+    gas_used: &BTreeMap<String, u64>,
+    threshold: f64,
+) -> Vec<String> {
+    baseline
+        .iter()
+        .filter_map(|(name, &before)| {
+            let after = *gas_used.get(name)?;
+            let allowed = before as f64 * (1.0 + threshold / 100.0);
+            if after as f64 > allowed {
+                Some(format!("{}: {} -> {}", name, before, after))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Parses the gas used by each test from the statistics the Move unit test runner prints
+///
+/// Each test is a row of the statistics table, its name in the first column and the gas, or
+/// instructions, used in the last.
+pub fn parse_test_statistics(output: &str) -> BTreeMap<String, u64> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let cells: Vec<_> = line
+                .trim()
+                .strip_prefix('│')?
+                .strip_suffix('│')?
+                .split('│')
+                .map(str::trim)
+                .collect();
+            let gas = cells.last()?.parse().ok()?;
+            Some((cells.first()?.to_string(), gas))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_test_statistics() {
+        let output = "\
+Test Statistics:
+
+┌────────────────────┬────────────┬───────────────────────────┐
+│     Test Name      │    Time    │   Instructions Executed   │
+├────────────────────┼────────────┼───────────────────────────┤
+│ 0x1::coin::test_a  │   0.002    │            120            │
+├────────────────────┼────────────┼───────────────────────────┤
+│ 0x1::coin::test_b  │   0.001    │            35             │
+└────────────────────┴────────────┴───────────────────────────┘
+";
+        let gas_used = parse_test_statistics(output);
+        assert_eq!(
+            BTreeMap::from([
+                ("0x1::coin::test_a".to_string(), 120),
+                ("0x1::coin::test_b".to_string(), 35),
+            ]),
+            gas_used
+        );
+
+        let baseline = BTreeMap::from([
+            ("0x1::coin::test_a".to_string(), 100),
+            ("0x1::coin::test_b".to_string(), 35),
+            ("0x1::coin::removed".to_string(), 1),
+        ]);
+        assert_eq!(
+            vec!["0x1::coin::test_a: 100 -> 120".to_string()],
+            regressions(&baseline, &gas_used, 10.0)
+        );
+        assert!(regressions(&baseline, &gas_used, 20.0).is_empty());
+    }
+}
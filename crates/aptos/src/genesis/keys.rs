@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::common::types::OptionalPoolAddressArgs;
-use crate::common::utils::{create_dir_if_not_exist, current_dir, dir_default_to_current};
+use crate::common::utils::{
+    create_dir_if_not_exist, current_dir, dir_default_to_current, read_line,
+};
 use crate::genesis::git::{LAYOUT_FILE, OPERATOR_FILE, OWNER_FILE};
 use crate::governance::CompileScriptFunction;
 use crate::{
@@ -10,7 +12,7 @@ use crate::{
         types::{CliError, CliTypedResult, PromptOptions, RngArgs},
         utils::{check_if_file_exists, read_from_file, write_to_user_only_file},
     },
-    genesis::git::{from_yaml, to_yaml, GitOptions},
+    genesis::git::{from_yaml, to_yaml, Client, GitOptions},
     CliCommand,
 };
 use aptos_genesis::config::{Layout, OperatorConfiguration, OwnerConfiguration};
@@ -20,7 +22,10 @@ use aptos_types::account_address::AccountAddress;
 use aptos_types::transaction::{Script, Transaction, WriteSetPayload};
 use async_trait::async_trait;
 use clap::Parser;
+use std::collections::HashSet;
+use std::fmt::Display;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 const PRIVATE_KEYS_FILE: &str = "private-keys.yaml";
 pub const PUBLIC_KEYS_FILE: &str = "public-keys.yaml";
@@ -263,12 +268,22 @@ pub fn read_public_identity_file(public_identity_file: &Path) -> CliTypedResult<
 ///
 /// This will generate a layout template file for genesis with some default values.  To start a
 /// new chain, these defaults should be carefully thought through and chosen.
+///
+/// With `--interactive`, it prompts for the chain id, validator users, epoch duration, and stake
+/// limits instead.  If a git repository is given, each user must already have their
+/// configuration in it.
 #[derive(Parser)]
 pub struct GenerateLayoutTemplate {
     /// Path of the output layout template
     #[clap(long, parse(from_os_str), default_value = LAYOUT_FILE)]
     pub(crate) output_file: PathBuf,
 
+    /// Prompt for the values of the layout, rather than using defaults
+    #[clap(long)]
+    pub(crate) interactive: bool,
+
+    #[clap(flatten)]
+    pub(crate) git_options: GitOptions,
     #[clap(flatten)]
     pub(crate) prompt_options: PromptOptions,
 }
@@ -281,7 +296,11 @@ impl CliCommand<()> for GenerateLayoutTemplate {
 
     async fn execute(self) -> CliTypedResult<()> {
         check_if_file_exists(self.output_file.as_path(), self.prompt_options)?;
-        let layout = Layout::default();
+        let layout = if self.interactive {
+            self.prompt_layout()?
+        } else {
+            Layout::default()
+        };
 
         write_to_user_only_file(
             self.output_file.as_path(),
@@ -291,6 +310,99 @@ impl CliCommand<()> for GenerateLayoutTemplate {
     }
 }
 
+impl GenerateLayoutTemplate {
+    fn prompt_layout(&self) -> CliTypedResult<Layout> {
+        let client = if self.git_options.local_repository_dir.is_some()
+            || self.git_options.github_repository.is_some()
+        {
+            Some(self.git_options.clone().get_client()?)
+        } else {
+            None
+        };
+
+        let mut layout = Layout::default();
+        layout.chain_id = prompt_value("Chain id", layout.chain_id)?;
+        loop {
+            eprintln!("Validator users, separated by commas:");
+            let users: Vec<String> = read_line("users")?
+                .split(',')
+                .map(|user| user.trim().to_string())
+                .filter(|user| !user.is_empty())
+                .collect();
+            match validate_users(&users, client.as_ref()) {
+                Ok(()) => {
+                    layout.users = users;
+                    break;
+                }
+                Err(err) => eprintln!("{}", err),
+            }
+        }
+        layout.epoch_duration_secs =
+            prompt_value("Epoch duration in seconds", layout.epoch_duration_secs)?;
+        layout.min_stake = prompt_value("Minimum stake in Octas", layout.min_stake)?;
+        loop {
+            layout.max_stake = prompt_value("Maximum stake in Octas", layout.max_stake)?;
+            if layout.max_stake >= layout.min_stake {
+                break;
+            }
+            eprintln!(
+                "Maximum stake must be at least the minimum stake {}",
+                layout.min_stake
+            );
+        }
+        Ok(layout)
+    }
+}
+
+/// Prompts for a value until it parses, keeping the default for an empty line
+fn prompt_value<T: FromStr + Display>(name: &'static str, default: T) -> CliTypedResult<T>
+where
+    T::Err: Display,
+{
+    loop {
+        eprintln!("{} [{}]:", name, default);
+        let input = read_line(name)?;
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(default);
+        }
+        match input.parse() {
+            Ok(value) => return Ok(value),
+            Err(err) => eprintln!("Invalid {} '{}': {}", name, input, err),
+        }
+    }
+}
+
+/// Checks users are unique, and have their configuration in the repository if given
+fn validate_users(users: &[String], client: Option<&Client>) -> CliTypedResult<()> {
+    if users.is_empty() {
+        return Err(CliError::CommandArgumentError(
+            "At least one user is required".to_string(),
+        ));
+    }
+    let mut seen = HashSet::new();
+    for user in users {
+        if !seen.insert(user) {
+            return Err(CliError::CommandArgumentError(format!(
+                "User {} is repeated",
+                user
+            )));
+        }
+        if let Some(client) = client {
+            let user_dir = PathBuf::from(user);
+            client
+                .get::<serde_yaml::Value>(user_dir.join(OWNER_FILE).as_path())
+                .map_err(|err| {
+                    CliError::CommandArgumentError(format!(
+                        "User {} has no owner configuration in the repository: {}",
+                        user, err
+                    ))
+                })?;
+        }
+    }
+    Ok(())
+}
+
 /// Generate a WriteSet genesis compiled from a script file.
 ///
 /// This will compile a Move script and generate a writeset from that script.
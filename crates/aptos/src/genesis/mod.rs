@@ -12,7 +12,7 @@ use crate::genesis::git::{OPERATOR_FILE, OWNER_FILE};
 use crate::{
     common::{
         types::{CliError, CliTypedResult, PromptOptions},
-        utils::{check_if_file_exists, write_to_file},
+        utils::{check_if_file_exists, read_from_file, write_to_file},
     },
     genesis::git::{
         Client, GitOptions, BALANCES_FILE, EMPLOYEE_VESTING_ACCOUNTS_FILE, LAYOUT_FILE,
@@ -34,8 +34,10 @@ use aptos_genesis::{
 use aptos_logger::info;
 use aptos_types::account_address::{AccountAddress, AccountAddressWithChecks};
 use aptos_types::on_chain_config::OnChainConsensusConfig;
+use aptos_types::waypoint::Waypoint;
 use async_trait::async_trait;
 use clap::Parser;
+use serde::Serialize;
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::path::Path;
@@ -53,11 +55,13 @@ const GENESIS_FILE: &str = "genesis.blob";
 pub enum GenesisTool {
     GenerateGenesis(GenerateGenesis),
     GenerateKeys(keys::GenerateKeys),
+    #[clap(alias = "generate-layout")]
     GenerateLayoutTemplate(keys::GenerateLayoutTemplate),
     GenerateAdminWriteSet(keys::GenerateAdminWriteSet),
     SetupGit(git::SetupGit),
     SetValidatorConfiguration(keys::SetValidatorConfiguration),
     GetPoolAddresses(tools::PoolAddresses),
+    VerifyGenesis(VerifyGenesis),
 }
 
 impl GenesisTool {
@@ -70,6 +74,7 @@ impl GenesisTool {
             GenesisTool::SetupGit(tool) => tool.execute_serialized_success().await,
             GenesisTool::SetValidatorConfiguration(tool) => tool.execute_serialized_success().await,
             GenesisTool::GetPoolAddresses(tool) => tool.execute_serialized().await,
+            GenesisTool::VerifyGenesis(tool) => tool.execute_serialized().await,
         }
     }
 }
@@ -109,17 +114,7 @@ impl CliCommand<Vec<PathBuf>> for GenerateGenesis {
         check_if_file_exists(waypoint_file.as_path(), self.prompt_options)?;
 
         // Generate genesis and waypoint files
-        let (genesis_bytes, waypoint) = if self.mainnet {
-            let mut mainnet_genesis = fetch_mainnet_genesis_info(self.git_options)?;
-            let genesis_bytes = bcs::to_bytes(mainnet_genesis.clone().get_genesis())
-                .map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
-            (genesis_bytes, mainnet_genesis.generate_waypoint()?)
-        } else {
-            let mut test_genesis = fetch_genesis_info(self.git_options)?;
-            let genesis_bytes = bcs::to_bytes(test_genesis.clone().get_genesis())
-                .map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
-            (genesis_bytes, test_genesis.generate_waypoint()?)
-        };
+        let (genesis_bytes, waypoint) = generate_genesis(self.git_options, self.mainnet)?;
         write_to_file(genesis_file.as_path(), GENESIS_FILE, &genesis_bytes)?;
         write_to_file(
             waypoint_file.as_path(),
@@ -130,6 +125,78 @@ impl CliCommand<Vec<PathBuf>> for GenerateGenesis {
     }
 }
 
+/// Generates the genesis transaction and its waypoint from the Git repository
+fn generate_genesis(git_options: GitOptions, mainnet: bool) -> CliTypedResult<(Vec<u8>, Waypoint)> {
+    if mainnet {
+        let mut mainnet_genesis = fetch_mainnet_genesis_info(git_options)?;
+        let genesis_bytes = bcs::to_bytes(mainnet_genesis.clone().get_genesis())
+            .map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
+        Ok((genesis_bytes, mainnet_genesis.generate_waypoint()?))
+    } else {
+        let mut test_genesis = fetch_genesis_info(git_options)?;
+        let genesis_bytes = bcs::to_bytes(test_genesis.clone().get_genesis())
+            .map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
+        Ok((genesis_bytes, test_genesis.generate_waypoint()?))
+    }
+}
+
+/// Verify a published genesis against the git repository it was generated from
+///
+/// This regenerates genesis from the layout, validator configurations, and framework in the
+/// repository, validating each validator's configuration along the way, and compares the result
+/// with the published genesis.blob and waypoint.txt.
+#[derive(Parser)]
+pub struct VerifyGenesis {
+    /// Directory with the published genesis.blob and waypoint.txt
+    ///
+    /// Defaults to the current directory
+    #[clap(long, parse(from_os_str))]
+    genesis_dir: Option<PathBuf>,
+    /// Whether this is mainnet genesis.
+    ///
+    /// Default is false
+    #[clap(long)]
+    mainnet: bool,
+
+    #[clap(flatten)]
+    git_options: GitOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GenesisVerification {
+    verified: bool,
+    genesis_matches: bool,
+    computed_waypoint: String,
+    published_waypoint: String,
+}
+
+#[async_trait]
+impl CliCommand<GenesisVerification> for VerifyGenesis {
+    fn command_name(&self) -> &'static str {
+        "VerifyGenesis"
+    }
+
+    async fn execute(self) -> CliTypedResult<GenesisVerification> {
+        let genesis_dir = dir_default_to_current(self.genesis_dir)?;
+        let published_genesis = read_from_file(genesis_dir.join(GENESIS_FILE).as_path())?;
+        let published_waypoint =
+            String::from_utf8(read_from_file(genesis_dir.join(WAYPOINT_FILE).as_path())?)
+                .map_err(CliError::from)?
+                .trim()
+                .to_string();
+
+        let (genesis_bytes, waypoint) = generate_genesis(self.git_options, self.mainnet)?;
+        let genesis_matches = genesis_bytes == published_genesis;
+        let computed_waypoint = waypoint.to_string();
+        Ok(GenesisVerification {
+            verified: genesis_matches && computed_waypoint == published_waypoint,
+            genesis_matches,
+            computed_waypoint,
+            published_waypoint,
+        })
+    }
+}
+
 /// Retrieves all information for mainnet genesis from the Git repository
 pub fn fetch_mainnet_genesis_info(git_options: GitOptions) -> CliTypedResult<MainnetGenesisInfo> {
     let client = git_options.get_client()?;
@@ -282,6 +282,8 @@ async fn create_layout_file(
 ) {
     GenerateLayoutTemplate {
         output_file: PathBuf::from(file),
+        interactive: false,
+        git_options: GitOptions::default(),
         prompt_options: PromptOptions::yes(),
     }
     .execute()
@@ -3,7 +3,7 @@
 
 use crate::common::types::{
     CliError, CliTypedResult, MovePackageDir, PoolAddressArgs, ProfileOptions, PromptOptions,
-    RestOptions, TransactionOptions, TransactionSummary,
+    RestOptions, Submission, TransactionOptions, TransactionSummary,
 };
 use crate::common::utils::prompt_yes_with_override;
 #[cfg(feature = "no-upload-proposal")]
@@ -209,18 +209,7 @@ impl CliCommand<VerifyProposalResponse> for VerifyProposal {
 
         // Retrieve the onchain proposal
         let client = self.rest_options.client(&self.profile)?;
-        let forum = client
-            .get_account_resource_bcs::<VotingForum>(
-                AccountAddress::ONE,
-                "0x1::voting::VotingForum<0x1::governance_proposal::GovernanceProposal>",
-            )
-            .await?
-            .into_inner();
-        let voting_table = forum.table_handle.0;
-
-        let proposal: Proposal = get_proposal(&client, voting_table, self.proposal_id)
-            .await?
-            .into();
+        let proposal = get_onchain_proposal(&client, self.proposal_id).await?;
 
         // Compare the hashes
         let computed_hash = hash.to_hex();
@@ -234,6 +223,22 @@ impl CliCommand<VerifyProposalResponse> for VerifyProposal {
     }
 }
 
+/// Retrieve a proposal from the governance voting forum
+async fn get_onchain_proposal(client: &Client, proposal_id: u64) -> CliTypedResult<Proposal> {
+    let forum = client
+        .get_account_resource_bcs::<VotingForum>(
+            AccountAddress::ONE,
+            "0x1::voting::VotingForum<0x1::governance_proposal::GovernanceProposal>",
+        )
+        .await?
+        .into_inner();
+    let voting_table = forum.table_handle.0;
+
+    Ok(get_proposal(client, voting_table, proposal_id)
+        .await?
+        .into())
+}
+
 async fn get_proposal(
     client: &aptos_rest_client::Client,
     voting_table: AccountAddress,
@@ -279,12 +284,12 @@ pub struct SubmitProposal {
 }
 
 #[async_trait]
-impl CliCommand<ProposalSubmissionSummary> for SubmitProposal {
+impl CliCommand<Submission<ProposalSubmissionSummary>> for SubmitProposal {
     fn command_name(&self) -> &'static str {
         "SubmitProposal"
     }
 
-    async fn execute(mut self) -> CliTypedResult<ProposalSubmissionSummary> {
+    async fn execute(mut self) -> CliTypedResult<Submission<ProposalSubmissionSummary>> {
         let (_bytecode, script_hash) = self
             .compile_proposal_args
             .compile("SubmitProposal", self.txn_options.prompt_options)?;
@@ -301,7 +306,7 @@ impl CliCommand<ProposalSubmissionSummary> for SubmitProposal {
             self.txn_options.prompt_options,
         )?;
 
-        let txn = self
+        let txn = match self
             .txn_options
             .submit_transaction(aptos_stdlib::aptos_governance_create_proposal(
                 self.pool_address_args.pool_address,
@@ -309,8 +314,21 @@ impl CliCommand<ProposalSubmissionSummary> for SubmitProposal {
                 self.metadata_url.to_string().as_bytes().to_vec(),
                 metadata_hash.to_hex().as_bytes().to_vec(),
             ))
-            .await?;
+            .await?
+        {
+            Submission::Submitted(txn) => txn,
+            Submission::NotSubmitted(not_submitted) => {
+                return Ok(Submission::NotSubmitted(not_submitted))
+            }
+        };
         let txn_summary = TransactionSummary::from(&txn);
+        // The proposal id is only known from its event once the transaction is committed
+        if txn.is_pending() {
+            return Ok(Submission::Submitted(ProposalSubmissionSummary {
+                proposal_id: None,
+                transaction: txn_summary,
+            }));
+        }
         if let Transaction::UserTransaction(inner) = txn {
             // Find event with proposal id
             let proposal_id = if let Some(event) = inner.events.into_iter().find(|event| {
@@ -328,10 +346,10 @@ impl CliCommand<ProposalSubmissionSummary> for SubmitProposal {
                 None
             };
 
-            return Ok(ProposalSubmissionSummary {
+            return Ok(Submission::Submitted(ProposalSubmissionSummary {
                 proposal_id,
                 transaction: txn_summary,
-            });
+            }));
         }
         Err(CliError::UnexpectedError(
             "Unable to find parse proposal transaction output".to_string(),
@@ -440,12 +458,12 @@ pub struct SubmitVote {
 }
 
 #[async_trait]
-impl CliCommand<Vec<TransactionSummary>> for SubmitVote {
+impl CliCommand<Submission<Vec<TransactionSummary>>> for SubmitVote {
     fn command_name(&self) -> &'static str {
         "SubmitVote"
     }
 
-    async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
+    async fn execute(mut self) -> CliTypedResult<Submission<Vec<TransactionSummary>>> {
         let (vote_str, vote) = match (self.yes, self.no) {
             (true, false) => ("Yes", true),
             (false, true) => ("No", false),
@@ -508,18 +526,22 @@ impl CliCommand<Vec<TransactionSummary>> for SubmitVote {
                 self.txn_options.prompt_options,
             )?;
 
-            summaries.push(
-                self.txn_options
-                    .submit_transaction(aptos_stdlib::aptos_governance_vote(
-                        pool_address,
-                        proposal_id,
-                        vote,
-                    ))
-                    .await
-                    .map(TransactionSummary::from)?,
-            );
+            match self
+                .txn_options
+                .submit_transaction(aptos_stdlib::aptos_governance_vote(
+                    pool_address,
+                    proposal_id,
+                    vote,
+                ))
+                .await?
+            {
+                Submission::Submitted(txn) => summaries.push(TransactionSummary::from(txn)),
+                Submission::NotSubmitted(not_submitted) => {
+                    return Ok(Submission::NotSubmitted(not_submitted))
+                }
+            }
         }
-        Ok(summaries)
+        Ok(Submission::Submitted(summaries))
     }
 }
 
@@ -547,7 +569,7 @@ impl std::fmt::Display for ProposalMetadata {
     }
 }
 
-fn compile_in_temp_dir(
+pub(crate) fn compile_in_temp_dir(
     script_name: &str,
     script_path: &Path,
     framework_package_args: &FrameworkPackageArgs,
@@ -630,16 +652,36 @@ pub struct ExecuteProposal {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for ExecuteProposal {
+impl CliCommand<Submission<TransactionSummary>> for ExecuteProposal {
     fn command_name(&self) -> &'static str {
         "ExecuteProposal"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
-        let (bytecode, _script_hash) = self
+    async fn execute(mut self) -> CliTypedResult<Submission<TransactionSummary>> {
+        let (bytecode, script_hash) = self
             .compile_proposal_args
             .compile("ExecuteProposal", self.txn_options.prompt_options)?;
-        // TODO: Check hash so we don't do a failed roundtrip?
+
+        // Check the hash, so a different script doesn't fail on-chain
+        let client = self
+            .txn_options
+            .rest_options
+            .client(&self.txn_options.profile_options)?;
+        let proposal = get_onchain_proposal(&client, self.proposal_id).await?;
+        if proposal.execution_hash != script_hash.to_hex() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Script hash {} doesn't match the execution hash {} of proposal {}",
+                script_hash.to_hex(),
+                proposal.execution_hash,
+                self.proposal_id
+            )));
+        }
+        if proposal.is_resolved {
+            return Err(CliError::CommandArgumentError(format!(
+                "Proposal {} has already been resolved",
+                self.proposal_id
+            )));
+        }
 
         let args = vec![TransactionArgument::U64(self.proposal_id)];
         let txn = TransactionPayload::Script(Script::new(bytecode, vec![], args));
@@ -647,7 +689,7 @@ impl CliCommand<TransactionSummary> for ExecuteProposal {
         self.txn_options
             .submit_transaction(txn)
             .await
-            .map(TransactionSummary::from)
+            .map(|submission| submission.map(TransactionSummary::from))
     }
 }
 
@@ -662,6 +704,13 @@ pub struct CompileScriptFunction {
     #[clap(long, group = "script", parse(from_os_str))]
     pub compiled_script_path: Option<PathBuf>,
 
+    /// Path to a Move package with the script for the proposal
+    ///
+    /// The package must have exactly one script, which can use the package's modules and
+    /// dependencies
+    #[clap(long, group = "script", parse(from_os_str))]
+    pub script_package_dir: Option<PathBuf>,
+
     #[clap(flatten)]
     pub(crate) framework_package_args: FrameworkPackageArgs,
 }
@@ -679,6 +728,9 @@ impl CompileScriptFunction {
             let hash = HashValue::sha3_256_of(bytes.as_slice());
             return Ok((bytes, hash));
         }
+        if let Some(script_package_dir) = &self.script_package_dir {
+            return compile_script(script_package_dir);
+        }
 
         // Check script file
         let script_path = self
@@ -686,7 +738,9 @@ impl CompileScriptFunction {
             .as_ref()
             .ok_or_else(|| {
                 CliError::CommandArgumentError(
-                    "Must choose either --compiled-script-path or --script-path".to_string(),
+                    "Must choose one of --compiled-script-path, --script-package-dir, or \
+                     --script-path"
+                        .to_string(),
                 )
             })?
             .as_path();
@@ -756,7 +810,8 @@ impl CliCommand<()> for GenerateUpgradeProposal {
             testnet,
         } = self;
         let package_path = move_options.get_package_path()?;
-        let options = included_artifacts.build_options(move_options.named_addresses());
+        let options = included_artifacts
+            .build_options(move_options.named_addresses(&ProfileOptions::default())?);
         let package = BuiltPackage::build(package_path, options)?;
         let release = ReleasePackage::new(package)?;
         if testnet {
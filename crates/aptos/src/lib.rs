@@ -6,6 +6,7 @@
 pub mod account;
 pub mod common;
 pub mod config;
+pub mod gas;
 pub mod genesis;
 pub mod governance;
 pub mod move_tool;
@@ -14,22 +15,111 @@ pub mod op;
 pub mod stake;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod test;
+pub mod token;
+pub mod transaction;
 
-use crate::common::types::{CliCommand, CliResult, CliTypedResult};
-use crate::common::utils::cli_build_information;
+use crate::common::output::{
+    set_output_format, set_render_options, OutputFormat, OUTPUT_SCHEMA_VERSION,
+};
+use crate::common::progress::set_progress_enabled;
+use crate::common::types::{
+    CliCommand, CliConfig, CliResult, CliTypedResult, ConfigSearchMode, ProfileOptions,
+    RestOptions, DEFAULT_PROFILE,
+};
+use crate::common::utils::{cli_build_information, set_assume_yes, set_dry_run};
+use crate::config::GlobalConfig;
 use async_trait::async_trait;
 use clap::Parser;
 use std::collections::BTreeMap;
+use std::time::Duration;
 
 /// Command Line Interface (CLI) for developing and interacting with the Aptos blockchain
+///
+/// Failed commands exit with a code for the kind of failure:
+///   1  unexpected error
+///   2  invalid arguments, config, or Move code
+///   3  network or API error
+///   4  transaction rejected, it wasn't committed
+///   5  transaction committed, but failed on-chain
+///   6  timed out waiting for the transaction, it may still be committed
+#[derive(Parser)]
+#[clap(
+    name = "aptos",
+    author,
+    version,
+    propagate_version = true,
+    verbatim_doc_comment
+)]
+pub struct Tool {
+    /// Format of the command output, one of [json, yaml, table, csv]
+    ///
+    /// Defaults to the `output_format` in the global config, or JSON if not set
+    #[clap(long, global = true)]
+    output: Option<OutputFormat>,
+
+    /// Don't color tables, also turned off by setting `NO_COLOR`
+    #[clap(long, global = true)]
+    no_color: bool,
+
+    /// Show hashes in tables in full, rather than shortened to their first and last digits
+    #[clap(long, global = true)]
+    full_hashes: bool,
+
+    /// Build and simulate transactions, and output them instead of submitting them
+    ///
+    /// Commands run as they would otherwise up to submitting, so what's output is what would
+    /// have been sent.  Confirmation prompts are skipped, and nothing is signed
+    #[clap(long, global = true)]
+    dry_run: bool,
+
+    #[clap(subcommand)]
+    command: ToolCommand,
+}
+
+impl Tool {
+    pub async fn execute(self) -> CliResult {
+        // A broken global config shouldn't prevent commands like `config` from running
+        let global_config = GlobalConfig::load().ok();
+        let output_format = self
+            .output
+            .or_else(|| {
+                global_config
+                    .as_ref()
+                    .and_then(|config| config.output_format)
+            })
+            .unwrap_or_default();
+        set_output_format(output_format);
+        set_render_options(
+            global_config
+                .as_ref()
+                .and_then(|config| config.color_theme)
+                .unwrap_or_default(),
+            self.no_color,
+            self.full_hashes,
+        );
+        // Scripts asking for JSON don't want progress, even on a terminal
+        set_progress_enabled(output_format != OutputFormat::Json);
+        set_dry_run(self.dry_run);
+        set_assume_yes(
+            global_config
+                .and_then(|config| config.assume_yes)
+                .unwrap_or(false),
+        );
+
+        self.command.execute().await
+    }
+}
+
+/// All top level commands of the CLI
 #[derive(Parser)]
-#[clap(name = "aptos", author, version, propagate_version = true)]
-pub enum Tool {
+pub enum ToolCommand {
     #[clap(subcommand)]
     Account(account::AccountTool),
     #[clap(subcommand)]
     Config(config::ConfigTool),
     #[clap(subcommand)]
+    Gas(gas::GasTool),
+    #[clap(subcommand)]
     Genesis(genesis::GenesisTool),
     #[clap(subcommand)]
     Governance(governance::GovernanceTool),
@@ -43,14 +133,19 @@ pub enum Tool {
     Node(node::NodeTool),
     #[clap(subcommand)]
     Stake(stake::StakeTool),
+    #[clap(subcommand)]
+    Token(token::TokenTool),
+    #[clap(subcommand)]
+    Transaction(transaction::TransactionTool),
 }
 
-impl Tool {
+impl ToolCommand {
     pub async fn execute(self) -> CliResult {
-        use Tool::*;
+        use ToolCommand::*;
         match self {
             Account(tool) => tool.execute().await,
             Config(tool) => tool.execute().await,
+            Gas(tool) => tool.execute().await,
             Genesis(tool) => tool.execute().await,
             Governance(tool) => tool.execute().await,
             Info(tool) => tool.execute_serialized().await,
@@ -60,15 +155,28 @@ impl Tool {
             Move(tool) => tool.execute().await,
             Node(tool) => tool.execute().await,
             Stake(tool) => tool.execute().await,
+            Token(tool) => tool.execute().await,
+            Transaction(tool) => tool.execute().await,
         }
     }
 }
 
-/// Show build information about the CLI
+/// Show information about the CLI and its environment, e.g. for bug reports
 ///
-/// This is useful for debugging as well as determining what versions are compatible with the CLI
+/// Shows the CLI's version and build, the Move bytecode version it compiles to, the profile
+/// used and its endpoints, the chain ID and ledger version of the profile's network, and
+/// whether a local testnet is running.  Problems reaching the network are shown rather than
+/// failing, as they're often what's being diagnosed.
 #[derive(Parser)]
-pub struct InfoTool {}
+pub struct InfoTool {
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+}
+
+/// REST endpoint of a local testnet started with its default ports
+const LOCAL_TESTNET_URL: &str = "http://localhost:8080";
 
 #[async_trait]
 impl CliCommand<BTreeMap<String, String>> for InfoTool {
@@ -77,6 +185,101 @@ impl CliCommand<BTreeMap<String, String>> for InfoTool {
     }
 
     async fn execute(self) -> CliTypedResult<BTreeMap<String, String>> {
-        Ok(cli_build_information())
+        let mut info = cli_build_information();
+        info.insert(
+            "output_schema_version".to_string(),
+            OUTPUT_SCHEMA_VERSION.to_string(),
+        );
+        info.insert(
+            "move_bytecode_version".to_string(),
+            move_binary_format::file_format_common::VERSION_MAX.to_string(),
+        );
+
+        let config = if CliConfig::config_exists(ConfigSearchMode::CurrentDirAndParents) {
+            CliConfig::load(ConfigSearchMode::CurrentDirAndParents).ok()
+        } else {
+            None
+        };
+        let profile_name = match config {
+            Some(ref config) => config.active_profile_name(self.profile_options.profile_name()),
+            None => self
+                .profile_options
+                .profile_name()
+                .unwrap_or(DEFAULT_PROFILE)
+                .to_string(),
+        };
+        let profile = if config.is_some() {
+            CliConfig::load_profile(Some(&profile_name), ConfigSearchMode::CurrentDirAndParents)
+                .ok()
+                .flatten()
+        } else {
+            None
+        };
+        info.insert(
+            "profile".to_string(),
+            match profile {
+                Some(_) => profile_name,
+                None => format!("{} (not found)", profile_name),
+            },
+        );
+        if let Some(network) = profile.as_ref().and_then(|profile| profile.network) {
+            info.insert("network".to_string(), format!("{:?}", network));
+        }
+        if let Some(faucet_url) = profile.and_then(|profile| profile.faucet_url) {
+            info.insert("faucet_url".to_string(), faucet_url);
+        }
+
+        match self.rest_options.url(&self.profile_options) {
+            Ok(rest_url) => {
+                info.insert("rest_url".to_string(), rest_url.to_string());
+                match self.rest_options.client(&self.profile_options) {
+                    Ok(client) => match client.get_ledger_information().await {
+                        Ok(response) => {
+                            let state = response.into_inner();
+                            info.insert("chain_id".to_string(), state.chain_id.to_string());
+                            info.insert("ledger_version".to_string(), state.version.to_string());
+                            info.insert("epoch".to_string(), state.epoch.to_string());
+                        }
+                        Err(err) => {
+                            info.insert("network_error".to_string(), err.to_string());
+                        }
+                    },
+                    Err(err) => {
+                        info.insert("network_error".to_string(), err.to_string());
+                    }
+                }
+            }
+            Err(err) => {
+                info.insert("rest_url".to_string(), format!("not set: {}", err));
+            }
+        }
+
+        info.insert("local_testnet".to_string(), local_testnet_status().await);
+        Ok(info)
+    }
+}
+
+/// Whether a local testnet is running on the default port, and where its files are
+async fn local_testnet_status() -> String {
+    let client = aptos_rest_client::Client::new_with_timeout(
+        LOCAL_TESTNET_URL.parse().expect("Valid local testnet URL"),
+        Duration::from_secs(2),
+    );
+    let running = match client.get_ledger_information().await {
+        Ok(response) => format!(
+            "running at {} at version {}",
+            LOCAL_TESTNET_URL,
+            response.inner().version
+        ),
+        Err(_) => "not running".to_string(),
+    };
+    let test_dir = GlobalConfig::load()
+        .and_then(|config| config.get_config_location(ConfigSearchMode::CurrentDirAndParents))
+        .map(|location| location.join(node::TESTNET_FOLDER))
+        .ok()
+        .filter(|test_dir| test_dir.exists());
+    match test_dir {
+        Some(test_dir) => format!("{}, files in {}", running, test_dir.display()),
+        None => running,
     }
 }
@@ -9,23 +9,26 @@
 #[global_allocator]
 static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
-use aptos::{move_tool, Tool};
+use aptos::{common::utils::exit_code, move_tool, Tool};
 use clap::Parser;
 use std::process::exit;
 
 #[tokio::main]
 async fn main() {
-    // Register hooks
+    // Register hooks, and cache dependencies in the global config folder
     move_tool::register_package_hooks();
+    move_tool::set_dependency_cache_dir();
     // Run the corresponding tools
     let result = Tool::parse().execute().await;
 
     // At this point, we'll want to print and determine whether to exit for an error code
     match result {
+        // Commands which printed their output as they ran, e.g. with `--follow`, have none left
+        Ok(inner) if inner.is_empty() => {}
         Ok(inner) => println!("{}", inner),
         Err(inner) => {
             println!("{}", inner);
-            exit(1);
+            exit(exit_code());
         }
     }
 }
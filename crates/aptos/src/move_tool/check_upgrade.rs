@@ -0,0 +1,351 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    CliCommand, CliError, CliTypedResult, MovePackageDir, ProfileOptions, RestOptions,
+};
+use crate::move_tool::{CachedPackageRegistry, IncludedArtifacts};
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::Parser;
+use framework::natives::code::UpgradePolicy;
+use framework::BuiltPackage;
+use move_binary_format::{
+    file_format::Visibility,
+    normalized::{Module, Type},
+    CompiledModule,
+};
+use serde::Serialize;
+
+/// Check that a package can upgrade the one already published, before publishing it
+///
+/// Downloads the published modules and checks the package's modules are compatible with them,
+/// as the chain will when the package is published, along with its upgrade policy.  Fails listing
+/// each struct and function that breaks compatibility.
+#[derive(Parser)]
+pub struct CheckUpgrade {
+    /// Address of the account the package is published under
+    ///
+    /// Defaults to the profile's account
+    #[clap(long, parse(try_from_str = crate::common::types::load_account_arg))]
+    pub(crate) account: Option<AccountAddress>,
+
+    /// Upgrade policy to publish with, instead of the one in Move.toml
+    ///
+    /// One of `compatible` or `immutable`
+    #[clap(long)]
+    pub(crate) upgrade_policy: Option<UpgradePolicy>,
+
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+/// What would stop a package from upgrading the published one
+#[derive(Debug, Serialize)]
+pub struct UpgradeCheck {
+    pub package: String,
+    pub account: AccountAddress,
+    /// Upgrade policy of the published package, if it's published
+    pub published_upgrade_policy: Option<String>,
+    pub upgrade_policy: String,
+    pub incompatibilities: Vec<String>,
+}
+
+#[async_trait]
+impl CliCommand<UpgradeCheck> for CheckUpgrade {
+    fn command_name(&self) -> &'static str {
+        "CheckUpgrade"
+    }
+
+    async fn execute(self) -> CliTypedResult<UpgradeCheck> {
+        let account = match self.account {
+            Some(account) => account,
+            None => self.profile_options.account_address()?,
+        };
+        self.move_options.apply_offline();
+        let build_options = IncludedArtifacts::None
+            .build_options(self.move_options.named_addresses(&self.profile_options)?);
+        let pack = BuiltPackage::build(self.move_options.get_package_path()?, build_options)
+            .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
+        let upgrade_policy = match self.upgrade_policy {
+            Some(upgrade_policy) => upgrade_policy,
+            None => pack.extract_metadata()?.upgrade_policy,
+        };
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        let registry = CachedPackageRegistry::create(client.clone(), account).await?;
+        let mut check = UpgradeCheck {
+            package: pack.name().to_string(),
+            account,
+            published_upgrade_policy: None,
+            upgrade_policy: upgrade_policy.to_string(),
+            incompatibilities: vec![],
+        };
+        let published = match registry.get_package(pack.name()).await {
+            Ok(published) => published,
+            // A first publish has nothing to be compatible with
+            Err(_) => return Ok(check),
+        };
+        check.published_upgrade_policy = Some(published.upgrade_policy().to_string());
+
+        let incompatibilities = &mut check.incompatibilities;
+        if published.upgrade_policy() == UpgradePolicy::immutable() {
+            incompatibilities.push("The published package is immutable".to_string());
+        }
+        if upgrade_policy.policy < published.upgrade_policy().policy {
+            incompatibilities.push(format!(
+                "The upgrade policy can't be weakened from {} to {}",
+                published.upgrade_policy(),
+                upgrade_policy
+            ));
+        }
+        let new_modules: Vec<_> = pack.modules().collect();
+        for name in published.module_names() {
+            if !new_modules
+                .iter()
+                .any(|module| module.self_id().name().as_str() == name)
+            {
+                incompatibilities.push(format!("Module {} was removed", name));
+            }
+        }
+
+        // Modules of `arbitrary` packages can change in any way
+        if published.upgrade_policy() != UpgradePolicy::arbitrary() {
+            for new_module in new_modules {
+                let name = new_module.self_id().name().to_string();
+                if !published.module_names().contains(&name.as_str()) {
+                    continue;
+                }
+                let bytecode = client
+                    .get_account_module_bcs(account, &name)
+                    .await?
+                    .into_inner();
+                let old_module = CompiledModule::deserialize(&bytecode)
+                    .map_err(|err| CliError::UnableToParse("module bytecode", err.to_string()))?;
+                incompatibilities.extend(module_incompatibilities(
+                    &Module::new(&old_module),
+                    &Module::new(new_module),
+                ));
+            }
+        }
+
+        if check.incompatibilities.is_empty() {
+            Ok(check)
+        } else {
+            Err(CliError::UnexpectedError(format!(
+                "Package {} can't upgrade the one at {}:\n{}",
+                check.package,
+                check.account,
+                check.incompatibilities.join("\n")
+            )))
+        }
+    }
+}
+
+/// Describes each change to a module's structs and public functions that breaks code using them
+///
+/// Mirrors the VM's compatibility check on publish, where structs can't change their layout and
+/// public functions can't change their signature, but friend and private functions can change.
+pub fn module_incompatibilities(old: &Module, new: &Module) -> Vec<String> {
+    let mut incompatibilities = vec![];
+    let module = &old.name;
+
+    for (name, old_struct) in &old.structs {
+        let new_struct = match new.structs.get(name) {
+            Some(new_struct) => new_struct,
+            None => {
+                incompatibilities.push(format!("Struct {}::{} was removed", module, name));
+                continue;
+            }
+        };
+        if !old_struct.abilities.is_subset(new_struct.abilities) {
+            incompatibilities.push(format!(
+                "Struct {}::{} lost abilities, from {:?} to {:?}",
+                module, name, old_struct.abilities, new_struct.abilities
+            ));
+        }
+        let type_parameters_compatible = old_struct.type_parameters.len()
+            == new_struct.type_parameters.len()
+            && old_struct
+                .type_parameters
+                .iter()
+                .zip(&new_struct.type_parameters)
+                .all(|(old, new)| {
+                    new.constraints.is_subset(old.constraints)
+                        && (!old.is_phantom || new.is_phantom)
+                });
+        if !type_parameters_compatible {
+            incompatibilities.push(format!(
+                "Struct {}::{} changed its type parameters",
+                module, name
+            ));
+        }
+        if old_struct.fields != new_struct.fields {
+            incompatibilities.push(format!(
+                "Struct {}::{} changed its fields, from {{{}}} to {{{}}}",
+                module,
+                name,
+                fields(&old_struct.fields),
+                fields(&new_struct.fields)
+            ));
+        }
+    }
+
+    for (name, old_function) in &old.exposed_functions {
+        if old_function.visibility != Visibility::Public {
+            continue;
+        }
+        let new_function = match new.exposed_functions.get(name) {
+            Some(new_function) => new_function,
+            None => {
+                incompatibilities.push(format!("Function {}::{} was removed", module, name));
+                continue;
+            }
+        };
+        if new_function.visibility != Visibility::Public {
+            incompatibilities.push(format!("Function {}::{} is no longer public", module, name));
+        }
+        if old_function.is_entry && !new_function.is_entry {
+            incompatibilities.push(format!(
+                "Function {}::{} is no longer an entry function",
+                module, name
+            ));
+        }
+        if old_function.parameters != new_function.parameters {
+            incompatibilities.push(format!(
+                "Function {}::{} changed its parameters, from ({}) to ({})",
+                module,
+                name,
+                types(&old_function.parameters),
+                types(&new_function.parameters)
+            ));
+        }
+        if old_function.return_ != new_function.return_ {
+            incompatibilities.push(format!(
+                "Function {}::{} changed its return types, from ({}) to ({})",
+                module,
+                name,
+                types(&old_function.return_),
+                types(&new_function.return_)
+            ));
+        }
+        let type_parameters_compatible = old_function.type_parameters.len()
+            == new_function.type_parameters.len()
+            && old_function
+                .type_parameters
+                .iter()
+                .zip(&new_function.type_parameters)
+                .all(|(old, new)| new.is_subset(*old));
+        if !type_parameters_compatible {
+            incompatibilities.push(format!(
+                "Function {}::{} changed its type parameters",
+                module, name
+            ));
+        }
+    }
+    incompatibilities
+}
+
+fn types(types: &[Type]) -> String {
+    types
+        .iter()
+        .map(|typ| typ.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn fields(fields: &[move_binary_format::normalized::Field]) -> String {
+    fields
+        .iter()
+        .map(|field| format!("{}: {}", field.name, field.type_))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::{
+        file_format::AbilitySet,
+        normalized::{Field, Function, Struct},
+    };
+    use move_core_types::identifier::Identifier;
+    use std::collections::BTreeMap;
+
+    fn module(fields: Vec<Field>, function: Option<(Visibility, Vec<Type>)>) -> Module {
+        let ident = |name: &str| Identifier::new(name).unwrap();
+        Module {
+            file_format_version: 5,
+            address: AccountAddress::ONE,
+            name: ident("coin"),
+            friends: vec![],
+            structs: BTreeMap::from([(
+                ident("Coin"),
+                Struct {
+                    abilities: AbilitySet::EMPTY,
+                    type_parameters: vec![],
+                    fields,
+                },
+            )]),
+            exposed_functions: function
+                .into_iter()
+                .map(|(visibility, parameters)| {
+                    (
+                        ident("value"),
+                        Function {
+                            visibility,
+                            is_entry: false,
+                            type_parameters: vec![],
+                            parameters,
+                            return_: vec![Type::U64],
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_module_incompatibilities() {
+        let field = |name: &str| Field {
+            name: Identifier::new(name).unwrap(),
+            type_: Type::U64,
+        };
+        let old = module(
+            vec![field("value")],
+            Some((Visibility::Public, vec![Type::Address])),
+        );
+
+        assert!(module_incompatibilities(&old, &old).is_empty());
+        assert_eq!(
+            vec![
+                "Struct coin::Coin changed its fields, from {value: u64} to {value: u64, frozen: u64}"
+                    .to_string(),
+                "Function coin::value changed its parameters, from (address) to (address, u64)"
+                    .to_string(),
+            ],
+            module_incompatibilities(
+                &old,
+                &module(
+                    vec![field("value"), field("frozen")],
+                    Some((Visibility::Public, vec![Type::Address, Type::U64])),
+                )
+            )
+        );
+        assert_eq!(
+            vec!["Function coin::value was removed".to_string()],
+            module_incompatibilities(&old, &module(vec![field("value")], None))
+        );
+
+        // Friend functions can change freely
+        let old = module(
+            vec![field("value")],
+            Some((Visibility::Friend, vec![Type::Address])),
+        );
+        assert!(module_incompatibilities(&old, &module(vec![field("value")], None)).is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Splits packages too large for one transaction into transactions staging them in chunks
+//!
+//! Chunks are staged with `large_packages::stage_code`, from
+//! `aptos-move/move-examples/large_packages`, which publishes the package after the last chunk.
+
+use aptos_types::account_address::AccountAddress;
+use aptos_types::transaction::{EntryFunction, TransactionPayload};
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+
+/// Bytes of metadata and code staged by each transaction, leaving room for the rest of it
+pub const CHUNK_SIZE_IN_BYTES: usize = 55_000;
+
+/// Metadata and code staged by one transaction
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct StagedChunk {
+    pub metadata_chunk: Vec<u8>,
+    /// Index in the package of the module each code chunk belongs to
+    pub code_indices: Vec<u16>,
+    pub code_chunks: Vec<Vec<u8>>,
+}
+
+impl StagedChunk {
+    fn size(&self) -> usize {
+        self.metadata_chunk.len() + self.code_chunks.iter().map(Vec::len).sum::<usize>()
+    }
+
+    /// The `stage_code` payload, publishing the package if it's the last chunk
+    pub fn payload(
+        self,
+        large_packages_address: AccountAddress,
+        publish: bool,
+    ) -> TransactionPayload {
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(
+                large_packages_address,
+                Identifier::new("large_packages").unwrap(),
+            ),
+            Identifier::new("stage_code").unwrap(),
+            vec![],
+            vec![
+                bcs::to_bytes(&self.metadata_chunk).unwrap(),
+                bcs::to_bytes(&self.code_indices).unwrap(),
+                bcs::to_bytes(&self.code_chunks).unwrap(),
+                bcs::to_bytes(&publish).unwrap(),
+            ],
+        ))
+    }
+}
+
+/// The payloads staging the package in order, the last of which publishes it
+pub fn chunked_publish_payloads(
+    large_packages_address: AccountAddress,
+    metadata_serialized: &[u8],
+    code: &[Vec<u8>],
+) -> Vec<TransactionPayload> {
+    let chunks = split_package(metadata_serialized, code, CHUNK_SIZE_IN_BYTES);
+    let last = chunks.len() - 1;
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| chunk.payload(large_packages_address, i == last))
+        .collect()
+}
+
+/// Splits the metadata, then each module, into chunks of at most `chunk_size` bytes in total
+///
+/// There is always at least one chunk, to publish the package with.
+pub fn split_package(
+    metadata_serialized: &[u8],
+    code: &[Vec<u8>],
+    chunk_size: usize,
+) -> Vec<StagedChunk> {
+    let mut chunks = vec![];
+    let mut current = StagedChunk::default();
+
+    let mut metadata = metadata_serialized;
+    while !metadata.is_empty() {
+        let (chunk, rest) = metadata.split_at((chunk_size - current.size()).min(metadata.len()));
+        current.metadata_chunk.extend_from_slice(chunk);
+        metadata = rest;
+        if current.size() == chunk_size {
+            chunks.push(std::mem::take(&mut current));
+        }
+    }
+
+    for (index, module) in code.iter().enumerate() {
+        let mut module = module.as_slice();
+        while !module.is_empty() {
+            let (chunk, rest) = module.split_at((chunk_size - current.size()).min(module.len()));
+            current.code_indices.push(index as u16);
+            current.code_chunks.push(chunk.to_vec());
+            module = rest;
+            if current.size() == chunk_size {
+                chunks.push(std::mem::take(&mut current));
+            }
+        }
+    }
+
+    if chunks.is_empty() || current.size() > 0 {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_package() {
+        let chunks = split_package(&[1, 2, 3], &[vec![4, 5, 6, 7], vec![8]], 3);
+        assert_eq!(
+            vec![
+                StagedChunk {
+                    metadata_chunk: vec![1, 2, 3],
+                    ..Default::default()
+                },
+                StagedChunk {
+                    metadata_chunk: vec![],
+                    code_indices: vec![0],
+                    code_chunks: vec![vec![4, 5, 6]],
+                },
+                StagedChunk {
+                    metadata_chunk: vec![],
+                    code_indices: vec![0, 1],
+                    code_chunks: vec![vec![7], vec![8]],
+                },
+            ],
+            chunks
+        );
+
+        // A package filling the last chunk exactly isn't followed by an empty chunk
+        assert_eq!(2, split_package(&[1, 2], &[vec![3, 4]], 2).len());
+        assert_eq!(1, split_package(&[], &[], 2).len());
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    CliCommand, CliError, CliResult, CliTypedResult, MovePackageDir, ProfileOptions,
+};
+use crate::move_tool::run_unit_tests;
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
+use move_coverage::{
+    coverage_map::CoverageMap, format_csv_summary, format_human_summary,
+    source_coverage::SourceCoverageBuilder, summary::summarize_inst_cov,
+};
+use move_disassembler::disassembler::Disassembler;
+use move_package::{compilation::compiled_package::CompiledPackage, BuildConfig};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Where `move_cli` saves the coverage of unit tests, in the package directory
+const COVERAGE_MAP_FILE: &str = ".coverage_map.mvcov";
+
+/// Tool for Move unit test coverage
+///
+/// Each command runs the package's unit tests with coverage, then shows how much of the package's
+/// code they executed.
+#[derive(Subcommand)]
+pub enum CoveragePackage {
+    Summary(SummaryCoverage),
+    Source(SourceCoverage),
+    Bytecode(BytecodeCoverage),
+}
+
+impl CoveragePackage {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            CoveragePackage::Summary(tool) => tool.execute_serialized().await,
+            CoveragePackage::Source(tool) => tool.execute_serialized().await,
+            CoveragePackage::Bytecode(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// Options to run the unit tests whose coverage is shown
+#[derive(Debug, Parser)]
+pub struct CoverageTestOptions {
+    /// A filter string to determine which unit tests to run
+    #[clap(long, short)]
+    pub filter: Option<String>,
+
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+impl CoverageTestOptions {
+    /// Runs the unit tests, returning their coverage with the package it covers
+    fn run(&self) -> CliTypedResult<(CoverageMap, CompiledPackage)> {
+        run_unit_tests(
+            &self.move_options,
+            &self.profile_options,
+            self.filter.clone(),
+            true,
+            false,
+            &mut std::io::stdout(),
+        )?;
+
+        let package_path = self.move_options.get_package_path()?;
+        let coverage_map = CoverageMap::from_binary_file(package_path.join(COVERAGE_MAP_FILE))
+            .map_err(|err| {
+                CliError::UnexpectedError(format!("Failed to load coverage: {}", err))
+            })?;
+        let config = BuildConfig {
+            additional_named_addresses: self.move_options.named_addresses(&self.profile_options)?,
+            install_dir: self.move_options.output_dir.clone(),
+            skip_fetch_latest_git_deps: self.move_options.offline,
+            ..Default::default()
+        };
+        let package = config
+            .compile_package(package_path.as_path(), &mut Vec::new())
+            .map_err(|err| CliError::MoveCompilationError(format!("{:#}", err)))?;
+        Ok((coverage_map, package))
+    }
+}
+
+/// Summarize the coverage of each module
+///
+/// With `--fail-under`, fails if the package's coverage is below the percentage, for CI.
+#[derive(Debug, Parser)]
+pub struct SummaryCoverage {
+    /// Show the coverage of each function, not just of each module
+    #[clap(long)]
+    pub summarize_functions: bool,
+
+    /// Print the summary as CSV, with the coverage of each function
+    #[clap(long)]
+    pub output_csv: bool,
+
+    /// Fail if less than this percentage of the package's instructions are covered
+    #[clap(long)]
+    pub fail_under: Option<f64>,
+
+    #[clap(flatten)]
+    pub(crate) test_options: CoverageTestOptions,
+}
+
+/// Instruction coverage of a package
+#[derive(Debug, Serialize)]
+pub struct CoverageSummary {
+    /// Percentage of the package's instructions covered
+    pub coverage: f64,
+    /// Percentage of each module's instructions covered
+    pub modules: BTreeMap<String, f64>,
+}
+
+#[async_trait]
+impl CliCommand<CoverageSummary> for SummaryCoverage {
+    fn command_name(&self) -> &'static str {
+        "SummaryCoverage"
+    }
+
+    async fn execute(self) -> CliTypedResult<CoverageSummary> {
+        let (coverage_map, package) = self.test_options.run()?;
+        let coverage_map = coverage_map.to_unified_exec_map();
+        let modules: Vec<_> = package
+            .root_modules()
+            .filter_map(|unit| match &unit.unit {
+                CompiledUnit::Module(NamedCompiledModule { module, .. }) => Some(module.clone()),
+                _ => None,
+            })
+            .collect();
+
+        let mut stdout = std::io::stdout();
+        if self.output_csv {
+            format_csv_summary(&modules, &coverage_map, summarize_inst_cov, &mut stdout)
+        } else {
+            format_human_summary(
+                &modules,
+                &coverage_map,
+                summarize_inst_cov,
+                &mut stdout,
+                self.summarize_functions,
+            )
+        }
+
+        let (mut package_covered, mut package_total) = (0, 0);
+        let mut module_coverage = BTreeMap::new();
+        for module in &modules {
+            let summary = summarize_inst_cov(module, &coverage_map);
+            let (covered, total) = summary
+                .function_summaries
+                .values()
+                .filter(|function| !function.fn_is_native)
+                .fold((0, 0), |(covered, total), function| {
+                    (covered + function.covered, total + function.total)
+                });
+            module_coverage.insert(summary.module_name.to_string(), percentage(covered, total));
+            package_covered += covered;
+            package_total += total;
+        }
+
+        let coverage = percentage(package_covered, package_total);
+        if let Some(fail_under) = self.fail_under {
+            if coverage < fail_under {
+                return Err(CliError::UnexpectedError(format!(
+                    "Coverage of {:.2}% is under the required {:.2}%",
+                    coverage, fail_under
+                )));
+            }
+        }
+        Ok(CoverageSummary {
+            coverage,
+            modules: module_coverage,
+        })
+    }
+}
+
+/// Percentage of instructions covered, where code without instructions is fully covered
+fn percentage(covered: u64, total: u64) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        covered as f64 * 100.0 / total as f64
+    }
+}
+
+/// Show a module's source, with the code not covered highlighted
+#[derive(Debug, Parser)]
+pub struct SourceCoverage {
+    /// Name of the module in the package
+    #[clap(long)]
+    pub module_name: String,
+
+    #[clap(flatten)]
+    pub(crate) test_options: CoverageTestOptions,
+}
+
+#[async_trait]
+impl CliCommand<String> for SourceCoverage {
+    fn command_name(&self) -> &'static str {
+        "SourceCoverage"
+    }
+
+    async fn execute(self) -> CliTypedResult<String> {
+        let (coverage_map, package) = self.test_options.run()?;
+        let unit = package
+            .get_module_by_name_from_root(&self.module_name)
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+        let (module, source_map) = match &unit.unit {
+            CompiledUnit::Module(NamedCompiledModule {
+                module, source_map, ..
+            }) => (module, source_map),
+            _ => {
+                return Err(CliError::CommandArgumentError(format!(
+                    "{} is not a module",
+                    self.module_name
+                )))
+            }
+        };
+        let mut output = Vec::new();
+        SourceCoverageBuilder::new(module, &coverage_map, source_map)
+            .compute_source_coverage(&unit.source_path)
+            .output_source_coverage(&mut output)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        String::from_utf8(output).map_err(|err| CliError::UnexpectedError(err.to_string()))
+    }
+}
+
+/// Show a module's disassembled bytecode, with how often each instruction ran
+#[derive(Debug, Parser)]
+pub struct BytecodeCoverage {
+    /// Name of the module in the package
+    #[clap(long)]
+    pub module_name: String,
+
+    #[clap(flatten)]
+    pub(crate) test_options: CoverageTestOptions,
+}
+
+#[async_trait]
+impl CliCommand<String> for BytecodeCoverage {
+    fn command_name(&self) -> &'static str {
+        "BytecodeCoverage"
+    }
+
+    async fn execute(self) -> CliTypedResult<String> {
+        let (coverage_map, package) = self.test_options.run()?;
+        let unit = package
+            .get_module_by_name_from_root(&self.module_name)
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+        let mut disassembler = Disassembler::from_unit(&unit.unit);
+        disassembler.add_coverage_map(coverage_map.to_unified_exec_map());
+        disassembler
+            .disassemble()
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliError, CliTypedResult, ProfileOptions, RestOptions};
+use crate::move_tool::CachedPackageRegistry;
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::Parser;
+use framework::{unzip_metadata, unzip_metadata_str};
+use move_binary_format::{binary_views::BinaryIndexedView, CompiledModule};
+use move_bytecode_source_map::{mapping::SourceMapping, source_map::SourceMap};
+use move_disassembler::disassembler::{Disassembler, DisassemblerOptions};
+use move_ir_types::location::Spanned;
+
+/// Disassemble a module deployed on-chain
+///
+/// Fetches the module's bytecode and returns it disassembled.  With `--with-source-map`, the source
+/// map and source published with the module's package, if any, are used to name locals and show
+/// the code each function came from.
+#[derive(Debug, Parser)]
+pub struct DisassembleModule {
+    /// Address of the account the module is published under
+    #[clap(long, parse(try_from_str = crate::common::types::load_account_arg))]
+    pub(crate) account: AccountAddress,
+
+    /// Name of the module
+    #[clap(long)]
+    pub(crate) module: String,
+
+    /// Use the source map published with the module, when its package included it
+    #[clap(long)]
+    pub(crate) with_source_map: bool,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<String> for DisassembleModule {
+    fn command_name(&self) -> &'static str {
+        "DisassembleModule"
+    }
+
+    async fn execute(self) -> CliTypedResult<String> {
+        let client = self.rest_options.client(&self.profile_options)?;
+        let bytecode = client
+            .get_account_module_bcs(self.account, &self.module)
+            .await?
+            .into_inner();
+        let module = CompiledModule::deserialize(&bytecode)
+            .map_err(|err| CliError::UnableToParse("module bytecode", err.to_string()))?;
+        let view = BinaryIndexedView::Module(&module);
+
+        let mut source_mapping = None;
+        if self.with_source_map {
+            let registry = CachedPackageRegistry::create(client, self.account).await?;
+            let metadata = registry
+                .get_module(&self.module)
+                .await
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            if metadata.zipped_source_map_raw().is_empty() {
+                eprintln!(
+                    "The module's package was published without source maps, disassembling without"
+                );
+            } else {
+                let source_map: SourceMap = bcs::from_bytes(
+                    &unzip_metadata(metadata.zipped_source_map_raw())
+                        .map_err(|err| CliError::UnableToParse("source map", err.to_string()))?,
+                )?;
+                let mut mapping = SourceMapping::new(source_map, view);
+                if !metadata.zipped_source().is_empty() {
+                    let source = unzip_metadata_str(metadata.zipped_source())
+                        .map_err(|err| CliError::UnableToParse("source", err.to_string()))?;
+                    mapping.with_source_code((format!("{}.move", self.module), source));
+                }
+                source_mapping = Some(mapping);
+            }
+        }
+        let source_mapping = match source_mapping {
+            Some(mapping) => mapping,
+            None => SourceMapping::new_from_view(view, Spanned::unsafe_no_loc(()).loc)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
+        };
+
+        let mut options = DisassemblerOptions::new();
+        options.print_code = true;
+        options.print_basic_blocks = true;
+        options.print_locals = true;
+        Disassembler::new(source_mapping, options)
+            .disassemble()
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))
+    }
+}
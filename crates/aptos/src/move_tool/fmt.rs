@@ -0,0 +1,247 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliError, CliTypedResult};
+use crate::common::utils::{dir_default_to_current, write_to_file};
+use async_trait::async_trait;
+use clap::Parser;
+use move_package::source_package::layout::SourcePackageLayout;
+use std::path::{Path, PathBuf};
+
+/// Spaces per level of indentation
+const INDENT: usize = 4;
+
+/// Format the Move source files of a package
+///
+/// Formats the `.move` files in the package's `sources`, `scripts`, `tests`, `examples`, and
+/// `spec` directories, leaving its dependencies and build output alone.  Each line is indented
+/// by its nesting in braces, brackets, and parentheses, trailing whitespace is removed, and runs
+/// of blank lines become one.  Comments and the rest of each line are kept as written.
+///
+/// Prints the files changed.  With `--check`, nothing is written, and it fails naming the files
+/// which aren't formatted, e.g. to enforce formatting in CI.
+#[derive(Parser)]
+pub struct FormatPackage {
+    /// Path to a move package (the folder with a Move.toml file)
+    #[clap(long, parse(from_os_str))]
+    pub(crate) package_dir: Option<PathBuf>,
+
+    /// Only check the files are formatted, failing if any aren't
+    #[clap(long)]
+    pub(crate) check: bool,
+}
+
+#[async_trait]
+impl CliCommand<Vec<String>> for FormatPackage {
+    fn command_name(&self) -> &'static str {
+        "FormatPackage"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<String>> {
+        let package_dir = dir_default_to_current(self.package_dir)?;
+        if !package_dir
+            .join(SourcePackageLayout::Manifest.path())
+            .exists()
+        {
+            return Err(CliError::CommandArgumentError(format!(
+                "No Move.toml in {}, it's not a Move package",
+                package_dir.display()
+            )));
+        }
+
+        let mut unformatted = vec![];
+        for path in package_source_files(&package_dir)? {
+            let source = std::fs::read_to_string(&path)
+                .map_err(|err| CliError::IO(path.display().to_string(), err))?;
+            let formatted = format_move_source(&source);
+            if formatted == source {
+                continue;
+            }
+            if !self.check {
+                write_to_file(&path, &path.display().to_string(), formatted.as_bytes())?;
+            }
+            unformatted.push(path.display().to_string());
+        }
+
+        if self.check && !unformatted.is_empty() {
+            Err(CliError::UnexpectedError(format!(
+                "These files aren't formatted, format them with `aptos move fmt`:\n{}",
+                unformatted.join("\n")
+            )))
+        } else {
+            Ok(unformatted)
+        }
+    }
+}
+
+/// The package's own Move files, from the directories of the package layout
+fn package_source_files(package_dir: &Path) -> CliTypedResult<Vec<PathBuf>> {
+    let mut files = vec![];
+    for layout in [
+        SourcePackageLayout::Sources,
+        SourcePackageLayout::Scripts,
+        SourcePackageLayout::Tests,
+        SourcePackageLayout::Examples,
+        SourcePackageLayout::Specifications,
+    ] {
+        let dir = package_dir.join(layout.path());
+        if dir.is_dir() {
+            find_move_files(&dir, &mut files)?;
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn find_move_files(dir: &Path, files: &mut Vec<PathBuf>) -> CliTypedResult<()> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|err| CliError::IO(dir.display().to_string(), err))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|err| CliError::IO(dir.display().to_string(), err))?
+            .path();
+        if path.is_dir() {
+            find_move_files(&path, files)?;
+        } else if path.extension().map_or(false, |ext| ext == "move") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Formats Move source, re-indenting each line by its nesting
+///
+/// Lines inside block comments are kept as written, other than their trailing whitespace.
+pub fn format_move_source(source: &str) -> String {
+    let mut formatted = String::new();
+    let mut depth = 0;
+    let mut in_block_comment = false;
+    let mut blank_line = false;
+    for line in source.lines() {
+        let line = line.trim_end();
+        if in_block_comment {
+            formatted.push_str(line);
+            formatted.push('\n');
+            in_block_comment = scan_line(line, &mut depth, true);
+            continue;
+        }
+
+        let line = line.trim_start();
+        if line.is_empty() {
+            blank_line = true;
+            continue;
+        }
+        if blank_line && !formatted.is_empty() {
+            formatted.push('\n');
+        }
+        blank_line = false;
+
+        // A line starting with closing delimiters is at the nesting they return to
+        let closing = line
+            .chars()
+            .take_while(|c| matches!(c, '}' | ')' | ']'))
+            .count();
+        let indent = depth.saturating_sub(closing) * INDENT;
+        formatted.push_str(&" ".repeat(indent));
+        formatted.push_str(line);
+        formatted.push('\n');
+        in_block_comment = scan_line(line, &mut depth, false);
+    }
+    formatted
+}
+
+/// Updates the nesting by the line's delimiters outside comments and strings, returning whether
+/// the line ends inside a block comment
+fn scan_line(line: &str, depth: &mut usize, mut in_block_comment: bool) -> bool {
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_block_comment {
+            if c == '*' && chars.peek() == Some(&'/') {
+                chars.next();
+                in_block_comment = false;
+            }
+            continue;
+        }
+        match c {
+            '/' if chars.peek() == Some(&'/') => break,
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                in_block_comment = true;
+            }
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '{' | '(' | '[' => *depth += 1,
+            '}' | ')' | ']' => *depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    in_block_comment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_move_source() {
+        let source = "
+
+module 0x1::example {
+  use std::vector;
+
+        /// Doc comment with { a brace
+    fun f(v: vector<u64>): u64 {
+    let x = vector::length(&v); // ( in a comment
+  if (x > 0) {
+            *vector::borrow(&v, 0)
+    } else {
+  assert!(
+  x == 0,
+    1
+  );
+  0
+      }
+    }
+
+
+
+    /* A block comment
+       kept as written { */
+  const E: vector<u8> = b\"a } brace\";
+}
+";
+        let expected = "module 0x1::example {
+    use std::vector;
+
+    /// Doc comment with { a brace
+    fun f(v: vector<u64>): u64 {
+        let x = vector::length(&v); // ( in a comment
+        if (x > 0) {
+            *vector::borrow(&v, 0)
+        } else {
+            assert!(
+                x == 0,
+                1
+            );
+            0
+        }
+    }
+
+    /* A block comment
+       kept as written { */
+    const E: vector<u8> = b\"a } brace\";
+}
+";
+        assert_eq!(expected, format_move_source(source));
+        assert_eq!(expected, format_move_source(expected));
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Templates for `aptos move init --template`, a runnable starting point for a new package
+
+use crate::common::types::{CliError, CliTypedResult};
+use crate::common::utils::{create_dir_if_not_exist, write_to_file};
+use crate::move_tool::{APTOS_FRAMEWORK, APTOS_TOKEN};
+use clap::ArgEnum;
+use move_core_types::identifier::Identifier;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+    str::FromStr,
+};
+
+/// Named address of the modules when the template isn't given one, for unit tests
+pub const TEMPLATE_DEV_ADDRESS: &str = "0xcafe";
+
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PackageTemplate {
+    Empty,
+    Coin,
+    Nft,
+    Dapp,
+}
+
+impl Display for PackageTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            PackageTemplate::Empty => "empty",
+            PackageTemplate::Coin => "coin",
+            PackageTemplate::Nft => "nft",
+            PackageTemplate::Dapp => "dapp",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+impl FromStr for PackageTemplate {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "empty" => Ok(PackageTemplate::Empty),
+            "coin" => Ok(PackageTemplate::Coin),
+            "nft" => Ok(PackageTemplate::Nft),
+            "dapp" => Ok(PackageTemplate::Dapp),
+            _ => Err("Invalid template. Valid values are empty, coin, nft, dapp"),
+        }
+    }
+}
+
+/// Language of the client generated with a template
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClientLanguage {
+    Typescript,
+    Rust,
+    None,
+}
+
+impl Display for ClientLanguage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            ClientLanguage::Typescript => "typescript",
+            ClientLanguage::Rust => "rust",
+            ClientLanguage::None => "none",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+impl FromStr for ClientLanguage {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "typescript" => Ok(ClientLanguage::Typescript),
+            "rust" => Ok(ClientLanguage::Rust),
+            "none" => Ok(ClientLanguage::None),
+            _ => Err("Invalid client. Valid values are typescript, rust, none"),
+        }
+    }
+}
+
+/// A file of a template, as its path in the package and its contents
+type TemplateFile = (&'static str, &'static str);
+
+impl PackageTemplate {
+    /// Packages of the framework the template's Move code depends on
+    pub fn framework_packages(self) -> &'static [&'static str] {
+        match self {
+            PackageTemplate::Nft => &[APTOS_FRAMEWORK, APTOS_TOKEN],
+            _ => &[APTOS_FRAMEWORK],
+        }
+    }
+
+    fn sources(self) -> &'static [TemplateFile] {
+        match self {
+            PackageTemplate::Empty => &[],
+            PackageTemplate::Coin => &[(
+                "sources/example_coin.move",
+                include_str!("templates/coin/example_coin.move"),
+            )],
+            PackageTemplate::Nft => &[(
+                "sources/example_nft.move",
+                include_str!("templates/nft/example_nft.move"),
+            )],
+            PackageTemplate::Dapp => &[(
+                "sources/todo_list.move",
+                include_str!("templates/dapp/todo_list.move"),
+            )],
+        }
+    }
+
+    fn client(self, language: ClientLanguage) -> Vec<TemplateFile> {
+        let (typescript, rust) = match self {
+            PackageTemplate::Empty => return vec![],
+            PackageTemplate::Coin => (
+                include_str!("templates/coin/index.ts"),
+                include_str!("templates/coin/main.rs"),
+            ),
+            PackageTemplate::Nft => (
+                include_str!("templates/nft/index.ts"),
+                include_str!("templates/nft/main.rs"),
+            ),
+            PackageTemplate::Dapp => (
+                include_str!("templates/dapp/index.ts"),
+                include_str!("templates/dapp/main.rs"),
+            ),
+        };
+        match language {
+            ClientLanguage::Typescript => vec![
+                (
+                    "client/package.json",
+                    include_str!("templates/client/typescript/package.json"),
+                ),
+                (
+                    "client/common.ts",
+                    include_str!("templates/client/typescript/common.ts"),
+                ),
+                ("client/index.ts", typescript),
+            ],
+            ClientLanguage::Rust => vec![
+                (
+                    "client/Cargo.toml",
+                    include_str!("templates/client/rust/Cargo.toml"),
+                ),
+                (
+                    "client/src/common.rs",
+                    include_str!("templates/client/rust/common.rs"),
+                ),
+                ("client/src/main.rs", rust),
+            ],
+            ClientLanguage::None => vec![],
+        }
+    }
+
+    /// Writes the template's Move sources and client into the package
+    ///
+    /// The modules are published under `address_name`, and the Rust client uses the SDK at the
+    /// framework's git revision if given.
+    pub fn write(
+        self,
+        package_dir: &Path,
+        address_name: &str,
+        language: ClientLanguage,
+        framework_git_rev: Option<&str>,
+    ) -> CliTypedResult<()> {
+        let aptos_sdk_ref = match framework_git_rev {
+            Some(rev) => format!("rev = \"{}\"", rev),
+            None => "branch = \"main\"".to_string(),
+        };
+        for (path, contents) in self.sources().iter().copied().chain(self.client(language)) {
+            let path = package_dir.join(path);
+            if let Some(parent) = path.parent() {
+                create_dir_if_not_exist(parent)?;
+            }
+            let contents = contents
+                .replace("{{address}}", address_name)
+                .replace("{{package}}", &address_name.replace('_', "-"))
+                .replace("{{aptos_sdk_ref}}", &aptos_sdk_ref);
+            write_to_file(&path, &path.display().to_string(), contents.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// The named address for a template's modules, from the package's name, e.g. `my_coin` for
+/// `MyCoin` or `my-coin`
+pub fn template_address_name(package_name: &str) -> CliTypedResult<String> {
+    let mut address_name = String::new();
+    for (i, c) in package_name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 && !address_name.ends_with('_') {
+                address_name.push('_');
+            }
+            address_name.push(c.to_ascii_lowercase());
+        } else if c == '-' || c == ' ' {
+            address_name.push('_');
+        } else {
+            address_name.push(c);
+        }
+    }
+    if Identifier::is_valid(&address_name) {
+        Ok(address_name)
+    } else {
+        Err(CliError::CommandArgumentError(format!(
+            "Package name {} can't be made into a named address for the template",
+            package_name
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_address_name() {
+        assert_eq!("my_coin", template_address_name("MyCoin").unwrap());
+        assert_eq!("my_coin", template_address_name("my-coin").unwrap());
+        assert_eq!("todo_list", template_address_name("todo_list").unwrap());
+        assert!(template_address_name("1coin").is_err());
+    }
+}
@@ -0,0 +1,324 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Entry function payloads from JSON, BCS encoded by the types in the function's on-chain ABI
+
+use crate::common::types::{load_account_arg, CliError, CliTypedResult};
+use crate::move_tool::MemberId;
+use aptos_rest_client::aptos_api_types::{MoveFunction, MoveStruct, MoveStructTag, MoveType};
+use aptos_rest_client::Client;
+use aptos_types::account_address::AccountAddress;
+use aptos_types::transaction::{EntryFunction, TransactionPayload};
+use futures::future::{BoxFuture, FutureExt};
+use move_core_types::language_storage::TypeTag;
+use serde::Deserialize;
+use serde_json::Value;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+/// An entry function call, as in the file given to `aptos move run --json-file`
+///
+/// Example:
+/// ```json
+/// {
+///   "function_id": "0x1::coin::transfer",
+///   "type_args": ["0x1::aptos_coin::AptosCoin"],
+///   "args": ["0xb0b", "100"]
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct EntryFunctionCall {
+    pub function_id: String,
+    #[serde(default)]
+    pub type_args: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<Value>,
+}
+
+impl EntryFunctionCall {
+    /// Resolves the function's ABI on-chain, then encodes the arguments by its parameter types
+    pub async fn payload(&self, client: &Client) -> CliTypedResult<TransactionPayload> {
+        let function_id = MemberId::from_str(&self.function_id)?;
+        let type_args = self
+            .type_args
+            .iter()
+            .map(|type_arg| {
+                MoveType::from_str(type_arg)
+                    .map_err(|err| CliError::UnableToParse("type_args", err.to_string()))
+            })
+            .collect::<CliTypedResult<Vec<_>>>()?;
+        let function = entry_function_abi(client, &function_id).await?;
+
+        if function.generic_type_params.len() != type_args.len() {
+            return Err(CliError::CommandArgumentError(format!(
+                "{} takes {} type arguments, but got {}",
+                function.name,
+                function.generic_type_params.len(),
+                type_args.len()
+            )));
+        }
+        let params: Vec<_> = function
+            .params
+            .iter()
+            .filter(|param| !param.is_signer())
+            .collect();
+        if params.len() != self.args.len() {
+            return Err(CliError::CommandArgumentError(format!(
+                "{} takes {} arguments, but got {}",
+                function.name,
+                params.len(),
+                self.args.len()
+            )));
+        }
+
+        let mut args = vec![];
+        for (param, arg) in params.into_iter().zip(&self.args) {
+            let typ = param
+                .instantiate(&type_args)
+                .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+            let mut bytes = vec![];
+            encode(client, &typ, arg, &mut bytes).await?;
+            args.push(bytes);
+        }
+        let type_tags = type_args
+            .into_iter()
+            .map(|type_arg| {
+                TypeTag::try_from(type_arg)
+                    .map_err(|err| CliError::UnableToParse("type_args", err.to_string()))
+            })
+            .collect::<CliTypedResult<_>>()?;
+        Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+            function_id.module_id,
+            function_id.member_id,
+            type_tags,
+            args,
+        )))
+    }
+}
+
+/// Looks up the function in its module's on-chain ABI, which must be an entry function
+async fn entry_function_abi(
+    client: &Client,
+    function_id: &MemberId,
+) -> CliTypedResult<MoveFunction> {
+    let abi = client
+        .get_account_module(
+            *function_id.module_id.address(),
+            function_id.module_id.name().as_str(),
+        )
+        .await?
+        .into_inner()
+        .try_parse_abi()
+        .map_err(|err| CliError::UnableToParse("module ABI", err.to_string()))?
+        .abi;
+    abi.and_then(|abi| {
+        abi.exposed_functions
+            .into_iter()
+            .find(|function| function.name.0 == function_id.member_id)
+    })
+    .filter(|function| function.is_entry)
+    .ok_or_else(|| {
+        CliError::CommandArgumentError(format!(
+            "{}::{} is not an entry function",
+            function_id.module_id, function_id.member_id
+        ))
+    })
+}
+
+/// Looks up the struct in its module's on-chain ABI
+async fn struct_abi(client: &Client, tag: &MoveStructTag) -> CliTypedResult<MoveStruct> {
+    let abi = client
+        .get_account_module(AccountAddress::from(tag.address), tag.module.as_str())
+        .await?
+        .into_inner()
+        .try_parse_abi()
+        .map_err(|err| CliError::UnableToParse("module ABI", err.to_string()))?
+        .abi;
+    abi.and_then(|abi| {
+        abi.structs
+            .into_iter()
+            .find(|move_struct| move_struct.name == tag.name)
+    })
+    .ok_or_else(|| CliError::CommandArgumentError(format!("No struct {} on-chain", tag)))
+}
+
+/// Encodes the JSON value as BCS of the Move type, appending it
+///
+/// Integers can be numbers or strings, `vector<u8>` can be a hex string, `0x1::string::String` is
+/// a string, and `0x1::option::Option` is `null` or its value.  Other structs are objects of
+/// their fields, encoded by the types in the struct's on-chain ABI.
+pub fn encode<'a>(
+    client: &'a Client,
+    typ: &'a MoveType,
+    value: &'a Value,
+    out: &'a mut Vec<u8>,
+) -> BoxFuture<'a, CliTypedResult<()>> {
+    async move {
+        let invalid = || {
+            CliError::UnableToParse("JSON argument", format!("{} is not a valid {}", value, typ))
+        };
+        match typ {
+            MoveType::Bool => out.push(value.as_bool().ok_or_else(invalid)? as u8),
+            MoveType::U8 => out.push(parse_integer(value).ok_or_else(invalid)?),
+            MoveType::U64 => out.extend(
+                parse_integer::<u64>(value)
+                    .ok_or_else(invalid)?
+                    .to_le_bytes(),
+            ),
+            MoveType::U128 => out.extend(
+                parse_integer::<u128>(value)
+                    .ok_or_else(invalid)?
+                    .to_le_bytes(),
+            ),
+            MoveType::Address => {
+                let address = load_account_arg(value.as_str().ok_or_else(invalid)?)?;
+                out.extend(address.to_vec())
+            }
+            MoveType::Vector { items } if **items == MoveType::U8 && value.is_string() => {
+                let hex = value.as_str().unwrap();
+                let bytes =
+                    hex::decode(hex.strip_prefix("0x").unwrap_or(hex)).map_err(|_| invalid())?;
+                write_uleb128(out, bytes.len());
+                out.extend(bytes);
+            }
+            MoveType::Vector { items } => {
+                let values = value.as_array().ok_or_else(invalid)?;
+                write_uleb128(out, values.len());
+                for value in values {
+                    encode(client, items, value, out).await?;
+                }
+            }
+            MoveType::Struct(tag) if is_framework_struct(tag, "string", "String") => {
+                let string = value.as_str().ok_or_else(invalid)?;
+                write_uleb128(out, string.len());
+                out.extend(string.as_bytes());
+            }
+            MoveType::Struct(tag) if is_framework_struct(tag, "option", "Option") => {
+                let inner = tag.generic_type_params.first().ok_or_else(invalid)?;
+                match value {
+                    Value::Null => write_uleb128(out, 0),
+                    // The API's form of an option, as a vector of at most one value
+                    Value::Object(fields) if fields.len() == 1 && fields.contains_key("vec") => {
+                        let vec = MoveType::Vector {
+                            items: Box::new(inner.clone()),
+                        };
+                        encode(client, &vec, &fields["vec"], out).await?;
+                    }
+                    value => {
+                        write_uleb128(out, 1);
+                        encode(client, inner, value, out).await?;
+                    }
+                }
+            }
+            MoveType::Struct(tag) => {
+                let fields = value.as_object().ok_or_else(invalid)?;
+                let abi = struct_abi(client, tag).await?;
+                for field in &abi.fields {
+                    let field_type = field
+                        .typ
+                        .instantiate(&tag.generic_type_params)
+                        .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+                    let field_value = fields.get(field.name.as_str()).ok_or_else(|| {
+                        CliError::CommandArgumentError(format!(
+                            "{} is missing field {}",
+                            value, field.name
+                        ))
+                    })?;
+                    encode(client, &field_type, field_value, out).await?;
+                }
+            }
+            typ => {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Arguments of type {} can't be encoded",
+                    typ
+                )))
+            }
+        }
+        Ok(())
+    }
+    .boxed()
+}
+
+fn is_framework_struct(tag: &MoveStructTag, module: &str, name: &str) -> bool {
+    *tag.address.inner() == AccountAddress::ONE
+        && tag.module.as_str() == module
+        && tag.name.as_str() == name
+}
+
+/// Integers are numbers, or strings where too large for JSON numbers
+fn parse_integer<T: FromStr>(value: &Value) -> Option<T> {
+    match value {
+        Value::Number(number) => number.to_string().parse().ok(),
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Writes a length as a ULEB128, as BCS prefixes sequences with
+fn write_uleb128(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_encode() {
+        // Only structs other than strings and options need the client
+        let client = Client::new("http://localhost:8080".parse().unwrap());
+        let encode = |typ: &str, value: Value| {
+            let client = client.clone();
+            let typ = MoveType::from_str(typ).unwrap();
+            async move {
+                let mut out = vec![];
+                encode(&client, &typ, &value, &mut out).await.map(|_| out)
+            }
+        };
+
+        assert_eq!(
+            bcs::to_bytes(&100u64).unwrap(),
+            encode("u64", json!(100)).await.unwrap()
+        );
+        assert_eq!(
+            bcs::to_bytes(&u128::MAX).unwrap(),
+            encode("u128", json!(u128::MAX.to_string())).await.unwrap()
+        );
+        assert_eq!(
+            bcs::to_bytes(&vec![vec![1u8, 2], vec![]]).unwrap(),
+            encode("vector<vector<u8>>", json!(["0x0102", []]))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            bcs::to_bytes(&"hello".to_string()).unwrap(),
+            encode("0x1::string::String", json!("hello")).await.unwrap()
+        );
+        assert_eq!(
+            bcs::to_bytes(&vec![Some(1u64), None]).unwrap(),
+            encode("vector<0x1::option::Option<u64>>", json!([1, null]))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            bcs::to_bytes(&Some(Some(true))).unwrap(),
+            encode(
+                "0x1::option::Option<0x1::option::Option<bool>>",
+                json!({"vec": [true]})
+            )
+            .await
+            .unwrap()
+        );
+        assert_eq!(
+            bcs::to_bytes(&AccountAddress::ONE).unwrap(),
+            encode("address", json!("0x1")).await.unwrap()
+        );
+        assert!(encode("u8", json!(256)).await.is_err());
+        assert!(encode("bool", json!("yes")).await.is_err());
+    }
+}
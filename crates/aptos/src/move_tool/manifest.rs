@@ -15,6 +15,12 @@ pub struct MovePackageManifest {
     pub package: PackageInfo,
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     pub addresses: BTreeMap<String, ManifestNamedAddress>,
+    #[serde(
+        rename = "dev-addresses",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub dev_addresses: BTreeMap<String, ManifestNamedAddress>,
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     pub dependencies: BTreeMap<String, Dependency>,
 }
@@ -2,27 +2,44 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod aptos_debug_natives;
+pub mod check_upgrade;
+pub mod chunked_publish;
+pub mod coverage;
+pub mod disassemble;
+pub mod fmt;
+pub mod init_template;
+pub mod json_payload;
 mod manifest;
 pub mod package_hooks;
 pub use package_hooks::*;
 pub mod stored_package;
 mod transactional_tests_runner;
+pub mod view;
 
 pub use stored_package::*;
 
+use crate::account::create_resource_account::ResourceAccountSeed;
+use crate::common::progress::Progress;
 use crate::common::types::MoveManifestAccountWrapper;
 use crate::common::types::{CliConfig, ConfigSearchMode, ProfileOptions, RestOptions};
 use crate::common::utils::{
-    create_dir_if_not_exist, dir_default_to_current, prompt_yes_with_override, write_to_file,
+    create_dir_if_not_exist, dir_default_to_current, prompt_yes_with_override, read_from_file,
+    write_to_file,
 };
+use crate::gas::report::{parse_test_statistics, GasReportOptions};
 use crate::governance::CompileScriptFunction;
+use crate::move_tool::init_template::{
+    template_address_name, ClientLanguage, PackageTemplate, TEMPLATE_DEV_ADDRESS,
+};
+use crate::move_tool::json_payload::EntryFunctionCall;
 use crate::move_tool::manifest::{
     Dependency, ManifestNamedAddress, MovePackageManifest, PackageInfo,
 };
+use crate::transaction::simulate::SimulatePayload;
 use crate::{
     common::{
         types::{
-            load_account_arg, CliError, CliTypedResult, MovePackageDir, PromptOptions,
+            load_account_arg, CliError, CliTypedResult, MovePackageDir, PromptOptions, Submission,
             TransactionOptions, TransactionSummary,
         },
         utils::check_if_file_exists,
@@ -31,28 +48,31 @@ use crate::{
 };
 use aptos_gas::{AbstractValueSizeGasParameters, NativeGasParameters};
 use aptos_module_verifier::module_init::verify_module_init_function;
-use aptos_rest_client::aptos_api_types::MoveType;
+use aptos_rest_client::aptos_api_types::{AptosError, AptosErrorCode, MoveType};
+use aptos_rest_client::error::{AptosErrorResponse, RestError};
 use aptos_transactional_test_harness::run_aptos_test;
 use aptos_types::account_address::{create_resource_address, AccountAddress};
 use aptos_types::transaction::{EntryFunction, Script, TransactionArgument, TransactionPayload};
 use async_trait::async_trait;
 use clap::{ArgEnum, Parser, Subcommand};
 use framework::docgen::DocgenOptions;
-use framework::natives::code::UpgradePolicy;
-use framework::prover::ProverOptions;
+use framework::natives::code::{PackageDep, UpgradePolicy};
+use framework::prover::{parse_diagnostics, ProverOptions};
 use framework::{BuildOptions, BuiltPackage};
 use itertools::Itertools;
 use move_cli::base::test::UnitTestResult;
 use move_command_line_common::env::MOVE_HOME;
 use serde::Serialize;
 use std::fmt::{Display, Formatter};
+use std::io::Write;
 use std::ops::Deref;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet, VecDeque},
     convert::TryFrom,
     path::{Path, PathBuf},
     str::FromStr,
 };
+use termcolor::{Buffer, ColorChoice, StandardStream};
 use tokio::task;
 use transactional_tests_runner::TransactionalTestOpts;
 use {
@@ -61,7 +81,12 @@ use {
         identifier::Identifier,
         language_storage::{ModuleId, TypeTag},
     },
-    move_package::{source_package::layout::SourcePackageLayout, BuildConfig},
+    move_package::{
+        source_package::{
+            layout::SourcePackageLayout, manifest_parser::parse_move_manifest_from_file,
+        },
+        BuildConfig,
+    },
     move_unit_test::UnitTestingConfig,
 };
 
@@ -72,45 +97,76 @@ use {
 /// about this code.
 #[derive(Subcommand)]
 pub enum MoveTool {
+    CheckUpgrade(check_upgrade::CheckUpgrade),
     Compile(CompilePackage),
+    #[clap(subcommand)]
+    Coverage(coverage::CoveragePackage),
     Init(InitPackage),
     Publish(PublishPackage),
+    Disassemble(disassemble::DisassembleModule),
     Download(DownloadPackage),
     List(ListPackage),
     Clean(CleanPackage),
+    ClearCache(ClearCache),
     VerifyPackage(VerifyPackage),
     Run(RunFunction),
     RunScript(RunScript),
     Test(TestPackage),
     Prove(ProvePackage),
     Document(DocumentPackage),
+    Fmt(fmt::FormatPackage),
     TransactionalTest(TransactionalTestOpts),
     CreateResourceAccountAndPublishPackage(CreateResourceAccountAndPublishPackage),
+    View(view::ViewFunction),
 }
 
 impl MoveTool {
     pub async fn execute(self) -> CliResult {
         match self {
+            MoveTool::CheckUpgrade(tool) => tool.execute_serialized().await,
             MoveTool::Compile(tool) => tool.execute_serialized().await,
+            MoveTool::Coverage(tool) => tool.execute().await,
             MoveTool::Init(tool) => tool.execute_serialized_success().await,
+            MoveTool::Publish(tool) if tool.simulate => {
+                let payload = tool.payload();
+                SimulatePayload::new("SimulatePublishPackage", payload, tool.txn_options)
+                    .execute_serialized()
+                    .await
+            }
             MoveTool::Publish(tool) => tool.execute_serialized().await,
+            MoveTool::Disassemble(tool) => tool.execute_serialized().await,
             MoveTool::Download(tool) => tool.execute_serialized().await,
             MoveTool::List(tool) => tool.execute_serialized().await,
             MoveTool::Clean(tool) => tool.execute_serialized().await,
+            MoveTool::ClearCache(tool) => tool.execute_serialized().await,
             MoveTool::VerifyPackage(tool) => tool.execute_serialized().await,
+            MoveTool::Run(tool) if tool.simulate => {
+                let payload = tool.payload().await;
+                SimulatePayload::new("SimulateRunFunction", payload, tool.txn_options)
+                    .execute_serialized()
+                    .await
+            }
             MoveTool::Run(tool) => tool.execute_serialized().await,
             MoveTool::RunScript(tool) => tool.execute_serialized().await,
             MoveTool::Test(tool) => tool.execute_serialized().await,
             MoveTool::Prove(tool) => tool.execute_serialized().await,
             MoveTool::Document(tool) => tool.execute_serialized().await,
+            MoveTool::Fmt(tool) => tool.execute_serialized().await,
             MoveTool::TransactionalTest(tool) => tool.execute_serialized_success().await,
             MoveTool::CreateResourceAccountAndPublishPackage(tool) => {
                 tool.execute_serialized_success().await
             }
+            MoveTool::View(tool) => tool.execute_serialized().await,
         }
     }
 }
 
+pub(crate) const APTOS_FRAMEWORK: &str = "AptosFramework";
+pub(crate) const APTOS_TOKEN: &str = "AptosToken";
+const APTOS_GIT_PATH: &str = "https://github.com/aptos-labs/aptos-core.git";
+const FRAMEWORK_SUBDIR_PATH: &str = "aptos-move/framework";
+const DEFAULT_BRANCH: &str = "main";
+
 #[derive(Parser)]
 pub struct FrameworkPackageArgs {
     /// Git revision or branch for the Aptos framework
@@ -134,11 +190,27 @@ impl FrameworkPackageArgs {
         addresses: BTreeMap<String, ManifestNamedAddress>,
         prompt_options: PromptOptions,
     ) -> CliTypedResult<()> {
-        const APTOS_FRAMEWORK: &str = "AptosFramework";
-        const APTOS_GIT_PATH: &str = "https://github.com/aptos-labs/aptos-core.git";
-        const SUBDIR_PATH: &str = "aptos-move/framework/aptos-framework";
-        const DEFAULT_BRANCH: &str = "main";
+        self.init_move_dir_with_dependencies(
+            package_dir,
+            name,
+            addresses,
+            BTreeMap::new(),
+            &[APTOS_FRAMEWORK],
+            prompt_options,
+        )
+    }
 
+    /// Like `init_move_dir`, with dev addresses, and depending on each of the given packages of
+    /// the framework, e.g. `AptosToken` as well as `AptosFramework`
+    pub fn init_move_dir_with_dependencies(
+        &self,
+        package_dir: &Path,
+        name: &str,
+        addresses: BTreeMap<String, ManifestNamedAddress>,
+        dev_addresses: BTreeMap<String, ManifestNamedAddress>,
+        framework_packages: &[&str],
+        prompt_options: PromptOptions,
+    ) -> CliTypedResult<()> {
         let move_toml = package_dir.join(SourcePackageLayout::Manifest.path());
         check_if_file_exists(move_toml.as_path(), prompt_options)?;
         create_dir_if_not_exist(
@@ -147,33 +219,9 @@ impl FrameworkPackageArgs {
                 .as_path(),
         )?;
 
-        // Add the framework dependency if it's provided
         let mut dependencies = BTreeMap::new();
-        if let Some(ref path) = self.framework_local_dir {
-            dependencies.insert(
-                APTOS_FRAMEWORK.to_string(),
-                Dependency {
-                    local: Some(path.display().to_string()),
-                    git: None,
-                    rev: None,
-                    subdir: None,
-                    aptos: None,
-                    address: None,
-                },
-            );
-        } else {
-            let git_rev = self.framework_git_rev.as_deref().unwrap_or(DEFAULT_BRANCH);
-            dependencies.insert(
-                APTOS_FRAMEWORK.to_string(),
-                Dependency {
-                    local: None,
-                    git: Some(APTOS_GIT_PATH.to_string()),
-                    rev: Some(git_rev.to_string()),
-                    subdir: Some(SUBDIR_PATH.to_string()),
-                    aptos: None,
-                    address: None,
-                },
-            );
+        for package in framework_packages {
+            dependencies.insert(package.to_string(), self.framework_dependency(package)?);
         }
 
         let manifest = MovePackageManifest {
@@ -183,6 +231,7 @@ impl FrameworkPackageArgs {
                 author: None,
             },
             addresses,
+            dev_addresses,
             dependencies,
         };
 
@@ -194,12 +243,58 @@ impl FrameworkPackageArgs {
                 .as_bytes(),
         )
     }
+
+    /// The dependency on a package of the framework, from `--framework-local-dir` if given
+    fn framework_dependency(&self, package: &str) -> CliTypedResult<Dependency> {
+        let subdir = match package {
+            APTOS_FRAMEWORK => "aptos-framework",
+            APTOS_TOKEN => "aptos-token",
+            _ => {
+                return Err(CliError::UnexpectedError(format!(
+                    "{} isn't a package of the framework",
+                    package
+                )))
+            }
+        };
+
+        // Other packages of a local framework are next to the Aptos framework
+        if let Some(ref path) = self.framework_local_dir {
+            let path = if package == APTOS_FRAMEWORK {
+                path.clone()
+            } else {
+                path.with_file_name(subdir)
+            };
+            Ok(Dependency {
+                local: Some(path.display().to_string()),
+                git: None,
+                rev: None,
+                subdir: None,
+                aptos: None,
+                address: None,
+            })
+        } else {
+            Ok(Dependency {
+                local: None,
+                git: Some(APTOS_GIT_PATH.to_string()),
+                rev: Some(
+                    self.framework_git_rev
+                        .as_deref()
+                        .unwrap_or(DEFAULT_BRANCH)
+                        .to_string(),
+                ),
+                subdir: Some(format!("{}/{}", FRAMEWORK_SUBDIR_PATH, subdir)),
+                aptos: None,
+                address: None,
+            })
+        }
+    }
 }
 
 /// Creates a new Move package at the given location
 ///
 /// This will create a directory for a Move package and a corresponding
-/// `Move.toml` file.
+/// `Move.toml` file.  With `--template`, it also generates Move sources with unit tests, and a
+/// client calling them.
 #[derive(Parser)]
 pub struct InitPackage {
     /// Name of the new Move package
@@ -225,6 +320,18 @@ pub struct InitPackage {
 
     #[clap(flatten)]
     pub(crate) framework_package_args: FrameworkPackageArgs,
+
+    /// Template to start the package from: [empty, coin, nft, dapp]
+    ///
+    /// The template's modules are published under a named address from the package's name, e.g.
+    /// `my_coin` for `MyCoin`.  Unless given with `--named-addresses`, it's set at publish time,
+    /// with a dev address for the unit tests.
+    #[clap(long, default_value_t = PackageTemplate::Empty)]
+    pub(crate) template: PackageTemplate,
+
+    /// Language of the client generated with a template: [typescript, rust, none]
+    #[clap(long, default_value_t = ClientLanguage::Typescript)]
+    pub(crate) client: ClientLanguage,
 }
 
 #[async_trait]
@@ -235,17 +342,44 @@ impl CliCommand<()> for InitPackage {
 
     async fn execute(self) -> CliTypedResult<()> {
         let package_dir = dir_default_to_current(self.package_dir.clone())?;
-        let addresses = self
+        let mut addresses: BTreeMap<String, ManifestNamedAddress> = self
             .named_addresses
             .into_iter()
             .map(|(key, value)| (key, value.account_address.into()))
             .collect();
+        if self.template == PackageTemplate::Empty {
+            return self.framework_package_args.init_move_dir(
+                package_dir.as_path(),
+                &self.name,
+                addresses,
+                self.prompt_options,
+            );
+        }
 
-        self.framework_package_args.init_move_dir(
+        let address_name = template_address_name(&self.name)?;
+        let mut dev_addresses = BTreeMap::new();
+        let address = addresses
+            .entry(address_name.clone())
+            .or_insert(ManifestNamedAddress { address: None });
+        if address.address.is_none() {
+            let dev_address = AccountAddress::from_hex_literal(TEMPLATE_DEV_ADDRESS)
+                .expect("Template dev address is valid");
+            dev_addresses.insert(address_name.clone(), Some(dev_address).into());
+        }
+        self.framework_package_args
+            .init_move_dir_with_dependencies(
+                package_dir.as_path(),
+                &self.name,
+                addresses,
+                dev_addresses,
+                self.template.framework_packages(),
+                self.prompt_options,
+            )?;
+        self.template.write(
             package_dir.as_path(),
-            &self.name,
-            addresses,
-            self.prompt_options,
+            &address_name,
+            self.client,
+            self.framework_package_args.framework_git_rev.as_deref(),
         )
     }
 }
@@ -264,6 +398,8 @@ pub struct CompilePackage {
     pub(crate) included_artifacts_args: IncludedArtifactsArgs,
     #[clap(flatten)]
     pub(crate) move_options: MovePackageDir,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
 }
 
 #[async_trait]
@@ -273,15 +409,18 @@ impl CliCommand<Vec<String>> for CompilePackage {
     }
 
     async fn execute(self) -> CliTypedResult<Vec<String>> {
+        self.move_options.apply_offline();
         let build_options = BuildOptions {
             install_dir: self.move_options.output_dir.clone(),
             ..self
                 .included_artifacts_args
                 .included_artifacts
-                .build_options(self.move_options.named_addresses())
+                .build_options(self.move_options.named_addresses(&self.profile_options)?)
         };
+        let progress = Progress::spinner("Compiling package");
         let pack = BuiltPackage::build(self.move_options.get_package_path()?, build_options)
             .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
+        drop(progress);
         if self.save_metadata {
             pack.extract_metadata_and_save()?;
         }
@@ -307,6 +446,8 @@ pub struct TestPackage {
 
     #[clap(flatten)]
     pub(crate) move_options: MovePackageDir,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
 
     /// The maximum number of instructions that can be executed by a test
     ///
@@ -319,6 +460,9 @@ pub struct TestPackage {
         long = "instructions"
     )]
     pub instruction_execution_bound: u64,
+
+    #[clap(flatten)]
+    pub(crate) gas_report_options: GasReportOptions,
 }
 
 #[async_trait]
@@ -328,35 +472,79 @@ impl CliCommand<&'static str> for TestPackage {
     }
 
     async fn execute(self) -> CliTypedResult<&'static str> {
-        let config = BuildConfig {
-            additional_named_addresses: self.move_options.named_addresses(),
-            test_mode: true,
-            install_dir: self.move_options.output_dir.clone(),
-            ..Default::default()
-        };
-        let result = move_cli::base::test::run_move_unit_tests(
-            self.move_options.get_package_path()?.as_path(),
-            config,
-            UnitTestingConfig {
-                filter: self.filter,
-                report_stacktrace_on_abort: true,
-                ..UnitTestingConfig::default_with_bound(None)
-            },
-            // TODO(Gas): we may want to switch to non-zero costs in the future
-            aptos_debug_natives::aptos_debug_natives(
-                NativeGasParameters::zeros(),
-                AbstractValueSizeGasParameters::zeros(),
-            ),
-            None,
+        if !self.gas_report_options.enabled() {
+            run_unit_tests(
+                &self.move_options,
+                &self.profile_options,
+                self.filter,
+                false,
+                false,
+                &mut std::io::stdout(),
+            )?;
+            return Ok("Success");
+        }
+
+        // The gas used by each test is only in the runner's output, so capture it to parse
+        let mut output = Vec::new();
+        let result = run_unit_tests(
+            &self.move_options,
+            &self.profile_options,
+            self.filter,
             false,
-            &mut std::io::stdout(),
-        )
-        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            true,
+            &mut output,
+        );
+        let output = String::from_utf8_lossy(&output);
+        print!("{}", output);
+        result?;
+        self.gas_report_options
+            .report(&parse_test_statistics(&output))?;
+        Ok("Success")
+    }
+}
 
-        match result {
-            UnitTestResult::Success => Ok("Success"),
-            UnitTestResult::Failure => Err(CliError::MoveTestError),
-        }
+/// Runs the package's unit tests, saving their coverage in the package if `compute_coverage`
+///
+/// With `report_statistics`, the time and gas used by each test are written after the results.
+pub(crate) fn run_unit_tests<W: Write + Send>(
+    move_options: &MovePackageDir,
+    profile_options: &ProfileOptions,
+    filter: Option<String>,
+    compute_coverage: bool,
+    report_statistics: bool,
+    writer: &mut W,
+) -> CliTypedResult<()> {
+    move_options.apply_offline();
+    let config = BuildConfig {
+        additional_named_addresses: move_options.named_addresses(profile_options)?,
+        test_mode: true,
+        install_dir: move_options.output_dir.clone(),
+        skip_fetch_latest_git_deps: move_options.offline,
+        ..Default::default()
+    };
+    let result = move_cli::base::test::run_move_unit_tests(
+        move_options.get_package_path()?.as_path(),
+        config,
+        UnitTestingConfig {
+            filter,
+            report_stacktrace_on_abort: true,
+            report_statistics,
+            ..UnitTestingConfig::default_with_bound(None)
+        },
+        // TODO(Gas): we may want to switch to non-zero costs in the future
+        aptos_debug_natives::aptos_debug_natives(
+            NativeGasParameters::zeros(),
+            AbstractValueSizeGasParameters::zeros(),
+        ),
+        None,
+        compute_coverage,
+        writer,
+    )
+    .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+
+    match result {
+        UnitTestResult::Success => Ok(()),
+        UnitTestResult::Failure => Err(CliError::MoveTestError),
     }
 }
 
@@ -383,11 +571,19 @@ impl CliCommand<()> for TransactionalTestOpts {
 /// Proves a Move package
 ///
 /// This is a tool for formal verification of a Move package using
-/// the Move prover
+/// the Move prover.  The package is resolved the same way as by `aptos move compile`.
 #[derive(Parser)]
 pub struct ProvePackage {
+    /// Also write the prover's diagnostics to this file as JSON
+    ///
+    /// Each diagnostic has its severity, message, location and full text, for editors and CI.
+    #[clap(long, parse(from_os_str))]
+    diagnostics_file: Option<PathBuf>,
+
     #[clap(flatten)]
     move_options: MovePackageDir,
+    #[clap(flatten)]
+    profile_options: ProfileOptions,
 
     #[clap(flatten)]
     prover_options: ProverOptions,
@@ -401,18 +597,50 @@ impl CliCommand<&'static str> for ProvePackage {
 
     async fn execute(self) -> CliTypedResult<&'static str> {
         let ProvePackage {
+            diagnostics_file,
             move_options,
+            profile_options,
             prover_options,
         } = self;
 
-        let result = task::spawn_blocking(move || {
-            prover_options.prove(
-                move_options.get_package_path()?.as_path(),
-                move_options.named_addresses(),
-            )
+        let named_addresses = move_options.named_addresses(&profile_options)?;
+        let package_path = move_options.get_package_path()?;
+        let install_dir = move_options.output_dir.clone();
+        let capture_output = diagnostics_file.is_some();
+        let (result, output) = task::spawn_blocking(move || {
+            if !capture_output {
+                let result = prover_options.prove_with_writer(
+                    package_path.as_path(),
+                    named_addresses,
+                    install_dir,
+                    &mut StandardStream::stderr(ColorChoice::Auto),
+                );
+                return (result, String::new());
+            }
+            // Diagnostics are parsed from the uncolored output, which is still shown as usual
+            let mut buffer = Buffer::no_color();
+            let result = prover_options.prove_with_writer(
+                package_path.as_path(),
+                named_addresses,
+                install_dir,
+                &mut buffer,
+            );
+            let output = String::from_utf8_lossy(buffer.as_slice()).to_string();
+            eprint!("{}", output);
+            (result, output)
         })
         .await
         .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+
+        if let Some(diagnostics_file) = diagnostics_file {
+            let diagnostics = serde_json::to_string_pretty(&parse_diagnostics(&output))
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            write_to_file(
+                diagnostics_file.as_path(),
+                "Prover diagnostics",
+                diagnostics.as_bytes(),
+            )?;
+        }
         match result {
             Ok(_) => Ok("Success"),
             Err(e) => Err(CliError::MoveProverError(format!("{:#}", e))),
@@ -422,11 +650,15 @@ impl CliCommand<&'static str> for ProvePackage {
 
 /// Documents a Move package
 ///
-/// This converts the content of the package into markdown for documentation.
+/// This converts the content of the package into markdown for documentation, written to the
+/// package's `doc` directory unless `--output-directory` is given.  With `--check`, nothing is
+/// written, and it fails if the documentation there is out of date, so it can be checked in CI.
 #[derive(Parser)]
 pub struct DocumentPackage {
     #[clap(flatten)]
     move_options: MovePackageDir,
+    #[clap(flatten)]
+    profile_options: ProfileOptions,
 
     #[clap(flatten)]
     docgen_options: DocgenOptions,
@@ -441,8 +673,10 @@ impl CliCommand<&'static str> for DocumentPackage {
     async fn execute(self) -> CliTypedResult<&'static str> {
         let DocumentPackage {
             move_options,
+            profile_options,
             docgen_options,
         } = self;
+        move_options.apply_offline();
         let build_options = BuildOptions {
             with_srcs: false,
             with_abis: false,
@@ -450,7 +684,7 @@ impl CliCommand<&'static str> for DocumentPackage {
             with_error_map: false,
             with_docs: true,
             install_dir: None,
-            named_addresses: move_options.named_addresses(),
+            named_addresses: move_options.named_addresses(&profile_options)?,
             docgen_options: Some(docgen_options),
         };
         BuiltPackage::build(move_options.get_package_path()?, build_options)?;
@@ -480,6 +714,36 @@ pub struct PublishPackage {
     #[clap(long)]
     pub(crate) override_size_check: bool,
 
+    /// Simulate publishing the package instead of submitting it
+    ///
+    /// Shows the gas, VM status, events, and state changes, like `aptos transaction simulate`
+    #[clap(long)]
+    pub(crate) simulate: bool,
+
+    /// Publish a package too large for one transaction, by staging it over several transactions
+    ///
+    /// The metadata and code are staged in chunks with the `large_packages` module, from
+    /// `aptos-move/move-examples/large_packages`, and the last transaction publishes the package.
+    #[clap(long)]
+    pub(crate) chunked_publish: bool,
+
+    /// Address the `large_packages` module is published at, for `--chunked-publish`
+    #[clap(long, parse(try_from_str = crate::common::types::load_account_arg))]
+    pub(crate) large_packages_module_address: Option<AccountAddress>,
+
+    /// Upgrade policy to publish with, instead of the one in Move.toml
+    ///
+    /// One of `compatible` or `immutable`.  Check an upgrade with `aptos move check-upgrade` first.
+    #[clap(long)]
+    pub(crate) upgrade_policy: Option<UpgradePolicy>,
+
+    /// Build the package a second time from scratch, and fail if the two builds differ
+    ///
+    /// The package can only be verified against its source, e.g. with `aptos move verify-package`,
+    /// if it builds the same every time.
+    #[clap(long)]
+    pub(crate) check_reproducibility: bool,
+
     #[clap(flatten)]
     pub(crate) included_artifacts_args: IncludedArtifactsArgs,
     #[clap(flatten)]
@@ -559,53 +823,142 @@ impl IncludedArtifacts {
 pub const MAX_PUBLISH_PACKAGE_SIZE: usize = 60_000;
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for PublishPackage {
+impl CliCommand<Submission<TransactionSummary>> for PublishPackage {
     fn command_name(&self) -> &'static str {
         "PublishPackage"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
-        let PublishPackage {
-            move_options,
-            txn_options,
-            override_size_check,
-            included_artifacts_args,
-        } = self;
-        let package_path = move_options.get_package_path()?;
-        let options = included_artifacts_args
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        if self.chunked_publish {
+            return self.chunked_publish().await;
+        }
+        let payload = self.payload()?;
+        self.txn_options
+            .submit_transaction(payload)
+            .await
+            .map(|submission| submission.map(TransactionSummary::from))
+    }
+}
+
+impl PublishPackage {
+    /// Builds the package into its serialized metadata and the code of each module
+    fn build(&self) -> CliTypedResult<(Vec<u8>, Vec<Vec<u8>>)> {
+        self.move_options.apply_offline();
+        let package_path = self.move_options.get_package_path()?;
+        let options = self
+            .included_artifacts_args
             .included_artifacts
-            .build_options(move_options.named_addresses());
+            .build_options(
+                self.move_options
+                    .named_addresses(&self.txn_options.profile_options)?,
+            );
+        let progress = Progress::spinner("Compiling package");
         let package = BuiltPackage::build(package_path, options)?;
-        let compiled_units = package.extract_code();
+        drop(progress);
+        if self.check_reproducibility {
+            package
+                .check_reproducibility()
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        }
+        let mut metadata = package.extract_metadata()?;
+        if let Some(upgrade_policy) = self.upgrade_policy {
+            metadata.upgrade_policy = upgrade_policy;
+        }
+        Ok((
+            bcs::to_bytes(&metadata).expect("PackageMetadata has BCS"),
+            package.extract_code(),
+        ))
+    }
+
+    /// Builds the package into the payload to publish it
+    fn payload(&self) -> CliTypedResult<TransactionPayload> {
+        if self.chunked_publish {
+            return Err(CliError::CommandArgumentError(
+                "--simulate can't be used with --chunked-publish".to_string(),
+            ));
+        }
+        let (metadata_serialized, compiled_units) = self.build()?;
 
         // Send the compiled module and metadata using the code::publish_package_txn.
-        let metadata = package.extract_metadata()?;
         let payload = cached_packages::aptos_stdlib::code_publish_package_txn(
-            bcs::to_bytes(&metadata).expect("PackageMetadata has BCS"),
+            metadata_serialized,
             compiled_units,
         );
         let size = bcs::serialized_size(&payload)?;
         println!("package size {} bytes", size);
-        if !override_size_check && size > MAX_PUBLISH_PACKAGE_SIZE {
+        if !self.override_size_check && size > MAX_PUBLISH_PACKAGE_SIZE {
             return Err(CliError::UnexpectedError(format!(
                 "The package is larger than {} bytes ({} bytes)! To lower the size \
                 you may want to include less artifacts via `--included_artifacts`. \
-                You can also override this check with `--override-size-check",
+                You can also override this check with `--override-size-check`, \
+                or publish it over several transactions with `--chunked-publish`",
                 MAX_PUBLISH_PACKAGE_SIZE, size
             )));
         }
-        txn_options
-            .submit_transaction(payload)
-            .await
-            .map(TransactionSummary::from)
+        Ok(payload)
+    }
+
+    /// Stages the package in chunks, one transaction at a time, returning the summary of the last
+    /// transaction, which publishes it
+    ///
+    /// Stopping before submitting, e.g. with `--dry-run`, stops at the first transaction.
+    async fn chunked_publish(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        let large_packages_address = self.large_packages_module_address.ok_or_else(|| {
+            CliError::CommandArgumentError(
+                "--large-packages-module-address is required for --chunked-publish".to_string(),
+            )
+        })?;
+        if self.txn_options.encode_only {
+            return Err(CliError::CommandArgumentError(
+                "--encode-only can't be used with --chunked-publish".to_string(),
+            ));
+        }
+        let (metadata_serialized, code) = self.build()?;
+        let payloads = chunked_publish::chunked_publish_payloads(
+            large_packages_address,
+            &metadata_serialized,
+            &code,
+        );
+
+        let count = payloads.len();
+        let mut summary = None;
+        for (i, payload) in payloads.into_iter().enumerate() {
+            eprintln!("Submitting transaction {} of {}", i + 1, count);
+            let submission = self
+                .txn_options
+                .submit_transaction(payload)
+                .await
+                .map_err(|err| match i {
+                    0 => err,
+                    _ => CliError::UnexpectedError(format!(
+                        "Transaction {} of {} failed, the staged code can be discarded with \
+                        {}::large_packages::cleanup: {}",
+                        i + 1,
+                        count,
+                        large_packages_address.to_hex_literal(),
+                        err
+                    )),
+                })?;
+            match submission {
+                Submission::Submitted(transaction) => {
+                    summary = Some(TransactionSummary::from(transaction))
+                }
+                Submission::NotSubmitted(not_submitted) => {
+                    return Ok(Submission::NotSubmitted(not_submitted))
+                }
+            }
+        }
+        Ok(Submission::Submitted(
+            summary.expect("Chunked publish has at least one transaction"),
+        ))
     }
 }
 
 /// Publishes the modules in a Move package to the Aptos blockchain under a resource account
 #[derive(Parser)]
 pub struct CreateResourceAccountAndPublishPackage {
-    #[clap(long)]
-    pub(crate) seed: String,
+    #[clap(flatten)]
+    pub(crate) seed_args: ResourceAccountSeed,
 
     #[clap(long)]
     pub(crate) address_name: String,
@@ -623,14 +976,14 @@ pub struct CreateResourceAccountAndPublishPackage {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for CreateResourceAccountAndPublishPackage {
+impl CliCommand<Submission<TransactionSummary>> for CreateResourceAccountAndPublishPackage {
     fn command_name(&self) -> &'static str {
         "ResourceAccountPublishPackage"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
         let CreateResourceAccountAndPublishPackage {
-            seed,
+            seed_args,
             address_name,
             mut move_options,
             txn_options,
@@ -651,14 +1004,15 @@ impl CliCommand<TransactionSummary> for CreateResourceAccountAndPublishPackage {
             ));
         };
 
-        let resource_address =
-            create_resource_address(account, &bcs::to_bytes(&seed.clone()).unwrap());
+        let seed = seed_args.seed()?;
+        let resource_address = create_resource_address(account, &seed);
         move_options.add_named_address(address_name, resource_address.to_string());
 
+        move_options.apply_offline();
         let package_path = move_options.get_package_path()?;
         let options = included_artifacts_args
             .included_artifacts
-            .build_options(move_options.named_addresses());
+            .build_options(move_options.named_addresses(&txn_options.profile_options)?);
         let package = BuiltPackage::build(package_path, options)?;
         let compiled_units = package.extract_code();
 
@@ -672,7 +1026,7 @@ impl CliCommand<TransactionSummary> for CreateResourceAccountAndPublishPackage {
         prompt_yes_with_override(&message, txn_options.prompt_options)?;
 
         let payload = cached_packages::aptos_stdlib::resource_account_create_resource_account_and_publish_package(
-            bcs::to_bytes(&seed)?,
+            seed,
             bcs::to_bytes(&metadata).expect("PackageMetadata has BCS"),
             compiled_units,
         );
@@ -689,7 +1043,7 @@ impl CliCommand<TransactionSummary> for CreateResourceAccountAndPublishPackage {
         txn_options
             .submit_transaction(payload)
             .await
-            .map(TransactionSummary::from)
+            .map(|submission| submission.map(TransactionSummary::from))
     }
 }
 
@@ -711,6 +1065,12 @@ pub struct DownloadPackage {
     #[clap(long, parse(from_os_str))]
     pub output_dir: Option<PathBuf>,
 
+    /// Also download the package's dependencies, transitively, next to it
+    ///
+    /// The downloaded packages' Move.toml files are changed to depend on each other locally.
+    #[clap(long)]
+    pub with_dependencies: bool,
+
     #[clap(flatten)]
     pub(crate) rest_options: RestOptions,
     #[clap(flatten)]
@@ -724,8 +1084,8 @@ impl CliCommand<&'static str> for DownloadPackage {
     }
 
     async fn execute(self) -> CliTypedResult<&'static str> {
-        let url = self.rest_options.url(&self.profile_options)?;
-        let registry = CachedPackageRegistry::create(url, self.account).await?;
+        let client = self.rest_options.client(&self.profile_options)?;
+        let registry = CachedPackageRegistry::create(client.clone(), self.account).await?;
         let output_dir = dir_default_to_current(self.output_dir)?;
 
         let package = registry
@@ -739,20 +1099,61 @@ impl CliCommand<&'static str> for DownloadPackage {
                     .to_owned(),
             ));
         }
-        let package_path = output_dir.join(package.name());
-        package
-            .save_package_to_disk(package_path.as_path())
-            .map_err(|e| CliError::UnexpectedError(format!("Failed to save package: {}", e)))?;
-        println!(
-            "Saved package with {} module(s) to `{}`",
-            package.module_names().len(),
-            package_path.display()
-        );
+        save_package(&package, &output_dir)?;
+        if !self.with_dependencies {
+            return Ok("Download succeeded");
+        }
+
+        let mut downloaded = BTreeSet::from([package.name().to_string()]);
+        let mut seen = BTreeSet::new();
+        let mut pending: VecDeque<PackageDep> = package.deps().iter().cloned().collect();
+        while let Some(dep) = pending.pop_front() {
+            if !seen.insert((dep.account, dep.package_name.clone())) {
+                continue;
+            }
+            let registry = CachedPackageRegistry::create(client.clone(), dep.account).await?;
+            let dep_package = registry
+                .get_package(&dep.package_name)
+                .await
+                .map_err(|s| CliError::UnexpectedError(s.to_string()))?;
+            save_package(&dep_package, &output_dir)?;
+            downloaded.insert(dep_package.name().to_string());
+            pending.extend(dep_package.deps().iter().cloned());
+        }
+
+        for name in &downloaded {
+            let manifest_path = output_dir
+                .join(name)
+                .join(SourcePackageLayout::Manifest.path());
+            let manifest = String::from_utf8(read_from_file(&manifest_path)?)
+                .map_err(|err| CliError::UnableToParse("Move.toml", err.to_string()))?;
+            let manifest = localize_dependencies(&manifest, &downloaded)
+                .map_err(|err| CliError::UnableToParse("Move.toml", err.to_string()))?;
+            write_to_file(&manifest_path, "Move.toml", manifest.as_bytes())?;
+        }
         Ok("Download succeeded")
     }
 }
 
+/// Saves a package under the directory, in a directory named after it
+fn save_package(package: &CachedPackageMetadata, output_dir: &Path) -> CliTypedResult<()> {
+    let package_path = output_dir.join(package.name());
+    package
+        .save_package_to_disk(package_path.as_path())
+        .map_err(|e| CliError::UnexpectedError(format!("Failed to save package: {}", e)))?;
+    println!(
+        "Saved package with {} module(s) to `{}`",
+        package.module_names().len(),
+        package_path.display()
+    );
+    Ok(())
+}
+
 /// Downloads a package and verifies that the bytecode matches a local compilation of the Move code
+///
+/// The package is rebuilt with the artifacts it was published with, then each module's bytecode
+/// and the package's metadata are compared with those on-chain.  Fails listing every module and
+/// whether it matches, if anything doesn't.
 #[derive(Parser)]
 pub struct VerifyPackage {
     /// Address of the account containing the package
@@ -760,8 +1161,10 @@ pub struct VerifyPackage {
     pub(crate) account: AccountAddress,
 
     /// Artifacts to be generated when building this package.
-    #[clap(long, default_value_t = IncludedArtifacts::Sparse)]
-    pub(crate) included_artifacts: IncludedArtifacts,
+    ///
+    /// Defaults to the artifacts in the on-chain metadata, as the package was published with
+    #[clap(long)]
+    pub(crate) included_artifacts: Option<IncludedArtifacts>,
 
     #[clap(flatten)]
     pub(crate) move_options: MovePackageDir,
@@ -771,29 +1174,55 @@ pub struct VerifyPackage {
     pub(crate) profile_options: ProfileOptions,
 }
 
+/// Whether a module built locally matches the one on-chain
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModuleVerification {
+    Match,
+    Mismatch,
+    MissingOnChain,
+    MissingLocally,
+}
+
+/// The result of comparing a local build of a package with the package on-chain
+#[derive(Debug, Serialize)]
+pub struct PackageVerification {
+    pub package: String,
+    pub account: AccountAddress,
+    pub included_artifacts: String,
+    /// Fields of the package metadata that don't match, e.g. `source_digest`
+    pub metadata_mismatches: Vec<&'static str>,
+    pub modules: BTreeMap<String, ModuleVerification>,
+}
+
+impl PackageVerification {
+    fn verified(&self) -> bool {
+        self.metadata_mismatches.is_empty()
+            && self
+                .modules
+                .values()
+                .all(|module| *module == ModuleVerification::Match)
+    }
+}
+
 #[async_trait]
-impl CliCommand<&'static str> for VerifyPackage {
+impl CliCommand<PackageVerification> for VerifyPackage {
     fn command_name(&self) -> &'static str {
-        "DownloadPackage"
+        "VerifyPackage"
     }
 
-    async fn execute(self) -> CliTypedResult<&'static str> {
-        // First build the package locally to get the package metadata
-        let build_options = BuildOptions {
-            install_dir: self.move_options.output_dir.clone(),
-            ..self
-                .included_artifacts
-                .build_options(self.move_options.named_addresses())
-        };
-        let pack = BuiltPackage::build(self.move_options.get_package_path()?, build_options)
-            .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
-        let compiled_metadata = pack.extract_metadata()?;
+    async fn execute(self) -> CliTypedResult<PackageVerification> {
+        // The package's name is needed to find it on-chain, before it can be built to match
+        let package_path = self.move_options.get_package_path()?;
+        let manifest =
+            parse_move_manifest_from_file(&package_path.join(SourcePackageLayout::Manifest.path()))
+                .map_err(|err| CliError::UnableToParse("Move.toml", err.to_string()))?;
+        let package_name = manifest.package.name.to_string();
 
-        // Now pull the compiled package
-        let url = self.rest_options.url(&self.profile_options)?;
-        let registry = CachedPackageRegistry::create(url, self.account).await?;
+        let client = self.rest_options.client(&self.profile_options)?;
+        let registry = CachedPackageRegistry::create(client.clone(), self.account).await?;
         let package = registry
-            .get_package(pack.name())
+            .get_package(&package_name)
             .await
             .map_err(|s| CliError::CommandArgumentError(s.to_string()))?;
 
@@ -806,10 +1235,65 @@ impl CliCommand<&'static str> for VerifyPackage {
             ));
         }
 
-        // Verify that the source digest matches
-        package.verify(&compiled_metadata)?;
+        // Build the package with the same artifacts, or the metadata can't match
+        let included_artifacts = self
+            .included_artifacts
+            .unwrap_or_else(|| package.included_artifacts());
+        let build_options = BuildOptions {
+            install_dir: self.move_options.output_dir.clone(),
+            ..included_artifacts
+                .build_options(self.move_options.named_addresses(&self.profile_options)?)
+        };
+        let pack = BuiltPackage::build(package_path, build_options)
+            .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
+        let compiled_metadata = pack.extract_metadata()?;
+
+        let mut modules = BTreeMap::new();
+        for (module, code) in compiled_metadata.modules.iter().zip(pack.extract_code()) {
+            let verification = match client
+                .get_account_module_bcs(self.account, &module.name)
+                .await
+            {
+                Ok(bytecode) if bytecode.inner().as_ref() == code.as_slice() => {
+                    ModuleVerification::Match
+                }
+                Ok(_) => ModuleVerification::Mismatch,
+                Err(RestError::Api(AptosErrorResponse {
+                    error:
+                        AptosError {
+                            error_code: AptosErrorCode::ModuleNotFound,
+                            ..
+                        },
+                    ..
+                })) => ModuleVerification::MissingOnChain,
+                Err(err) => return Err(err.into()),
+            };
+            modules.insert(module.name.clone(), verification);
+        }
+        for name in package.module_names() {
+            modules
+                .entry(name.to_string())
+                .or_insert(ModuleVerification::MissingLocally);
+        }
 
-        Ok("Successfully verified source of package")
+        let verification = PackageVerification {
+            package: package_name,
+            account: self.account,
+            included_artifacts: included_artifacts.to_string(),
+            metadata_mismatches: package.mismatches(&compiled_metadata),
+            modules,
+        };
+        if verification.verified() {
+            Ok(verification)
+        } else {
+            Err(CliError::UnexpectedError(format!(
+                "Package {} doesn't match the one at {}: {}",
+                verification.package,
+                verification.account,
+                serde_json::to_string_pretty(&verification)
+                    .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+            )))
+        }
     }
 }
 
@@ -863,8 +1347,8 @@ impl CliCommand<&'static str> for ListPackage {
     }
 
     async fn execute(self) -> CliTypedResult<&'static str> {
-        let url = self.rest_options.url(&self.profile_options)?;
-        let registry = CachedPackageRegistry::create(url, self.account).await?;
+        let client = self.rest_options.client(&self.profile_options)?;
+        let registry = CachedPackageRegistry::create(client, self.account).await?;
         match self.query {
             MoveListQuery::Packages => {
                 for name in registry.package_names() {
@@ -919,6 +1403,40 @@ impl CliCommand<&'static str> for CleanPackage {
     }
 }
 
+/// Deletes the cache of git and on-chain dependencies
+///
+/// The cache is in the global config folder, or at `MOVE_HOME` if it's set.  Dependencies are
+/// fetched again by the next build.
+#[derive(Parser)]
+pub struct ClearCache {
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<&'static str> for ClearCache {
+    fn command_name(&self) -> &'static str {
+        "ClearCache"
+    }
+
+    async fn execute(self) -> CliTypedResult<&'static str> {
+        let move_dir = PathBuf::from(&*MOVE_HOME);
+        if !move_dir.exists() {
+            return Ok("Cache is already empty");
+        }
+        prompt_yes_with_override(
+            &format!(
+                "Do you want to delete the dependency cache at `{}`?",
+                move_dir.display()
+            ),
+            self.prompt_options,
+        )?;
+        std::fs::remove_dir_all(&move_dir)
+            .map_err(|e| CliError::IO("Removing Move cache dir".to_string(), e))?;
+        Ok("succeeded")
+    }
+}
+
 /// Run a Move function
 #[derive(Parser)]
 pub struct RunFunction {
@@ -926,7 +1444,16 @@ pub struct RunFunction {
     ///
     /// Example: `0x842ed41fad9640a2ad08fdd7d3e4f7f505319aac7d67e1c0dd6a7cce8732c7e3::message::set_message`
     #[clap(long)]
-    pub(crate) function_id: MemberId,
+    pub(crate) function_id: Option<MemberId>,
+
+    /// JSON file giving the function id, type arguments, and arguments, instead of the flags
+    ///
+    /// Arguments are encoded by the function's parameter types on-chain, so they can be vectors,
+    /// structs, and options.
+    ///
+    /// Example: `{"function_id": "0x1::coin::transfer", "type_args": ["0x1::aptos_coin::AptosCoin"], "args": ["0xb0b", "100"]}`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) json_file: Option<PathBuf>,
 
     /// Arguments combined with their type separated by spaces.
     ///
@@ -942,50 +1469,100 @@ pub struct RunFunction {
     #[clap(long, multiple_values = true)]
     pub(crate) type_args: Vec<MoveType>,
 
+    /// Simulate the transaction instead of submitting it
+    ///
+    /// Shows the gas, VM status, events, and state changes, like `aptos transaction simulate`
+    #[clap(long)]
+    pub(crate) simulate: bool,
+
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for RunFunction {
+impl CliCommand<Submission<TransactionSummary>> for RunFunction {
     fn command_name(&self) -> &'static str {
         "RunFunction"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
-        let args: Vec<Vec<u8>> = self
-            .args
-            .into_iter()
-            .map(|arg_with_type| arg_with_type.arg)
-            .collect();
-        let mut type_args: Vec<TypeTag> = Vec::new();
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        let payload = self.payload().await?;
+        self.txn_options
+            .submit_transaction(payload)
+            .await
+            .map(|submission| submission.map(TransactionSummary::from))
+    }
+}
 
-        // These TypeArgs are used for generics
-        for type_arg in self.type_args.into_iter() {
-            let type_tag = TypeTag::try_from(type_arg)
-                .map_err(|err| CliError::UnableToParse("--type-args", err.to_string()))?;
-            type_args.push(type_tag)
+impl RunFunction {
+    /// Builds the payload from the JSON file if given, otherwise from the flags
+    async fn payload(&self) -> CliTypedResult<TransactionPayload> {
+        match (&self.json_file, &self.function_id) {
+            (Some(json_file), None) if self.args.is_empty() && self.type_args.is_empty() => {
+                let call: EntryFunctionCall =
+                    serde_json::from_slice(&read_from_file(json_file)?)
+                        .map_err(|err| CliError::UnableToParse("--json-file", err.to_string()))?;
+                let client = self
+                    .txn_options
+                    .rest_options
+                    .client(&self.txn_options.profile_options)?;
+                call.payload(&client).await
+            }
+            (None, Some(function_id)) => entry_function_payload(
+                function_id.clone(),
+                self.type_args.clone(),
+                self.args.clone(),
+            ),
+            _ => Err(CliError::CommandArgumentError(
+                "Give either --json-file, or --function-id with --type-args and --args".to_string(),
+            )),
         }
+    }
+}
 
-        self.txn_options
-            .submit_transaction(TransactionPayload::EntryFunction(EntryFunction::new(
-                self.function_id.module_id,
-                self.function_id.member_id,
-                type_args,
-                args,
-            )))
-            .await
-            .map(TransactionSummary::from)
+/// Builds the payload to call an entry function
+pub(crate) fn entry_function_payload(
+    function_id: MemberId,
+    type_args: Vec<MoveType>,
+    args: Vec<ArgWithType>,
+) -> CliTypedResult<TransactionPayload> {
+    let args: Vec<Vec<u8>> = args
+        .into_iter()
+        .map(|arg_with_type| arg_with_type.arg)
+        .collect();
+    let mut type_tags: Vec<TypeTag> = Vec::new();
+
+    // These TypeArgs are used for generics
+    for type_arg in type_args.into_iter() {
+        let type_tag = TypeTag::try_from(type_arg)
+            .map_err(|err| CliError::UnableToParse("--type-args", err.to_string()))?;
+        type_tags.push(type_tag)
     }
+
+    Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+        function_id.module_id,
+        function_id.member_id,
+        type_tags,
+        args,
+    )))
 }
 
 /// Run a Move script
+///
+/// The script is either a single file compiled against the framework with `--script-path`, a
+/// script in the package at `--package-dir` with `--script-name`, or already compiled with
+/// `--compiled-script-path`.
 #[derive(Parser)]
 pub struct RunScript {
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
     #[clap(flatten)]
     pub(crate) compile_proposal_args: CompileScriptFunction,
+    /// Name of a script's function in the package at `--package-dir`, to compile and run it
+    #[clap(long, group = "script")]
+    pub(crate) script_name: Option<String>,
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
     /// Arguments combined with their type separated by spaces.
     ///
     /// Supported types [u8, u64, u128, bool, hex, string, address, raw]
@@ -1001,15 +1578,20 @@ pub struct RunScript {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for RunScript {
+impl CliCommand<Submission<TransactionSummary>> for RunScript {
     fn command_name(&self) -> &'static str {
         "RunScript"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
-        let (bytecode, _script_hash) = self
-            .compile_proposal_args
-            .compile("RunScript", self.txn_options.prompt_options)?;
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        let bytecode = match &self.script_name {
+            Some(script_name) => self.compile_package_script(script_name)?,
+            None => {
+                self.compile_proposal_args
+                    .compile("RunScript", self.txn_options.prompt_options)?
+                    .0
+            }
+        };
 
         let mut args: Vec<TransactionArgument> = vec![];
         for arg in self.args {
@@ -1025,13 +1607,37 @@ impl CliCommand<TransactionSummary> for RunScript {
             type_args.push(type_tag)
         }
 
-        let txn = self
-            .txn_options
+        self.txn_options
             .submit_transaction(TransactionPayload::Script(Script::new(
                 bytecode, type_args, args,
             )))
-            .await?;
-        Ok(TransactionSummary::from(&txn))
+            .await
+            .map(|submission| submission.map(TransactionSummary::from))
+    }
+}
+
+impl RunScript {
+    /// Builds the package, returning the bytecode of its script with the name
+    fn compile_package_script(&self, script_name: &str) -> CliTypedResult<Vec<u8>> {
+        let build_options = BuildOptions {
+            install_dir: self.move_options.output_dir.clone(),
+            named_addresses: self
+                .move_options
+                .named_addresses(&self.txn_options.profile_options)?,
+            ..BuildOptions::default()
+        };
+        let package = BuiltPackage::build(self.move_options.get_package_path()?, build_options)
+            .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
+        package
+            .extract_script_code_by_name(script_name)
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!(
+                    "No script named {} in package {}, its scripts are [{}]",
+                    script_name,
+                    package.name(),
+                    package.script_names().join(", ")
+                ))
+            })
     }
 }
 
@@ -1133,8 +1739,8 @@ impl FunctionArgType {
                             .map_err(|err| CliError::UnableToParse("vector<u64>", err.to_string()))
                     }),
                     FunctionArgType::U128 => parse_vector_arg(arg, |arg| {
-                        u64::from_str(arg)
-                            .map_err(|err| CliError::UnableToParse("vector<128>", err.to_string()))
+                        u128::from_str(arg)
+                            .map_err(|err| CliError::UnableToParse("vector<u128>", err.to_string()))
                     }),
                     vector_type => {
                         panic!("Unsupported vector type vector<{}>", vector_type)
@@ -1207,6 +1813,7 @@ impl FromStr for FunctionArgType {
 }
 
 /// A parseable arg with a type separated by a colon
+#[derive(Clone, Debug)]
 pub struct ArgWithType {
     pub(crate) _ty: FunctionArgType,
     pub(crate) arg: Vec<u8>,
@@ -1,15 +1,47 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::common::http::HttpConfig;
 use crate::common::types::load_account_arg;
+use crate::config::global_folder;
 use crate::move_tool::CachedPackageRegistry;
-use framework::UPGRADE_POLICY_CUSTOM_FIELD;
+use anyhow::bail;
+use framework::natives::code::PackageMetadata;
+use framework::{METADATA_FILE_NAME, UPGRADE_POLICY_CUSTOM_FIELD};
 use futures::executor::block_on;
 use move_package::compilation::package_layout::CompiledPackageLayout;
 use move_package::package_hooks::PackageHooks;
 use move_package::source_package::parsed_manifest::CustomDepInfo;
 use move_symbol_pool::Symbol;
 use reqwest::Url;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Same as the REST client's default timeout
+const REST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Folder in the global config folder that dependencies are cached in
+pub const MOVE_CACHE_FOLDER: &str = "move-cache";
+
+/// Whether on-chain dependencies must come from the cache, set by `--offline`
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Caches dependencies in the global config folder, unless `MOVE_HOME` sets somewhere else
+///
+/// Must be called before `MOVE_HOME` is first read, which is when the first package is built.
+pub fn set_dependency_cache_dir() {
+    if std::env::var_os("MOVE_HOME").is_none() {
+        if let Ok(folder) = global_folder() {
+            std::env::set_var("MOVE_HOME", folder.join(MOVE_CACHE_FOLDER));
+        }
+    }
+}
+
+/// Only uses on-chain dependencies already in the cache, for the builds that follow
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
 
 pub fn register_package_hooks() {
     move_package::package_hooks::register_package_hooks(Box::new(AptosPackageHooks {}))
@@ -35,20 +67,45 @@ impl PackageHooks for AptosPackageHooks {
     }
 }
 
+/// Downloads the package, unless the cached copy has the same source digest as on-chain
 async fn maybe_download_package(info: &CustomDepInfo) -> anyhow::Result<()> {
-    if !info
-        .download_to
-        .join(CompiledPackageLayout::BuildInfo.path())
-        .exists()
-    {
-        let registry = CachedPackageRegistry::create(
-            Url::parse(info.node_url.as_str())?,
-            load_account_arg(info.package_address.as_str())?,
-        )
-        .await?;
-        let package = registry.get_package(info.package_name).await?;
-        package.save_package_to_disk(info.download_to.as_path())
-    } else {
-        Ok(())
+    let download_to = info.download_to.as_path();
+    let cached_metadata = cached_metadata(download_to);
+    if OFFLINE.load(Ordering::Relaxed) {
+        if cached_metadata.is_none()
+            && !download_to
+                .join(CompiledPackageLayout::BuildInfo.path())
+                .exists()
+        {
+            bail!(
+                "Package {} isn't in the dependency cache, so can't be used offline",
+                info.package_name
+            )
+        }
+        return Ok(());
+    }
+
+    // There's no profile for a dependency's node, so only the global HTTP settings apply
+    let client = HttpConfig::resolve(None)?
+        .rest_client(Url::parse(info.node_url.as_str())?, REST_TIMEOUT)?;
+    let registry =
+        CachedPackageRegistry::create(client, load_account_arg(info.package_address.as_str())?)
+            .await?;
+    let package = registry.get_package(info.package_name).await?;
+    match cached_metadata {
+        Some(metadata) if metadata.source_digest == package.source_digest() => Ok(()),
+        _ => {
+            // Drop the outdated sources and build, so none of it outlives the upgrade
+            if download_to.exists() {
+                std::fs::remove_dir_all(download_to)?;
+            }
+            package.save_package_to_disk(download_to)
+        }
     }
 }
+
+/// The metadata saved with a downloaded package, if it's cached
+fn cached_metadata(download_to: &Path) -> Option<PackageMetadata> {
+    let bytes = std::fs::read(download_to.join(METADATA_FILE_NAME)).ok()?;
+    bcs::from_bytes(&bytes).ok()
+}
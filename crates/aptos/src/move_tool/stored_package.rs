@@ -1,13 +1,16 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::move_tool::IncludedArtifacts;
 use anyhow::bail;
 use aptos_rest_client::Client;
 use aptos_types::account_address::AccountAddress;
-use framework::natives::code::{ModuleMetadata, PackageMetadata, PackageRegistry, UpgradePolicy};
-use framework::unzip_metadata_str;
+use framework::natives::code::{
+    ModuleMetadata, PackageDep, PackageMetadata, PackageRegistry, UpgradePolicy,
+};
+use framework::{unzip_metadata_str, METADATA_FILE_NAME};
 use move_package::compilation::package_layout::CompiledPackageLayout;
-use reqwest::Url;
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::Path;
 
@@ -31,8 +34,7 @@ pub struct CachedModuleMetadata<'a> {
 
 impl CachedPackageRegistry {
     /// Creates a new registry.
-    pub async fn create(url: Url, addr: AccountAddress) -> anyhow::Result<Self> {
-        let client = Client::new(url);
+    pub async fn create(client: Client, addr: AccountAddress) -> anyhow::Result<Self> {
         // Need to use a different type to deserialize JSON
         let inner = client
             .get_account_resource_bcs::<PackageRegistry>(addr, "0x1::code::PackageRegistry")
@@ -120,81 +122,74 @@ impl<'a> CachedPackageMetadata<'a> {
         bail!("module `{}` not found", name)
     }
 
+    pub fn deps(&self) -> &[PackageDep] {
+        &self.metadata.deps
+    }
+
+    /// Saves the manifest, the source of each module published with its source, and the
+    /// package's metadata as BCS
     pub fn save_package_to_disk(&self, path: &Path) -> anyhow::Result<()> {
         fs::create_dir_all(path)?;
         fs::write(
             path.join("Move.toml"),
             unzip_metadata_str(&self.metadata.manifest)?,
         )?;
+        fs::write(path.join(METADATA_FILE_NAME), bcs::to_bytes(self.metadata)?)?;
         let sources_dir = path.join(CompiledPackageLayout::Sources.path());
         fs::create_dir_all(&sources_dir)?;
         for module in &self.metadata.modules {
-            let source = match module.source.is_empty() {
-                true => {
-                    println!("module without code: {}", module.name);
-                    "".into()
-                }
-                false => unzip_metadata_str(&module.source)?,
-            };
-            fs::write(sources_dir.join(format!("{}.move", module.name)), source)?;
+            if module.source.is_empty() {
+                println!("module without source: {}", module.name);
+                continue;
+            }
+            fs::write(
+                sources_dir.join(format!("{}.move", module.name)),
+                unzip_metadata_str(&module.source)?,
+            )?;
         }
         Ok(())
     }
 
-    pub fn verify(&self, package_metadata: &PackageMetadata) -> anyhow::Result<()> {
-        let self_metadata = self.metadata;
+    /// The artifacts the package was published with, so a rebuild includes the same
+    pub fn included_artifacts(&self) -> IncludedArtifacts {
+        let modules = &self.metadata.modules;
+        if modules.iter().any(|module| !module.source_map.is_empty()) {
+            IncludedArtifacts::All
+        } else if modules.iter().any(|module| !module.source.is_empty()) {
+            IncludedArtifacts::Sparse
+        } else {
+            IncludedArtifacts::None
+        }
+    }
 
+    /// Names the fields of the metadata which don't match a local build's
+    ///
+    /// The upgrade number isn't compared, as it's counted on-chain and always 0 locally.
+    pub fn mismatches(&self, package_metadata: &PackageMetadata) -> Vec<&'static str> {
+        let self_metadata = self.metadata;
+        let mut mismatches = vec![];
         if self_metadata.name != package_metadata.name {
-            bail!(
-                "Package name doesn't match {} : {}",
-                package_metadata.name,
-                self_metadata.name
-            )
-        } else if self_metadata.deps != package_metadata.deps {
-            bail!(
-                "Dependencies don't match {:?} : {:?}",
-                package_metadata.deps,
-                self_metadata.deps
-            )
-        } else if self_metadata.modules != package_metadata.modules {
-            bail!(
-                "Modules don't match {:?} : {:?}",
-                package_metadata.modules,
-                self_metadata.modules
-            )
-        } else if self_metadata.manifest != package_metadata.manifest {
-            bail!(
-                "Manifest doesn't match {:?} : {:?}",
-                package_metadata.manifest,
-                self_metadata.manifest
-            )
-        } else if self_metadata.upgrade_policy != package_metadata.upgrade_policy {
-            bail!(
-                "Upgrade policy doesn't match {:?} : {:?}",
-                package_metadata.upgrade_policy,
-                self_metadata.upgrade_policy
-            )
-        } else if self_metadata.upgrade_number != package_metadata.upgrade_number {
-            bail!(
-                "Upgrade number doesn't match {:?} : {:?}",
-                package_metadata.upgrade_number,
-                self_metadata.upgrade_number
-            )
-        } else if self_metadata.extension != package_metadata.extension {
-            bail!(
-                "Extensions doesn't match {:?} : {:?}",
-                package_metadata.extension,
-                self_metadata.extension
-            )
-        } else if self_metadata.source_digest != package_metadata.source_digest {
-            bail!(
-                "Source digests doesn't match {:?} : {:?}",
-                package_metadata.source_digest,
-                self_metadata.source_digest
-            )
+            mismatches.push("name");
         }
-
-        Ok(())
+        if self_metadata.deps != package_metadata.deps {
+            mismatches.push("deps");
+        }
+        if self_metadata.modules != package_metadata.modules {
+            mismatches.push("modules");
+        }
+        if self_metadata.manifest != package_metadata.manifest {
+            mismatches.push("manifest");
+        }
+        if self_metadata.upgrade_policy != package_metadata.upgrade_policy {
+            mismatches.push("upgrade_policy");
+        }
+        if self_metadata.extension != package_metadata.extension {
+            mismatches.push("extension");
+        }
+        if self_metadata.source_digest != package_metadata.source_digest {
+            mismatches.push("source_digest");
+        }
+        mismatches
     }
 }
 
@@ -211,3 +206,62 @@ impl<'a> CachedModuleMetadata<'a> {
         &self.metadata.source_map
     }
 }
+
+/// Points the manifest's dependencies on the given packages at their directories next to it
+pub fn localize_dependencies(
+    manifest: &str,
+    packages: &BTreeSet<String>,
+) -> anyhow::Result<String> {
+    let mut manifest: toml::Value = toml::from_str(manifest)?;
+    if let Some(toml::Value::Table(dependencies)) = manifest.get_mut("dependencies") {
+        for (name, dependency) in dependencies.iter_mut() {
+            if packages.contains(name) {
+                let mut local = toml::value::Table::new();
+                local.insert(
+                    "local".to_string(),
+                    toml::Value::String(format!("../{}", name)),
+                );
+                *dependency = toml::Value::Table(local);
+            }
+        }
+    }
+    Ok(toml::to_string(&manifest)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localize_dependencies() {
+        let manifest = r#"
+[package]
+name = "Example"
+version = "0.0.1"
+
+[dependencies]
+AptosFramework = { git = "https://github.com/aptos-labs/aptos-core.git", subdir = "aptos-move/framework/aptos-framework", rev = "main" }
+Other = { local = "../other" }
+
+[addresses]
+example = "0xcafe"
+"#;
+        let packages = BTreeSet::from(["AptosFramework".to_string()]);
+        let manifest: toml::Value =
+            toml::from_str(&localize_dependencies(manifest, &packages).unwrap()).unwrap();
+        assert_eq!(
+            "../AptosFramework",
+            manifest["dependencies"]["AptosFramework"]["local"]
+                .as_str()
+                .unwrap()
+        );
+        assert!(manifest["dependencies"]["AptosFramework"]
+            .get("git")
+            .is_none());
+        assert_eq!(
+            "../other",
+            manifest["dependencies"]["Other"]["local"].as_str().unwrap()
+        );
+        assert_eq!("0xcafe", manifest["addresses"]["example"].as_str().unwrap());
+    }
+}
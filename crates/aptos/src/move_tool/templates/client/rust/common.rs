@@ -0,0 +1,50 @@
+use anyhow::{Context, Result};
+use aptos_sdk::crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterialStringExt};
+use aptos_sdk::move_types::{identifier::Identifier, language_storage::ModuleId};
+use aptos_sdk::rest_client::Client;
+use aptos_sdk::transaction_builder::TransactionFactory;
+use aptos_sdk::types::{
+    chain_id::ChainId,
+    transaction::{EntryFunction, TransactionPayload},
+    AccountKey, LocalAccount,
+};
+use url::Url;
+
+pub fn client() -> Result<Client> {
+    let node_url = std::env::var("APTOS_NODE_URL")
+        .unwrap_or_else(|_| "https://fullnode.devnet.aptoslabs.com".to_string());
+    Ok(Client::new(Url::parse(&node_url)?))
+}
+
+/// The account the package is published under, from the hex private key in `PRIVATE_KEY`
+pub async fn load_account(client: &Client) -> Result<LocalAccount> {
+    let private_key = std::env::var("PRIVATE_KEY").context(
+        "Set PRIVATE_KEY to the private key of the account the package is published under",
+    )?;
+    let key = AccountKey::from_private_key(Ed25519PrivateKey::from_encoded_string(&private_key)?);
+    let address = key.authentication_key().derived_address();
+    let sequence_number = client.get_account(address).await?.into_inner().sequence_number;
+    Ok(LocalAccount::new(address, key, sequence_number))
+}
+
+/// Calls an entry function of the package with BCS encoded arguments, and waits for it to commit
+pub async fn submit(
+    client: &Client,
+    account: &mut LocalAccount,
+    module: &str,
+    function: &str,
+    args: Vec<Vec<u8>>,
+) -> Result<()> {
+    let chain_id = client.get_index().await?.into_inner().chain_id;
+    let payload = TransactionPayload::EntryFunction(EntryFunction::new(
+        ModuleId::new(account.address(), Identifier::new(module)?),
+        Identifier::new(function)?,
+        vec![],
+        args,
+    ));
+    let transaction = account.sign_with_transaction_builder(
+        TransactionFactory::new(ChainId::new(chain_id)).payload(payload),
+    );
+    client.submit_and_wait(&transaction).await?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+import { AptosAccount, AptosClient, HexString } from "aptos";
+
+export const NODE_URL = process.env.APTOS_NODE_URL || "https://fullnode.devnet.aptoslabs.com";
+
+export const client = new AptosClient(NODE_URL);
+
+/** The account the package is published under, from the hex private key in `PRIVATE_KEY` */
+export function loadAccount(): AptosAccount {
+  const privateKey = process.env.PRIVATE_KEY;
+  if (!privateKey) {
+    throw new Error("Set PRIVATE_KEY to the private key of the account the package is published under");
+  }
+  return new AptosAccount(new HexString(privateKey).toUint8Array());
+}
+
+/** Calls an entry function of the package, e.g. `module::function`, and waits for it to commit */
+export async function submit(account: AptosAccount, func: string, args: any[]): Promise<string> {
+  const rawTxn = await client.generateTransaction(account.address(), {
+    function: `${account.address().hex()}::${func}`,
+    type_arguments: [],
+    arguments: args,
+  });
+  const bcsTxn = await client.signTransaction(account, rawTxn);
+  const pendingTxn = await client.submitTransaction(bcsTxn);
+  await client.waitForTransaction(pendingTxn.hash, { checkSuccess: true });
+  return pendingTxn.hash;
+}
@@ -0,0 +1,82 @@
+/// A coin, which only the account the module is published under can mint and burn
+module {{address}}::example_coin {
+    use std::signer;
+    use std::string;
+    use aptos_framework::coin::{Self, BurnCapability, FreezeCapability, MintCapability};
+
+    /// The signer isn't the account the coin belongs to
+    const ENOT_OWNER: u64 = 1;
+
+    struct ExampleCoin {}
+
+    /// Capabilities over the coin, kept by its owner
+    struct Capabilities has key {
+        burn_cap: BurnCapability<ExampleCoin>,
+        freeze_cap: FreezeCapability<ExampleCoin>,
+        mint_cap: MintCapability<ExampleCoin>,
+    }
+
+    fun init_module(owner: &signer) {
+        let (burn_cap, freeze_cap, mint_cap) = coin::initialize<ExampleCoin>(
+            owner,
+            string::utf8(b"Example Coin"),
+            string::utf8(b"EXC"),
+            8,
+            true,
+        );
+        move_to(owner, Capabilities { burn_cap, freeze_cap, mint_cap });
+    }
+
+    /// Registers the account to hold the coin
+    public entry fun register(account: &signer) {
+        coin::register<ExampleCoin>(account);
+    }
+
+    /// Mints coins to the recipient, who must be registered for the coin
+    public entry fun mint(owner: &signer, recipient: address, amount: u64) acquires Capabilities {
+        let owner_addr = signer::address_of(owner);
+        assert!(owner_addr == @{{address}}, ENOT_OWNER);
+        let capabilities = borrow_global<Capabilities>(owner_addr);
+        coin::deposit(recipient, coin::mint(amount, &capabilities.mint_cap));
+    }
+
+    /// Burns coins from the owner's balance
+    public entry fun burn(owner: &signer, amount: u64) acquires Capabilities {
+        let owner_addr = signer::address_of(owner);
+        assert!(owner_addr == @{{address}}, ENOT_OWNER);
+        let capabilities = borrow_global<Capabilities>(owner_addr);
+        coin::burn_from(owner_addr, amount, &capabilities.burn_cap);
+    }
+
+    #[test_only]
+    use aptos_framework::account;
+
+    #[test(owner = @{{address}}, user = @0x123)]
+    fun test_mint_and_burn(owner: signer, user: signer) acquires Capabilities {
+        let owner_addr = signer::address_of(&owner);
+        let user_addr = signer::address_of(&user);
+        account::create_account_for_test(owner_addr);
+        account::create_account_for_test(user_addr);
+        init_module(&owner);
+        register(&owner);
+        register(&user);
+
+        mint(&owner, user_addr, 100);
+        mint(&owner, owner_addr, 50);
+        burn(&owner, 20);
+        assert!(coin::balance<ExampleCoin>(user_addr) == 100, 0);
+        assert!(coin::balance<ExampleCoin>(owner_addr) == 30, 1);
+    }
+
+    #[test(owner = @{{address}}, user = @0x123)]
+    #[expected_failure(abort_code = 1)]
+    fun test_only_owner_mints(owner: signer, user: signer) acquires Capabilities {
+        let user_addr = signer::address_of(&user);
+        account::create_account_for_test(signer::address_of(&owner));
+        account::create_account_for_test(user_addr);
+        init_module(&owner);
+        register(&user);
+
+        mint(&user, user_addr, 100);
+    }
+}
@@ -0,0 +1,17 @@
+import { client, loadAccount, submit } from "./common";
+
+async function main() {
+  const owner = loadAccount();
+  const address = owner.address().hex();
+
+  console.log("Registered:", await submit(owner, "example_coin::register", []));
+  console.log("Minted:", await submit(owner, "example_coin::mint", [address, 100]));
+
+  const coinStore = await client.getAccountResource(
+    address,
+    `0x1::coin::CoinStore<${address}::example_coin::ExampleCoin>`,
+  );
+  console.log("Balance:", (coinStore.data as any).coin.value);
+}
+
+main();
@@ -0,0 +1,34 @@
+mod common;
+
+use anyhow::Result;
+use aptos_sdk::bcs;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let client = common::client()?;
+    let mut owner = common::load_account(&client).await?;
+    let address = owner.address();
+
+    common::submit(&client, &mut owner, "example_coin", "register", vec![]).await?;
+    common::submit(
+        &client,
+        &mut owner,
+        "example_coin",
+        "mint",
+        vec![bcs::to_bytes(&address)?, bcs::to_bytes(&100u64)?],
+    )
+    .await?;
+
+    let coin_store = format!(
+        "0x1::coin::CoinStore<{}::example_coin::ExampleCoin>",
+        address.to_hex_literal()
+    );
+    if let Some(resource) = client
+        .get_account_resource(address, &coin_store)
+        .await?
+        .into_inner()
+    {
+        println!("Balance: {}", resource.data["coin"]["value"]);
+    }
+    Ok(())
+}
@@ -0,0 +1,14 @@
+import { client, loadAccount, submit } from "./common";
+
+async function main() {
+  const account = loadAccount();
+  const address = account.address().hex();
+
+  console.log("Added:", await submit(account, "todo_list::add_task", ["Publish the package"]));
+  console.log("Completed:", await submit(account, "todo_list::complete_task", [0]));
+
+  const todoList = await client.getAccountResource(address, `${address}::todo_list::TodoList`);
+  console.log("Tasks:", (todoList.data as any).tasks);
+}
+
+main();
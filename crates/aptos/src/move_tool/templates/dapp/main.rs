@@ -0,0 +1,38 @@
+mod common;
+
+use anyhow::Result;
+use aptos_sdk::bcs;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let client = common::client()?;
+    let mut account = common::load_account(&client).await?;
+    let address = account.address();
+
+    common::submit(
+        &client,
+        &mut account,
+        "todo_list",
+        "add_task",
+        vec![bcs::to_bytes("Publish the package")?],
+    )
+    .await?;
+    common::submit(
+        &client,
+        &mut account,
+        "todo_list",
+        "complete_task",
+        vec![bcs::to_bytes(&0u64)?],
+    )
+    .await?;
+
+    let todo_list = format!("{}::todo_list::TodoList", address.to_hex_literal());
+    if let Some(resource) = client
+        .get_account_resource(address, &todo_list)
+        .await?
+        .into_inner()
+    {
+        println!("Tasks: {}", resource.data["tasks"]);
+    }
+    Ok(())
+}
@@ -0,0 +1,74 @@
+/// A todo list for each account, as the on-chain half of a dapp
+module {{address}}::todo_list {
+    use std::signer;
+    use std::string::String;
+    use std::vector;
+
+    /// The account has no todo list
+    const ENO_TODO_LIST: u64 = 1;
+    /// There's no task at the index
+    const ETASK_NOT_FOUND: u64 = 2;
+
+    struct Task has store, drop, copy {
+        content: String,
+        completed: bool,
+    }
+
+    struct TodoList has key {
+        tasks: vector<Task>,
+    }
+
+    /// Adds a task to the account's todo list, creating the list on the first task
+    public entry fun add_task(account: &signer, content: String) acquires TodoList {
+        let addr = signer::address_of(account);
+        if (!exists<TodoList>(addr)) {
+            move_to(account, TodoList { tasks: vector::empty() });
+        };
+        let todo_list = borrow_global_mut<TodoList>(addr);
+        vector::push_back(&mut todo_list.tasks, Task { content, completed: false });
+    }
+
+    /// Marks the task at the index completed
+    public entry fun complete_task(account: &signer, index: u64) acquires TodoList {
+        let addr = signer::address_of(account);
+        assert!(exists<TodoList>(addr), ENO_TODO_LIST);
+        let todo_list = borrow_global_mut<TodoList>(addr);
+        assert!(index < vector::length(&todo_list.tasks), ETASK_NOT_FOUND);
+        vector::borrow_mut(&mut todo_list.tasks, index).completed = true;
+    }
+
+    public fun task_count(addr: address): u64 acquires TodoList {
+        if (!exists<TodoList>(addr)) {
+            return 0
+        };
+        vector::length(&borrow_global<TodoList>(addr).tasks)
+    }
+
+    public fun is_completed(addr: address, index: u64): bool acquires TodoList {
+        assert!(exists<TodoList>(addr), ENO_TODO_LIST);
+        let todo_list = borrow_global<TodoList>(addr);
+        assert!(index < vector::length(&todo_list.tasks), ETASK_NOT_FOUND);
+        vector::borrow(&todo_list.tasks, index).completed
+    }
+
+    #[test_only]
+    use std::string;
+
+    #[test(account = @0x123)]
+    fun test_tasks(account: signer) acquires TodoList {
+        let addr = signer::address_of(&account);
+        add_task(&account, string::utf8(b"Publish the package"));
+        add_task(&account, string::utf8(b"Write the frontend"));
+        complete_task(&account, 0);
+
+        assert!(task_count(addr) == 2, 0);
+        assert!(is_completed(addr, 0), 1);
+        assert!(!is_completed(addr, 1), 2);
+    }
+
+    #[test(account = @0x123)]
+    #[expected_failure(abort_code = 1)]
+    fun test_complete_without_list(account: signer) acquires TodoList {
+        complete_task(&account, 0);
+    }
+}
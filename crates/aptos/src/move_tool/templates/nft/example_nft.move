@@ -0,0 +1,74 @@
+/// A collection of NFTs, which only the account the module is published under can mint
+module {{address}}::example_nft {
+    use std::signer;
+    use std::string::{Self, String};
+    use aptos_token::token;
+
+    /// The signer isn't the account the collection belongs to
+    const ENOT_OWNER: u64 = 1;
+
+    const COLLECTION_NAME: vector<u8> = b"Example Collection";
+
+    fun init_module(owner: &signer) {
+        // A maximum of 0 leaves the collection's size unlimited
+        token::create_collection(
+            owner,
+            string::utf8(COLLECTION_NAME),
+            string::utf8(b"An example collection"),
+            string::utf8(b"https://example.com"),
+            0,
+            vector[false, false, false],
+        );
+    }
+
+    /// Mints a single token of the collection to the owner
+    public entry fun mint(owner: &signer, name: String, uri: String) {
+        let owner_addr = signer::address_of(owner);
+        assert!(owner_addr == @{{address}}, ENOT_OWNER);
+        token::create_token_script(
+            owner,
+            string::utf8(COLLECTION_NAME),
+            name,
+            string::utf8(b"An example token"),
+            1,
+            1,
+            uri,
+            owner_addr,
+            100,
+            0,
+            vector[false, false, false, false, false],
+            vector[],
+            vector[],
+            vector[],
+        );
+    }
+
+    #[test_only]
+    use aptos_framework::account;
+
+    #[test(owner = @{{address}})]
+    fun test_mint(owner: signer) {
+        let owner_addr = signer::address_of(&owner);
+        account::create_account_for_test(owner_addr);
+        init_module(&owner);
+
+        mint(&owner, string::utf8(b"Token #1"), string::utf8(b"https://example.com/1"));
+        let token_id = token::create_token_id_raw(
+            owner_addr,
+            string::utf8(COLLECTION_NAME),
+            string::utf8(b"Token #1"),
+            0,
+        );
+        assert!(token::balance_of(owner_addr, token_id) == 1, 0);
+    }
+
+    #[test(owner = @{{address}}, user = @0x123)]
+    #[expected_failure(abort_code = 1)]
+    fun test_only_owner_mints(owner: signer, user: signer) {
+        account::create_account_for_test(signer::address_of(&owner));
+        account::create_account_for_test(signer::address_of(&user));
+        init_module(&owner);
+
+        mint(&user, string::utf8(b"Token #1"), string::utf8(b"https://example.com/1"));
+    }
+}
@@ -0,0 +1,9 @@
+import { loadAccount, submit } from "./common";
+
+async function main() {
+  const owner = loadAccount();
+
+  console.log("Minted:", await submit(owner, "example_nft::mint", ["Token #1", "https://example.com/1"]));
+}
+
+main();
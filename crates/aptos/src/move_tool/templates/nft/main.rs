@@ -0,0 +1,24 @@
+mod common;
+
+use anyhow::Result;
+use aptos_sdk::bcs;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let client = common::client()?;
+    let mut owner = common::load_account(&client).await?;
+
+    common::submit(
+        &client,
+        &mut owner,
+        "example_nft",
+        "mint",
+        vec![
+            bcs::to_bytes("Token #1")?,
+            bcs::to_bytes("https://example.com/1")?,
+        ],
+    )
+    .await?;
+    println!("Minted Token #1");
+    Ok(())
+}
@@ -0,0 +1,308 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliError, CliTypedResult, ProfileOptions, RestOptions};
+use crate::move_tool::{FunctionArgType, MemberId};
+use aptos_debugger::AptosDebugger;
+use aptos_rest_client::aptos_api_types::{
+    MoveFunction, MoveFunctionVisibility, MoveStructTag, MoveType, MoveValue,
+};
+use aptos_rest_client::Client;
+use aptos_types::account_address::AccountAddress;
+use aptos_validator_interface::{DebuggerStateView, RestDebuggerInterface};
+use aptos_vm::data_cache::StorageAdapter;
+use async_trait::async_trait;
+use clap::Parser;
+use move_core_types::language_storage::TypeTag;
+use move_resource_viewer::MoveValueAnnotator;
+use move_vm_types::gas::UnmeteredGasMeter;
+use serde_json::Value;
+use std::{convert::TryFrom, sync::Arc};
+
+/// Call a public function to read on-chain state, without a transaction
+///
+/// The function runs locally, on the state at the ledger version read through the REST API, and
+/// nothing it writes is kept.  Modules don't mark their view functions in this framework, so any
+/// public function without a signer parameter can be called.
+///
+/// The arguments are parsed by the types of the function's parameters from its on-chain ABI,
+/// e.g. `--args 0x1 true 100`.  Vectors are comma separated, and `vector<u8>` is hex.  The return
+/// values are printed as JSON.
+#[derive(Debug, Parser)]
+pub struct ViewFunction {
+    /// Function name as `<ADDRESS>::<MODULE_ID>::<FUNCTION_NAME>`
+    ///
+    /// Example: `0x1::coin::balance`
+    #[clap(long)]
+    pub(crate) function_id: MemberId,
+
+    /// Arguments separated by spaces, in the order of the function's parameters
+    ///
+    /// Example: `0x1 true 100`
+    #[clap(long, multiple_values = true)]
+    pub(crate) args: Vec<String>,
+
+    /// TypeTag arguments separated by spaces.
+    ///
+    /// Example: `0x1::aptos_coin::AptosCoin`
+    #[clap(long, multiple_values = true)]
+    pub(crate) type_args: Vec<MoveType>,
+
+    /// Ledger version to read the state at, defaulting to the latest
+    #[clap(long)]
+    pub(crate) ledger_version: Option<u64>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<Vec<Value>> for ViewFunction {
+    fn command_name(&self) -> &'static str {
+        "ViewFunction"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<Value>> {
+        let client = self.rest_options.client(&self.profile_options)?;
+        let function = function_abi(&client, &self.function_id).await?;
+
+        if function.generic_type_params.len() != self.type_args.len() {
+            return Err(CliError::CommandArgumentError(format!(
+                "{} takes {} type arguments, but got {}",
+                function.name,
+                function.generic_type_params.len(),
+                self.type_args.len()
+            )));
+        }
+        if function.params.iter().any(MoveType::is_signer) {
+            return Err(CliError::CommandArgumentError(format!(
+                "{} takes a signer, so it can't be called without a transaction",
+                function.name
+            )));
+        }
+        if function.params.len() != self.args.len() {
+            return Err(CliError::CommandArgumentError(format!(
+                "{} takes {} arguments [{}], but got {}",
+                function.name,
+                function.params.len(),
+                function
+                    .params
+                    .iter()
+                    .map(|param| param.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                self.args.len()
+            )));
+        }
+        let args = function
+            .params
+            .iter()
+            .zip(self.args.iter())
+            .map(|(param, arg)| parse_view_arg(&self.instantiate(param)?, arg))
+            .collect::<CliTypedResult<_>>()?;
+        let return_types = function
+            .return_
+            .iter()
+            .map(|typ| type_tag(&self.instantiate(typ)?))
+            .collect::<CliTypedResult<_>>()?;
+        let type_args = self
+            .type_args
+            .iter()
+            .map(type_tag)
+            .collect::<CliTypedResult<_>>()?;
+
+        let version = match self.ledger_version {
+            Some(version) => version,
+            None => client.get_ledger_information().await?.into_inner().version,
+        };
+        let function_id = self.function_id;
+        // The state is read with blocking requests, which can't be made on the async runtime
+        tokio::task::spawn_blocking(move || {
+            view_at_version(
+                client,
+                version,
+                &function_id,
+                type_args,
+                args,
+                &return_types,
+            )
+        })
+        .await
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+    }
+}
+
+impl ViewFunction {
+    fn instantiate(&self, typ: &MoveType) -> CliTypedResult<MoveType> {
+        typ.instantiate(&self.type_args)
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))
+    }
+}
+
+/// Looks up the function in its module's on-chain ABI, which must be public to be viewed
+async fn function_abi(client: &Client, function_id: &MemberId) -> CliTypedResult<MoveFunction> {
+    let module = client
+        .get_account_module(
+            *function_id.module_id.address(),
+            function_id.module_id.name().as_str(),
+        )
+        .await?
+        .into_inner()
+        .try_parse_abi()
+        .map_err(|err| CliError::UnableToParse("module ABI", err.to_string()))?;
+    module
+        .abi
+        .and_then(|abi| {
+            abi.exposed_functions
+                .into_iter()
+                .find(|function| function.name.0 == function_id.member_id)
+        })
+        .filter(|function| function.visibility == MoveFunctionVisibility::Public)
+        .ok_or_else(|| {
+            CliError::CommandArgumentError(format!(
+                "{}::{} is not a public function",
+                function_id.module_id, function_id.member_id
+            ))
+        })
+}
+
+/// Runs the function on the state at `version`, and decodes its return values as JSON
+fn view_at_version(
+    client: Client,
+    version: u64,
+    function_id: &MemberId,
+    type_args: Vec<TypeTag>,
+    args: Vec<Vec<u8>>,
+    return_types: &[TypeTag],
+) -> CliTypedResult<Vec<Value>> {
+    // Both read the state before the version they're given, which is the one of a transaction
+    let debugger = AptosDebugger::rest_client(client.clone())
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+    let mut return_values = vec![];
+    debugger
+        .run_session_at_version(version + 1, |session| {
+            return_values = session
+                .execute_function_bypass_visibility(
+                    &function_id.module_id,
+                    &function_id.member_id,
+                    type_args,
+                    args,
+                    &mut UnmeteredGasMeter,
+                )?
+                .return_values;
+            Ok(())
+        })
+        .map_err(|err| {
+            CliError::UnexpectedError(format!(
+                "Failed to execute {}::{}: {}",
+                function_id.module_id, function_id.member_id, err
+            ))
+        })?;
+
+    let state_view =
+        DebuggerStateView::new(Arc::new(RestDebuggerInterface::new(client)), version + 1);
+    let storage = StorageAdapter::new(&state_view);
+    let annotator = MoveValueAnnotator::new(&storage);
+    return_types
+        .iter()
+        .zip(return_values)
+        .map(|(typ, (bytes, _layout))| {
+            annotator
+                .view_value(typ, &bytes)
+                .and_then(MoveValue::try_from)
+                .and_then(|value| value.json())
+                .map_err(|err| CliError::UnableToParse("return value", err.to_string()))
+        })
+        .collect()
+}
+
+fn type_tag(typ: &MoveType) -> CliTypedResult<TypeTag> {
+    TypeTag::try_from(typ.clone())
+        .map_err(|err| CliError::CommandArgumentError(format!("{}: {}", typ, err)))
+}
+
+/// Parses an argument to BCS, the same way as `move run` arguments of the parameter's type
+fn parse_view_arg(typ: &MoveType, arg: &str) -> CliTypedResult<Vec<u8>> {
+    let arg_type = view_arg_type(typ)?;
+    if arg_type == FunctionArgType::Hex {
+        arg_type.parse_arg(arg.strip_prefix("0x").unwrap_or(arg))
+    } else {
+        arg_type.parse_arg(arg)
+    }
+}
+
+fn view_arg_type(typ: &MoveType) -> CliTypedResult<FunctionArgType> {
+    Ok(match typ {
+        MoveType::Bool => FunctionArgType::Bool,
+        MoveType::U8 => FunctionArgType::U8,
+        MoveType::U64 => FunctionArgType::U64,
+        MoveType::U128 => FunctionArgType::U128,
+        MoveType::Address => FunctionArgType::Address,
+        MoveType::Vector { items } => match items.as_ref() {
+            MoveType::U8 => FunctionArgType::Hex,
+            MoveType::Vector { items: inner } if **inner == MoveType::U8 => {
+                FunctionArgType::HexArray
+            }
+            MoveType::Bool | MoveType::U64 | MoveType::U128 | MoveType::Address => {
+                FunctionArgType::Vector(Box::new(view_arg_type(items)?))
+            }
+            _ => return Err(unsupported_arg(typ)),
+        },
+        MoveType::Struct(tag) if is_string(tag) => FunctionArgType::String,
+        _ => return Err(unsupported_arg(typ)),
+    })
+}
+
+fn is_string(tag: &MoveStructTag) -> bool {
+    *tag.address.inner() == AccountAddress::ONE
+        && tag.module.as_str() == "string"
+        && tag.name.as_str() == "String"
+}
+
+fn unsupported_arg(typ: &MoveType) -> CliError {
+    CliError::CommandArgumentError(format!(
+        "Arguments of type {} can't be given on the command line",
+        typ
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_parse_view_arg() {
+        let parse = |typ: &str, arg| parse_view_arg(&MoveType::from_str(typ).unwrap(), arg);
+        assert_eq!(
+            bcs::to_bytes(&true).unwrap(),
+            parse("bool", "true").unwrap()
+        );
+        assert_eq!(vec![7], parse("u8", "7").unwrap());
+        assert_eq!(
+            bcs::to_bytes(&u64::MAX).unwrap(),
+            parse("u64", "18446744073709551615").unwrap()
+        );
+        assert_eq!(
+            bcs::to_bytes(&AccountAddress::ONE).unwrap(),
+            parse("address", "0x1").unwrap()
+        );
+        assert_eq!(
+            bcs::to_bytes(&vec![0xcau8, 0xfe]).unwrap(),
+            parse("vector<u8>", "0xcafe").unwrap()
+        );
+        assert_eq!(
+            bcs::to_bytes(&vec![1u128, u128::MAX]).unwrap(),
+            parse("vector<u128>", &format!("1,{}", u128::MAX)).unwrap()
+        );
+        assert_eq!(
+            bcs::to_bytes("hello").unwrap(),
+            parse("0x1::string::String", "hello").unwrap()
+        );
+        assert!(parse("u8", "256").is_err());
+        assert!(parse("0x1::coin::Coin<u8>", "1").is_err());
+        assert!(parse("vector<0x1::string::String>", "a,b").is_err());
+    }
+}
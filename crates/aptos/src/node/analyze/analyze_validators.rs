@@ -544,4 +544,49 @@ impl AnalyzeValidators {
             );
         }
     }
+
+    /// Per epoch and validator stats as CSV, one row per validator in each epoch
+    pub fn epoch_stats_csv(stats: &HashMap<u64, EpochStats>) -> String {
+        let mut csv = String::from(
+            "epoch,validator,state,voting_power,proposal_successes,proposal_failures,\
+             proposal_failure_rate,votes,vote_rate,transactions\n",
+        );
+        for (epoch, epoch_stats) in stats.iter().sorted_by_key(|(epoch, _)| **epoch) {
+            for (validator, validator_stats) in epoch_stats
+                .validator_stats
+                .iter()
+                .sorted_by_key(|(validator, _)| **validator)
+            {
+                let proposals =
+                    validator_stats.proposal_successes + validator_stats.proposal_failures;
+                let failure_rate = if proposals > 0 {
+                    format!("{:.4}", validator_stats.failure_rate())
+                } else {
+                    String::new()
+                };
+                let vote_rate = if epoch_stats.total_rounds > 0 {
+                    format!(
+                        "{:.4}",
+                        validator_stats.votes as f32 / epoch_stats.total_rounds as f32
+                    )
+                } else {
+                    String::new()
+                };
+                csv.push_str(&format!(
+                    "{},{},{:?},{},{},{},{},{},{},{}\n",
+                    epoch,
+                    validator,
+                    epoch_stats.to_state(validator),
+                    validator_stats.voting_power,
+                    validator_stats.proposal_successes,
+                    validator_stats.proposal_failures,
+                    failure_rate,
+                    validator_stats.votes,
+                    vote_rate,
+                    validator_stats.transactions,
+                ));
+            }
+        }
+        csv
+    }
 }
@@ -2,17 +2,31 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    account::multisig::{multisig_address, parse_public_key},
     common::{
+        ledger::DEFAULT_DERIVATION_PATH,
+        mnemonic::{derive_private_key, generate_mnemonic, MNEMONIC_EXTENSION},
         types::{
-            CliError, CliTypedResult, EncodingOptions, EncodingType, KeyType, RngArgs, SaveFile,
+            account_address_from_public_key, CliError, CliTypedResult, EncodingOptions,
+            EncodingType, KeyType, RngArgs, SaveFile,
         },
-        utils::{append_file_extension, check_if_file_exists, write_to_file},
+        utils::{
+            append_file_extension, check_if_file_exists, write_to_file, write_to_user_only_file,
+        },
+    },
+    op::{
+        message::{SignMessage, VerifyMessage},
+        vanity::generate_vanity_key,
     },
     CliCommand, CliResult,
 };
 use aptos_config::config::{Peer, PeerRole};
-use aptos_crypto::{bls12381, ed25519, x25519, PrivateKey, ValidCryptoMaterial};
+use aptos_crypto::{
+    bls12381, ed25519, multi_ed25519::MultiEd25519PublicKey, x25519, PrivateKey,
+    ValidCryptoMaterial,
+};
 use aptos_genesis::config::HostAndPort;
+use aptos_keygen::KeyGen;
 use aptos_types::account_address::{from_identity_public_key, AccountAddress};
 use async_trait::async_trait;
 use clap::{Parser, Subcommand};
@@ -31,6 +45,8 @@ pub const PUBLIC_KEY_EXTENSION: &str = "pub";
 pub enum KeyTool {
     Generate(GenerateKey),
     ExtractPeer(ExtractPeer),
+    Sign(SignMessage),
+    Verify(VerifyMessage),
 }
 
 impl KeyTool {
@@ -38,6 +54,8 @@ impl KeyTool {
         match self {
             KeyTool::Generate(tool) => tool.execute_serialized().await,
             KeyTool::ExtractPeer(tool) => tool.execute_serialized().await,
+            KeyTool::Sign(tool) => tool.execute_serialized().await,
+            KeyTool::Verify(tool) => tool.execute_serialized().await,
         }
     }
 }
@@ -50,7 +68,9 @@ impl KeyTool {
 ///
 /// A `private-network-key` or `public-network-key` can be given encoded on the command line, or
 /// a `private-network-key-file` or a `public-network-key-file` can be given to read from.
-/// The `output-file` will be a YAML serialized peer information for use in network config.
+/// With `--from-ed25519`, these are ed25519 keys, which are converted to the x25519 network key.
+/// The `output-file` will be a YAML serialized peer information for use in network config, e.g.
+/// as an entry of `seeds`.
 #[derive(Debug, Parser)]
 pub struct ExtractPeer {
     /// Host and port of the full node
@@ -59,6 +79,10 @@ pub struct ExtractPeer {
     #[clap(long)]
     pub(crate) host: HostAndPort,
 
+    /// Role of the peer, e.g. `upstream` for a fullnode's seed or `validator` for a validator's
+    #[clap(long, default_value_t = PeerRole::Upstream, parse(try_from_str = parse_peer_role))]
+    pub(crate) role: PeerRole,
+
     #[clap(flatten)]
     pub(crate) network_key_input_options: NetworkKeyInputOptions,
     #[clap(flatten)]
@@ -91,7 +115,7 @@ impl CliCommand<HashMap<AccountAddress, Peer>> for ExtractPeer {
             CliError::UnexpectedError(format!("Failed to build network address: {}", err))
         })?;
 
-        let peer = Peer::new(vec![address], public_keys, PeerRole::Upstream);
+        let peer = Peer::new(vec![address], public_keys, self.role);
 
         let mut map = HashMap::new();
         map.insert(peer_id, peer);
@@ -122,6 +146,13 @@ pub struct NetworkKeyInputOptions {
     /// x25519 Public key encoded in a type as shown in `encoding`
     #[clap(long, group = "network_key_input")]
     public_network_key: Option<String>,
+
+    /// The keys are ed25519 keys, to convert to x25519
+    ///
+    /// This is for a network identity derived from an account key, don't use an
+    /// account key for the network otherwise
+    #[clap(long)]
+    from_ed25519: bool,
 }
 
 impl NetworkKeyInputOptions {
@@ -131,6 +162,7 @@ impl NetworkKeyInputOptions {
             private_network_key: None,
             public_network_key_file: None,
             public_network_key: None,
+            from_ed25519: false,
         }
     }
 
@@ -138,6 +170,10 @@ impl NetworkKeyInputOptions {
         self,
         encoding: EncodingType,
     ) -> CliTypedResult<x25519::PublicKey> {
+        if self.from_ed25519 {
+            return self.extract_ed25519_network_key(encoding);
+        }
+
         // The grouping above prevents there from being more than one, but just in case
         match (self.public_network_key,  self.public_network_key_file, self.private_network_key, self.private_network_key_file){
             (Some(public_network_key), None, None, None) => encoding.decode_key("--public-network-key", public_network_key.as_bytes().to_vec()),
@@ -153,6 +189,60 @@ impl NetworkKeyInputOptions {
             _ => Err(CliError::CommandArgumentError("Must provide exactly one of [--public-network-key, --public-network-key-file, --private-network-key, --private-network-key-file]".to_string()))
         }
     }
+
+    /// Converts the given ed25519 key to the x25519 public network key
+    fn extract_ed25519_network_key(
+        self,
+        encoding: EncodingType,
+    ) -> CliTypedResult<x25519::PublicKey> {
+        let public_key: ed25519::Ed25519PublicKey = match (self.public_network_key, self.public_network_key_file, self.private_network_key, self.private_network_key_file) {
+            (Some(public_key), None, None, None) => encoding.decode_key("--public-network-key", public_key.as_bytes().to_vec())?,
+            (None, Some(public_key_file), None, None) => encoding.load_key("--public-network-key-file", public_key_file.as_path())?,
+            (None, None, Some(private_key), None) => {
+                let private_key: ed25519::Ed25519PrivateKey = encoding.decode_key("--private-network-key", private_key.as_bytes().to_vec())?;
+                // Converting the private key checks the public key matches it
+                return Ok(x25519::PrivateKey::from_ed25519_private_bytes(&private_key.to_bytes())?.public_key());
+            },
+            (None, None, None, Some(private_key_file)) => {
+                let private_key: ed25519::Ed25519PrivateKey = encoding.load_key("--private-network-key-file", private_key_file.as_path())?;
+                return Ok(x25519::PrivateKey::from_ed25519_private_bytes(&private_key.to_bytes())?.public_key());
+            },
+            _ => return Err(CliError::CommandArgumentError("Must provide exactly one of [--public-network-key, --public-network-key-file, --private-network-key, --private-network-key-file]".to_string()))
+        };
+        Ok(x25519::PublicKey::from_ed25519_public_bytes(
+            &public_key.to_bytes(),
+        )?)
+    }
+}
+
+/// Parses a peer role by its name in network configs, e.g. `Upstream` or `upstream_peer`
+fn parse_peer_role(str: &str) -> CliTypedResult<PeerRole> {
+    let roles = [
+        PeerRole::Validator,
+        PeerRole::PreferredUpstream,
+        PeerRole::Upstream,
+        PeerRole::ValidatorFullNode,
+        PeerRole::Downstream,
+        PeerRole::Known,
+        PeerRole::Unknown,
+    ];
+    let normalized = str.to_lowercase().replace('_', "");
+    roles
+        .into_iter()
+        .find(|role| {
+            let name = role.as_str().replace('_', "");
+            normalized == name || normalized == name.trim_end_matches("peer")
+        })
+        .ok_or_else(|| {
+            CliError::UnableToParse(
+                "role",
+                format!(
+                    "{} is not one of [{}]",
+                    str,
+                    roles.map(|role| role.as_str()).join(", ")
+                ),
+            )
+        })
 }
 
 /// Generates a `x25519` or `ed25519` key.
@@ -163,10 +253,54 @@ impl NetworkKeyInputOptions {
 /// key encoded with the `encoding`.
 #[derive(Debug, Parser)]
 pub struct GenerateKey {
-    /// Key type to generate. Must be one of [x25519, ed25519, bls12381]
+    /// Key type to generate. Must be one of [x25519, ed25519, bls12381, secp256k1]
+    ///
+    /// bls12381 keys also get a proof of possession, as needed to register a validator
     #[clap(long, default_value_t = KeyType::Ed25519)]
     pub(crate) key_type: KeyType,
 
+    /// Generate an ed25519 key whose account address starts with this hex prefix
+    ///
+    /// Keys are generated on every core until one matches, and each extra character takes 16
+    /// times as long on average
+    #[clap(long)]
+    pub(crate) vanity_prefix: Option<String>,
+
+    /// Match the prefix against the address of a multisig account owned by the generated key
+    /// and `--multisig-public-keys`, instead of the key's own account
+    #[clap(long, requires_all = &["vanity_prefix", "multisig_threshold"])]
+    pub(crate) vanity_multisig: bool,
+
+    /// Public keys of the other owners of the multisig account, separated by spaces
+    ///
+    /// The generated key is the last owner
+    #[clap(long, multiple_values = true, parse(try_from_str = parse_public_key))]
+    pub(crate) multisig_public_keys: Vec<ed25519::Ed25519PublicKey>,
+
+    /// Number of owners that have to approve each transaction of the multisig account
+    #[clap(long)]
+    pub(crate) multisig_threshold: Option<u8>,
+
+    /// Generate an ed25519 key from a new BIP-39 mnemonic phrase, saved to
+    /// `<output-file>.mnemonic`
+    ///
+    /// The phrase can be imported into wallets such as Petra, or recovered with
+    /// `aptos init --from-mnemonic`
+    #[clap(long, conflicts_with = "vanity_prefix")]
+    pub(crate) mnemonic: bool,
+
+    /// Number of words of the mnemonic phrase, 12 or 24
+    #[clap(long, default_value_t = 12)]
+    pub(crate) mnemonic_words: usize,
+
+    /// Derivation path of the key from the mnemonic phrase
+    #[clap(long, default_value = DEFAULT_DERIVATION_PATH)]
+    pub(crate) derivation_path: String,
+
+    /// Maximum number of keys to try for `--vanity-prefix` before giving up
+    #[clap(long, requires = "vanity_prefix")]
+    pub(crate) vanity_max_attempts: Option<u64>,
+
     #[clap(flatten)]
     pub rng_args: RngArgs,
     #[clap(flatten)]
@@ -181,6 +315,20 @@ impl CliCommand<HashMap<&'static str, PathBuf>> for GenerateKey {
 
     async fn execute(self) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
         self.save_params.check_key_file()?;
+        if (self.vanity_prefix.is_some() || self.mnemonic)
+            && !matches!(self.key_type, KeyType::Ed25519)
+        {
+            return Err(CliError::CommandArgumentError(
+                "--vanity-prefix and --mnemonic can only be used with ed25519 keys".to_string(),
+            ));
+        }
+        let mnemonic_file = append_file_extension(
+            self.save_params.file_options.output_file.as_path(),
+            MNEMONIC_EXTENSION,
+        )?;
+        if self.mnemonic {
+            check_if_file_exists(&mnemonic_file, self.save_params.file_options.prompt_options)?;
+        }
         let mut keygen = self.rng_args.key_generator()?;
 
         match self.key_type {
@@ -194,13 +342,68 @@ impl CliCommand<HashMap<&'static str, PathBuf>> for GenerateKey {
                 self.save_params.save_key(&private_key, "x25519")
             }
             KeyType::Ed25519 => {
-                let private_key = keygen.generate_ed25519_private_key();
-                self.save_params.save_key(&private_key, "ed25519")
+                let mut mnemonic = None;
+                let private_key = match self.vanity_prefix {
+                    Some(ref prefix) if self.vanity_multisig => {
+                        let public_keys = self.multisig_public_keys.clone();
+                        let threshold = self.multisig_threshold.unwrap_or_default();
+                        // Check the multisig is valid before grinding
+                        MultiEd25519PublicKey::new(public_keys.clone(), threshold).map_err(
+                            |err| {
+                                CliError::CommandArgumentError(format!("Invalid multisig: {}", err))
+                            },
+                        )?;
+                        generate_vanity_key(
+                            &mut keygen,
+                            prefix,
+                            |public_key| {
+                                let mut public_keys = public_keys.clone();
+                                public_keys.push(public_key.clone());
+                                multisig_address(
+                                    &MultiEd25519PublicKey::new(public_keys, threshold).unwrap(),
+                                )
+                            },
+                            self.vanity_max_attempts,
+                        )?
+                        .0
+                    }
+                    Some(ref prefix) => {
+                        generate_vanity_key(
+                            &mut keygen,
+                            prefix,
+                            account_address_from_public_key,
+                            self.vanity_max_attempts,
+                        )?
+                        .0
+                    }
+                    None if self.mnemonic => {
+                        let phrase = generate_mnemonic(&mut keygen, self.mnemonic_words)?;
+                        let private_key = derive_private_key(&phrase, &self.derivation_path)?;
+                        mnemonic = Some(phrase);
+                        private_key
+                    }
+                    None => keygen.generate_ed25519_private_key(),
+                };
+                let mut files = self.save_params.save_key(&private_key, "ed25519")?;
+                if let Some(mnemonic) = mnemonic {
+                    write_to_user_only_file(
+                        &mnemonic_file,
+                        "mnemonic",
+                        mnemonic.phrase().as_bytes(),
+                    )?;
+                    files.insert("Mnemonic Path", mnemonic_file);
+                }
+                Ok(files)
             }
             KeyType::Bls12381 => {
                 let private_key = keygen.generate_bls12381_private_key();
                 self.save_params.save_bls_key(&private_key, "bls12381")
             }
+            KeyType::Secp256k1 => {
+                let private_key = generate_secp256k1_private_key(&mut keygen);
+                self.save_params
+                    .save_secp256k1_key(&private_key, "secp256k1")
+            }
         }
     }
 }
@@ -291,6 +494,8 @@ impl SaveKey {
             .encoding_options
             .encoding
             .encode_key(key_name, &key.public_key())?;
+        self.check_encoded_key(key_name, key, &encoded_private_key)?;
+        self.check_encoded_key(key_name, &key.public_key(), &encoded_public_key)?;
 
         // Write private and public keys to files
         let public_key_file = self.public_key_file()?;
@@ -319,6 +524,17 @@ impl SaveKey {
             .encoding_options
             .encoding
             .encode_key(key_name, &bls12381::ProofOfPossession::create(key))?;
+        self.check_encoded_key(key_name, key, &encoded_private_key)?;
+        self.check_encoded_key(key_name, &key.public_key(), &encoded_public_key)?;
+        let proof_of_possession: bls12381::ProofOfPossession = self
+            .encoding_options
+            .encoding
+            .decode_key(key_name, encoded_proof_of_posession.clone())?;
+        proof_of_possession
+            .verify(&key.public_key())
+            .map_err(|err| {
+                CliError::UnexpectedError(format!("Invalid proof of possession: {}", err))
+            })?;
 
         // Write private and public keys to files
         let public_key_file = self.public_key_file()?;
@@ -338,4 +554,67 @@ impl SaveKey {
         map.insert("Proof of possession Path", proof_of_possession_file);
         Ok(map)
     }
+
+    /// Saves a secp256k1 key, with the public key in its 65 byte uncompressed form
+    pub fn save_secp256k1_key(
+        self,
+        key: &libsecp256k1::SecretKey,
+        key_name: &'static str,
+    ) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
+        let encoding = self.encoding_options.encoding;
+        let private_key = key.serialize();
+        let public_key = libsecp256k1::PublicKey::from_secret_key(key).serialize();
+        let encoded_private_key = encoding.encode_bytes(key_name, &private_key)?;
+        let encoded_public_key = encoding.encode_bytes(key_name, &public_key)?;
+        for (bytes, encoded) in [
+            (&private_key[..], &encoded_private_key),
+            (&public_key[..], &encoded_public_key),
+        ] {
+            if encoding.decode_bytes(key_name, encoded.clone())? != bytes {
+                return Err(encoding_mismatch(key_name, encoding));
+            }
+        }
+
+        // Write private and public keys to files
+        let public_key_file = self.public_key_file()?;
+        self.file_options
+            .save_to_file_confidential(key_name, &encoded_private_key)?;
+        write_to_file(&public_key_file, key_name, &encoded_public_key)?;
+
+        let mut map = HashMap::new();
+        map.insert("PrivateKey Path", self.file_options.output_file);
+        map.insert("PublicKey Path", public_key_file);
+        Ok(map)
+    }
+
+    /// Checks an encoded key decodes back to the key, before it's saved
+    fn check_encoded_key<Key: ValidCryptoMaterial>(
+        &self,
+        key_name: &'static str,
+        key: &Key,
+        encoded: &[u8],
+    ) -> CliTypedResult<()> {
+        let encoding = self.encoding_options.encoding;
+        let decoded: Key = encoding.decode_key(key_name, encoded.to_vec())?;
+        if decoded.to_bytes() != key.to_bytes() {
+            return Err(encoding_mismatch(key_name, encoding));
+        }
+        Ok(())
+    }
+}
+
+fn encoding_mismatch(key_name: &'static str, encoding: EncodingType) -> CliError {
+    CliError::UnexpectedError(format!(
+        "Encoded {} key doesn't decode back to the key with {} encoding",
+        key_name, encoding
+    ))
+}
+
+/// Generates a secp256k1 private key, skipping the rare random bytes that aren't a valid key
+fn generate_secp256k1_private_key(keygen: &mut KeyGen) -> libsecp256k1::SecretKey {
+    loop {
+        if let Ok(key) = libsecp256k1::SecretKey::parse_slice(&keygen.generate_bytes(32)) {
+            return key;
+        }
+    }
 }
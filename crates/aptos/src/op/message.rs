@@ -0,0 +1,238 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing of arbitrary messages off-chain, to prove control of an account's key
+
+use crate::common::{
+    types::{
+        account_address_from_public_key, CliCommand, CliError, CliTypedResult, EncodingOptions,
+        PrivateKeyInputOptions, ProfileOptions, RestOptions, SaveFile,
+    },
+    utils::{get_auth_key, read_from_file},
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    HashValue, PrivateKey, Signature, SigningKey,
+};
+use aptos_rest_client::aptos_api_types::HexEncodedBytes;
+use aptos_types::{account_address::AccountAddress, transaction::authenticator::AuthenticationKey};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Domain separator of signed messages
+///
+/// The signed bytes start with its hash, so a signed message can never be a valid transaction,
+/// or a message of another application.
+pub const SIGNED_MESSAGE_DOMAIN: &str = "APTOS::SignedMessage";
+
+/// A message signed by an account's key, as written by `aptos key sign`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignedMessage {
+    /// Always [`SIGNED_MESSAGE_DOMAIN`]
+    pub domain: String,
+    /// Account the signer claims to control
+    pub address: AccountAddress,
+    pub public_key: Ed25519PublicKey,
+    pub message: HexEncodedBytes,
+    pub signature: Ed25519Signature,
+}
+
+impl SignedMessage {
+    pub fn sign(
+        private_key: &Ed25519PrivateKey,
+        address: AccountAddress,
+        message: Vec<u8>,
+    ) -> CliTypedResult<SignedMessage> {
+        let signature = private_key.sign_arbitrary_message(&signing_message(address, &message)?);
+        Ok(SignedMessage {
+            domain: SIGNED_MESSAGE_DOMAIN.to_string(),
+            address,
+            public_key: private_key.public_key(),
+            message: HexEncodedBytes(message),
+            signature,
+        })
+    }
+
+    /// Verifies the signature, but not that the key still controls the account
+    pub fn verify(&self) -> CliTypedResult<()> {
+        if self.domain != SIGNED_MESSAGE_DOMAIN {
+            return Err(CliError::UnableToParse(
+                "signed message",
+                format!("Domain {} is not {}", self.domain, SIGNED_MESSAGE_DOMAIN),
+            ));
+        }
+        self.signature
+            .verify_arbitrary_msg(
+                &signing_message(self.address, &self.message.0)?,
+                &self.public_key,
+            )
+            .map_err(|err| CliError::UnexpectedError(format!("Invalid signature: {}", err)))
+    }
+}
+
+/// The signed bytes, the hash of the domain followed by the BCS of the address and message
+fn signing_message(address: AccountAddress, message: &[u8]) -> CliTypedResult<Vec<u8>> {
+    let mut signing_message = HashValue::sha3_256_of(SIGNED_MESSAGE_DOMAIN.as_bytes()).to_vec();
+    signing_message.extend(bcs::to_bytes(&(address, message))?);
+    Ok(signing_message)
+}
+
+/// Sign an arbitrary message with an account's key
+///
+/// The signed message is saved to `--output-file` with the account, public key and signature,
+/// for anyone to check with `aptos key verify`.  It is domain separated, so it can't be
+/// submitted as a transaction.
+#[derive(Debug, Parser)]
+pub struct SignMessage {
+    /// File of the message to sign
+    #[clap(long, group = "message_input", parse(from_os_str))]
+    pub(crate) message_file: Option<PathBuf>,
+
+    /// Message to sign
+    #[clap(long, group = "message_input")]
+    pub(crate) message: Option<String>,
+
+    /// Account the key controls
+    ///
+    /// Defaults to the profile's account, or the address derived from the key
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) account: Option<AccountAddress>,
+
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<SignedMessage> for SignMessage {
+    fn command_name(&self) -> &'static str {
+        "SignMessage"
+    }
+
+    async fn execute(self) -> CliTypedResult<SignedMessage> {
+        let message = match (self.message_file, self.message) {
+            (Some(message_file), None) => read_from_file(&message_file)?,
+            (None, Some(message)) => message.into_bytes(),
+            _ => {
+                return Err(CliError::CommandArgumentError(
+                    "Must provide exactly one of [--message-file, --message]".to_string(),
+                ))
+            }
+        };
+        self.save_file.check_file()?;
+        let (private_key, address) = self.private_key_options.extract_private_key_and_address(
+            self.encoding_options.encoding,
+            &self.profile_options,
+            self.account,
+        )?;
+
+        let signed_message = SignedMessage::sign(&private_key, address, message)?;
+        let json = serde_json::to_string_pretty(&signed_message)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        self.save_file
+            .save_to_file("Signed message", json.as_bytes())?;
+        Ok(signed_message)
+    }
+}
+
+/// Verify a message signed with `aptos key sign`
+///
+/// Without `--check-account`, this only checks the signature, and that the key's derived address
+/// is the account.  With it, the account's current authentication key is checked on-chain instead,
+/// which also works for accounts whose key was rotated.
+#[derive(Debug, Parser)]
+pub struct VerifyMessage {
+    /// File of the signed message
+    #[clap(long, parse(from_os_str))]
+    pub(crate) signed_message_file: PathBuf,
+
+    /// Check the key is the current key of the account on-chain
+    #[clap(long)]
+    pub(crate) check_account: bool,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+/// A verified message
+#[derive(Debug, Serialize)]
+pub struct VerifiedMessage {
+    pub address: AccountAddress,
+    pub public_key: Ed25519PublicKey,
+    /// The message as text, or hex if it isn't UTF-8
+    pub message: String,
+}
+
+#[async_trait]
+impl CliCommand<VerifiedMessage> for VerifyMessage {
+    fn command_name(&self) -> &'static str {
+        "VerifyMessage"
+    }
+
+    async fn execute(self) -> CliTypedResult<VerifiedMessage> {
+        let signed_message: SignedMessage =
+            serde_json::from_slice(&read_from_file(&self.signed_message_file)?)
+                .map_err(|err| CliError::UnableToParse("signed message", err.to_string()))?;
+        signed_message.verify()?;
+
+        let auth_key = AuthenticationKey::ed25519(&signed_message.public_key);
+        if self.check_account {
+            let client = self.rest_options.client(&self.profile_options)?;
+            let current_auth_key = get_auth_key(&client, signed_message.address).await?;
+            if current_auth_key != auth_key {
+                return Err(CliError::UnexpectedError(format!(
+                    "The key signing the message is not the current key of account {}",
+                    signed_message.address
+                )));
+            }
+        } else if account_address_from_public_key(&signed_message.public_key)
+            != signed_message.address
+        {
+            return Err(CliError::UnexpectedError(format!(
+                "The key signing the message doesn't derive account {}, use --check-account \
+                if its key was rotated",
+                signed_message.address
+            )));
+        }
+
+        let message = match String::from_utf8(signed_message.message.0.clone()) {
+            Ok(message) => message,
+            Err(_) => signed_message.message.to_string(),
+        };
+        Ok(VerifiedMessage {
+            address: signed_message.address,
+            public_key: signed_message.public_key,
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_keygen::KeyGen;
+
+    #[test]
+    fn test_sign_and_verify_message() {
+        let private_key = KeyGen::from_seed([0; 32]).generate_ed25519_private_key();
+        let address = account_address_from_public_key(&private_key.public_key());
+        let mut signed_message =
+            SignedMessage::sign(&private_key, address, b"I control this account".to_vec()).unwrap();
+        signed_message.verify().unwrap();
+
+        signed_message.address = AccountAddress::ONE;
+        assert!(signed_message.verify().is_err());
+        signed_message.address = address;
+        signed_message.message = HexEncodedBytes(b"I control another account".to_vec());
+        assert!(signed_message.verify().is_err());
+    }
+}
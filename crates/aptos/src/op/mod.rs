@@ -2,3 +2,5 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod key;
+pub mod message;
+pub mod vanity;
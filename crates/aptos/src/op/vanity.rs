@@ -0,0 +1,141 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Grinding for keys whose account address starts with a chosen prefix
+
+use crate::common::types::{CliError, CliTypedResult};
+use aptos_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use aptos_keygen::KeyGen;
+use aptos_types::account_address::AccountAddress;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// How often progress is reported while grinding
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Validates a vanity prefix, returning it as lowercase hex without `0x`
+pub fn parse_vanity_prefix(prefix: &str) -> CliTypedResult<String> {
+    let prefix = prefix.strip_prefix("0x").unwrap_or(prefix).to_lowercase();
+    if prefix.is_empty()
+        || prefix.len() > AccountAddress::LENGTH * 2
+        || !prefix.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return Err(CliError::UnableToParse(
+            "--vanity-prefix",
+            format!(
+                "{} must be 1 to {} hex characters",
+                prefix,
+                AccountAddress::LENGTH * 2
+            ),
+        ));
+    }
+    Ok(prefix)
+}
+
+/// Generates keys on every core until the address `address_of` derives from a public key
+/// starts with the prefix, or `max_attempts` keys have been tried
+///
+/// Each extra character of prefix takes 16 times as many attempts on average.
+pub fn generate_vanity_key<F>(
+    keygen: &mut KeyGen,
+    prefix: &str,
+    address_of: F,
+    max_attempts: Option<u64>,
+) -> CliTypedResult<(Ed25519PrivateKey, AccountAddress)>
+where
+    F: Fn(&Ed25519PublicKey) -> AccountAddress + Sync,
+{
+    let prefix = parse_vanity_prefix(prefix)?;
+    let threads = std::thread::available_parallelism()
+        .map(|threads| threads.get())
+        .unwrap_or(1);
+    let attempts = AtomicU64::new(0);
+    let done = AtomicBool::new(false);
+    let found: Mutex<Option<(Ed25519PrivateKey, AccountAddress)>> = Mutex::new(None);
+    let start = Instant::now();
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            let mut keygen = keygen.fork();
+            let (prefix, address_of) = (&prefix, &address_of);
+            let (attempts, done, found) = (&attempts, &done, &found);
+            scope.spawn(move || {
+                while !done.load(Ordering::Relaxed) {
+                    let attempt = attempts.fetch_add(1, Ordering::Relaxed) + 1;
+                    if max_attempts.map_or(false, |max_attempts| attempt > max_attempts) {
+                        done.store(true, Ordering::Relaxed);
+                        break;
+                    }
+                    let (private_key, public_key) = keygen.generate_ed25519_keypair();
+                    let address = address_of(&public_key);
+                    if address.to_hex().starts_with(prefix.as_str()) {
+                        found.lock().unwrap().get_or_insert((private_key, address));
+                        done.store(true, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+
+        let mut last_report = Instant::now();
+        while !done.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(100));
+            if last_report.elapsed() >= PROGRESS_INTERVAL {
+                let tried = attempts.load(Ordering::Relaxed);
+                eprintln!(
+                    "Tried {} keys on {} threads ({:.0} keys/s)",
+                    tried,
+                    threads,
+                    tried as f64 / start.elapsed().as_secs_f64()
+                );
+                last_report = Instant::now();
+            }
+        }
+    });
+
+    let tried = attempts.load(Ordering::Relaxed);
+    match found.into_inner().unwrap() {
+        Some((private_key, address)) => {
+            eprintln!(
+                "Found address {} after {} keys in {:.1}s",
+                address.to_hex_literal(),
+                tried,
+                start.elapsed().as_secs_f64()
+            );
+            Ok((private_key, address))
+        }
+        None => Err(CliError::UnexpectedError(format!(
+            "No address with prefix 0x{} found in {} attempts",
+            prefix,
+            max_attempts.unwrap_or(tried)
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::account_address_from_public_key;
+
+    #[test]
+    fn test_generate_vanity_key() {
+        let mut keygen = KeyGen::from_seed([0; 32]);
+        let (private_key, address) =
+            generate_vanity_key(&mut keygen, "0xA", account_address_from_public_key, None).unwrap();
+        assert!(address.to_hex().starts_with('a'));
+        assert_eq!(
+            address,
+            account_address_from_public_key(&aptos_crypto::PrivateKey::public_key(&private_key))
+        );
+
+        assert!(
+            generate_vanity_key(&mut keygen, "ff", account_address_from_public_key, Some(0))
+                .is_err()
+        );
+        assert!(parse_vanity_prefix("0xg").is_err());
+    }
+}
@@ -2,17 +2,25 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::common::types::{
-    CliCommand, CliError, CliResult, CliTypedResult, TransactionOptions, TransactionSummary,
+    CliCommand, CliError, CliResult, CliTypedResult, ProfileOptions, RestOptions, Submission,
+    TransactionOptions, TransactionSummary,
 };
-use crate::common::utils::prompt_yes_with_override;
-use crate::node::{get_stake_pools, StakePoolType};
+use crate::common::utils::{parse_octas, prompt_yes_with_override, write_to_file};
+use crate::governance::compile_in_temp_dir;
+use crate::move_tool::FrameworkPackageArgs;
+use crate::node::{get_stake_pools, StakePoolType, Time};
 use aptos_types::account_address::{
     create_vesting_contract_address, default_stake_pool_address, AccountAddress,
 };
+use aptos_types::transaction::{Script, TransactionPayload};
 use aptos_types::vesting::VestingAdminStore;
 use async_trait::async_trait;
 use cached_packages::aptos_stdlib;
+use chrono::DateTime;
 use clap::Parser;
+use serde::Serialize;
+use std::convert::TryFrom;
+use tempfile::TempDir;
 
 /// Tool for manipulating stake and stake pools
 ///
@@ -29,6 +37,9 @@ pub enum StakeTool {
     SetDelegatedVoter(SetDelegatedVoter),
     UnlockVestedCoins(UnlockVestedCoins),
     DistributeVestedCoins(DistributeVestedCoins),
+    CreateVestingContract(CreateVestingContract),
+    ShowVestingContract(ShowVestingContract),
+    TerminateVestingContract(TerminateVestingContract),
 }
 
 impl StakeTool {
@@ -46,6 +57,9 @@ impl StakeTool {
             SetDelegatedVoter(tool) => tool.execute_serialized().await,
             UnlockVestedCoins(tool) => tool.execute_serialized().await,
             DistributeVestedCoins(tool) => tool.execute_serialized().await,
+            CreateVestingContract(tool) => tool.execute_serialized().await,
+            ShowVestingContract(tool) => tool.execute_serialized().await,
+            TerminateVestingContract(tool) => tool.execute_serialized().await,
         }
     }
 }
@@ -55,8 +69,8 @@ impl StakeTool {
 /// This command allows stake pool owners to add APT to their stake.
 #[derive(Parser)]
 pub struct AddStake {
-    /// Amount of Octas (10^-8 APT) to add to stake
-    #[clap(long)]
+    /// Amount of Octas (10^-8 APT) to add to stake, or of APT with an `APT` suffix e.g. `1.5APT`
+    #[clap(long, parse(try_from_str = parse_octas))]
     pub amount: u64,
 
     #[clap(flatten)]
@@ -64,12 +78,12 @@ pub struct AddStake {
 }
 
 #[async_trait]
-impl CliCommand<Vec<TransactionSummary>> for AddStake {
+impl CliCommand<Submission<Vec<TransactionSummary>>> for AddStake {
     fn command_name(&self) -> &'static str {
         "AddStake"
     }
 
-    async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
+    async fn execute(mut self) -> CliTypedResult<Submission<Vec<TransactionSummary>>> {
         let client = self
             .txn_options
             .rest_options
@@ -80,34 +94,36 @@ impl CliCommand<Vec<TransactionSummary>> for AddStake {
 
         let stake_pool_results = get_stake_pools(&client, owner_address).await?;
         for stake_pool in stake_pool_results {
-            match stake_pool.pool_type {
+            let submission = match stake_pool.pool_type {
                 StakePoolType::Direct => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_add_stake(amount))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(aptos_stdlib::stake_add_stake(amount))
+                        .await?
                 }
                 StakePoolType::StakingContract => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::staking_contract_add_stake(
-                                stake_pool.operator_address,
-                                amount,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(aptos_stdlib::staking_contract_add_stake(
+                            stake_pool.operator_address,
+                            amount,
+                        ))
+                        .await?
                 }
                 StakePoolType::Vesting => {
                     return Err(CliError::UnexpectedError(
                         "Adding stake is not supported for vesting contracts".into(),
                     ))
                 }
+            };
+            match submission {
+                Submission::Submitted(transaction) => {
+                    transaction_summaries.push(transaction.into())
+                }
+                Submission::NotSubmitted(not_submitted) => {
+                    return Ok(Submission::NotSubmitted(not_submitted))
+                }
             }
         }
-        Ok(transaction_summaries)
+        Ok(Submission::Submitted(transaction_summaries))
     }
 }
 
@@ -116,8 +132,8 @@ impl CliCommand<Vec<TransactionSummary>> for AddStake {
 /// APT coins can only be unlocked if they no longer have an applied lockup period
 #[derive(Parser)]
 pub struct UnlockStake {
-    /// Amount of Octas (10^-8 APT) to unlock
-    #[clap(long)]
+    /// Amount of Octas (10^-8 APT) to unlock, or of APT with an `APT` suffix e.g. `1.5APT`
+    #[clap(long, parse(try_from_str = parse_octas))]
     pub amount: u64,
 
     #[clap(flatten)]
@@ -125,12 +141,12 @@ pub struct UnlockStake {
 }
 
 #[async_trait]
-impl CliCommand<Vec<TransactionSummary>> for UnlockStake {
+impl CliCommand<Submission<Vec<TransactionSummary>>> for UnlockStake {
     fn command_name(&self) -> &'static str {
         "UnlockStake"
     }
 
-    async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
+    async fn execute(mut self) -> CliTypedResult<Submission<Vec<TransactionSummary>>> {
         let client = self
             .txn_options
             .rest_options
@@ -141,34 +157,36 @@ impl CliCommand<Vec<TransactionSummary>> for UnlockStake {
 
         let stake_pool_results = get_stake_pools(&client, owner_address).await?;
         for stake_pool in stake_pool_results {
-            match stake_pool.pool_type {
+            let submission = match stake_pool.pool_type {
                 StakePoolType::Direct => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_unlock(amount))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(aptos_stdlib::stake_unlock(amount))
+                        .await?
                 }
                 StakePoolType::StakingContract => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::staking_contract_unlock_stake(
-                                stake_pool.operator_address,
-                                amount,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(aptos_stdlib::staking_contract_unlock_stake(
+                            stake_pool.operator_address,
+                            amount,
+                        ))
+                        .await?
                 }
                 StakePoolType::Vesting => {
                     return Err(CliError::UnexpectedError(
                         "Unlocking stake is not supported for vesting contracts".into(),
                     ))
                 }
+            };
+            match submission {
+                Submission::Submitted(transaction) => {
+                    transaction_summaries.push(transaction.into())
+                }
+                Submission::NotSubmitted(not_submitted) => {
+                    return Ok(Submission::NotSubmitted(not_submitted))
+                }
             }
         }
-        Ok(transaction_summaries)
+        Ok(Submission::Submitted(transaction_summaries))
     }
 }
 
@@ -178,8 +196,8 @@ impl CliCommand<Vec<TransactionSummary>> for UnlockStake {
 /// Before calling `WithdrawStake`, `UnlockStake` must be called first.
 #[derive(Parser)]
 pub struct WithdrawStake {
-    /// Amount of Octas (10^-8 APT) to withdraw
-    #[clap(long)]
+    /// Amount of Octas (10^-8 APT) to withdraw, or of APT with an `APT` suffix e.g. `1.5APT`
+    #[clap(long, parse(try_from_str = parse_octas))]
     pub amount: u64,
 
     #[clap(flatten)]
@@ -187,16 +205,16 @@ pub struct WithdrawStake {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for WithdrawStake {
+impl CliCommand<Submission<TransactionSummary>> for WithdrawStake {
     fn command_name(&self) -> &'static str {
         "WithdrawStake"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<Submission<TransactionSummary>> {
         self.node_op_options
             .submit_transaction(aptos_stdlib::stake_withdraw(self.amount))
             .await
-            .map(|inner| inner.into())
+            .map(|submission| submission.map(|inner| inner.into()))
     }
 }
 
@@ -210,12 +228,12 @@ pub struct IncreaseLockup {
 }
 
 #[async_trait]
-impl CliCommand<Vec<TransactionSummary>> for IncreaseLockup {
+impl CliCommand<Submission<Vec<TransactionSummary>>> for IncreaseLockup {
     fn command_name(&self) -> &'static str {
         "IncreaseLockup"
     }
 
-    async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
+    async fn execute(mut self) -> CliTypedResult<Submission<Vec<TransactionSummary>>> {
         let client = self
             .txn_options
             .rest_options
@@ -225,38 +243,37 @@ impl CliCommand<Vec<TransactionSummary>> for IncreaseLockup {
 
         let stake_pool_results = get_stake_pools(&client, owner_address).await?;
         for stake_pool in stake_pool_results {
-            match stake_pool.pool_type {
+            let submission = match stake_pool.pool_type {
                 StakePoolType::Direct => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_increase_lockup())
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(aptos_stdlib::stake_increase_lockup())
+                        .await?
                 }
                 StakePoolType::StakingContract => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::staking_contract_reset_lockup(
-                                stake_pool.operator_address,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(aptos_stdlib::staking_contract_reset_lockup(
+                            stake_pool.operator_address,
+                        ))
+                        .await?
                 }
                 StakePoolType::Vesting => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::vesting_reset_lockup(
-                                stake_pool.vesting_contract.unwrap(),
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(aptos_stdlib::vesting_reset_lockup(
+                            stake_pool.vesting_contract.unwrap(),
+                        ))
+                        .await?
+                }
+            };
+            match submission {
+                Submission::Submitted(transaction) => {
+                    transaction_summaries.push(transaction.into())
+                }
+                Submission::NotSubmitted(not_submitted) => {
+                    return Ok(Submission::NotSubmitted(not_submitted))
                 }
             }
         }
-        Ok(transaction_summaries)
+        Ok(Submission::Submitted(transaction_summaries))
     }
 }
 
@@ -266,8 +283,8 @@ impl CliCommand<Vec<TransactionSummary>> for IncreaseLockup {
 /// stake pool to an operator, or delegate voting to a different account.
 #[derive(Parser)]
 pub struct InitializeStakeOwner {
-    /// Initial amount of Octas (10^-8 APT) to be staked
-    #[clap(long)]
+    /// Initial amount of Octas (10^-8 APT) to be staked, or of APT with an `APT` suffix
+    #[clap(long, parse(try_from_str = parse_octas))]
     pub initial_stake_amount: u64,
 
     /// Account Address of delegated operator
@@ -283,12 +300,12 @@ pub struct InitializeStakeOwner {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for InitializeStakeOwner {
+impl CliCommand<Submission<TransactionSummary>> for InitializeStakeOwner {
     fn command_name(&self) -> &'static str {
         "InitializeStakeOwner"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<Submission<TransactionSummary>> {
         let owner_address = self.txn_options.sender_address()?;
         self.txn_options
             .submit_transaction(aptos_stdlib::stake_initialize_stake_owner(
@@ -297,7 +314,7 @@ impl CliCommand<TransactionSummary> for InitializeStakeOwner {
                 self.voter_address.unwrap_or(owner_address),
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|submission| submission.map(|inner| inner.into()))
     }
 }
 
@@ -317,12 +334,12 @@ pub struct SetOperator {
 }
 
 #[async_trait]
-impl CliCommand<Vec<TransactionSummary>> for SetOperator {
+impl CliCommand<Submission<Vec<TransactionSummary>>> for SetOperator {
     fn command_name(&self) -> &'static str {
         "SetOperator"
     }
 
-    async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
+    async fn execute(mut self) -> CliTypedResult<Submission<Vec<TransactionSummary>>> {
         let client = self
             .txn_options
             .rest_options
@@ -333,46 +350,43 @@ impl CliCommand<Vec<TransactionSummary>> for SetOperator {
 
         let stake_pool_results = get_stake_pools(&client, owner_address).await?;
         for stake_pool in stake_pool_results {
-            match stake_pool.pool_type {
+            let submission = match stake_pool.pool_type {
                 StakePoolType::Direct => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_set_operator(
-                                new_operator_address,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(aptos_stdlib::stake_set_operator(new_operator_address))
+                        .await?
                 }
                 StakePoolType::StakingContract => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(
-                                aptos_stdlib::staking_contract_switch_operator_with_same_commission(
-                                    stake_pool.operator_address,
-                                    new_operator_address,
-                                ),
-                            )
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(
+                            aptos_stdlib::staking_contract_switch_operator_with_same_commission(
+                                stake_pool.operator_address,
+                                new_operator_address,
+                            ),
+                        )
+                        .await?
                 }
                 StakePoolType::Vesting => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(
-                                aptos_stdlib::vesting_update_operator_with_same_commission(
-                                    stake_pool.vesting_contract.unwrap(),
-                                    new_operator_address,
-                                ),
-                            )
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(
+                            aptos_stdlib::vesting_update_operator_with_same_commission(
+                                stake_pool.vesting_contract.unwrap(),
+                                new_operator_address,
+                            ),
+                        )
+                        .await?
+                }
+            };
+            match submission {
+                Submission::Submitted(transaction) => {
+                    transaction_summaries.push(transaction.into())
+                }
+                Submission::NotSubmitted(not_submitted) => {
+                    return Ok(Submission::NotSubmitted(not_submitted))
                 }
             }
         }
-        Ok(transaction_summaries)
+        Ok(Submission::Submitted(transaction_summaries))
     }
 }
 
@@ -392,12 +406,12 @@ pub struct SetDelegatedVoter {
 }
 
 #[async_trait]
-impl CliCommand<Vec<TransactionSummary>> for SetDelegatedVoter {
+impl CliCommand<Submission<Vec<TransactionSummary>>> for SetDelegatedVoter {
     fn command_name(&self) -> &'static str {
         "SetDelegatedVoter"
     }
 
-    async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
+    async fn execute(mut self) -> CliTypedResult<Submission<Vec<TransactionSummary>>> {
         let client = self
             .txn_options
             .rest_options
@@ -408,42 +422,41 @@ impl CliCommand<Vec<TransactionSummary>> for SetDelegatedVoter {
 
         let stake_pool_results = get_stake_pools(&client, owner_address).await?;
         for stake_pool in stake_pool_results {
-            match stake_pool.pool_type {
+            let submission = match stake_pool.pool_type {
                 StakePoolType::Direct => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_set_delegated_voter(
-                                new_voter_address,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(aptos_stdlib::stake_set_delegated_voter(
+                            new_voter_address,
+                        ))
+                        .await?
                 }
                 StakePoolType::StakingContract => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::staking_contract_update_voter(
-                                stake_pool.operator_address,
-                                new_voter_address,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(aptos_stdlib::staking_contract_update_voter(
+                            stake_pool.operator_address,
+                            new_voter_address,
+                        ))
+                        .await?
                 }
                 StakePoolType::Vesting => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::vesting_update_voter(
-                                stake_pool.vesting_contract.unwrap(),
-                                new_voter_address,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    self.txn_options
+                        .submit_transaction(aptos_stdlib::vesting_update_voter(
+                            stake_pool.vesting_contract.unwrap(),
+                            new_voter_address,
+                        ))
+                        .await?
+                }
+            };
+            match submission {
+                Submission::Submitted(transaction) => {
+                    transaction_summaries.push(transaction.into())
+                }
+                Submission::NotSubmitted(not_submitted) => {
+                    return Ok(Submission::NotSubmitted(not_submitted))
                 }
             }
         }
-        Ok(transaction_summaries)
+        Ok(Submission::Submitted(transaction_summaries))
     }
 }
 
@@ -460,8 +473,9 @@ pub struct CreateStakingContract {
     #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
     pub voter: AccountAddress,
 
-    /// Amount to create the staking contract with
-    #[clap(long)]
+    /// Amount of Octas (10^-8 APT) to create the staking contract with, or of APT with an `APT`
+    /// suffix
+    #[clap(long, parse(try_from_str = parse_octas))]
     pub amount: u64,
 
     /// Percentage of accumulated rewards to pay the operator as commission
@@ -473,12 +487,12 @@ pub struct CreateStakingContract {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for CreateStakingContract {
+impl CliCommand<Submission<TransactionSummary>> for CreateStakingContract {
     fn command_name(&self) -> &'static str {
         "CreateStakingContract"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<Submission<TransactionSummary>> {
         let pool_address = default_stake_pool_address(
             self.txn_options.profile_options.account_address()?,
             self.operator,
@@ -500,7 +514,7 @@ impl CliCommand<TransactionSummary> for CreateStakingContract {
                 vec![],
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|submission| submission.map(|inner| inner.into()))
     }
 }
 
@@ -517,17 +531,17 @@ pub struct DistributeVestedCoins {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for DistributeVestedCoins {
+impl CliCommand<Submission<TransactionSummary>> for DistributeVestedCoins {
     fn command_name(&self) -> &'static str {
         "DistributeVestedCoins"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<Submission<TransactionSummary>> {
         let vesting_contract_address = create_vesting_contract_address(self.admin_address, 0, &[]);
         self.txn_options
             .submit_transaction(aptos_stdlib::vesting_distribute(vesting_contract_address))
             .await
-            .map(|inner| inner.into())
+            .map(|submission| submission.map(|inner| inner.into()))
     }
 }
 
@@ -548,17 +562,17 @@ pub struct UnlockVestedCoins {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for UnlockVestedCoins {
+impl CliCommand<Submission<TransactionSummary>> for UnlockVestedCoins {
     fn command_name(&self) -> &'static str {
         "UnlockVestedCoins"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<Submission<TransactionSummary>> {
         let vesting_contract_address = create_vesting_contract_address(self.admin_address, 0, &[]);
         self.txn_options
             .submit_transaction(aptos_stdlib::vesting_vest(vesting_contract_address))
             .await
-            .map(|inner| inner.into())
+            .map(|submission| submission.map(|inner| inner.into()))
     }
 }
 
@@ -582,12 +596,12 @@ pub struct RequestCommission {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for RequestCommission {
+impl CliCommand<Submission<TransactionSummary>> for RequestCommission {
     fn command_name(&self) -> &'static str {
         "RequestCommission"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<Submission<TransactionSummary>> {
         let client = self
             .txn_options
             .rest_options
@@ -614,6 +628,351 @@ impl CliCommand<TransactionSummary> for RequestCommission {
                 self.operator_address,
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|submission| submission.map(|inner| inner.into()))
+    }
+}
+
+/// Create a vesting contract
+///
+/// Withdraws each shareholder's grant from the admin's account, and stakes it in a new vesting
+/// contract's stake pool.  Each period of the schedule vests a fraction of the grant, and the
+/// last fraction repeats until the grant runs out.  There's no entry function for this, so a
+/// script is compiled against the framework and submitted.
+#[derive(Parser)]
+pub struct CreateVestingContract {
+    /// Addresses of the shareholders of the grant
+    #[clap(long, multiple_values = true, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub shareholders: Vec<AccountAddress>,
+
+    /// Grant of each shareholder, in the order of `--shareholders`
+    ///
+    /// In Octas (10^-8 APT), or in APT with an `APT` suffix e.g. `1.5APT`
+    #[clap(long, multiple_values = true, parse(try_from_str = parse_octas))]
+    pub amounts: Vec<u64>,
+
+    /// Fraction of the grant vesting in each period e.g. `1/24 1/24 1/48`
+    #[clap(long, multiple_values = true, parse(try_from_str = parse_fraction))]
+    pub schedule: Vec<(u64, u64)>,
+
+    /// When vesting starts, as an RFC 3339 date e.g. `2023-01-01T00:00:00Z` or unix seconds
+    #[clap(long, parse(try_from_str = parse_timestamp_secs))]
+    pub start_time: u64,
+
+    /// Length of each vesting period in seconds
+    #[clap(long)]
+    pub period_duration_secs: u64,
+
+    /// Address the remaining grant is returned to if the contract is terminated
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub withdrawal_address: AccountAddress,
+
+    /// Address of the operator of the stake pool
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub operator_address: AccountAddress,
+
+    /// Address of the delegated voter of the stake pool
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub voter_address: AccountAddress,
+
+    /// Percentage of staking rewards paid to the operator as commission
+    #[clap(long)]
+    pub commission_percentage: u64,
+
+    #[clap(flatten)]
+    pub(crate) framework_package_args: FrameworkPackageArgs,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<Submission<TransactionSummary>> for CreateVestingContract {
+    fn command_name(&self) -> &'static str {
+        "CreateVestingContract"
+    }
+
+    async fn execute(mut self) -> CliTypedResult<Submission<TransactionSummary>> {
+        if self.shareholders.is_empty() || self.shareholders.len() != self.amounts.len() {
+            return Err(CliError::CommandArgumentError(
+                "Each of --shareholders needs a grant in --amounts".to_string(),
+            ));
+        }
+        if self.schedule.is_empty() {
+            return Err(CliError::CommandArgumentError(
+                "--schedule needs at least one fraction".to_string(),
+            ));
+        }
+
+        let temp_dir = TempDir::new().map_err(|err| {
+            CliError::UnexpectedError(format!("Failed to create temporary directory {}", err))
+        })?;
+        let script_path = temp_dir.path().join("create_vesting_contract.move");
+        write_to_file(
+            script_path.as_path(),
+            "create_vesting_contract.move",
+            self.script().as_bytes(),
+        )?;
+        let (bytecode, _script_hash) = compile_in_temp_dir(
+            "CreateVestingContract",
+            script_path.as_path(),
+            &self.framework_package_args,
+            self.txn_options.prompt_options,
+        )?;
+
+        self.txn_options
+            .submit_transaction(TransactionPayload::Script(Script::new(
+                bytecode,
+                vec![],
+                vec![],
+            )))
+            .await
+            .map(|submission| submission.map(|inner| inner.into()))
+    }
+}
+
+impl CreateVestingContract {
+    /// A script creating the vesting contract, with the arguments inlined
+    fn script(&self) -> String {
+        let addresses = |addresses: &[AccountAddress]| {
+            addresses
+                .iter()
+                .map(|address| format!("@{}", address.to_hex_literal()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let amounts = self
+            .amounts
+            .iter()
+            .map(|amount| format!("{}u64", amount))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let schedule = self
+            .schedule
+            .iter()
+            .map(|(numerator, denominator)| {
+                format!(
+                    "fixed_point32::create_from_rational({}, {})",
+                    numerator, denominator
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            r#"script {{
+    use aptos_framework::aptos_coin::AptosCoin;
+    use aptos_framework::coin::{{Self, Coin}};
+    use aptos_framework::vesting;
+    use aptos_std::fixed_point32;
+    use aptos_std::simple_map;
+    use std::vector;
+
+    fun main(admin: &signer) {{
+        let shareholders = vector[{shareholders}];
+        let amounts = vector[{amounts}];
+        let buy_ins = simple_map::create<address, Coin<AptosCoin>>();
+        let i = 0;
+        while (i < vector::length(&shareholders)) {{
+            let grant = coin::withdraw<AptosCoin>(admin, *vector::borrow(&amounts, i));
+            simple_map::add(&mut buy_ins, *vector::borrow(&shareholders, i), grant);
+            i = i + 1;
+        }};
+        let schedule = vesting::create_vesting_schedule(vector[{schedule}], {start}, {period});
+        let _contract = vesting::create_vesting_contract(
+            admin,
+            &shareholders,
+            buy_ins,
+            schedule,
+            @{withdrawal},
+            @{operator},
+            @{voter},
+            {commission},
+            vector[],
+        );
+    }}
+}}
+"#,
+            shareholders = addresses(&self.shareholders),
+            amounts = amounts,
+            schedule = schedule,
+            start = self.start_time,
+            period = self.period_duration_secs,
+            withdrawal = self.withdrawal_address.to_hex_literal(),
+            operator = self.operator_address.to_hex_literal(),
+            voter = self.voter_address.to_hex_literal(),
+            commission = self.commission_percentage,
+        )
+    }
+}
+
+/// Parses a fraction e.g. `1/24`
+fn parse_fraction(str: &str) -> CliTypedResult<(u64, u64)> {
+    let invalid =
+        || CliError::UnableToParse("fraction", format!("{} is not a fraction e.g. 1/24", str));
+    let (numerator, denominator) = str.split_once('/').ok_or_else(invalid)?;
+    let numerator: u64 = numerator.trim().parse().map_err(|_| invalid())?;
+    let denominator: u64 = denominator.trim().parse().map_err(|_| invalid())?;
+    if denominator == 0 || numerator > denominator {
+        return Err(invalid());
+    }
+    Ok((numerator, denominator))
+}
+
+/// Parses a time as unix seconds, or as an RFC 3339 date
+fn parse_timestamp_secs(str: &str) -> CliTypedResult<u64> {
+    if let Ok(secs) = str.parse() {
+        return Ok(secs);
+    }
+    let date_time = DateTime::parse_from_rfc3339(str).map_err(|err| {
+        CliError::UnableToParse("time", format!("{} is not an RFC 3339 date: {}", str, err))
+    })?;
+    u64::try_from(date_time.timestamp())
+        .map_err(|_| CliError::UnableToParse("time", format!("{} is before 1970", str)))
+}
+
+/// Show a vesting contract and its schedule
+///
+/// Shows the contract's grant, staking, and shareholders, and when each period of the schedule
+/// vests.
+#[derive(Parser)]
+pub struct ShowVestingContract {
+    /// Address of the vesting contract's admin.
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub admin_address: AccountAddress,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<VestingContractSummary> for ShowVestingContract {
+    fn command_name(&self) -> &'static str {
+        "ShowVestingContract"
+    }
+
+    async fn execute(self) -> CliTypedResult<VestingContractSummary> {
+        let client = self.rest_options.client(&self.profile_options)?;
+        let contract_address = create_vesting_contract_address(self.admin_address, 0, &[]);
+        let contract = client
+            .get_account_resource(contract_address, "0x1::vesting::VestingContract")
+            .await?
+            .into_inner()
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!(
+                    "No vesting contract at {}",
+                    contract_address
+                ))
+            })?
+            .data;
+        VestingContractSummary::from_json(contract_address, &contract).ok_or_else(|| {
+            CliError::UnexpectedError(format!(
+                "Failed to parse vesting contract {}",
+                contract_address
+            ))
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct VestingContractSummary {
+    contract_address: AccountAddress,
+    admin: AccountAddress,
+    terminated: bool,
+    remaining_grant: u64,
+    withdrawal_address: AccountAddress,
+    pool_address: AccountAddress,
+    operator: AccountAddress,
+    voter: AccountAddress,
+    commission_percentage: u64,
+    start_time: Time,
+    period_duration_secs: u64,
+    /// Fractions of the grant vesting each period, the last of which repeats
+    schedule: Vec<VestingPeriod>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VestingPeriod {
+    period: u64,
+    vests_at: Time,
+    fraction: f64,
+    vested: bool,
+}
+
+impl VestingContractSummary {
+    /// Reads the contract from its JSON resource, where numbers are strings
+    fn from_json(contract_address: AccountAddress, contract: &serde_json::Value) -> Option<Self> {
+        let address =
+            |value: &serde_json::Value| AccountAddress::from_hex_literal(value.as_str()?).ok();
+        let number = |value: &serde_json::Value| value.as_str()?.parse::<u64>().ok();
+
+        let vesting_schedule = &contract["vesting_schedule"];
+        let start_timestamp_secs = number(&vesting_schedule["start_timestamp_secs"])?;
+        let period_duration = number(&vesting_schedule["period_duration"])?;
+        let last_vested_period = number(&vesting_schedule["last_vested_period"])?;
+        let schedule = vesting_schedule["schedule"]
+            .as_array()?
+            .iter()
+            .zip(1..)
+            .map(|(fraction, period)| {
+                Some(VestingPeriod {
+                    period,
+                    vests_at: Time::new_seconds(start_timestamp_secs + period * period_duration),
+                    // A FixedPoint32 is a u64 with 32 fractional bits
+                    fraction: number(&fraction["value"])? as f64 / (1u64 << 32) as f64,
+                    vested: period <= last_vested_period,
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        let staking = &contract["staking"];
+        Some(VestingContractSummary {
+            contract_address,
+            admin: address(&contract["admin"])?,
+            terminated: number(&contract["state"])? == VESTING_POOL_TERMINATED,
+            remaining_grant: number(&contract["remaining_grant"])?,
+            withdrawal_address: address(&contract["withdrawal_address"])?,
+            pool_address: address(&staking["pool_address"])?,
+            operator: address(&staking["operator"])?,
+            voter: address(&staking["voter"])?,
+            commission_percentage: number(&staking["commission_percentage"])?,
+            start_time: Time::new_seconds(start_timestamp_secs),
+            period_duration_secs: period_duration,
+            schedule,
+        })
+    }
+}
+
+/// State of a terminated vesting contract, as in `0x1::vesting`
+const VESTING_POOL_TERMINATED: u64 = 2;
+
+/// Terminate a vesting contract
+///
+/// Stops vesting, distributing what has vested to the shareholders.  The remaining grant is
+/// unlocked from the stake pool, and can be withdrawn to the withdrawal address with
+/// `admin_withdraw` after the lockup expires.
+#[derive(Parser)]
+pub struct TerminateVestingContract {
+    /// Address of the vesting contract's admin.
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub admin_address: AccountAddress,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<Submission<TransactionSummary>> for TerminateVestingContract {
+    fn command_name(&self) -> &'static str {
+        "TerminateVestingContract"
+    }
+
+    async fn execute(mut self) -> CliTypedResult<Submission<TransactionSummary>> {
+        let vesting_contract_address = create_vesting_contract_address(self.admin_address, 0, &[]);
+        self.txn_options
+            .submit_transaction(aptos_stdlib::vesting_terminate_vesting_contract(
+                vesting_contract_address,
+            ))
+            .await
+            .map(|submission| submission.map(|inner| inner.into()))
     }
 }
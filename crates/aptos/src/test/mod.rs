@@ -10,16 +10,19 @@ use crate::account::{
     transfer::{TransferCoins, TransferSummary},
 };
 use crate::common::init::{InitTool, Network};
+use crate::common::ledger::DEFAULT_DERIVATION_PATH;
 use crate::common::types::{
     account_address_from_public_key, AccountAddressWrapper, CliError, CliTypedResult,
-    EncodingOptions, FaucetOptions, GasOptions, KeyType, MoveManifestAccountWrapper,
+    EncodingOptions, FaucetOptions, GasOptions, GasUnitPrice, KeyType, MoveManifestAccountWrapper,
     MovePackageDir, OptionalPoolAddressArgs, PrivateKeyInputOptions, PromptOptions,
-    PublicKeyInputOptions, RestOptions, RngArgs, SaveFile, TransactionOptions, TransactionSummary,
+    PublicKeyInputOptions, RestOptions, RngArgs, SaveFile, Submission, TransactionOptions,
+    TransactionSummary,
 };
 
-use crate::common::utils::write_to_file;
+use crate::common::utils::{write_to_file, DEFAULT_FAUCET_ATTEMPTS};
 
 use crate::governance::CompileScriptFunction;
+use crate::move_tool::init_template::{ClientLanguage, PackageTemplate};
 use crate::move_tool::{
     ArgWithType, CompilePackage, DownloadPackage, FrameworkPackageArgs, IncludedArtifacts,
     IncludedArtifactsArgs, InitPackage, MemberId, PublishPackage, RunFunction, RunScript,
@@ -37,7 +40,7 @@ use crate::stake::{
     WithdrawStake,
 };
 use crate::CliCommand;
-use aptos_config::config::Peer;
+use aptos_config::config::{Peer, PeerRole};
 use aptos_crypto::ed25519::Ed25519PublicKey;
 use aptos_crypto::{bls12381, ed25519::Ed25519PrivateKey, x25519, PrivateKey};
 use aptos_genesis::config::HostAndPort;
@@ -176,7 +179,8 @@ impl CliTestFramework {
             account: self.account_id(index),
         }
         .execute()
-        .await?;
+        .await?
+        .submitted()?;
 
         Ok(index)
     }
@@ -204,6 +208,7 @@ impl CliTestFramework {
             account: self.account_id(index),
             faucet_options: self.faucet_options(),
             amount: amount.unwrap_or(DEFAULT_FUNDED_COINS),
+            faucet_attempts: DEFAULT_FAUCET_ATTEMPTS,
             rest_options: self.rest_options(),
         }
         .execute()
@@ -215,6 +220,7 @@ impl CliTestFramework {
         public_key: &Ed25519PublicKey,
     ) -> CliTypedResult<AccountAddress> {
         LookupAddress {
+            auth_key: None,
             public_key_options: PublicKeyInputOptions::from_key(public_key),
             rest_options: self.rest_options(),
             encoding_options: Default::default(),
@@ -245,10 +251,14 @@ impl CliTestFramework {
             new_private_key: Some(new_private_key),
             save_to_profile: None,
             new_private_key_file: None,
+            generate_new_key: false,
+            rng_args: Default::default(),
+            update_profile: false,
             skip_saving_profile: true,
         }
         .execute()
-        .await?;
+        .await?
+        .submitted()?;
 
         Ok(response)
     }
@@ -259,9 +269,13 @@ impl CliTestFramework {
             profile_options: Default::default(),
             account: Some(self.account_id(index)),
             query,
+            resource_type: vec![],
+            limit: None,
+            cursor: None,
         }
-        .execute()
+        .list()
         .await
+        .map(|page| page.items)
     }
 
     pub async fn transfer_coins(
@@ -275,9 +289,12 @@ impl CliTestFramework {
             txn_options: self.transaction_options(sender_index, gas_options),
             account: self.account_id(receiver_index),
             amount,
+            coin_type: None,
+            register_recipient: false,
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn transfer_invalid_addr(
@@ -287,13 +304,15 @@ impl CliTestFramework {
         gas_options: Option<GasOptions>,
     ) -> CliTypedResult<TransactionSummary> {
         RunFunction {
-            function_id: MemberId {
+            simulate: false,
+            function_id: Some(MemberId {
                 module_id: ModuleId::new(
                     AccountAddress::ONE,
                     Identifier::from_str("coin").unwrap(),
                 ),
                 member_id: Identifier::from_str("transfer").unwrap(),
-            },
+            }),
+            json_file: None,
             args: vec![
                 ArgWithType::from_str("address:0xdeadbeefcafebabe").unwrap(),
                 ArgWithType::from_str(&format!("u64:{}", amount)).unwrap(),
@@ -308,6 +327,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn show_validator_config(
@@ -370,6 +390,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn add_stake(
@@ -382,7 +403,7 @@ impl CliTestFramework {
                 index,
                 // TODO(greg): revisit after fixing gas estimation
                 Some(GasOptions {
-                    gas_unit_price: Some(1),
+                    gas_unit_price: Some(GasUnitPrice::Fixed(1)),
                     max_gas: Some(10000),
                 }),
             ),
@@ -390,6 +411,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn unlock_stake(
@@ -403,6 +425,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn withdraw_stake(
@@ -416,6 +439,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn increase_lockup(&self, index: usize) -> CliTypedResult<Vec<TransactionSummary>> {
@@ -424,6 +448,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn join_validator_set(
@@ -437,6 +462,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn leave_validator_set(
@@ -450,6 +476,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn update_validator_network_addresses(
@@ -474,6 +501,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn analyze_validator_performance(
@@ -513,6 +541,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn init(&self, private_key: &Ed25519PrivateKey) -> CliTypedResult<()> {
@@ -526,6 +555,9 @@ impl CliTestFramework {
             prompt_options: PromptOptions::yes(),
             encoding_options: EncodingOptions::default(),
             skip_faucet: false,
+            encrypt_private_key: false,
+            ledger: None,
+            from_mnemonic: None,
         }
         .execute()
         .await
@@ -556,7 +588,7 @@ impl CliTestFramework {
                 owner_index,
                 // TODO(greg): revisit after fixing gas estimation
                 Some(GasOptions {
-                    gas_unit_price: Some(1),
+                    gas_unit_price: Some(GasUnitPrice::Fixed(1)),
                     max_gas: Some(100000),
                 }),
             ),
@@ -566,6 +598,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn create_stake_pool(
@@ -577,8 +610,11 @@ impl CliTestFramework {
         commission_percentage: u64,
     ) -> CliTypedResult<TransactionSummary> {
         RunFunction {
-            function_id: MemberId::from_str("0x1::staking_contract::create_staking_contract")
-                .unwrap(),
+            simulate: false,
+            function_id: Some(
+                MemberId::from_str("0x1::staking_contract::create_staking_contract").unwrap(),
+            ),
+            json_file: None,
             args: vec![
                 ArgWithType::address(self.account_id(operator_index)),
                 ArgWithType::address(self.account_id(voter_index)),
@@ -591,6 +627,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn set_operator(
@@ -604,6 +641,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn set_delegated_voter(
@@ -617,6 +655,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     /// Wait for an account to exist
@@ -703,6 +742,14 @@ impl CliTestFramework {
     ) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
         GenerateKey {
             key_type: KeyType::X25519,
+            vanity_prefix: None,
+            vanity_multisig: false,
+            multisig_public_keys: vec![],
+            multisig_threshold: None,
+            vanity_max_attempts: None,
+            mnemonic: false,
+            mnemonic_words: 12,
+            derivation_path: DEFAULT_DERIVATION_PATH.to_string(),
             rng_args: RngArgs::from_seed(seed),
             save_params: SaveKey {
                 file_options: SaveFile {
@@ -724,6 +771,7 @@ impl CliTestFramework {
     ) -> CliTypedResult<HashMap<AccountAddress, Peer>> {
         ExtractPeer {
             host,
+            role: PeerRole::Upstream,
             network_key_input_options: NetworkKeyInputOptions::from_private_key_file(
                 private_key_file,
             ),
@@ -794,6 +842,8 @@ impl CliTestFramework {
                 framework_git_rev: None,
                 framework_local_dir: framework_dir,
             },
+            template: PackageTemplate::Empty,
+            client: ClientLanguage::Typescript,
         }
         .execute()
         .await
@@ -810,6 +860,7 @@ impl CliTestFramework {
             included_artifacts_args: IncludedArtifactsArgs {
                 included_artifacts: included_artifacts.unwrap_or(IncludedArtifacts::Sparse),
             },
+            profile_options: Default::default(),
         }
         .execute()
         .await
@@ -824,6 +875,8 @@ impl CliTestFramework {
             instruction_execution_bound: 100_000,
             move_options: self.move_options(account_strs),
             filter: filter.map(|str| str.to_string()),
+            profile_options: Default::default(),
+            gas_report_options: Default::default(),
         }
         .execute()
         .await
@@ -837,6 +890,11 @@ impl CliTestFramework {
         included_artifacts: Option<IncludedArtifacts>,
     ) -> CliTypedResult<TransactionSummary> {
         PublishPackage {
+            simulate: false,
+            chunked_publish: false,
+            large_packages_module_address: None,
+            upgrade_policy: None,
+            check_reproducibility: false,
             move_options: self.move_options(account_strs),
             txn_options: self.transaction_options(index, gas_options),
             override_size_check: false,
@@ -846,6 +904,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn download_package(
@@ -860,6 +919,7 @@ impl CliTestFramework {
             account: self.account_id(index),
             package,
             output_dir: Some(output_dir),
+            with_dependencies: false,
         }
         .execute()
         .await
@@ -890,13 +950,16 @@ impl CliTestFramework {
         }
 
         RunFunction {
+            simulate: false,
             txn_options: self.transaction_options(index, gas_options),
-            function_id,
+            function_id: Some(function_id),
+            json_file: None,
             args: parsed_args,
             type_args: parsed_type_args,
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     pub async fn run_script(
@@ -922,16 +985,26 @@ impl CliTestFramework {
             compile_proposal_args: CompileScriptFunction {
                 script_path: Some(source_path),
                 compiled_script_path: None,
+                script_package_dir: None,
                 framework_package_args: FrameworkPackageArgs {
                     framework_git_rev: None,
                     framework_local_dir: Some(Self::aptos_framework_dir()),
                 },
             },
+            script_name: None,
+            move_options: MovePackageDir {
+                package_dir: None,
+                output_dir: None,
+                named_addresses: Default::default(),
+                named_addresses_file: None,
+                offline: false,
+            },
             args: Vec::new(),
             type_args: Vec::new(),
         }
         .execute()
         .await
+        .and_then(Submission::submitted)
     }
 
     fn aptos_framework_dir() -> PathBuf {
@@ -948,6 +1021,8 @@ impl CliTestFramework {
             package_dir: Some(self.move_dir()),
             output_dir: None,
             named_addresses: Self::named_addresses(account_strs),
+            named_addresses_file: None,
+            offline: false,
         }
     }
 
@@ -0,0 +1,533 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    load_account_arg, CliCommand, CliError, CliResult, CliTypedResult, Submission,
+    TransactionOptions, TransactionSummary,
+};
+use crate::common::utils::read_from_file;
+use crate::move_tool::json_payload::encode;
+use aptos_rest_client::aptos_api_types::MoveType;
+use aptos_rest_client::Client;
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use cached_packages::aptos_stdlib::aptos_token_stdlib;
+use clap::{Parser, Subcommand};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Tool for creating and transferring tokens (NFTs)
+///
+/// This tool wraps the `0x3::token` module, to create collections and tokens, and to send
+/// tokens by offering them or transferring them directly.
+#[derive(Subcommand)]
+pub enum TokenTool {
+    Claim(ClaimToken),
+    CreateCollection(CreateCollection),
+    CreateToken(CreateToken),
+    Mint(MintToken),
+    Offer(OfferToken),
+    OptInDirectTransfer(OptInDirectTransfer),
+    Transfer(TransferToken),
+}
+
+impl TokenTool {
+    pub async fn execute(self) -> CliResult {
+        use TokenTool::*;
+        match self {
+            Claim(tool) => tool.execute_serialized().await,
+            CreateCollection(tool) => tool.execute_serialized().await,
+            CreateToken(tool) => tool.execute_serialized().await,
+            Mint(tool) => tool.execute_serialized().await,
+            Offer(tool) => tool.execute_serialized().await,
+            OptInDirectTransfer(tool) => tool.execute_serialized().await,
+            Transfer(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// Identifies a token by its creator, collection, name, and property version
+#[derive(Parser)]
+pub struct TokenIdArgs {
+    /// Address of the token's creator
+    #[clap(long, parse(try_from_str = load_account_arg))]
+    pub(crate) creator: AccountAddress,
+
+    /// Name of the token's collection
+    #[clap(long)]
+    pub(crate) collection_name: String,
+
+    /// Name of the token
+    #[clap(long)]
+    pub(crate) token_name: String,
+
+    /// Property version of the token, 0 unless its properties were mutated
+    #[clap(long, default_value_t = 0)]
+    pub(crate) property_version: u64,
+}
+
+/// Create a collection of tokens
+///
+/// The collection belongs to the sender, who can then create tokens in it.
+#[derive(Parser)]
+pub struct CreateCollection {
+    /// Name of the collection, unique among the sender's collections
+    #[clap(long)]
+    pub(crate) collection_name: String,
+
+    /// Description of the collection
+    #[clap(long, default_value = "")]
+    pub(crate) description: String,
+
+    /// URI of the collection's metadata e.g. an image
+    #[clap(long, default_value = "")]
+    pub(crate) uri: String,
+
+    /// Maximum number of tokens in the collection, 0 for no maximum
+    #[clap(long, default_value_t = 0)]
+    pub(crate) maximum: u64,
+
+    /// Allow the description to be changed later
+    #[clap(long)]
+    pub(crate) mutable_description: bool,
+
+    /// Allow the URI to be changed later
+    #[clap(long)]
+    pub(crate) mutable_uri: bool,
+
+    /// Allow the maximum to be changed later
+    #[clap(long)]
+    pub(crate) mutable_maximum: bool,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<Submission<TransactionSummary>> for CreateCollection {
+    fn command_name(&self) -> &'static str {
+        "CreateCollection"
+    }
+
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        self.txn_options
+            .submit_transaction(aptos_token_stdlib::token_create_collection_script(
+                self.collection_name.into_bytes(),
+                self.description.into_bytes(),
+                self.uri.into_bytes(),
+                self.maximum,
+                vec![
+                    self.mutable_description,
+                    self.mutable_uri,
+                    self.mutable_maximum,
+                ],
+            ))
+            .await
+            .map(|submission| submission.map(TransactionSummary::from))
+    }
+}
+
+/// Create a token in one of the sender's collections, and mint its first supply
+///
+/// Properties of the token are read from a JSON object of property names to values.  Strings,
+/// booleans, and numbers are stored as `0x1::string::String`, `bool`, and `u64`, and other types
+/// can be given as `{"type": "<type>", "value": <value>}` e.g. `{"type": "address", "value":
+/// "0x1"}`.
+#[derive(Parser)]
+pub struct CreateToken {
+    /// Name of the sender's collection to create the token in
+    #[clap(long)]
+    pub(crate) collection_name: String,
+
+    /// Name of the token, unique in its collection
+    #[clap(long)]
+    pub(crate) token_name: String,
+
+    /// Description of the token
+    #[clap(long, default_value = "")]
+    pub(crate) description: String,
+
+    /// URI of the token's metadata e.g. an image
+    #[clap(long, default_value = "")]
+    pub(crate) uri: String,
+
+    /// Amount of the token to mint to the sender
+    #[clap(long, default_value_t = 1)]
+    pub(crate) balance: u64,
+
+    /// Maximum supply of the token, 0 for no maximum
+    #[clap(long, default_value_t = 1)]
+    pub(crate) maximum: u64,
+
+    /// Address royalties are paid to
+    ///
+    /// Defaults to the sender
+    #[clap(long, parse(try_from_str = load_account_arg))]
+    pub(crate) royalty_payee: Option<AccountAddress>,
+
+    /// Royalty as a fraction of sales, over `--royalty-points-denominator`
+    #[clap(long, default_value_t = 0)]
+    pub(crate) royalty_points_numerator: u64,
+
+    /// Denominator of the royalty
+    #[clap(long, default_value_t = 100)]
+    pub(crate) royalty_points_denominator: u64,
+
+    /// JSON file of the token's properties
+    #[clap(long, parse(from_os_str))]
+    pub(crate) properties_file: Option<PathBuf>,
+
+    /// Allow the maximum to be changed later
+    #[clap(long)]
+    pub(crate) mutable_maximum: bool,
+
+    /// Allow the URI to be changed later
+    #[clap(long)]
+    pub(crate) mutable_uri: bool,
+
+    /// Allow the royalty to be changed later
+    #[clap(long)]
+    pub(crate) mutable_royalty: bool,
+
+    /// Allow the description to be changed later
+    #[clap(long)]
+    pub(crate) mutable_description: bool,
+
+    /// Allow the properties to be changed later
+    #[clap(long)]
+    pub(crate) mutable_properties: bool,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<Submission<TransactionSummary>> for CreateToken {
+    fn command_name(&self) -> &'static str {
+        "CreateToken"
+    }
+
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        let properties = if let Some(ref properties_file) = self.properties_file {
+            let client = self.txn_options.rest_client()?;
+            load_properties(&client, properties_file).await?
+        } else {
+            PropertyMap::default()
+        };
+        let royalty_payee = if let Some(royalty_payee) = self.royalty_payee {
+            royalty_payee
+        } else {
+            self.txn_options.sender_address()?
+        };
+
+        self.txn_options
+            .submit_transaction(aptos_token_stdlib::token_create_token_script(
+                self.collection_name.into_bytes(),
+                self.token_name.into_bytes(),
+                self.description.into_bytes(),
+                self.balance,
+                self.maximum,
+                self.uri.into_bytes(),
+                royalty_payee,
+                self.royalty_points_denominator,
+                self.royalty_points_numerator,
+                vec![
+                    self.mutable_maximum,
+                    self.mutable_uri,
+                    self.mutable_royalty,
+                    self.mutable_description,
+                    self.mutable_properties,
+                ],
+                properties.keys,
+                properties.values,
+                properties.types,
+            ))
+            .await
+            .map(|submission| submission.map(TransactionSummary::from))
+    }
+}
+
+/// A token's properties, as the entry functions take them
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PropertyMap {
+    pub keys: Vec<Vec<u8>>,
+    pub values: Vec<Vec<u8>>,
+    pub types: Vec<Vec<u8>>,
+}
+
+async fn load_properties(client: &Client, path: &Path) -> CliTypedResult<PropertyMap> {
+    let properties: Value = serde_json::from_slice(&read_from_file(path)?).map_err(|err| {
+        CliError::UnableToParse("properties", format!("{}: {}", path.display(), err))
+    })?;
+    encode_properties(client, &properties).await
+}
+
+/// Encodes a JSON object of properties, each as BCS of its type
+pub async fn encode_properties(client: &Client, properties: &Value) -> CliTypedResult<PropertyMap> {
+    let properties = properties.as_object().ok_or_else(|| {
+        CliError::UnableToParse("properties", "Properties must be a JSON object".to_string())
+    })?;
+
+    let mut property_map = PropertyMap::default();
+    for (key, value) in properties {
+        let (typ, value) = match value {
+            Value::String(_) => ("0x1::string::String", value),
+            Value::Bool(_) => ("bool", value),
+            Value::Number(_) => ("u64", value),
+            Value::Object(typed) if typed.contains_key("type") && typed.contains_key("value") => (
+                typed["type"].as_str().ok_or_else(|| {
+                    CliError::UnableToParse("properties", format!("{} has no type", key))
+                })?,
+                &typed["value"],
+            ),
+            _ => {
+                return Err(CliError::UnableToParse(
+                    "properties",
+                    format!(
+                        "Property {} must be a string, bool, number, or typed value",
+                        key
+                    ),
+                ))
+            }
+        };
+        let move_type = MoveType::from_str(typ)
+            .map_err(|err| CliError::UnableToParse("properties", err.to_string()))?;
+        let mut bytes = vec![];
+        encode(client, &move_type, value, &mut bytes).await?;
+
+        property_map.keys.push(key.as_bytes().to_vec());
+        property_map.values.push(bytes);
+        // The token module compares types by these names
+        property_map.types.push(move_type.to_string().into_bytes());
+    }
+    Ok(property_map)
+}
+
+/// Mint more of one of the sender's tokens
+#[derive(Parser)]
+pub struct MintToken {
+    /// Name of the token's collection
+    #[clap(long)]
+    pub(crate) collection_name: String,
+
+    /// Name of the token
+    #[clap(long)]
+    pub(crate) token_name: String,
+
+    /// Amount of the token to mint
+    #[clap(long, default_value_t = 1)]
+    pub(crate) amount: u64,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<Submission<TransactionSummary>> for MintToken {
+    fn command_name(&self) -> &'static str {
+        "MintToken"
+    }
+
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        let creator = self.txn_options.sender_address()?;
+        self.txn_options
+            .submit_transaction(aptos_token_stdlib::token_mint_script(
+                creator,
+                self.collection_name.into_bytes(),
+                self.token_name.into_bytes(),
+                self.amount,
+            ))
+            .await
+            .map(|submission| submission.map(TransactionSummary::from))
+    }
+}
+
+/// Offer a token to another account, which can then claim it
+#[derive(Parser)]
+pub struct OfferToken {
+    /// Address of the account to offer the token to
+    #[clap(long, parse(try_from_str = load_account_arg))]
+    pub(crate) receiver: AccountAddress,
+
+    /// Amount of the token to offer
+    #[clap(long, default_value_t = 1)]
+    pub(crate) amount: u64,
+
+    #[clap(flatten)]
+    pub(crate) token_id_args: TokenIdArgs,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<Submission<TransactionSummary>> for OfferToken {
+    fn command_name(&self) -> &'static str {
+        "OfferToken"
+    }
+
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        let token = self.token_id_args;
+        self.txn_options
+            .submit_transaction(aptos_token_stdlib::token_transfers_offer_script(
+                self.receiver,
+                token.creator,
+                token.collection_name.into_bytes(),
+                token.token_name.into_bytes(),
+                token.property_version,
+                self.amount,
+            ))
+            .await
+            .map(|submission| submission.map(TransactionSummary::from))
+    }
+}
+
+/// Claim a token offered to the sender
+#[derive(Parser)]
+pub struct ClaimToken {
+    /// Address of the account which offered the token
+    #[clap(long, parse(try_from_str = load_account_arg))]
+    pub(crate) sender: AccountAddress,
+
+    #[clap(flatten)]
+    pub(crate) token_id_args: TokenIdArgs,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<Submission<TransactionSummary>> for ClaimToken {
+    fn command_name(&self) -> &'static str {
+        "ClaimToken"
+    }
+
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        let token = self.token_id_args;
+        self.txn_options
+            .submit_transaction(aptos_token_stdlib::token_transfers_claim_script(
+                self.sender,
+                token.creator,
+                token.collection_name.into_bytes(),
+                token.token_name.into_bytes(),
+                token.property_version,
+            ))
+            .await
+            .map(|submission| submission.map(TransactionSummary::from))
+    }
+}
+
+/// Transfer a token directly to another account
+///
+/// The receiver must have opted in to direct transfers with `opt-in-direct-transfer`,
+/// otherwise offer the token instead.
+#[derive(Parser)]
+pub struct TransferToken {
+    /// Address of the account to transfer the token to
+    #[clap(long, parse(try_from_str = load_account_arg))]
+    pub(crate) receiver: AccountAddress,
+
+    /// Amount of the token to transfer
+    #[clap(long, default_value_t = 1)]
+    pub(crate) amount: u64,
+
+    #[clap(flatten)]
+    pub(crate) token_id_args: TokenIdArgs,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<Submission<TransactionSummary>> for TransferToken {
+    fn command_name(&self) -> &'static str {
+        "TransferToken"
+    }
+
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        let token = self.token_id_args;
+        self.txn_options
+            .submit_transaction(aptos_token_stdlib::token_transfer_with_opt_in(
+                token.creator,
+                token.collection_name.into_bytes(),
+                token.token_name.into_bytes(),
+                token.property_version,
+                self.receiver,
+                self.amount,
+            ))
+            .await
+            .map(|submission| submission.map(TransactionSummary::from))
+    }
+}
+
+/// Opt in to receiving tokens by direct transfer
+#[derive(Parser)]
+pub struct OptInDirectTransfer {
+    /// Opt out instead, so tokens must be offered and claimed
+    #[clap(long)]
+    pub(crate) opt_out: bool,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<Submission<TransactionSummary>> for OptInDirectTransfer {
+    fn command_name(&self) -> &'static str {
+        "OptInDirectTransfer"
+    }
+
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        self.txn_options
+            .submit_transaction(aptos_token_stdlib::token_opt_in_direct_transfer(
+                !self.opt_out,
+            ))
+            .await
+            .map(|submission| submission.map(TransactionSummary::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_encode_properties() {
+        // Properties of these types don't need the client
+        let client = Client::new("http://localhost:8080".parse().unwrap());
+        let properties = encode_properties(
+            &client,
+            &json!({
+                "level": 5,
+                "name": "sword",
+                "owner": {"type": "address", "value": "0x1"},
+            }),
+        )
+        .await
+        .unwrap();
+
+        // JSON objects are ordered by key
+        assert_eq!(
+            vec![b"level".to_vec(), b"name".to_vec(), b"owner".to_vec()],
+            properties.keys
+        );
+        assert_eq!(
+            vec![
+                bcs::to_bytes(&5u64).unwrap(),
+                bcs::to_bytes("sword").unwrap(),
+                bcs::to_bytes(&AccountAddress::ONE).unwrap(),
+            ],
+            properties.values
+        );
+        assert_eq!(
+            vec![
+                b"u64".to_vec(),
+                b"0x1::string::String".to_vec(),
+                b"address".to_vec()
+            ],
+            properties.types
+        );
+        assert!(encode_properties(&client, &json!({"list": [1]}))
+            .await
+            .is_err());
+    }
+}
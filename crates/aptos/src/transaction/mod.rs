@@ -0,0 +1,289 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline signing of transactions
+//!
+//! A transaction is built unsigned on a machine with network access, signed on one with the key,
+//! e.g. an air-gapped machine, and submitted from any machine with network access.
+
+pub mod simulate;
+
+use crate::common::{
+    types::{
+        CliCommand, CliError, CliResult, CliTypedResult, EncodingOptions, PrivateKeyInputOptions,
+        ProfileOptions, RestOptions, SaveFile, Submission, TransactionOptions, TransactionSummary,
+        WaitOptions,
+    },
+    utils::{prompt_yes_with_override, read_from_file, write_to_file},
+};
+use crate::move_tool::{entry_function_payload, ArgWithType, MemberId};
+use aptos_crypto::{signing_message, HashValue, PrivateKey};
+use aptos_rest_client::aptos_api_types::{HexEncodedBytes, MoveType};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{RawTransaction, SignedTransaction, TransactionPayload},
+};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Seconds until an unsigned transaction expires, long enough to sign it offline
+pub const DEFAULT_UNSIGNED_EXPIRATION_SECS: u64 = 3600;
+
+/// Tool for building, signing, simulating, and submitting transactions separately
+///
+/// This allows signing transactions on a machine without network access.  Commands that submit
+/// transactions can also save them unsigned with `--save-unsigned`, to sign and submit here.
+#[derive(Debug, Subcommand)]
+pub enum TransactionTool {
+    Build(BuildTransaction),
+    Sign(SignTransaction),
+    Simulate(simulate::SimulateTransaction),
+    Submit(SubmitTransaction),
+}
+
+impl TransactionTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            TransactionTool::Build(tool) => tool.execute_serialized().await,
+            TransactionTool::Sign(tool) => tool.execute_serialized().await,
+            TransactionTool::Simulate(tool) => tool.execute_serialized().await,
+            TransactionTool::Submit(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// An unsigned transaction, as saved by `aptos transaction build` and `--save-unsigned`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    /// The BCS encoded `RawTransaction`
+    pub raw_transaction: HexEncodedBytes,
+    /// The bytes to sign, for signers other than the CLI
+    pub signing_message: HexEncodedBytes,
+}
+
+impl UnsignedTransaction {
+    pub fn new(raw_transaction: &RawTransaction) -> CliTypedResult<Self> {
+        Ok(UnsignedTransaction {
+            raw_transaction: HexEncodedBytes(bcs::to_bytes(raw_transaction)?),
+            signing_message: HexEncodedBytes(signing_message(raw_transaction)?),
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> CliTypedResult<()> {
+        write_to_file(
+            path,
+            "Unsigned transaction",
+            serde_json::to_string_pretty(self)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+                .as_bytes(),
+        )
+    }
+
+    /// Loads an unsigned transaction, checking that its signing message is the transaction's
+    pub fn load(path: &Path) -> CliTypedResult<RawTransaction> {
+        let unsigned: UnsignedTransaction = serde_json::from_slice(&read_from_file(path)?)
+            .map_err(|err| CliError::UnableToParse("unsigned transaction", err.to_string()))?;
+        let raw_transaction: RawTransaction = bcs::from_bytes(&unsigned.raw_transaction.0)?;
+        if signing_message(&raw_transaction)? != unsigned.signing_message.0 {
+            return Err(CliError::UnexpectedError(format!(
+                "The signing message of {} doesn't match its transaction",
+                path.display()
+            )));
+        }
+        Ok(raw_transaction)
+    }
+}
+
+/// Summary of a transaction for a signer to review before signing it
+pub(crate) fn describe_transaction(raw_transaction: &RawTransaction) -> String {
+    let call = match raw_transaction.payload() {
+        TransactionPayload::EntryFunction(entry_function) => format!(
+            "Function: {}::{}\nType arguments: {:?}\nArguments: {}",
+            entry_function.module(),
+            entry_function.function(),
+            entry_function.ty_args(),
+            entry_function
+                .args()
+                .iter()
+                .map(|arg| format!("0x{}", hex::encode(arg)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+        TransactionPayload::Script(_) => "Script".to_string(),
+        TransactionPayload::ModuleBundle(_) => "Module publishing".to_string(),
+    };
+    format!(
+        "Sender: {}\nSequence number: {}\n{}\nMax gas: {} at a gas unit price of {} Octas\nExpires at: {} (UNIX seconds)",
+        raw_transaction.sender(),
+        raw_transaction.sequence_number(),
+        call,
+        raw_transaction.max_gas_amount(),
+        raw_transaction.gas_unit_price(),
+        raw_transaction.expiration_timestamp_secs(),
+    )
+}
+
+/// Build an unsigned entry function transaction
+///
+/// The transaction is saved to `--output-file` for `aptos transaction sign`.  The sender's
+/// private key isn't needed, only its account, and its public key to estimate gas without
+/// `--max-gas`.
+#[derive(Debug, Parser)]
+pub struct BuildTransaction {
+    /// Function name as `<ADDRESS>::<MODULE_ID>::<FUNCTION_NAME>`
+    ///
+    /// Example: `0x1::coin::transfer`
+    #[clap(long)]
+    pub(crate) function_id: MemberId,
+
+    /// Arguments combined with their type separated by spaces.
+    ///
+    /// Supported types [u8, u64, u128, bool, hex, string, address, raw]
+    ///
+    /// Example: `address:0x1 bool:true u8:0`
+    #[clap(long, multiple_values = true)]
+    pub(crate) args: Vec<ArgWithType>,
+
+    /// TypeTag arguments separated by spaces.
+    ///
+    /// Example: `u8 u64 u128 bool address vector signer`
+    #[clap(long, multiple_values = true)]
+    pub(crate) type_args: Vec<MoveType>,
+
+    /// Seconds until the transaction expires, which must leave time to sign and submit it
+    #[clap(long, default_value_t = DEFAULT_UNSIGNED_EXPIRATION_SECS)]
+    pub(crate) expiration_secs: u64,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<UnsignedTransaction> for BuildTransaction {
+    fn command_name(&self) -> &'static str {
+        "BuildTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<UnsignedTransaction> {
+        self.save_file.check_file()?;
+        let payload = entry_function_payload(self.function_id, self.type_args, self.args)?;
+        let raw_transaction = self
+            .txn_options
+            .build_unsigned_transaction(payload, self.expiration_secs)
+            .await?;
+        let unsigned = UnsignedTransaction::new(&raw_transaction)?;
+        unsigned.save(&self.save_file.output_file)?;
+        Ok(unsigned)
+    }
+}
+
+/// Sign an unsigned transaction
+///
+/// This doesn't need network access.  The signed transaction is saved BCS encoded to
+/// `--output-file` for `aptos transaction submit`.
+#[derive(Debug, Parser)]
+pub struct SignTransaction {
+    /// Unsigned transaction file from `aptos transaction build` or `--save-unsigned`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) unsigned_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+/// A signed transaction, ready to submit
+#[derive(Debug, Serialize)]
+pub struct SignedTransactionSummary {
+    pub transaction_hash: HashValue,
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub signed_file: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<SignedTransactionSummary> for SignTransaction {
+    fn command_name(&self) -> &'static str {
+        "SignTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<SignedTransactionSummary> {
+        self.save_file.check_file()?;
+        let raw_transaction = UnsignedTransaction::load(&self.unsigned_file)?;
+        let private_key = self
+            .private_key_options
+            .extract_private_key(self.encoding_options.encoding, &self.profile_options)?;
+
+        eprintln!("{}", describe_transaction(&raw_transaction));
+        prompt_yes_with_override(
+            "Do you want to sign this transaction?",
+            self.save_file.prompt_options,
+        )?;
+
+        let sender = raw_transaction.sender();
+        let sequence_number = raw_transaction.sequence_number();
+        let signed_transaction = raw_transaction
+            .sign(&private_key, private_key.public_key())
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+            .into_inner();
+        self.save_file
+            .save_to_file("Signed transaction", &bcs::to_bytes(&signed_transaction)?)?;
+
+        Ok(SignedTransactionSummary {
+            transaction_hash: signed_transaction.committed_hash(),
+            sender,
+            sequence_number,
+            signed_file: self.save_file.output_file,
+        })
+    }
+}
+
+/// Submit a signed transaction
+///
+/// Submits a BCS encoded transaction from `aptos transaction sign`, or any other signer, and
+/// waits for it by `--wait-for`.
+#[derive(Debug, Parser)]
+pub struct SubmitTransaction {
+    /// Signed transaction file, BCS encoded
+    #[clap(long, parse(from_os_str))]
+    pub(crate) signed_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) wait_options: WaitOptions,
+}
+
+#[async_trait]
+impl CliCommand<Submission<TransactionSummary>> for SubmitTransaction {
+    fn command_name(&self) -> &'static str {
+        "SubmitTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<Submission<TransactionSummary>> {
+        let signed_transaction: SignedTransaction =
+            bcs::from_bytes(&read_from_file(&self.signed_file)?)?;
+        // Fail before submitting if the file was signed with the wrong key or damaged
+        signed_transaction
+            .clone()
+            .check_signature()
+            .map_err(|err| CliError::UnexpectedError(format!("Invalid signature: {}", err)))?;
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        self.wait_options
+            .submit_and_wait(&client, &signed_transaction)
+            .await
+            .map(|submission| submission.map(|transaction| transaction.into()))
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliError, CliTypedResult, TransactionOptions};
+use crate::common::utils::read_from_file;
+use crate::gas::report::GasReportOptions;
+use crate::move_tool::{entry_function_payload, ArgWithType, MemberId};
+use aptos_rest_client::aptos_api_types::{
+    AptosError, AptosErrorCode, Event, MoveType, UserTransaction, WriteSetChange,
+};
+use aptos_rest_client::error::{AptosErrorResponse, RestError};
+use aptos_rest_client::Client;
+use aptos_types::{account_address::AccountAddress, transaction::TransactionPayload};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Simulate an entry function transaction without submitting it
+///
+/// Shows the gas it would use, its VM status, the events it would emit, and the changes it would
+/// make to on-chain state.  `aptos move run` and `aptos move publish` can also be simulated with
+/// `--simulate`.
+///
+/// With `--gas-report`, `--save-gas-baseline` or `--gas-baseline`, the gas used is reported for the
+/// function, so a baseline can track many functions' gas across runs.
+#[derive(Debug, Parser)]
+pub struct SimulateTransaction {
+    /// Function name as `<ADDRESS>::<MODULE_ID>::<FUNCTION_NAME>`
+    ///
+    /// Example: `0x1::coin::transfer`
+    #[clap(long)]
+    pub(crate) function_id: MemberId,
+
+    /// Arguments combined with their type separated by spaces.
+    ///
+    /// Supported types [u8, u64, u128, bool, hex, string, address, raw]
+    ///
+    /// Example: `address:0x1 bool:true u8:0`
+    #[clap(long, multiple_values = true)]
+    pub(crate) args: Vec<ArgWithType>,
+
+    /// TypeTag arguments separated by spaces.
+    ///
+    /// Example: `u8 u64 u128 bool address vector signer`
+    #[clap(long, multiple_values = true)]
+    pub(crate) type_args: Vec<MoveType>,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+    #[clap(flatten)]
+    pub(crate) gas_report_options: GasReportOptions,
+}
+
+#[async_trait]
+impl CliCommand<SimulationReport> for SimulateTransaction {
+    fn command_name(&self) -> &'static str {
+        "SimulateTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<SimulationReport> {
+        let function = format!(
+            "{}::{}",
+            self.function_id.module_id, self.function_id.member_id
+        );
+        let payload = entry_function_payload(self.function_id, self.type_args, self.args)?;
+        let report = simulate(&self.txn_options, payload).await?;
+
+        if self.gas_report_options.enabled() {
+            // Merge into an existing baseline, so each function can be simulated separately
+            let mut gas_used = match &self.gas_report_options.save_gas_baseline {
+                Some(path) if path.exists() => serde_json::from_slice(&read_from_file(path)?)
+                    .map_err(|err| CliError::UnableToParse("gas baseline", err.to_string()))?,
+                _ => BTreeMap::new(),
+            };
+            gas_used.insert(function, report.gas_used);
+            self.gas_report_options.report(&gas_used)?;
+        }
+        Ok(report)
+    }
+}
+
+/// Simulates another command's transaction in place of submitting it, for its `--simulate`
+pub struct SimulatePayload {
+    command_name: &'static str,
+    payload: CliTypedResult<TransactionPayload>,
+    txn_options: TransactionOptions,
+}
+
+impl SimulatePayload {
+    pub fn new(
+        command_name: &'static str,
+        payload: CliTypedResult<TransactionPayload>,
+        txn_options: TransactionOptions,
+    ) -> Self {
+        SimulatePayload {
+            command_name,
+            payload,
+            txn_options,
+        }
+    }
+}
+
+#[async_trait]
+impl CliCommand<SimulationReport> for SimulatePayload {
+    fn command_name(&self) -> &'static str {
+        self.command_name
+    }
+
+    async fn execute(self) -> CliTypedResult<SimulationReport> {
+        simulate(&self.txn_options, self.payload?).await
+    }
+}
+
+/// The outcome of a simulated transaction
+#[derive(Debug, Serialize)]
+pub struct SimulationReport {
+    pub success: bool,
+    pub vm_status: String,
+    pub gas_used: u64,
+    pub gas_unit_price: u64,
+    /// Gas used times the gas unit price, in Octas
+    pub fee: u64,
+    pub events: Vec<Event>,
+    /// Changes to on-chain state, as `+` for created, `-` for deleted and `~` for changed values
+    pub changes: Vec<String>,
+}
+
+async fn simulate(
+    txn_options: &TransactionOptions,
+    payload: TransactionPayload,
+) -> CliTypedResult<SimulationReport> {
+    let transaction = txn_options
+        .simulate_transaction(payload, None, None)
+        .await?;
+    let client = txn_options
+        .rest_options
+        .client(&txn_options.profile_options)?;
+    report(&client, transaction).await
+}
+
+async fn report(client: &Client, transaction: UserTransaction) -> CliTypedResult<SimulationReport> {
+    let mut changes = Vec::new();
+    for change in &transaction.info.changes {
+        describe_change(client, change, &mut changes).await?;
+    }
+
+    let gas_used = transaction.info.gas_used.0;
+    let gas_unit_price = transaction.request.gas_unit_price.0;
+    Ok(SimulationReport {
+        success: transaction.info.success,
+        vm_status: transaction.info.vm_status,
+        gas_used,
+        gas_unit_price,
+        fee: gas_used * gas_unit_price,
+        events: transaction.events,
+        changes,
+    })
+}
+
+/// Describes a change against the current state, as lines of the changed values
+async fn describe_change(
+    client: &Client,
+    change: &WriteSetChange,
+    lines: &mut Vec<String>,
+) -> CliTypedResult<()> {
+    match change {
+        WriteSetChange::WriteResource(write) => {
+            let resource_type = write.data.typ.to_string();
+            let name = format!("{} at {}", resource_type, write.address);
+            let new = serde_json::to_value(&write.data.data)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            match current_resource(client, AccountAddress::from(&write.address), &resource_type)
+                .await?
+            {
+                Some(old) => {
+                    let mut diffs = Vec::new();
+                    diff_values("", &old, &new, &mut diffs);
+                    lines.extend(
+                        diffs
+                            .into_iter()
+                            .map(|diff| format!("~ {}: {}", name, diff)),
+                    );
+                }
+                None => lines.push(format!("+ {}: {}", name, new)),
+            }
+        }
+        WriteSetChange::DeleteResource(delete) => {
+            lines.push(format!("- {} at {}", delete.resource, delete.address))
+        }
+        WriteSetChange::WriteModule(write) => {
+            let name = write
+                .data
+                .clone()
+                .try_parse_abi()
+                .ok()
+                .and_then(|module| module.abi)
+                .map(|abi| abi.name.to_string())
+                .unwrap_or_else(|| write.state_key_hash.clone());
+            lines.push(format!("~ module {}::{}", write.address, name))
+        }
+        WriteSetChange::DeleteModule(delete) => lines.push(format!("- module {}", delete.module)),
+        WriteSetChange::WriteTableItem(write) => match write.data {
+            Some(ref data) => lines.push(format!(
+                "~ table {}: {} = {}",
+                write.handle, data.key, data.value
+            )),
+            None => lines.push(format!(
+                "~ table {}: {} = {}",
+                write.handle, write.key, write.value
+            )),
+        },
+        WriteSetChange::DeleteTableItem(delete) => match delete.data {
+            Some(ref data) => lines.push(format!("- table {}: {}", delete.handle, data.key)),
+            None => lines.push(format!("- table {}: {}", delete.handle, delete.key)),
+        },
+    }
+    Ok(())
+}
+
+/// The resource as JSON, or `None` if it doesn't exist yet
+async fn current_resource(
+    client: &Client,
+    address: AccountAddress,
+    resource_type: &str,
+) -> CliTypedResult<Option<Value>> {
+    match client.get_account_resource(address, resource_type).await {
+        Ok(response) => Ok(response.into_inner().map(|resource| resource.data)),
+        Err(RestError::Api(AptosErrorResponse {
+            error:
+                AptosError {
+                    error_code: AptosErrorCode::AccountNotFound | AptosErrorCode::ResourceNotFound,
+                    ..
+                },
+            ..
+        })) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Adds `path: old -> new` for each value that differs, with fields of structs compared
+/// separately
+fn diff_values(path: &str, old: &Value, new: &Value, diffs: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old_fields), Value::Object(new_fields)) => {
+            let added = new_fields
+                .keys()
+                .filter(|key| !old_fields.contains_key(*key));
+            for key in old_fields.keys().chain(added) {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                diff_values(
+                    &path,
+                    old_fields.get(key).unwrap_or(&Value::Null),
+                    new_fields.get(key).unwrap_or(&Value::Null),
+                    diffs,
+                );
+            }
+        }
+        _ if old != new => diffs.push(format!("{}: {} -> {}", path, old, new)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_values() {
+        let old = json!({"coin": {"value": "100"}, "frozen": false, "events": [1]});
+        let new = json!({"coin": {"value": "90"}, "frozen": false, "events": [1, 2], "new": 1});
+        let mut diffs = Vec::new();
+        diff_values("", &old, &new, &mut diffs);
+        assert_eq!(
+            vec![
+                "coin.value: \"100\" -> \"90\"".to_string(),
+                "events: [1] -> [1,2]".to_string(),
+                "new: null -> 1".to_string(),
+            ],
+            diffs
+        );
+    }
+}
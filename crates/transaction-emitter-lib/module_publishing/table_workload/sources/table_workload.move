@@ -0,0 +1,109 @@
+/// Inserts, reads and removes entries of tables, spreading each account's keys over several
+/// table handles. The transaction emitter calls it to load storage with table items rather
+/// than resources.
+module table_workload::table_workload {
+    use std::error;
+    use std::signer;
+    use std::vector;
+    use aptos_std::table::{Self, Table};
+
+    /// Tables each account spreads its keys over
+    const NUM_TABLES: u64 = 8;
+
+    /// The account has no tables yet
+    const ENO_TABLES: u64 = 1;
+
+    struct Tables has key {
+        tables: vector<Table<u64, vector<u8>>>,
+    }
+
+    /// Adds or replaces the keys from `first_key` to `first_key + count`, with values of
+    /// `value_size` bytes
+    public entry fun insert(
+        account: &signer,
+        first_key: u64,
+        count: u64,
+        value_size: u64,
+    ) acquires Tables {
+        let addr = signer::address_of(account);
+        if (!exists<Tables>(addr)) {
+            let tables = vector::empty();
+            let i = 0;
+            while (i < NUM_TABLES) {
+                vector::push_back(&mut tables, table::new());
+                i = i + 1;
+            };
+            move_to(account, Tables { tables });
+        };
+
+        let value = vector::empty<u8>();
+        let i = 0;
+        while (i < value_size) {
+            vector::push_back(&mut value, ((i % 256) as u8));
+            i = i + 1;
+        };
+
+        let tables = &mut borrow_global_mut<Tables>(addr).tables;
+        let key = first_key;
+        while (key < first_key + count) {
+            table::upsert(vector::borrow_mut(tables, key % NUM_TABLES), key, copy value);
+            key = key + 1;
+        };
+    }
+
+    /// Reads the keys from `first_key` to `first_key + count` which are present
+    public entry fun read(account: &signer, first_key: u64, count: u64) acquires Tables {
+        let tables = &borrow_global<Tables>(tables_address(account)).tables;
+        let key = first_key;
+        while (key < first_key + count) {
+            let table = vector::borrow(tables, key % NUM_TABLES);
+            if (table::contains(table, key)) {
+                table::borrow(table, key);
+            };
+            key = key + 1;
+        };
+    }
+
+    /// Removes the keys from `first_key` to `first_key + count` which are present
+    public entry fun remove(account: &signer, first_key: u64, count: u64) acquires Tables {
+        let tables = &mut borrow_global_mut<Tables>(tables_address(account)).tables;
+        let key = first_key;
+        while (key < first_key + count) {
+            let table = vector::borrow_mut(tables, key % NUM_TABLES);
+            if (table::contains(table, key)) {
+                table::remove(table, key);
+            };
+            key = key + 1;
+        };
+    }
+
+    /// Address of the account's tables, which must exist
+    fun tables_address(account: &signer): address {
+        let addr = signer::address_of(account);
+        assert!(exists<Tables>(addr), error::not_found(ENO_TABLES));
+        addr
+    }
+
+    #[test_only]
+    fun contains(addr: address, key: u64): bool acquires Tables {
+        let tables = &borrow_global<Tables>(addr).tables;
+        table::contains(vector::borrow(tables, key % NUM_TABLES), key)
+    }
+
+    #[test(account = @0x123)]
+    public entry fun test_insert_read_remove(account: &signer) acquires Tables {
+        insert(account, 0, 20, 100);
+        assert!(contains(@0x123, 0) && contains(@0x123, 19), 0);
+        assert!(!contains(@0x123, 20), 1);
+        read(account, 10, 20);
+        remove(account, 5, 10);
+        assert!(contains(@0x123, 4) && !contains(@0x123, 5) && !contains(@0x123, 14), 2);
+        assert!(contains(@0x123, 15), 3);
+    }
+
+    #[test(account = @0x123)]
+    #[expected_failure(abort_code = 0x60001)]
+    public entry fun test_read_without_tables(account: &signer) acquires Tables {
+        read(account, 0, 1);
+    }
+}
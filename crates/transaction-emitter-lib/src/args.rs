@@ -1,8 +1,15 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{convert::TryFrom, path::Path};
-
+use std::{
+    convert::TryFrom,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    emitter::load_profile::LoadProfile,
+    workload::{GeneratorSpec, PhaseSpec, WorkloadSpec},
+};
 use anyhow::{bail, format_err, Result};
 use aptos::common::types::EncodingType;
 use aptos_config::keys::ConfigKey;
@@ -82,6 +89,14 @@ pub enum TransactionType {
     P2P,
     AccountGeneration,
     NftMintAndTransfer,
+    NftDrop,
+    MultiAgent,
+    Script,
+    LargePackage,
+    PackageUpgrade,
+    ArgSweep,
+    FailureInjection,
+    Conflict,
 }
 
 impl Default for TransactionType {
@@ -94,7 +109,7 @@ impl Default for TransactionType {
 #[clap(group(
     ArgGroup::new("mode")
         .required(true)
-        .args(&["mempool-backlog", "target-tps"]),
+        .args(&["mempool-backlog", "target-tps", "max-tps", "load-profile", "workload"]),
 ))]
 pub struct EmitArgs {
     #[clap(long)]
@@ -108,6 +123,24 @@ pub struct EmitArgs {
     #[clap(long)]
     pub target_tps: Option<usize>,
 
+    /// Max TPS, under which the TPS adapts to the commit latency and mempool backpressure, to
+    /// converge on the max sustainable TPS.
+    #[clap(long)]
+    pub max_tps: Option<usize>,
+
+    /// Target p50 commit latency of the adaptive TPS, in ms.
+    #[clap(long, requires = "max-tps")]
+    pub target_p50_latency_ms: Option<u64>,
+
+    /// Target p99 commit latency of the adaptive TPS, in ms.
+    #[clap(long, requires = "max-tps")]
+    pub target_p99_latency_ms: Option<u64>,
+
+    /// Load profile, in YAML or JSON, see `LoadProfile`, e.g.
+    /// '{shape: ramp, from_tps: 100, to_tps: 1000, duration_secs: 300}'
+    #[clap(long, parse(try_from_str = serde_yaml::from_str))]
+    pub load_profile: Option<LoadProfile>,
+
     #[clap(long, default_value = "30")]
     pub txn_expiration_time_secs: u64,
 
@@ -135,6 +168,51 @@ pub struct EmitArgs {
 
     #[clap(long)]
     pub expected_gas_per_txn: Option<u64>,
+
+    /// YAML or JSON file of the workload, with its generators and phases, see `WorkloadSpec`.
+    /// Replaces the transaction types, their weights, the load and the duration.
+    #[clap(long, parse(from_os_str))]
+    pub workload: Option<PathBuf>,
+}
+
+impl EmitArgs {
+    /// The workload from `--workload`, or else from the transaction types, load and duration.
+    pub fn workload_spec(&self) -> Result<WorkloadSpec> {
+        if let Some(workload) = &self.workload {
+            return WorkloadSpec::load(workload);
+        }
+
+        let weights = if self.transaction_type_weights.is_empty() {
+            vec![1; self.transaction_type.len()]
+        } else if self.transaction_type_weights.len() == self.transaction_type.len() {
+            self.transaction_type_weights.clone()
+        } else {
+            bail!("Transaction types and weights need to be the same length");
+        };
+        let spec = WorkloadSpec {
+            generators: self
+                .transaction_type
+                .iter()
+                .zip(weights)
+                .map(|(transaction_type, weight)| GeneratorSpec::new(*transaction_type, weight))
+                .collect(),
+            phases: vec![PhaseSpec {
+                name: None,
+                duration_secs: self.duration,
+                target_tps: self.target_tps,
+                mempool_backlog: self.mempool_backlog,
+                max_tps: self.max_tps,
+                target_p50_latency_ms: self.target_p50_latency_ms,
+                target_p99_latency_ms: self.target_p99_latency_ms,
+                load_profile: self.load_profile.clone(),
+                generators: vec![],
+            }],
+            mix_per_account: false,
+            txn_expiration_time_secs: self.txn_expiration_time_secs,
+        };
+        spec.validate()?;
+        Ok(spec)
+    }
 }
 
 fn parse_target(target: &str) -> Result<Url> {
@@ -0,0 +1,205 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::{f64::consts::PI, time::Duration};
+
+/// Shape of the TPS over the time of a job, to reproduce traffic spikes rather than only a
+/// steady load. Workers are set up for the peak TPS, and each skips its cycles while the TPS of
+/// the profile is below it.
+///
+/// Example, in a workload phase:
+/// ```yaml
+/// load_profile:
+///   shape: burst
+///   base_tps: 500
+///   burst_tps: 5000
+///   period_secs: 300
+///   burst_secs: 30
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum LoadProfile {
+    Constant {
+        tps: usize,
+    },
+    /// TPS going linearly from `from_tps` to `to_tps` over `duration_secs`, then staying there.
+    /// It ramps down when `to_tps` is below `from_tps`.
+    Ramp {
+        from_tps: usize,
+        to_tps: usize,
+        duration_secs: u64,
+    },
+    /// TPS of each step in turn, staying at the last one.
+    Step {
+        steps: Vec<LoadStep>,
+    },
+    /// TPS going from `min_tps` up to `max_tps` and back every `period_secs`.
+    Sine {
+        min_tps: usize,
+        max_tps: usize,
+        period_secs: u64,
+    },
+    /// `base_tps`, with `burst_tps` for the first `burst_secs` of every `period_secs`.
+    Burst {
+        base_tps: usize,
+        burst_tps: usize,
+        period_secs: u64,
+        burst_secs: u64,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LoadStep {
+    pub tps: usize,
+    pub duration_secs: u64,
+}
+
+impl LoadProfile {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            LoadProfile::Step { steps } if steps.is_empty() => {
+                bail!("Step load profile needs at least one step")
+            }
+            LoadProfile::Sine { period_secs, .. } | LoadProfile::Burst { period_secs, .. }
+                if *period_secs == 0 =>
+            {
+                bail!("Periods of load profiles must be positive")
+            }
+            _ if self.peak_tps() == 0 => bail!("Load profile needs a positive TPS"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Highest TPS of the profile, which the workers are set up for
+    pub fn peak_tps(&self) -> usize {
+        match self {
+            LoadProfile::Constant { tps } => *tps,
+            LoadProfile::Ramp {
+                from_tps, to_tps, ..
+            } => *from_tps.max(to_tps),
+            LoadProfile::Step { steps } => steps.iter().map(|step| step.tps).max().unwrap_or(0),
+            LoadProfile::Sine { max_tps, .. } => *max_tps,
+            LoadProfile::Burst {
+                base_tps,
+                burst_tps,
+                ..
+            } => *base_tps.max(burst_tps),
+        }
+    }
+
+    /// TPS of the profile at `elapsed` since the start of the job
+    pub fn tps_at(&self, elapsed: Duration) -> usize {
+        let secs = elapsed.as_secs_f64();
+        match self {
+            LoadProfile::Constant { tps } => *tps,
+            LoadProfile::Ramp {
+                from_tps,
+                to_tps,
+                duration_secs,
+            } => {
+                let progress = if *duration_secs == 0 {
+                    1.0
+                } else {
+                    (secs / *duration_secs as f64).min(1.0)
+                };
+                (*from_tps as f64 + (*to_tps as f64 - *from_tps as f64) * progress).round() as usize
+            }
+            LoadProfile::Step { steps } => {
+                let mut step_end = 0;
+                for step in steps {
+                    step_end += step.duration_secs;
+                    if elapsed.as_secs() < step_end {
+                        return step.tps;
+                    }
+                }
+                steps.last().map_or(0, |step| step.tps)
+            }
+            LoadProfile::Sine {
+                min_tps,
+                max_tps,
+                period_secs,
+            } => {
+                let phase = 2.0 * PI * secs / *period_secs as f64;
+                (*min_tps as f64 + (*max_tps as f64 - *min_tps as f64) * (1.0 - phase.cos()) / 2.0)
+                    .round() as usize
+            }
+            LoadProfile::Burst {
+                base_tps,
+                burst_tps,
+                period_secs,
+                burst_secs,
+            } => {
+                if elapsed.as_secs() % period_secs < *burst_secs {
+                    *burst_tps
+                } else {
+                    *base_tps
+                }
+            }
+        }
+    }
+
+    /// Workers to keep active at `elapsed`, out of the `num_workers` set up for the peak TPS
+    pub fn active_workers(&self, elapsed: Duration, num_workers: usize) -> usize {
+        let peak_tps = self.peak_tps().max(1);
+        (self.tps_at(elapsed) * num_workers + peak_tps - 1) / peak_tps
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_load_profiles() {
+        let secs = Duration::from_secs;
+
+        let ramp_down = LoadProfile::Ramp {
+            from_tps: 1000,
+            to_tps: 0,
+            duration_secs: 100,
+        };
+        assert_eq!(ramp_down.peak_tps(), 1000);
+        assert_eq!(ramp_down.tps_at(secs(25)), 750);
+        assert_eq!(ramp_down.tps_at(secs(200)), 0);
+        assert_eq!(ramp_down.active_workers(secs(25), 10), 8);
+
+        let step = LoadProfile::Step {
+            steps: vec![
+                LoadStep {
+                    tps: 100,
+                    duration_secs: 10,
+                },
+                LoadStep {
+                    tps: 300,
+                    duration_secs: 10,
+                },
+            ],
+        };
+        assert_eq!(step.tps_at(secs(9)), 100);
+        assert_eq!(step.tps_at(secs(10)), 300);
+        assert_eq!(step.tps_at(secs(60)), 300);
+
+        let sine = LoadProfile::Sine {
+            min_tps: 100,
+            max_tps: 300,
+            period_secs: 60,
+        };
+        assert_eq!(sine.tps_at(secs(0)), 100);
+        assert_eq!(sine.tps_at(secs(15)), 200);
+        assert_eq!(sine.tps_at(secs(30)), 300);
+
+        let burst = LoadProfile::Burst {
+            base_tps: 100,
+            burst_tps: 1000,
+            period_secs: 60,
+            burst_secs: 5,
+        };
+        assert_eq!(burst.tps_at(secs(64)), 1000);
+        assert_eq!(burst.tps_at(secs(65)), 100);
+
+        assert!(LoadProfile::Step { steps: vec![] }.validate().is_err());
+        assert!(LoadProfile::Constant { tps: 0 }.validate().is_err());
+    }
+}
@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod account_minter;
+pub mod load_profile;
 pub mod stats;
 pub mod submission_worker;
+pub mod tps_controller;
 
 use again::RetryPolicy;
 use anyhow::{anyhow, format_err, Result};
@@ -36,15 +38,41 @@ use crate::{
     args::TransactionType,
     emitter::{
         account_minter::AccountMinter,
+        load_profile::LoadProfile,
         stats::{DynamicStatsTracking, TxnStats},
-        submission_worker::SubmissionWorker,
+        submission_worker::{ActiveWorkers, PhaseWorkers, SubmissionWorker},
+        tps_controller::{run_tps_controller, TpsController},
     },
     transaction_generator::{
         account_generator::AccountGeneratorCreator,
+        arg_sweep_generator::{
+            ArgSweepGeneratorCreator, DEFAULT_MAX_ARG_SIZE, DEFAULT_MIN_ARG_SIZE,
+        },
+        conflict_generator::{ConflictGeneratorCreator, DEFAULT_CONFLICT_RATIO},
+        event_workload::{
+            EventWorkloadGeneratorCreator, DEFAULT_EVENTS_PER_TRANSACTION, DEFAULT_EVENT_SIZE,
+        },
+        failure_injection::{FailureInjectionGeneratorCreator, DEFAULT_FAILURE_RATIO},
+        large_package_generator::{LargePackageGeneratorCreator, DEFAULT_PACKAGE_SIZE},
+        mixed_generator::MixedGeneratorCreator,
+        multi_agent::MultiAgentGeneratorCreator,
+        nft_drop::{NftDropGeneratorCreator, DEFAULT_NUM_COLLECTIONS, DEFAULT_PROPERTY_MAP_SIZE},
         nft_mint_and_transfer::NFTMintAndTransferGeneratorCreator,
         p2p_transaction_generator::P2PTransactionGeneratorCreator,
-        transaction_mix_generator::TxnMixGeneratorCreator, TransactionGeneratorCreator,
+        package_upgrade_generator::{
+            PackageUpgradeGeneratorCreator, DEFAULT_INITIAL_SIZE, DEFAULT_MAX_SIZE,
+            DEFAULT_SIZE_DELTA,
+        },
+        publishing::upgrade::UpgradeSizes,
+        script_generator::ScriptGeneratorCreator,
+        table_workload::{
+            TableWorkloadGeneratorCreator, DEFAULT_KEYS_PER_ACCOUNT, DEFAULT_KEYS_PER_TRANSACTION,
+            DEFAULT_VALUE_SIZE,
+        },
+        transaction_mix_generator::TxnMixGeneratorCreator,
+        TransactionGeneratorCreator,
     },
+    workload::{GeneratorKind, GeneratorSpec},
 };
 use aptos_sdk::transaction_builder::aptos_stdlib;
 use rand::rngs::StdRng;
@@ -83,8 +111,23 @@ pub struct EmitModeParams {
 
 #[derive(Clone, Debug)]
 pub enum EmitJobMode {
-    MaxLoad { mempool_backlog: usize },
-    ConstTps { tps: usize },
+    MaxLoad {
+        mempool_backlog: usize,
+    },
+    ConstTps {
+        tps: usize,
+    },
+    /// TPS adjusted by a closed loop controller, up to `max_tps`, to converge on the max TPS
+    /// sustained within the latency targets.
+    Adaptive {
+        max_tps: usize,
+        target_p50_latency_ms: u64,
+        target_p99_latency_ms: u64,
+    },
+    /// TPS following the shape of the profile over time.
+    LoadProfile {
+        profile: LoadProfile,
+    },
 }
 
 impl EmitJobMode {
@@ -105,6 +148,13 @@ impl EmitJobMode {
     }
 }
 
+/// A phase of a job, with its own mode and generators, started with `EmitJob::start_next_phase`.
+#[derive(Clone, Debug)]
+pub struct EmitJobPhase {
+    pub mode: EmitJobMode,
+    pub generators: Vec<GeneratorSpec>,
+}
+
 /// total coins consumed are less than 2 * max_txns * expected_gas_per_txn,
 /// which is by default 100000000000 * 100000, but can be overriden.
 #[derive(Clone, Debug)]
@@ -117,7 +167,9 @@ pub struct EmitJobRequest {
     reuse_accounts: bool,
     mint_to_root: bool,
 
-    transaction_mix: Vec<(TransactionType, usize)>,
+    transaction_mix: Vec<GeneratorSpec>,
+    mix_per_account: bool,
+    phases: Vec<EmitJobPhase>,
 
     add_created_accounts_to_pool: bool,
     max_account_working_set: usize,
@@ -139,7 +191,9 @@ impl Default for EmitJobRequest {
             invalid_transaction_ratio: 0,
             reuse_accounts: false,
             mint_to_root: false,
-            transaction_mix: vec![(TransactionType::P2P, 1)],
+            transaction_mix: vec![GeneratorSpec::new(TransactionType::P2P, 1)],
+            mix_per_account: false,
+            phases: Vec::new(),
             add_created_accounts_to_pool: true,
             max_account_working_set: 1_000_000,
             txn_expiration_time_secs: 60,
@@ -186,12 +240,27 @@ impl EmitJobRequest {
     }
 
     pub fn transaction_type(mut self, transaction_type: TransactionType) -> Self {
-        self.transaction_mix = vec![(transaction_type, 1)];
+        self.transaction_mix = vec![GeneratorSpec::new(transaction_type, 1)];
         self
     }
 
     pub fn transaction_mix(mut self, transaction_mix: Vec<(TransactionType, usize)>) -> Self {
-        self.transaction_mix = transaction_mix;
+        self.transaction_mix = transaction_mix
+            .into_iter()
+            .map(|(transaction_type, weight)| GeneratorSpec::new(transaction_type, weight))
+            .collect();
+        self
+    }
+
+    /// Sets the generators, with their own parameters, as in a workload spec.
+    pub fn generators(mut self, generators: Vec<GeneratorSpec>) -> Self {
+        self.transaction_mix = generators;
+        self
+    }
+
+    /// Picks the generator of the mix for each account, rather than for each batch.
+    pub fn mix_per_account(mut self) -> Self {
+        self.mix_per_account = true;
         self
     }
 
@@ -200,6 +269,25 @@ impl EmitJobRequest {
         self
     }
 
+    /// Sets the phases the job goes through, each with its own mode and generators instead of
+    /// the job's. The same accounts and workers are kept through all of them.
+    pub fn phases(mut self, phases: Vec<EmitJobPhase>) -> Self {
+        self.phases = phases;
+        self
+    }
+
+    /// Phases of the job, or its own mode and generators if it has none
+    fn job_phases(&self) -> Vec<EmitJobPhase> {
+        if self.phases.is_empty() {
+            vec![EmitJobPhase {
+                mode: self.mode.clone(),
+                generators: self.transaction_mix.clone(),
+            }]
+        } else {
+            self.phases.clone()
+        }
+    }
+
     pub fn reuse_accounts(mut self) -> Self {
         self.reuse_accounts = true;
         self
@@ -221,9 +309,13 @@ impl EmitJobRequest {
     }
 
     pub fn calculate_mode_params(&self) -> EmitModeParams {
+        self.mode_params(&self.mode)
+    }
+
+    fn mode_params(&self, mode: &EmitJobMode) -> EmitModeParams {
         let clients_count = self.rest_clients.len();
 
-        match self.mode {
+        match *mode {
             EmitJobMode::MaxLoad { mempool_backlog } => {
                 // The target mempool backlog is set to be 3x of the target TPS because of the on an average,
                 // we can ~3 blocks in consensus queue. As long as we have 3x the target TPS as backlog,
@@ -258,7 +350,17 @@ impl EmitJobRequest {
                     check_account_sequence_sleep_millis: 300,
                 }
             }
-            EmitJobMode::ConstTps { tps } => {
+            // The adaptive and load profile modes are set up as the ConstTps mode at their max
+            // TPS, with part of their workers skipping their cycles.
+            EmitJobMode::ConstTps { .. }
+            | EmitJobMode::Adaptive { .. }
+            | EmitJobMode::LoadProfile { .. } => {
+                let tps = match mode {
+                    EmitJobMode::ConstTps { tps } => *tps,
+                    EmitJobMode::Adaptive { max_tps, .. } => *max_tps,
+                    EmitJobMode::LoadProfile { profile } => profile.peak_tps(),
+                    EmitJobMode::MaxLoad { .. } => unreachable!(),
+                };
                 // We are going to create ConstTps (open-loop) txn-emitter, by:
                 // - having a single worker handle a single account, with:
                 //   - issuing a batch request (which generally either suceeeds or fails)
@@ -348,11 +450,16 @@ pub struct EmitJob {
     workers: Vec<Worker>,
     stop: Arc<AtomicBool>,
     stats: Arc<DynamicStatsTracking>,
+    active_workers: Arc<ActiveWorkers>,
+    tps_controller: Option<JoinHandle<()>>,
 }
 
 impl EmitJob {
+    /// Starts tracking the stats of the next phase, switching the workers to its mode and
+    /// generators if the job has phases.
     pub fn start_next_phase(&self) {
         self.stats.start_next_phase();
+        self.active_workers.start_next_phase();
     }
 
     pub fn get_cur_phase(&self) -> usize {
@@ -398,7 +505,17 @@ impl TxnEmitter {
         req: EmitJobRequest,
         stats_tracking_phases: usize,
     ) -> Result<EmitJob> {
-        let mode_params = req.calculate_mode_params();
+        let job_phases = req.job_phases();
+        let phase_params: Vec<_> = job_phases
+            .iter()
+            .map(|phase| req.mode_params(&phase.mode))
+            .collect();
+        // the workers of each phase are the first of the job's, sized for the largest phase
+        let mode_params = phase_params
+            .iter()
+            .max_by_key(|params| params.workers_per_endpoint)
+            .unwrap()
+            .clone();
         let workers_per_endpoint = mode_params.workers_per_endpoint;
         let num_workers = req.rest_clients.len() * workers_per_endpoint;
         let num_accounts = num_workers * mode_params.accounts_per_worker;
@@ -418,49 +535,59 @@ impl TxnEmitter {
         let mut all_accounts = all_accounts.into_iter();
         let stop = Arc::new(AtomicBool::new(false));
         let stats = Arc::new(DynamicStatsTracking::new(stats_tracking_phases));
+        let tps_controllers: Vec<_> = job_phases
+            .iter()
+            .map(|phase| match phase.mode {
+                EmitJobMode::Adaptive {
+                    max_tps,
+                    target_p50_latency_ms,
+                    target_p99_latency_ms,
+                } => Some(TpsController::new(
+                    max_tps,
+                    target_p50_latency_ms,
+                    target_p99_latency_ms,
+                )),
+                _ => None,
+            })
+            .collect();
+        let active_workers = Arc::new(ActiveWorkers::new(
+            job_phases
+                .iter()
+                .zip(&phase_params)
+                .zip(&tps_controllers)
+                .map(|((phase, params), tps_controller)| {
+                    let num_workers = req.rest_clients.len() * params.workers_per_endpoint;
+                    PhaseWorkers {
+                        num_workers,
+                        active: tps_controller.as_ref().map_or(num_workers, |controller| {
+                            controller.active_workers(num_workers)
+                        }),
+                        load_profile: match &phase.mode {
+                            EmitJobMode::LoadProfile { profile } => Some(profile.clone()),
+                            _ => None,
+                        },
+                    }
+                })
+                .collect(),
+        ));
         let tokio_handle = Handle::current();
         let txn_factory = self
             .txn_factory
             .clone()
             .with_transaction_expiration_time(mode_params.txn_expiration_time_secs);
-        let mut txn_generator_creator_mix: Vec<(Box<dyn TransactionGeneratorCreator>, usize)> =
-            Vec::new();
-        for (transaction_type, weight) in req.transaction_mix {
-            let txn_generator_creator: Box<dyn TransactionGeneratorCreator> = match transaction_type
-            {
-                TransactionType::P2P => Box::new(P2PTransactionGeneratorCreator::new(
-                    self.from_rng(),
-                    txn_factory.clone(),
-                    SEND_AMOUNT,
-                    all_addresses.clone(),
-                    req.invalid_transaction_ratio,
-                    req.gas_price,
-                )),
-                TransactionType::AccountGeneration => Box::new(AccountGeneratorCreator::new(
-                    txn_factory.clone(),
-                    all_addresses.clone(),
-                    req.add_created_accounts_to_pool,
-                    req.max_account_working_set,
-                    req.gas_price,
-                )),
-                TransactionType::NftMintAndTransfer => Box::new(
-                    NFTMintAndTransferGeneratorCreator::new(
-                        self.from_rng(),
-                        txn_factory.clone(),
-                        root_account,
-                        req.rest_clients[0].clone(),
-                    )
-                    .await,
-                ),
-            };
-            txn_generator_creator_mix.push((txn_generator_creator, weight));
+        let mut txn_generator_creators = Vec::with_capacity(job_phases.len());
+        for phase in job_phases {
+            txn_generator_creators.push(
+                self.create_txn_generator_creator(
+                    root_account,
+                    &req,
+                    &txn_factory,
+                    &all_addresses,
+                    phase.generators,
+                )
+                .await,
+            );
         }
-        let txn_generator_creator: Box<dyn TransactionGeneratorCreator> =
-            if txn_generator_creator_mix.len() > 1 {
-                Box::new(TxnMixGeneratorCreator::new(txn_generator_creator_mix))
-            } else {
-                txn_generator_creator_mix.into_iter().next().unwrap().0
-            };
 
         let total_workers = req.rest_clients.len() * workers_per_endpoint;
 
@@ -487,17 +614,21 @@ impl TxnEmitter {
                     .collect::<Vec<_>>();
                 let stop = stop.clone();
                 let stats = Arc::clone(&stats);
-                let txn_generator = txn_generator_creator.create_transaction_generator().await;
+                let mut txn_generators = Vec::with_capacity(txn_generator_creators.len());
+                for txn_generator_creator in &txn_generator_creators {
+                    txn_generators.push(txn_generator_creator.create_transaction_generator().await);
+                }
 
                 let worker = SubmissionWorker::new(
                     accounts,
                     client.clone(),
                     stop,
-                    mode_params.clone(),
+                    phase_params.clone(),
                     stats,
-                    txn_generator,
+                    txn_generators,
                     workers.len(),
                     check_account_sequence_only_once_for.contains(&workers.len()),
+                    active_workers.clone(),
                     self.from_rng(),
                 );
                 let join_handle = tokio_handle.spawn(worker.run().boxed());
@@ -505,15 +636,175 @@ impl TxnEmitter {
             }
         }
         info!("Tx emitter workers started");
+        let tps_controller = if tps_controllers.iter().any(Option::is_some) {
+            Some(tokio_handle.spawn(run_tps_controller(
+                tps_controllers,
+                stats.clone(),
+                active_workers.clone(),
+                stop.clone(),
+            )))
+        } else {
+            None
+        };
         Ok(EmitJob {
             workers,
             stop,
             stats,
+            active_workers,
+            tps_controller,
         })
     }
 
+    /// Creator of the generators of a phase, picking one of them by weight if there are several
+    async fn create_txn_generator_creator(
+        &mut self,
+        root_account: &mut LocalAccount,
+        req: &EmitJobRequest,
+        txn_factory: &TransactionFactory,
+        all_addresses: &Arc<RwLock<Vec<AccountAddress>>>,
+        generators: Vec<GeneratorSpec>,
+    ) -> Box<dyn TransactionGeneratorCreator> {
+        let mut txn_generator_creator_mix: Vec<(Box<dyn TransactionGeneratorCreator>, usize)> =
+            Vec::new();
+        for generator in generators {
+            let gas_price = generator.gas_price.unwrap_or(req.gas_price);
+            let txn_factory = txn_factory.clone().with_gas_unit_price(gas_price);
+            let txn_generator_creator: Box<dyn TransactionGeneratorCreator> = match generator.kind {
+                GeneratorKind::P2p {
+                    send_amount,
+                    invalid_transaction_ratio,
+                    recipients,
+                } => Box::new(P2PTransactionGeneratorCreator::new(
+                    self.from_rng(),
+                    txn_factory,
+                    send_amount.unwrap_or(SEND_AMOUNT),
+                    all_addresses.clone(),
+                    invalid_transaction_ratio.unwrap_or(req.invalid_transaction_ratio),
+                    gas_price,
+                    recipients.unwrap_or_default(),
+                )),
+                GeneratorKind::AccountGeneration {
+                    add_created_accounts_to_pool,
+                    max_account_working_set,
+                } => Box::new(AccountGeneratorCreator::new(
+                    txn_factory,
+                    all_addresses.clone(),
+                    add_created_accounts_to_pool.unwrap_or(req.add_created_accounts_to_pool),
+                    max_account_working_set.unwrap_or(req.max_account_working_set),
+                    gas_price,
+                )),
+                GeneratorKind::NftMintAndTransfer {} => Box::new(
+                    NFTMintAndTransferGeneratorCreator::new(
+                        self.from_rng(),
+                        txn_factory,
+                        root_account,
+                        req.rest_clients[0].clone(),
+                    )
+                    .await,
+                ),
+                GeneratorKind::NftDrop {
+                    num_collections,
+                    property_map_size,
+                } => Box::new(NftDropGeneratorCreator::new(
+                    txn_factory,
+                    num_collections.unwrap_or(DEFAULT_NUM_COLLECTIONS),
+                    property_map_size.unwrap_or(DEFAULT_PROPERTY_MAP_SIZE),
+                )),
+                GeneratorKind::MultiAgent { module_address } => {
+                    Box::new(MultiAgentGeneratorCreator::new(txn_factory, module_address))
+                }
+                GeneratorKind::Script { send_amount } => Box::new(ScriptGeneratorCreator::new(
+                    self.from_rng(),
+                    txn_factory,
+                    send_amount.unwrap_or(SEND_AMOUNT),
+                    all_addresses.clone(),
+                )),
+                GeneratorKind::LargePackage { package_size } => {
+                    Box::new(LargePackageGeneratorCreator::new(
+                        txn_factory,
+                        package_size.unwrap_or(DEFAULT_PACKAGE_SIZE),
+                    ))
+                }
+                GeneratorKind::PackageUpgrade {
+                    initial_size,
+                    size_delta,
+                    max_size,
+                } => Box::new(PackageUpgradeGeneratorCreator::new(
+                    txn_factory,
+                    UpgradeSizes {
+                        initial_size: initial_size.unwrap_or(DEFAULT_INITIAL_SIZE),
+                        size_delta: size_delta.unwrap_or(DEFAULT_SIZE_DELTA),
+                        max_size: max_size.unwrap_or(DEFAULT_MAX_SIZE),
+                    },
+                )),
+                GeneratorKind::ArgSweep { min_size, max_size } => {
+                    Box::new(ArgSweepGeneratorCreator::new(
+                        txn_factory,
+                        min_size.unwrap_or(DEFAULT_MIN_ARG_SIZE),
+                        max_size.unwrap_or(DEFAULT_MAX_ARG_SIZE),
+                    ))
+                }
+                GeneratorKind::TableWorkload {
+                    module_address,
+                    keys_per_account,
+                    keys_per_transaction,
+                    value_size,
+                } => Box::new(TableWorkloadGeneratorCreator::new(
+                    txn_factory,
+                    module_address,
+                    keys_per_account.unwrap_or(DEFAULT_KEYS_PER_ACCOUNT),
+                    keys_per_transaction.unwrap_or(DEFAULT_KEYS_PER_TRANSACTION),
+                    value_size.unwrap_or(DEFAULT_VALUE_SIZE),
+                )),
+                GeneratorKind::EventWorkload {
+                    module_address,
+                    events_per_transaction,
+                    event_size,
+                } => Box::new(EventWorkloadGeneratorCreator::new(
+                    txn_factory,
+                    module_address,
+                    events_per_transaction.unwrap_or(DEFAULT_EVENTS_PER_TRANSACTION),
+                    event_size.unwrap_or(DEFAULT_EVENT_SIZE),
+                )),
+                GeneratorKind::FailureInjection {
+                    failure_ratio,
+                    kinds,
+                    send_amount,
+                } => Box::new(FailureInjectionGeneratorCreator::new(
+                    self.from_rng(),
+                    txn_factory,
+                    send_amount.unwrap_or(SEND_AMOUNT),
+                    all_addresses.clone(),
+                    failure_ratio.unwrap_or(DEFAULT_FAILURE_RATIO),
+                    kinds,
+                )),
+                GeneratorKind::Conflict {
+                    conflict_ratio,
+                    send_amount,
+                } => Box::new(ConflictGeneratorCreator::new(
+                    self.from_rng(),
+                    txn_factory,
+                    send_amount.unwrap_or(SEND_AMOUNT),
+                    all_addresses.clone(),
+                    conflict_ratio.unwrap_or(DEFAULT_CONFLICT_RATIO),
+                )),
+            };
+            txn_generator_creator_mix.push((txn_generator_creator, generator.weight));
+        }
+        if txn_generator_creator_mix.len() > 1 && req.mix_per_account {
+            Box::new(MixedGeneratorCreator::new(txn_generator_creator_mix))
+        } else if txn_generator_creator_mix.len() > 1 {
+            Box::new(TxnMixGeneratorCreator::new(txn_generator_creator_mix))
+        } else {
+            txn_generator_creator_mix.into_iter().next().unwrap().0
+        }
+    }
+
     pub async fn stop_job(&mut self, job: EmitJob) -> Vec<TxnStats> {
         job.stop.store(true, Ordering::Relaxed);
+        if let Some(tps_controller) = job.tps_controller {
+            tps_controller.abort();
+        }
         for worker in job.workers {
             let mut accounts = worker
                 .join_handle
@@ -579,6 +870,42 @@ impl TxnEmitter {
         Ok(stats.into_iter().next().unwrap())
     }
 
+    /// Runs the job through its phases, each for its duration, returning the stats of each of
+    /// them.
+    pub async fn emit_txn_phases_with_stats(
+        &mut self,
+        source_account: &mut LocalAccount,
+        emit_job_request: EmitJobRequest,
+        phase_durations: &[Duration],
+    ) -> Result<Vec<TxnStats>> {
+        let total: Duration = phase_durations.iter().sum();
+        info!(
+            "Starting emitting txns for {} secs, in {} phases",
+            total.as_secs(),
+            phase_durations.len()
+        );
+        let job = self
+            .start_job(source_account, emit_job_request, phase_durations.len())
+            .await?;
+        for (index, duration) in phase_durations.iter().enumerate() {
+            if index > 0 {
+                job.start_next_phase();
+            }
+            info!(
+                "Phase {} of {} for {} secs",
+                index + 1,
+                phase_durations.len(),
+                duration.as_secs()
+            );
+            self.periodic_stat(&job, *duration, min(10, max(duration.as_secs() / 5, 1)))
+                .await;
+        }
+        info!("Ran for {} secs, stopping job...", total.as_secs());
+        let stats = self.stop_job(job).await;
+        info!("Stopped job");
+        Ok(stats)
+    }
+
     pub async fn submit_single_transaction(
         &self,
         client: &RestClient,
@@ -631,38 +958,50 @@ async fn wait_for_single_account_sequence(
 /// It returns number of transactions that expired without being committed,
 /// and sum of completion timestamps for those that have.
 ///
+/// Only the sequence numbers the accounts advanced by since `start_sequence_numbers` are
+/// waited for, so transactions that don't take a sequence number of their own (rejected or
+/// conflicting ones) don't keep the worker waiting until expiration.
+///
 /// This function updates sequence_number for the account to match what
 /// we were able to fetch last.
 async fn wait_for_accounts_sequence(
     start_time: Instant,
     client: &RestClient,
     accounts: &mut [LocalAccount],
-    transactions_per_account: usize,
+    start_sequence_numbers: &[u64],
     txn_expiration_ts_secs: u64,
     sleep_between_cycles: Duration,
 ) -> (usize, u128) {
-    let mut pending_addresses: HashSet<_> = accounts.iter().map(|d| d.address()).collect();
+    assert_eq!(accounts.len(), start_sequence_numbers.len());
+    // indices of the accounts still waited for, queried in the order of the accounts
+    let mut pending: Vec<usize> = (0..accounts.len()).collect();
     let mut latest_fetched_counts = HashMap::new();
 
     let mut sum_of_completion_timestamps_millis = 0u128;
     loop {
+        let pending_addresses: Vec<_> = pending
+            .iter()
+            .map(|index| accounts[*index].address())
+            .collect();
         match query_sequence_numbers(client, pending_addresses.iter()).await {
             Ok((sequence_numbers, ledger_timestamp_secs)) => {
                 let millis_elapsed = start_time.elapsed().as_millis();
-                for (account, sequence_number) in zip(accounts.iter_mut(), &sequence_numbers) {
+                for (index, sequence_number) in zip(&pending, &sequence_numbers) {
                     let prev_sequence_number = latest_fetched_counts
-                        .insert(account.address(), *sequence_number)
-                        .unwrap_or(account.sequence_number() - transactions_per_account as u64);
+                        .insert(accounts[*index].address(), *sequence_number)
+                        .unwrap_or(start_sequence_numbers[*index]);
                     assert!(prev_sequence_number <= *sequence_number);
                     sum_of_completion_timestamps_millis +=
                         millis_elapsed * (*sequence_number - prev_sequence_number) as u128;
-
-                    if account.sequence_number() == *sequence_number {
-                        pending_addresses.remove(&account.address());
-                    }
                 }
-
-                if pending_addresses.is_empty() {
+                pending = zip(&pending, &sequence_numbers)
+                    .filter(|(index, sequence_number)| {
+                        accounts[**index].sequence_number() != **sequence_number
+                    })
+                    .map(|(index, _)| *index)
+                    .collect();
+
+                if pending.is_empty() {
                     break;
                 }
 
@@ -712,30 +1051,25 @@ async fn wait_for_accounts_sequence(
     }
 
     (
-        update_seq_num_and_get_num_expired(
-            accounts,
-            transactions_per_account,
-            latest_fetched_counts,
-        ),
+        update_seq_num_and_get_num_expired(accounts, start_sequence_numbers, latest_fetched_counts),
         sum_of_completion_timestamps_millis,
     )
 }
 
 fn update_seq_num_and_get_num_expired(
     accounts: &mut [LocalAccount],
-    transactions_per_account: usize,
+    start_sequence_numbers: &[u64],
     latest_fetched_counts: HashMap<AccountAddress, u64>,
 ) -> usize {
     accounts
         .iter_mut()
-        .filter_map(
-            |account| match latest_fetched_counts.get(&account.address()) {
+        .zip(start_sequence_numbers)
+        .filter_map(|(account, start_sequence_number)| {
+            match latest_fetched_counts.get(&account.address()) {
                 Some(count) => {
                     if *count != account.sequence_number() {
                         assert!(account.sequence_number() > *count);
-                        assert!(
-                            account.sequence_number() <= count + transactions_per_account as u64
-                        );
+                        assert!(*count >= *start_sequence_number);
                         let diff = (account.sequence_number() - count) as usize;
                         debug!(
                             "Stale sequence_number for {}, expected {}, setting to {}",
@@ -754,13 +1088,14 @@ fn update_seq_num_and_get_num_expired(
                         "Couldn't fetch sequence_number for {}, expected {}, setting to {}",
                         account.address(),
                         account.sequence_number(),
-                        account.sequence_number() - transactions_per_account as u64
+                        start_sequence_number
                     );
-                    *account.sequence_number_mut() -= transactions_per_account as u64;
-                    Some(transactions_per_account)
+                    let diff = (account.sequence_number() - start_sequence_number) as usize;
+                    *account.sequence_number_mut() = *start_sequence_number;
+                    Some(diff)
                 }
-            },
-        )
+            }
+        })
         .sum()
 }
 
@@ -806,3 +1141,49 @@ pub fn gen_transfer_txn_request(
         txn_factory.payload(aptos_stdlib::aptos_coin_transfer(*receiver, num_coins)),
     )
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use url::Url;
+
+    #[test]
+    pub fn test_job_phases() {
+        let client = RestClient::new(Url::parse("http://localhost:8080").unwrap());
+        let req = EmitJobRequest::new(vec![client]).mode(EmitJobMode::ConstTps { tps: 10 });
+        let phases = req.job_phases();
+        assert_eq!(phases.len(), 1);
+        assert!(matches!(phases[0].mode, EmitJobMode::ConstTps { tps: 10 }));
+
+        let req = req.phases(vec![
+            EmitJobPhase {
+                mode: EmitJobMode::MaxLoad {
+                    mempool_backlog: 2000,
+                },
+                generators: vec![GeneratorSpec::new(TransactionType::P2P, 1)],
+            },
+            EmitJobPhase {
+                mode: EmitJobMode::ConstTps { tps: 100 },
+                generators: vec![GeneratorSpec::new(TransactionType::AccountGeneration, 1)],
+            },
+        ]);
+        let phases = req.job_phases();
+        assert_eq!(phases.len(), 2);
+        assert_eq!(
+            phases[1].generators[0].kind,
+            GeneratorKind::AccountGeneration {
+                add_created_accounts_to_pool: None,
+                max_account_working_set: None,
+            }
+        );
+        // each phase paces its own workers, out of the job's
+        let params: Vec<_> = phases
+            .iter()
+            .map(|phase| req.mode_params(&phase.mode))
+            .collect();
+        assert_eq!(params[0].workers_per_endpoint, 100);
+        assert_eq!(params[0].wait_millis, 0);
+        assert_eq!(params[1].workers_per_endpoint, 1200);
+        assert_eq!(params[1].wait_millis, 240_000);
+    }
+}
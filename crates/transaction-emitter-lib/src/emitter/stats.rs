@@ -1,12 +1,13 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::emitter::tps_controller::TpsStep;
 use std::{
     fmt,
-    ops::Sub,
+    ops::{Add, Sub},
     sync::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
-        Arc,
+        Arc, Mutex,
     },
     time::Duration,
 };
@@ -20,6 +21,16 @@ pub struct TxnStats {
     pub latency: u64,
     pub latency_samples: u64,
     pub latency_buckets: AtomicHistogramSnapshot,
+    /// Transactions submitted after a later sequence number of their account, by whether they
+    /// were committed or rejected.
+    pub out_of_order_committed: u64,
+    pub out_of_order_rejected: u64,
+    /// Transactions with the sequence number of an earlier one of their account, by whether they
+    /// were committed in its place or rejected.
+    pub duplicate_committed: u64,
+    pub duplicate_rejected: u64,
+    /// Steps of the adaptive TPS controller, if the job had one.
+    pub tps_trajectory: Vec<TpsStep>,
 }
 
 #[derive(Debug, Default)]
@@ -75,7 +86,54 @@ impl fmt::Display for TxnStats {
             f,
             "submitted: {}, committed: {}, expired: {}, failed submission: {}",
             self.submitted, self.committed, self.expired, self.failed_submission,
-        )
+        )?;
+        if self.out_of_order_committed + self.out_of_order_rejected > 0 {
+            write!(
+                f,
+                ", out of order committed: {}, rejected: {}",
+                self.out_of_order_committed, self.out_of_order_rejected,
+            )?;
+        }
+        if self.duplicate_committed + self.duplicate_rejected > 0 {
+            write!(
+                f,
+                ", duplicate committed: {}, rejected: {}",
+                self.duplicate_committed, self.duplicate_rejected,
+            )?;
+        }
+        if !self.tps_trajectory.is_empty() {
+            write!(f, ", adaptive TPS trajectory:")?;
+            for step in &self.tps_trajectory {
+                write!(f, "\n  {}", step)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Add for &TxnStats {
+    type Output = TxnStats;
+
+    fn add(self, other: &TxnStats) -> TxnStats {
+        TxnStats {
+            submitted: self.submitted + other.submitted,
+            committed: self.committed + other.committed,
+            expired: self.expired + other.expired,
+            failed_submission: self.failed_submission + other.failed_submission,
+            latency: self.latency + other.latency,
+            latency_samples: self.latency_samples + other.latency_samples,
+            latency_buckets: &self.latency_buckets + &other.latency_buckets,
+            out_of_order_committed: self.out_of_order_committed + other.out_of_order_committed,
+            out_of_order_rejected: self.out_of_order_rejected + other.out_of_order_rejected,
+            duplicate_committed: self.duplicate_committed + other.duplicate_committed,
+            duplicate_rejected: self.duplicate_rejected + other.duplicate_rejected,
+            tps_trajectory: self
+                .tps_trajectory
+                .iter()
+                .chain(&other.tps_trajectory)
+                .cloned()
+                .collect(),
+        }
     }
 }
 
@@ -91,6 +149,17 @@ impl Sub for &TxnStats {
             latency: self.latency - other.latency,
             latency_samples: self.latency_samples - other.latency_samples,
             latency_buckets: &self.latency_buckets - &other.latency_buckets,
+            out_of_order_committed: self.out_of_order_committed - other.out_of_order_committed,
+            out_of_order_rejected: self.out_of_order_rejected - other.out_of_order_rejected,
+            duplicate_committed: self.duplicate_committed - other.duplicate_committed,
+            duplicate_rejected: self.duplicate_rejected - other.duplicate_rejected,
+            // the steps taken since `other`
+            tps_trajectory: self
+                .tps_trajectory
+                .iter()
+                .skip(other.tps_trajectory.len())
+                .cloned()
+                .collect(),
         }
     }
 }
@@ -104,6 +173,11 @@ pub struct StatsAccumulator {
     pub latency: AtomicU64,
     pub latency_samples: AtomicU64,
     pub latencies: Arc<AtomicHistogramAccumulator>,
+    pub out_of_order_committed: AtomicU64,
+    pub out_of_order_rejected: AtomicU64,
+    pub duplicate_committed: AtomicU64,
+    pub duplicate_rejected: AtomicU64,
+    pub tps_trajectory: Mutex<Vec<TpsStep>>,
 }
 
 impl StatsAccumulator {
@@ -116,6 +190,11 @@ impl StatsAccumulator {
             latency: self.latency.load(Ordering::Relaxed),
             latency_samples: self.latency_samples.load(Ordering::Relaxed),
             latency_buckets: self.latencies.snapshot(),
+            out_of_order_committed: self.out_of_order_committed.load(Ordering::Relaxed),
+            out_of_order_rejected: self.out_of_order_rejected.load(Ordering::Relaxed),
+            duplicate_committed: self.duplicate_committed.load(Ordering::Relaxed),
+            duplicate_rejected: self.duplicate_rejected.load(Ordering::Relaxed),
+            tps_trajectory: self.tps_trajectory.lock().unwrap().clone(),
         }
     }
 }
@@ -190,6 +269,30 @@ impl Default for AtomicHistogramSnapshot {
     }
 }
 
+impl Add for &AtomicHistogramSnapshot {
+    type Output = AtomicHistogramSnapshot;
+
+    fn add(self, other: &AtomicHistogramSnapshot) -> AtomicHistogramSnapshot {
+        assert_eq!(
+            self.buckets.len(),
+            other.buckets.len(),
+            "Histogram snapshots must have same size, prev: {}, cur: {}",
+            self.buckets.len(),
+            other.buckets.len()
+        );
+        AtomicHistogramSnapshot {
+            capacity: self.capacity,
+            step_width: self.step_width,
+            buckets: self
+                .buckets
+                .iter()
+                .zip(&other.buckets)
+                .map(|(a, b)| a + b)
+                .collect(),
+        }
+    }
+}
+
 impl Sub for &AtomicHistogramSnapshot {
     type Output = AtomicHistogramSnapshot;
 
@@ -267,9 +370,10 @@ impl DynamicStatsTracking {
 #[cfg(test)]
 mod test {
     use crate::emitter::stats::{
-        AtomicHistogramAccumulator, AtomicHistogramSnapshot, TxnStats, DEFAULT_HISTOGRAM_CAPACITY,
-        DEFAULT_HISTOGRAM_STEP_WIDTH,
+        AtomicHistogramAccumulator, AtomicHistogramSnapshot, DynamicStatsTracking, TxnStats,
+        DEFAULT_HISTOGRAM_CAPACITY, DEFAULT_HISTOGRAM_STEP_WIDTH,
     };
+    use std::sync::atomic::Ordering;
 
     #[test]
     pub fn test_default_atomic_histogram() {
@@ -336,8 +440,25 @@ mod test {
             latency: 0,
             latency_samples: 0,
             latency_buckets: histogram.snapshot(),
+            tps_trajectory: vec![],
+            ..Default::default()
         };
         let res = stat.latency_buckets.percentile(9, 10);
         assert_eq!(res, 900);
     }
+
+    #[test]
+    pub fn test_phase_stats() {
+        let stats = DynamicStatsTracking::new(2);
+        stats.get_cur().committed.fetch_add(5, Ordering::Relaxed);
+        stats.start_next_phase();
+        assert_eq!(stats.get_cur_phase(), 1);
+        stats.get_cur().committed.fetch_add(7, Ordering::Relaxed);
+        stats.get_cur().expired.fetch_add(1, Ordering::Relaxed);
+
+        let phases = stats.accumulate();
+        assert_eq!(phases.len(), 2);
+        assert_eq!((phases[0].committed, phases[0].expired), (5, 0));
+        assert_eq!((phases[1].committed, phases[1].expired), (7, 1));
+    }
 }
@@ -3,6 +3,7 @@
 
 use crate::{
     emitter::{
+        load_profile::LoadProfile,
         stats::{DynamicStatsTracking, StatsAccumulator},
         wait_for_accounts_sequence,
     },
@@ -11,7 +12,10 @@ use crate::{
 };
 use aptos_logger::{sample, sample::SampleRate, warn};
 use aptos_rest_client::Client as RestClient;
-use aptos_sdk::types::{transaction::SignedTransaction, vm_status::StatusCode, LocalAccount};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    types::{transaction::SignedTransaction, vm_status::StatusCode, LocalAccount},
+};
 use core::{
     cmp::{max, min},
     result::Result::{Err, Ok},
@@ -22,19 +26,108 @@ use futures::future::join_all;
 use itertools::Itertools;
 use rand::seq::IteratorRandom;
 use rand::Rng;
-use std::sync::atomic::AtomicU64;
-use std::{sync::Arc, time::Instant};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, AtomicUsize};
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 use tokio::time::sleep;
 
+/// Workers of a phase of a job, the first `num_workers` of the job's, of which `active` submit
+/// transactions when the phase starts.
+#[derive(Debug)]
+pub struct PhaseWorkers {
+    pub num_workers: usize,
+    pub active: usize,
+    pub load_profile: Option<LoadProfile>,
+}
+
+/// Workers of a job submitting transactions in its current phase, out of all of them, as set by
+/// the adaptive TPS controller and the load profile. The others skip their cycles.
+#[derive(Debug)]
+pub struct ActiveWorkers {
+    phases: Vec<PhaseWorkers>,
+    phase: AtomicUsize,
+    active: AtomicUsize,
+    start_time: Mutex<Instant>,
+}
+
+impl ActiveWorkers {
+    pub fn new(phases: Vec<PhaseWorkers>) -> Self {
+        assert!(!phases.is_empty());
+        let active = phases[0].active;
+        Self {
+            phases,
+            phase: AtomicUsize::new(0),
+            active: AtomicUsize::new(active),
+            start_time: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub fn phase(&self) -> usize {
+        self.phase.load(Ordering::Relaxed)
+    }
+
+    pub fn phase_start_time(&self) -> Instant {
+        *self.start_time.lock().unwrap()
+    }
+
+    /// Workers of the current phase
+    pub fn num_workers(&self) -> usize {
+        self.phases[self.phase()].num_workers
+    }
+
+    /// Switches the workers to the next phase, returning false if the current one is the last,
+    /// which jobs without phases keep.
+    pub fn start_next_phase(&self) -> bool {
+        let next = self.phase() + 1;
+        if next >= self.phases.len() {
+            return false;
+        }
+        *self.start_time.lock().unwrap() = Instant::now();
+        self.active
+            .store(self.phases[next].active, Ordering::Relaxed);
+        self.phase.store(next, Ordering::Relaxed);
+        true
+    }
+
+    pub fn set(&self, active: usize) {
+        self.active
+            .store(active.min(self.num_workers()), Ordering::Relaxed);
+    }
+
+    /// Whether the worker submits its transactions. Active workers are spread evenly over the
+    /// indices, as the start times of workers are, so the TPS changes right away.
+    pub fn is_active(&self, worker_index: usize) -> bool {
+        let phase = &self.phases[self.phase()];
+        let num_workers = phase.num_workers;
+        if worker_index >= num_workers {
+            return false;
+        }
+        let mut active = self.active.load(Ordering::Relaxed).min(num_workers);
+        if let Some(load_profile) = &phase.load_profile {
+            active = active
+                .min(load_profile.active_workers(self.phase_start_time().elapsed(), num_workers));
+        }
+        (worker_index + 1) * active / num_workers > worker_index * active / num_workers
+    }
+}
+
 pub struct SubmissionWorker {
     pub(crate) accounts: Vec<LocalAccount>,
     client: RestClient,
     stop: Arc<AtomicBool>,
+    /// Params and generator of each phase of the job, switched to as the active workers start
+    /// the phase
+    phase_params: Vec<EmitModeParams>,
+    txn_generators: Vec<Box<dyn TransactionGenerator>>,
+    phase: usize,
     params: EmitModeParams,
     stats: Arc<DynamicStatsTracking>,
-    txn_generator: Box<dyn TransactionGenerator>,
     worker_index: usize,
     skip_latency_stats: bool,
+    active_workers: Arc<ActiveWorkers>,
     rng: ::rand::rngs::StdRng,
 }
 
@@ -43,22 +136,28 @@ impl SubmissionWorker {
         accounts: Vec<LocalAccount>,
         client: RestClient,
         stop: Arc<AtomicBool>,
-        params: EmitModeParams,
+        phase_params: Vec<EmitModeParams>,
         stats: Arc<DynamicStatsTracking>,
-        txn_generator: Box<dyn TransactionGenerator>,
+        txn_generators: Vec<Box<dyn TransactionGenerator>>,
         worker_index: usize,
         skip_latency_stats: bool,
+        active_workers: Arc<ActiveWorkers>,
         rng: ::rand::rngs::StdRng,
     ) -> Self {
+        assert_eq!(phase_params.len(), txn_generators.len());
+        let params = phase_params[0].clone();
         Self {
             accounts,
             client,
             stop,
+            phase_params,
+            txn_generators,
+            phase: 0,
             params,
             stats,
-            txn_generator,
             worker_index,
             skip_latency_stats,
+            active_workers,
             rng,
         }
     }
@@ -73,10 +172,25 @@ impl SubmissionWorker {
 
         self.sleep_check_done(start_sleep_duration).await;
 
-        let wait_duration = Duration::from_millis(self.params.wait_millis);
+        let mut wait_duration = Duration::from_millis(self.params.wait_millis);
         let mut wait_until = start_time;
 
         while !self.stop.load(Ordering::Relaxed) {
+            let phase = self.active_workers.phase();
+            if phase != self.phase {
+                // switch to the params and generator of the phase, and to the pace of its
+                // workers from its start, keeping the accounts
+                self.phase = phase;
+                self.params = self.phase_params[phase].clone();
+                wait_duration = Duration::from_millis(self.params.wait_millis);
+                wait_until = self.active_workers.phase_start_time() + self.start_sleep_time();
+                let now = Instant::now();
+                if wait_until > now {
+                    self.sleep_check_done(wait_until - now).await;
+                }
+                continue;
+            }
+
             let stats_clone = self.stats.clone();
             let loop_stats = stats_clone.get_cur();
 
@@ -96,6 +210,20 @@ impl SubmissionWorker {
             // always add expected cycle duration, to not drift from expected pace.
             wait_until += wait_duration;
 
+            if !self.active_workers.is_active(self.worker_index) {
+                // skip the cycle, keeping to the pace so the worker is back in its slot
+                let now = Instant::now();
+                if wait_until > now {
+                    self.sleep_check_done(wait_until - now).await;
+                }
+                continue;
+            }
+
+            let start_sequence_numbers: Vec<_> = self
+                .accounts
+                .iter()
+                .map(|account| account.sequence_number())
+                .collect();
             let requests = self.gen_requests();
 
             let txn_expiration_time = requests
@@ -107,20 +235,25 @@ impl SubmissionWorker {
             let num_requests = requests.len();
             let txn_offset_time = Arc::new(AtomicU64::new(0));
 
-            join_all(
-                requests
-                    .chunks(self.params.max_submit_batch_size)
-                    .map(|reqs| {
-                        submit_transactions(
-                            &self.client,
-                            reqs,
-                            loop_start_time.clone(),
-                            txn_offset_time.clone(),
-                            loop_stats,
-                        )
-                    }),
-            )
-            .await;
+            let batch_size = self.params.max_submit_batch_size;
+            let rejected: HashSet<_> = join_all(requests.chunks(batch_size).map(|reqs| {
+                submit_transactions(
+                    &self.client,
+                    reqs,
+                    loop_start_time.clone(),
+                    txn_offset_time.clone(),
+                    loop_stats,
+                )
+            }))
+            .await
+            .into_iter()
+            .enumerate()
+            .flat_map(|(chunk, indices)| {
+                indices
+                    .into_iter()
+                    .map(move |index| chunk * batch_size + index)
+            })
+            .collect();
 
             if self.skip_latency_stats {
                 // we also don't want to be stuck waiting for txn_expiration_time_secs
@@ -135,6 +268,7 @@ impl SubmissionWorker {
                 *loop_start_time,
                 txn_offset_time.load(Ordering::Relaxed),
                 num_requests,
+                &start_sequence_numbers,
                 // skip latency if asked to check seq_num only once
                 // even if we check more often due to stop (to not affect sampling)
                 self.skip_latency_stats,
@@ -150,6 +284,7 @@ impl SubmissionWorker {
                 loop_stats,
             )
             .await;
+            count_conflicts(&requests, &rejected, &self.accounts, loop_stats);
 
             let now = Instant::now();
             if wait_until > now {
@@ -160,12 +295,12 @@ impl SubmissionWorker {
         self.accounts
     }
 
-    // returns true if it returned early
+    // returns early when stopped, or when the phase of the job changes
     async fn sleep_check_done(&self, duration: Duration) {
         let start_time = Instant::now();
         loop {
             sleep(Duration::from_secs(1)).await;
-            if self.stop.load(Ordering::Relaxed) {
+            if self.stop.load(Ordering::Relaxed) || self.active_workers.phase() != self.phase {
                 return;
             }
             if start_time.elapsed() >= duration {
@@ -186,9 +321,9 @@ impl SubmissionWorker {
         )
     }
 
-    /// This function assumes that num_requests == num_accounts, which is
-    /// precisely how gen_requests works. If this changes, this code will
-    /// need to be fixed.
+    /// Transactions are counted by the sequence numbers the accounts advanced by since
+    /// `start_sequence_numbers`, so the ones that don't take up a sequence number of their own,
+    /// like duplicates, are neither committed nor expired.
     ///
     /// Note, the latency values are not accurate if --check-stats-at-end
     /// is used. There is no easy way around this accurately. As such, we
@@ -198,26 +333,29 @@ impl SubmissionWorker {
         start_time: Instant,
         txn_offset_time: u64,
         num_requests: usize,
+        start_sequence_numbers: &[u64],
         skip_latency_stats: bool,
         txn_expiration_ts_secs: u64,
         check_account_sleep_duration: Duration,
         loop_stats: &StatsAccumulator,
     ) {
-        assert_eq!(
-            num_requests,
-            self.params.transactions_per_account * self.accounts.len()
-        );
+        let num_waited: usize = self
+            .accounts
+            .iter()
+            .zip(start_sequence_numbers)
+            .map(|(account, start)| (account.sequence_number() - start) as usize)
+            .sum();
         let (num_expired, sum_of_completion_timestamps_millis) = wait_for_accounts_sequence(
             start_time,
             &self.client,
             &mut self.accounts,
-            self.params.transactions_per_account,
+            start_sequence_numbers,
             txn_expiration_ts_secs,
             check_account_sleep_duration,
         )
         .await;
 
-        let num_committed = num_requests - num_expired;
+        let num_committed = num_waited - num_expired;
 
         if num_expired > 0 {
             loop_stats
@@ -271,18 +409,83 @@ impl SubmissionWorker {
             .accounts
             .iter_mut()
             .choose_multiple(&mut self.rng, batch_size);
-        self.txn_generator
+        self.txn_generators[self.phase]
             .generate_transactions(accounts, self.params.transactions_per_account)
     }
 }
 
+/// How the sequence number of a transaction conflicts with the earlier ones of its account in a
+/// batch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Conflict {
+    /// Below the highest sequence number of the account so far
+    OutOfOrder,
+    /// The same sequence number as an earlier transaction of the account
+    Duplicate,
+}
+
+/// The conflict of each transaction of the batch, if it has one
+fn find_conflicts(requests: &[SignedTransaction]) -> Vec<Option<Conflict>> {
+    let mut seen: HashMap<AccountAddress, (Option<u64>, HashSet<u64>)> = HashMap::new();
+    requests
+        .iter()
+        .map(|txn| {
+            let (highest, sequence_numbers) = seen.entry(txn.sender()).or_default();
+            let sequence_number = txn.sequence_number();
+            let conflict = if !sequence_numbers.insert(sequence_number) {
+                Some(Conflict::Duplicate)
+            } else if highest.map_or(false, |highest| sequence_number < highest) {
+                Some(Conflict::OutOfOrder)
+            } else {
+                None
+            };
+            *highest = (*highest).max(Some(sequence_number));
+            conflict
+        })
+        .collect()
+}
+
+/// Counts the out of order and duplicate transactions of the batch, once the sequence numbers of
+/// the accounts are settled. One is committed if it wasn't rejected on submission and its account
+/// got past its sequence number, and rejected otherwise.
+fn count_conflicts(
+    requests: &[SignedTransaction],
+    rejected: &HashSet<usize>,
+    accounts: &[LocalAccount],
+    stats: &StatsAccumulator,
+) {
+    let conflicts = find_conflicts(requests);
+    if conflicts.iter().all(Option::is_none) {
+        return;
+    }
+    let settled: HashMap<_, _> = accounts
+        .iter()
+        .map(|account| (account.address(), account.sequence_number()))
+        .collect();
+    for (index, (txn, conflict)) in requests.iter().zip(conflicts).enumerate() {
+        let committed = !rejected.contains(&index)
+            && settled
+                .get(&txn.sender())
+                .map_or(false, |settled| txn.sequence_number() < *settled);
+        let counter = match (conflict, committed) {
+            (None, _) => continue,
+            (Some(Conflict::OutOfOrder), true) => &stats.out_of_order_committed,
+            (Some(Conflict::OutOfOrder), false) => &stats.out_of_order_rejected,
+            (Some(Conflict::Duplicate), true) => &stats.duplicate_committed,
+            (Some(Conflict::Duplicate), false) => &stats.duplicate_rejected,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Submits the transactions, returning the indices of the ones that were rejected
 pub async fn submit_transactions(
     client: &RestClient,
     txns: &[SignedTransaction],
     loop_start_time: Arc<Instant>,
     txn_offset_time: Arc<AtomicU64>,
     stats: &StatsAccumulator,
-) {
+) -> Vec<usize> {
     let cur_time = Instant::now();
     let offset = cur_time - *loop_start_time;
     txn_offset_time.fetch_add(
@@ -306,6 +509,7 @@ pub async fn submit_transactions(
                     e
                 )
             );
+            (0..txns.len()).collect()
         }
         Ok(v) => {
             let failures = v.into_inner().transaction_failures;
@@ -354,6 +558,195 @@ pub async fn submit_transactions(
                     );
                 }
             });
+            failures
+                .iter()
+                .map(|failure| failure.transaction_index)
+                .collect()
         }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transaction_generator::failure_injection::{
+        FailureInjectionGenerator, DEFAULT_FAILURE_RATIO,
     };
+    use aptos_infallible::RwLock;
+    use aptos_sdk::{
+        transaction_builder::{aptos_stdlib, TransactionFactory},
+        types::chain_id::ChainId,
+    };
+    use rand::rngs::StdRng;
+    use rand_core::SeedableRng;
+
+    fn transfer(
+        factory: &TransactionFactory,
+        sender: &LocalAccount,
+        sequence_number: u64,
+    ) -> SignedTransaction {
+        sender.sign_transaction(
+            factory
+                .payload(aptos_stdlib::aptos_coin_transfer(sender.address(), 1))
+                .sender(sender.address())
+                .sequence_number(sequence_number)
+                .build(),
+        )
+    }
+
+    #[test]
+    pub fn test_count_conflicts() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let factory = TransactionFactory::new(ChainId::test());
+        let mut reversed = LocalAccount::generate(&mut rng);
+        let mut duplicating = LocalAccount::generate(&mut rng);
+        let requests = vec![
+            transfer(&factory, &reversed, 2),
+            transfer(&factory, &duplicating, 0),
+            transfer(&factory, &reversed, 1),
+            transfer(&factory, &duplicating, 1),
+            transfer(&factory, &reversed, 0),
+            transfer(&factory, &duplicating, 0),
+            transfer(&factory, &duplicating, 1),
+        ];
+        assert_eq!(
+            find_conflicts(&requests),
+            vec![
+                None,
+                None,
+                Some(Conflict::OutOfOrder),
+                None,
+                Some(Conflict::OutOfOrder),
+                Some(Conflict::Duplicate),
+                Some(Conflict::Duplicate),
+            ]
+        );
+
+        // the reversed account only got to 2, and the first duplicate was rejected on submission
+        *reversed.sequence_number_mut() = 2;
+        *duplicating.sequence_number_mut() = 2;
+        let stats = StatsAccumulator::default();
+        count_conflicts(
+            &requests,
+            &[5].into_iter().collect(),
+            &[reversed, duplicating],
+            &stats,
+        );
+        let stats = stats.accumulate();
+        assert_eq!(stats.out_of_order_committed, 2);
+        assert_eq!(stats.out_of_order_rejected, 0);
+        assert_eq!(stats.duplicate_committed, 1);
+        assert_eq!(stats.duplicate_rejected, 1);
+        assert!(stats
+            .to_string()
+            .contains("duplicate committed: 1, rejected: 1"));
+    }
+
+    #[test]
+    pub fn test_failures_dont_stall() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut accounts: Vec<_> = (0..20).map(|_| LocalAccount::generate(&mut rng)).collect();
+        let receiver = accounts[0].address();
+        let mut generator = FailureInjectionGenerator::new(
+            rng,
+            TransactionFactory::new(ChainId::test()),
+            1,
+            Arc::new(RwLock::new(vec![receiver])),
+            DEFAULT_FAILURE_RATIO,
+            vec![],
+        );
+
+        let mut num_submitted = 0;
+        let mut num_committed = 0;
+        for _ in 0..50 {
+            let start_sequence_numbers: Vec<_> = accounts
+                .iter()
+                .map(|account| account.sequence_number())
+                .collect();
+            let requests = generator.generate_transactions(accounts.iter_mut().collect(), 10);
+            num_submitted += requests.len();
+            for (account, start) in accounts.iter().zip(start_sequence_numbers) {
+                // the chain commits the transactions mempool accepts, in sequence number order
+                let committable: HashSet<_> = requests
+                    .iter()
+                    .filter(|txn| {
+                        txn.sender() == account.address()
+                            && txn.max_gas_amount() > 1
+                            && txn.expiration_timestamp_secs() > 1
+                    })
+                    .map(|txn| txn.sequence_number())
+                    .collect();
+                let mut settled = start;
+                while committable.contains(&settled) {
+                    settled += 1;
+                }
+                // so the account settles once the batch is committed, and the worker keeps to
+                // the pace of its target TPS instead of waiting for the failures to expire
+                assert_eq!(settled, account.sequence_number());
+                num_committed += (settled - start) as usize;
+            }
+        }
+        // the achieved TPS only misses the failures that aren't committed
+        assert!(num_committed * 100 >= num_submitted * (100 - DEFAULT_FAILURE_RATIO));
+        assert!(num_committed < num_submitted);
+    }
+
+    fn phase(num_workers: usize, load_profile: Option<LoadProfile>) -> PhaseWorkers {
+        PhaseWorkers {
+            num_workers,
+            active: num_workers,
+            load_profile,
+        }
+    }
+
+    fn active_indices(active_workers: &ActiveWorkers) -> Vec<usize> {
+        (0..10)
+            .filter(|index| active_workers.is_active(*index))
+            .collect()
+    }
+
+    #[test]
+    pub fn test_active_workers_spread() {
+        let active_workers = ActiveWorkers::new(vec![phase(10, None)]);
+        for active in 0..=10 {
+            active_workers.set(active);
+            let indices = active_indices(&active_workers);
+            assert_eq!(indices.len(), active);
+            if active == 5 {
+                assert!(indices.windows(2).all(|pair| pair[1] - pair[0] == 2));
+            }
+        }
+
+        let active_workers =
+            ActiveWorkers::new(vec![phase(10, Some(LoadProfile::Constant { tps: 100 }))]);
+        active_workers.set(4);
+        assert_eq!(active_indices(&active_workers).len(), 4);
+    }
+
+    #[test]
+    pub fn test_active_workers_phases() {
+        let active_workers = ActiveWorkers::new(vec![
+            phase(10, None),
+            PhaseWorkers {
+                num_workers: 6,
+                active: 3,
+                load_profile: None,
+            },
+        ]);
+        active_workers.set(2);
+        assert_eq!(active_indices(&active_workers).len(), 2);
+
+        let start_time = active_workers.phase_start_time();
+        assert!(active_workers.start_next_phase());
+        assert_eq!(active_workers.phase(), 1);
+        assert!(active_workers.phase_start_time() >= start_time);
+        // the phase starts with its own active workers, spread over its first ones
+        assert_eq!(active_indices(&active_workers), vec![1, 3, 5]);
+        active_workers.set(10);
+        assert_eq!(active_indices(&active_workers), (0..6).collect::<Vec<_>>());
+
+        // the last phase is kept
+        assert!(!active_workers.start_next_phase());
+        assert_eq!(active_workers.phase(), 1);
+    }
 }
@@ -0,0 +1,208 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::emitter::{
+    stats::{DynamicStatsTracking, TxnStats},
+    submission_worker::ActiveWorkers,
+};
+use aptos_logger::info;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+pub const DEFAULT_TARGET_P50_LATENCY_MS: u64 = 3_000;
+pub const DEFAULT_TARGET_P99_LATENCY_MS: u64 = 10_000;
+
+// long enough for the latency of the transactions submitted at the new TPS to be sampled.
+const ADJUSTMENT_INTERVAL: Duration = Duration::from_secs(20);
+// share of failed submissions or expired transactions above which the chain is overloaded.
+const MAX_FAILURE_RATIO: f64 = 0.01;
+// until the chain is first overloaded, the TPS ramps up fast, then it only probes above it.
+const RAMP_UP_FACTOR: f64 = 1.5;
+const PROBE_FACTOR: f64 = 1.05;
+const BACK_OFF_FACTOR: f64 = 0.75;
+
+/// An adjustment of the adaptive controller, from the stats of the window at `target_tps`.
+#[derive(Clone, Debug, Default)]
+pub struct TpsStep {
+    pub target_tps: usize,
+    pub committed_tps: u64,
+    pub p50_latency: u64,
+    pub p99_latency: u64,
+    pub failed_submission_tps: u64,
+    pub overloaded: bool,
+}
+
+impl fmt::Display for TpsStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "target: {} TPS, committed: {} txn/s, p50: {} ms, p99: {} ms, failed submission: {} txn/s{}",
+            self.target_tps,
+            self.committed_tps,
+            self.p50_latency,
+            self.p99_latency,
+            self.failed_submission_tps,
+            if self.overloaded { ", overloaded" } else { "" },
+        )
+    }
+}
+
+/// Closed loop controller of the TPS, converging on the max TPS the chain sustains within the
+/// latency targets.
+///
+/// The TPS ramps up from a tenth of `max_tps` while the chain keeps up, and backs off when the
+/// p50 or p99 latency is above its target, or mempool pushes back by rejecting submissions, or
+/// transactions expire. After backing off, it only probes slowly above the sustained TPS.
+#[derive(Debug)]
+pub struct TpsController {
+    max_tps: usize,
+    target_p50_latency_ms: u64,
+    target_p99_latency_ms: u64,
+    tps: usize,
+    backed_off: bool,
+}
+
+impl TpsController {
+    pub fn new(max_tps: usize, target_p50_latency_ms: u64, target_p99_latency_ms: u64) -> Self {
+        Self {
+            max_tps,
+            target_p50_latency_ms,
+            target_p99_latency_ms,
+            tps: (max_tps / 10).max(1),
+            backed_off: false,
+        }
+    }
+
+    pub fn tps(&self) -> usize {
+        self.tps
+    }
+
+    /// Workers to keep active, out of the `num_workers` sized for `max_tps`
+    pub fn active_workers(&self, num_workers: usize) -> usize {
+        (self.tps * num_workers + self.max_tps - 1) / self.max_tps
+    }
+
+    /// Adjusts the TPS from the stats of the last window, returning the step taken.
+    pub fn adjust(&mut self, window: &TxnStats, duration: Duration) -> TpsStep {
+        let rate = window.rate(duration);
+        let max_failures = (window.submitted as f64 * MAX_FAILURE_RATIO) as u64;
+        let overloaded = rate.p50_latency > self.target_p50_latency_ms
+            || rate.p99_latency > self.target_p99_latency_ms
+            || window.failed_submission > max_failures
+            || window.expired > max_failures;
+        let step = TpsStep {
+            target_tps: self.tps,
+            committed_tps: rate.committed,
+            p50_latency: rate.p50_latency,
+            p99_latency: rate.p99_latency,
+            failed_submission_tps: rate.failed_submission,
+            overloaded,
+        };
+        // nothing was submitted in the window, so there's nothing to go by
+        if window.submitted == 0 {
+            return step;
+        }
+
+        let factor = if overloaded {
+            self.backed_off = true;
+            BACK_OFF_FACTOR
+        } else if self.backed_off {
+            PROBE_FACTOR
+        } else {
+            RAMP_UP_FACTOR
+        };
+        let tps = (self.tps as f64 * factor) as usize;
+        self.tps = if overloaded {
+            tps
+        } else {
+            tps.max(self.tps + 1)
+        }
+        .clamp(1, self.max_tps);
+        step
+    }
+}
+
+/// Adjusts the active workers of the adaptive phases of a job every interval, with the controller
+/// of the current phase, until it's stopped, recording the steps in the stats of the phase.
+pub async fn run_tps_controller(
+    mut controllers: Vec<Option<TpsController>>,
+    stats: Arc<DynamicStatsTracking>,
+    active_workers: Arc<ActiveWorkers>,
+    stop: Arc<AtomicBool>,
+) {
+    let mut prev_stats: Option<(usize, TxnStats)> = None;
+    while !stop.load(Ordering::Relaxed) {
+        tokio::time::sleep(ADJUSTMENT_INTERVAL).await;
+        let cur_phase = stats.get_cur_phase();
+        let cur_stats = stats.get_cur().accumulate();
+        let window = match &prev_stats {
+            Some((phase, prev)) if *phase == cur_phase => &cur_stats - prev,
+            _ => &cur_stats - &TxnStats::default(),
+        };
+        prev_stats = Some((cur_phase, cur_stats));
+        // only the adaptive phases of the job have a controller
+        let controller = match &mut controllers[active_workers.phase()] {
+            Some(controller) => controller,
+            None => continue,
+        };
+        let step = controller.adjust(&window, ADJUSTMENT_INTERVAL);
+        info!(
+            "Adaptive TPS {}, next target: {} TPS",
+            step,
+            controller.tps()
+        );
+        stats.get_cur().tps_trajectory.lock().unwrap().push(step);
+        active_workers.set(controller.active_workers(active_workers.num_workers()));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::emitter::stats::AtomicHistogramAccumulator;
+
+    fn window(submitted: u64, failed_submission: u64, latency: u64) -> TxnStats {
+        let histogram = AtomicHistogramAccumulator::default();
+        histogram.record_data_point(latency, submitted);
+        TxnStats {
+            submitted,
+            committed: submitted - failed_submission,
+            failed_submission,
+            latency_samples: submitted,
+            latency_buckets: histogram.snapshot(),
+            ..TxnStats::default()
+        }
+    }
+
+    #[test]
+    pub fn test_converges_below_overload() {
+        let mut controller = TpsController::new(10_000, 3_000, 10_000);
+        let interval = Duration::from_secs(1);
+        let mut steps = vec![];
+        for _ in 0..50 {
+            // the chain sustains 4000 TPS, above which mempool rejects submissions
+            let tps = controller.tps() as u64;
+            let failed = tps.saturating_sub(4_000);
+            steps.push(controller.adjust(&window(tps, failed, 1_000), interval));
+        }
+        assert!(steps.iter().any(|step| step.overloaded));
+        assert!(steps[40..]
+            .iter()
+            .all(|step| step.target_tps > 2_500 && step.target_tps < 4_500));
+
+        // the targets cap the latency, even without failures
+        let tps = controller.tps();
+        assert!(
+            controller
+                .adjust(&window(100, 0, 5_000), interval)
+                .overloaded
+        );
+        assert!(controller.tps() < tps);
+    }
+}
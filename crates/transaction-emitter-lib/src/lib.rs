@@ -8,17 +8,23 @@ mod cluster;
 pub mod emitter;
 mod instance;
 mod transaction_generator;
+mod workload;
 mod wrappers;
 
 // These are the top level things you should need to run the emitter.
 pub use args::{ClusterArgs, CoinSourceArgs, EmitArgs, TransactionType};
-pub use wrappers::emit_transactions;
+pub use transaction_generator::{
+    failure_injection::FailureKind, p2p_transaction_generator::RecipientDistribution,
+};
+pub use workload::{GeneratorKind, GeneratorSpec, PhaseSpec, WorkloadSpec};
+pub use wrappers::{emit_transaction_phases, emit_transactions, total_stats, PhaseStats};
 
 // We export these if you want finer grained control.
 pub use cluster::Cluster;
 pub use emitter::{
+    load_profile::{LoadProfile, LoadStep},
     query_sequence_number, query_sequence_numbers,
     stats::{TxnStats, TxnStatsRate},
-    EmitJob, EmitJobMode, EmitJobRequest, EmitModeParams, TxnEmitter,
+    EmitJob, EmitJobMode, EmitJobPhase, EmitJobRequest, EmitModeParams, TxnEmitter,
 };
-pub use wrappers::emit_transactions_with_cluster;
+pub use wrappers::{emit_transaction_phases_with_cluster, emit_transactions_with_cluster};
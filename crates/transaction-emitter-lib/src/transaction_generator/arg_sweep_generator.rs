@@ -0,0 +1,109 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{
+    publishing::{
+        arg_sweep::{arg_sweep_module, arg_sweep_payload},
+        publish_module_payload,
+    },
+    TransactionGenerator, TransactionGeneratorCreator,
+};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::TransactionFactory,
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+pub const DEFAULT_MIN_ARG_SIZE: usize = 1;
+/// The largest argument which still fits in the 64KiB transaction size limit.
+pub const DEFAULT_MAX_ARG_SIZE: usize = 64_000;
+
+/// Calls entry functions with vector arguments of a ramping size, for profiling how
+/// serialization, signature verification and gas metering scale with the size of payloads.
+///
+/// The first transaction of each account publishes its own `arg_sweep` module. After that, the
+/// argument size of its transactions starts at `min_size` and doubles up to `max_size`, then
+/// starts again.
+/// With the same `min_size` and `max_size`, all arguments have that size.
+pub struct ArgSweepGenerator {
+    txn_factory: TransactionFactory,
+    min_size: usize,
+    max_size: usize,
+    /// Size of the next argument of each account, absent before it published its module
+    next_sizes: HashMap<AccountAddress, usize>,
+}
+
+impl ArgSweepGenerator {
+    pub fn new(txn_factory: TransactionFactory, min_size: usize, max_size: usize) -> Self {
+        Self {
+            txn_factory,
+            min_size,
+            max_size: max_size.max(min_size),
+            next_sizes: HashMap::new(),
+        }
+    }
+
+    fn size_after(&self, size: usize) -> usize {
+        if size >= self.max_size {
+            self.min_size
+        } else {
+            (size.max(1) * 2).min(self.max_size)
+        }
+    }
+}
+
+impl TransactionGenerator for ArgSweepGenerator {
+    fn generate_transactions(
+        &mut self,
+        accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(accounts.len() * transactions_per_account);
+        for account in accounts {
+            let address = account.address();
+            let mut size = self.next_sizes.get(&address).cloned();
+            for _ in 0..transactions_per_account {
+                // Sequence numbers order the publishing before the calls depending on it
+                let payload = match size {
+                    Some(size) => arg_sweep_payload(address, size, account.sequence_number()),
+                    None => publish_module_payload(&arg_sweep_module(address)),
+                };
+                requests
+                    .push(account.sign_with_transaction_builder(self.txn_factory.payload(payload)));
+                size = Some(size.map_or(self.min_size, |size| self.size_after(size)));
+            }
+            if let Some(size) = size {
+                self.next_sizes.insert(address, size);
+            }
+        }
+        requests
+    }
+}
+
+pub struct ArgSweepGeneratorCreator {
+    txn_factory: TransactionFactory,
+    min_size: usize,
+    max_size: usize,
+}
+
+impl ArgSweepGeneratorCreator {
+    pub fn new(txn_factory: TransactionFactory, min_size: usize, max_size: usize) -> Self {
+        Self {
+            txn_factory,
+            min_size,
+            max_size,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for ArgSweepGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(ArgSweepGenerator::new(
+            self.txn_factory.clone(),
+            self.min_size,
+            self.max_size,
+        ))
+    }
+}
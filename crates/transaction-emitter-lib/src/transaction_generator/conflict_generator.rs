@@ -0,0 +1,185 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_infallible::RwLock;
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::{aptos_stdlib, TransactionFactory},
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use async_trait::async_trait;
+use rand::{
+    prelude::{SliceRandom, StdRng},
+    Rng,
+};
+use std::sync::Arc;
+
+pub const DEFAULT_CONFLICT_RATIO: usize = 20;
+
+/// Sends coin transfers, with a `conflict_ratio` percentage of the accounts of each batch
+/// submitting theirs out of order or with a duplicate sequence number, for stressing how mempool
+/// orders and replaces each account's transactions.
+///
+/// An out of order account submits its transactions in reverse, which should all still be
+/// committed. A duplicating account submits another transaction with the sequence number of one
+/// of its earlier ones in its last slot, either a copy of it or a replacement at twice the gas
+/// price. The duplicate doesn't take up a sequence number of its own, so only one of the two is
+/// committed, and a rejected one is counted as a failed submission. Accounts need at least two
+/// transactions per batch to duplicate one.
+pub struct ConflictGenerator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    send_amount: u64,
+    all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+    conflict_ratio: usize,
+}
+
+impl ConflictGenerator {
+    pub fn new(
+        rng: StdRng,
+        txn_factory: TransactionFactory,
+        send_amount: u64,
+        all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+        conflict_ratio: usize,
+    ) -> Self {
+        Self {
+            rng,
+            txn_factory,
+            send_amount,
+            all_addresses,
+            conflict_ratio,
+        }
+    }
+
+    fn transfers(&mut self, sender: &mut LocalAccount, count: usize) -> Vec<SignedTransaction> {
+        (0..count)
+            .map(|_| {
+                let receiver = *self.all_addresses.read().choose(&mut self.rng).unwrap();
+                sender.sign_with_transaction_builder(self.txn_factory.payload(
+                    aptos_stdlib::aptos_coin_transfer(receiver, self.send_amount),
+                ))
+            })
+            .collect()
+    }
+
+    /// Another transaction with the sequence number of `txn`
+    fn duplicate(&mut self, sender: &LocalAccount, txn: &SignedTransaction) -> SignedTransaction {
+        if self.rng.gen() {
+            return txn.clone();
+        }
+        let receiver = *self.all_addresses.read().choose(&mut self.rng).unwrap();
+        sender.sign_transaction(
+            self.txn_factory
+                .payload(aptos_stdlib::aptos_coin_transfer(
+                    receiver,
+                    self.send_amount,
+                ))
+                .gas_unit_price(txn.gas_unit_price() * 2)
+                .sender(sender.address())
+                .sequence_number(txn.sequence_number())
+                .build(),
+        )
+    }
+}
+
+impl TransactionGenerator for ConflictGenerator {
+    fn generate_transactions(
+        &mut self,
+        accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(accounts.len() * transactions_per_account);
+        for sender in accounts {
+            if self.rng.gen_range(0, 100) >= self.conflict_ratio {
+                requests.extend(self.transfers(sender, transactions_per_account));
+            } else if transactions_per_account < 2 || self.rng.gen() {
+                let mut transfers = self.transfers(sender, transactions_per_account);
+                transfers.reverse();
+                requests.extend(transfers);
+            } else {
+                let mut transfers = self.transfers(sender, transactions_per_account - 1);
+                let original = transfers.choose(&mut self.rng).unwrap().clone();
+                transfers.push(self.duplicate(sender, &original));
+                requests.extend(transfers);
+            }
+        }
+        requests
+    }
+}
+
+pub struct ConflictGeneratorCreator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    send_amount: u64,
+    all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+    conflict_ratio: usize,
+}
+
+impl ConflictGeneratorCreator {
+    pub fn new(
+        rng: StdRng,
+        txn_factory: TransactionFactory,
+        send_amount: u64,
+        all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+        conflict_ratio: usize,
+    ) -> Self {
+        Self {
+            rng,
+            txn_factory,
+            send_amount,
+            all_addresses,
+            conflict_ratio,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for ConflictGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(ConflictGenerator::new(
+            self.rng.clone(),
+            self.txn_factory.clone(),
+            self.send_amount,
+            self.all_addresses.clone(),
+            self.conflict_ratio,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_sdk::types::chain_id::ChainId;
+    use rand_core::SeedableRng;
+    use std::collections::HashSet;
+
+    #[test]
+    pub fn test_conflicts_keep_sequence_numbers() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut accounts: Vec<_> = (0..20).map(|_| LocalAccount::generate(&mut rng)).collect();
+        let receiver = LocalAccount::generate(&mut rng).address();
+        let mut generator = ConflictGenerator::new(
+            rng,
+            TransactionFactory::new(ChainId::test()),
+            1,
+            Arc::new(RwLock::new(vec![receiver])),
+            100,
+        );
+
+        let txns = generator.generate_transactions(accounts.iter_mut().collect(), 10);
+        assert_eq!(txns.len(), 200);
+        for account in &accounts {
+            // a duplicating account only takes up the sequence numbers of its other transactions
+            let sequence_numbers: HashSet<_> = txns
+                .iter()
+                .filter(|txn| txn.sender() == account.address())
+                .map(|txn| txn.sequence_number())
+                .collect();
+            assert_eq!(sequence_numbers.len() as u64, account.sequence_number());
+            assert!(sequence_numbers
+                .iter()
+                .all(|n| *n < account.sequence_number()));
+            assert!(account.sequence_number() >= 9);
+        }
+    }
+}
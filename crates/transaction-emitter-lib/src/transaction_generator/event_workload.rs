@@ -0,0 +1,107 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::{
+    bcs,
+    move_types::{account_address::AccountAddress, ident_str, language_storage::ModuleId},
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{EntryFunction, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+
+pub const DEFAULT_EVENTS_PER_TRANSACTION: u64 = 10;
+pub const DEFAULT_EVENT_SIZE: u64 = 100;
+
+/// Emits `events_per_transaction` events of `event_size` bytes of data with each transaction,
+/// calling the `event_workload` sample module of move-examples, which needs to be published at
+/// `module_address` first.
+pub struct EventWorkloadGenerator {
+    txn_factory: TransactionFactory,
+    module_address: AccountAddress,
+    events_per_transaction: u64,
+    event_size: u64,
+}
+
+impl EventWorkloadGenerator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        module_address: AccountAddress,
+        events_per_transaction: u64,
+        event_size: u64,
+    ) -> Self {
+        Self {
+            txn_factory,
+            module_address,
+            events_per_transaction,
+            event_size,
+        }
+    }
+
+    fn payload(&self) -> TransactionPayload {
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(self.module_address, ident_str!("event_workload").to_owned()),
+            ident_str!("emit").to_owned(),
+            vec![],
+            vec![
+                bcs::to_bytes(&self.events_per_transaction).unwrap(),
+                bcs::to_bytes(&self.event_size).unwrap(),
+            ],
+        ))
+    }
+}
+
+impl TransactionGenerator for EventWorkloadGenerator {
+    fn generate_transactions(
+        &mut self,
+        accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(accounts.len() * transactions_per_account);
+        for account in accounts {
+            for _ in 0..transactions_per_account {
+                requests.push(
+                    account.sign_with_transaction_builder(self.txn_factory.payload(self.payload())),
+                );
+            }
+        }
+        requests
+    }
+}
+
+pub struct EventWorkloadGeneratorCreator {
+    txn_factory: TransactionFactory,
+    module_address: AccountAddress,
+    events_per_transaction: u64,
+    event_size: u64,
+}
+
+impl EventWorkloadGeneratorCreator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        module_address: AccountAddress,
+        events_per_transaction: u64,
+        event_size: u64,
+    ) -> Self {
+        Self {
+            txn_factory,
+            module_address,
+            events_per_transaction,
+            event_size,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for EventWorkloadGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(EventWorkloadGenerator::new(
+            self.txn_factory.clone(),
+            self.module_address,
+            self.events_per_transaction,
+            self.event_size,
+        ))
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_infallible::RwLock;
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::{aptos_stdlib, TransactionFactory},
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use async_trait::async_trait;
+use rand::{
+    prelude::{SliceRandom, StdRng},
+    Rng,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub const DEFAULT_FAILURE_RATIO: usize = 10;
+
+/// How far ahead of the account the sequence number of a failing transaction is, so it waits
+/// in mempool until it expires.
+const SEQUENCE_NUMBER_GAP: u64 = 1_000;
+
+/// Ways injected transactions fail.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// A max gas amount below the minimum of a transaction, rejected on submission.
+    InsufficientGas,
+    /// A transfer of more coins than the sender has, committed with the abort.
+    Abort,
+    /// A sequence number far ahead of the account's, kept in mempool until it expires.
+    SequenceNumber,
+    /// An expiration time in the past, rejected on submission.
+    Expired,
+}
+
+impl FailureKind {
+    pub const ALL: [FailureKind; 4] = [
+        FailureKind::InsufficientGas,
+        FailureKind::Abort,
+        FailureKind::SequenceNumber,
+        FailureKind::Expired,
+    ];
+}
+
+/// Sends coin transfers, a `failure_ratio` percentage of which are made to fail in one of
+/// `kinds` of ways, for validating how mempool and the emitter's stats handle failures.
+///
+/// Failing transactions are the last ones of each account in a batch, so they don't hold up its
+/// valid ones, with aborts first as they're committed. The others don't take up a sequence
+/// number of the account, so the worker doesn't wait for them until they expire: the ones
+/// rejected on submission reuse the sequence numbers following the account's, and the ones with
+/// a gap come last, ahead of those. They're only counted as submitted, and failed submissions
+/// when rejected.
+pub struct FailureInjectionGenerator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    send_amount: u64,
+    all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+    failure_ratio: usize,
+    kinds: Vec<FailureKind>,
+}
+
+impl FailureInjectionGenerator {
+    pub fn new(
+        rng: StdRng,
+        txn_factory: TransactionFactory,
+        send_amount: u64,
+        all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+        failure_ratio: usize,
+        kinds: Vec<FailureKind>,
+    ) -> Self {
+        Self {
+            rng,
+            txn_factory,
+            send_amount,
+            all_addresses,
+            failure_ratio,
+            kinds: if kinds.is_empty() {
+                FailureKind::ALL.to_vec()
+            } else {
+                kinds
+            },
+        }
+    }
+
+    /// A failing transaction of the kind, `offset` sequence numbers ahead of the account if it
+    /// doesn't take up one
+    fn failing_transaction(
+        &mut self,
+        sender: &mut LocalAccount,
+        receiver: AccountAddress,
+        kind: FailureKind,
+        offset: u64,
+    ) -> SignedTransaction {
+        let transfer = self.txn_factory.payload(aptos_stdlib::aptos_coin_transfer(
+            receiver,
+            self.send_amount,
+        ));
+        // Only aborts take up a sequence number of the account, as the others aren't committed
+        let (builder, ahead) = match kind {
+            FailureKind::InsufficientGas => (transfer.max_gas_amount(1), offset),
+            FailureKind::Abort => {
+                return sender.sign_with_transaction_builder(
+                    self.txn_factory
+                        .payload(aptos_stdlib::aptos_coin_transfer(receiver, u64::MAX)),
+                )
+            }
+            FailureKind::SequenceNumber => (transfer, SEQUENCE_NUMBER_GAP + offset),
+            FailureKind::Expired => (transfer.expiration_timestamp_secs(1), offset),
+        };
+        sender.sign_transaction(
+            builder
+                .sender(sender.address())
+                .sequence_number(sender.sequence_number() + ahead)
+                .build(),
+        )
+    }
+}
+
+impl TransactionGenerator for FailureInjectionGenerator {
+    fn generate_transactions(
+        &mut self,
+        accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(accounts.len() * transactions_per_account);
+        for sender in accounts {
+            let mut failures = vec![];
+            for _ in 0..transactions_per_account {
+                if self.rng.gen_range(0, 100) < self.failure_ratio {
+                    failures.push(*self.kinds.choose(&mut self.rng).unwrap());
+                }
+            }
+            // Aborts are committed, so they go first, and the gaps last, so the sequence numbers
+            // of each account still increase through the batch
+            failures.sort_by_key(|kind| match kind {
+                FailureKind::Abort => 0,
+                FailureKind::InsufficientGas | FailureKind::Expired => 1,
+                FailureKind::SequenceNumber => 2,
+            });
+            let num_valid = transactions_per_account - failures.len();
+            let mut offset = 0;
+            for i in 0..transactions_per_account {
+                let receiver = *self.all_addresses.read().choose(&mut self.rng).unwrap();
+                requests.push(if i < num_valid {
+                    sender.sign_with_transaction_builder(self.txn_factory.payload(
+                        aptos_stdlib::aptos_coin_transfer(receiver, self.send_amount),
+                    ))
+                } else {
+                    let kind = failures[i - num_valid];
+                    let txn = self.failing_transaction(sender, receiver, kind, offset);
+                    if kind != FailureKind::Abort {
+                        offset += 1;
+                    }
+                    txn
+                });
+            }
+        }
+        requests
+    }
+}
+
+pub struct FailureInjectionGeneratorCreator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    send_amount: u64,
+    all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+    failure_ratio: usize,
+    kinds: Vec<FailureKind>,
+}
+
+impl FailureInjectionGeneratorCreator {
+    pub fn new(
+        rng: StdRng,
+        txn_factory: TransactionFactory,
+        send_amount: u64,
+        all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+        failure_ratio: usize,
+        kinds: Vec<FailureKind>,
+    ) -> Self {
+        Self {
+            rng,
+            txn_factory,
+            send_amount,
+            all_addresses,
+            failure_ratio,
+            kinds,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for FailureInjectionGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(FailureInjectionGenerator::new(
+            self.rng.clone(),
+            self.txn_factory.clone(),
+            self.send_amount,
+            self.all_addresses.clone(),
+            self.failure_ratio,
+            self.kinds.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_sdk::types::chain_id::ChainId;
+    use rand_core::SeedableRng;
+
+    #[test]
+    pub fn test_failures_come_last() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let receiver = LocalAccount::generate(&mut rng).address();
+        let mut generator = FailureInjectionGenerator::new(
+            rng,
+            TransactionFactory::new(ChainId::test()),
+            1,
+            Arc::new(RwLock::new(vec![receiver])),
+            50,
+            vec![FailureKind::Expired],
+        );
+
+        let txns = generator.generate_transactions(vec![&mut account], 100);
+        assert_eq!(txns.len(), 100);
+        let first_failure = txns
+            .iter()
+            .position(|txn| txn.expiration_timestamp_secs() == 1)
+            .unwrap();
+        assert!(first_failure > 0);
+        assert!(txns[first_failure..]
+            .iter()
+            .all(|txn| txn.expiration_timestamp_secs() == 1));
+        // the failures don't take up sequence numbers, but still have increasing ones
+        assert_eq!(account.sequence_number(), first_failure as u64);
+        assert!(txns
+            .windows(2)
+            .all(|pair| pair[0].sequence_number() < pair[1].sequence_number()));
+    }
+}
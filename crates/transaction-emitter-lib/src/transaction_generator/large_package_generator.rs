@@ -0,0 +1,90 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{
+    publishing::large_package::large_package_payload, TransactionGenerator,
+    TransactionGeneratorCreator,
+};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::TransactionFactory,
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// Just under the 64KiB transaction size limit, leaving room for the rest of the transaction.
+pub const DEFAULT_PACKAGE_SIZE: usize = 60_000;
+
+const PACKAGE_NAME: &str = "large_package";
+
+/// Publishes packages of about `package_size` bytes, for loading mempool and execution with
+/// transactions and write sets near their maximum size.
+///
+/// Each account keeps republishing its own package with different padding, so all but its first
+/// publish are upgrades. How often packages are published against other transactions is set by
+/// the generator's weight in the mix.
+pub struct LargePackageGenerator {
+    txn_factory: TransactionFactory,
+    package_size: usize,
+    /// Packages each account has published, wrapping around, which fills the padding of the next
+    published: HashMap<AccountAddress, u8>,
+}
+
+impl LargePackageGenerator {
+    pub fn new(txn_factory: TransactionFactory, package_size: usize) -> Self {
+        Self {
+            txn_factory,
+            package_size,
+            published: HashMap::new(),
+        }
+    }
+}
+
+impl TransactionGenerator for LargePackageGenerator {
+    fn generate_transactions(
+        &mut self,
+        accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(accounts.len() * transactions_per_account);
+        for account in accounts {
+            let published = self.published.entry(account.address()).or_insert(0);
+            for _ in 0..transactions_per_account {
+                let payload = large_package_payload(
+                    account.address(),
+                    PACKAGE_NAME,
+                    self.package_size,
+                    *published,
+                );
+                requests
+                    .push(account.sign_with_transaction_builder(self.txn_factory.payload(payload)));
+                *published = published.wrapping_add(1);
+            }
+        }
+        requests
+    }
+}
+
+pub struct LargePackageGeneratorCreator {
+    txn_factory: TransactionFactory,
+    package_size: usize,
+}
+
+impl LargePackageGeneratorCreator {
+    pub fn new(txn_factory: TransactionFactory, package_size: usize) -> Self {
+        Self {
+            txn_factory,
+            package_size,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for LargePackageGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(LargePackageGenerator::new(
+            self.txn_factory.clone(),
+            self.package_size,
+        ))
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::types::{transaction::SignedTransaction, LocalAccount};
+use async_trait::async_trait;
+use futures::future::join_all;
+use rand::prelude::StdRng;
+use rand::Rng;
+use rand_core::{OsRng, SeedableRng};
+
+/// Picks a generator by weight for each account, rather than for the whole batch like
+/// `TxnMixGenerator`, so every round has the mix of transactions.
+pub struct MixedGenerator {
+    rng: StdRng,
+    generators: Vec<(Box<dyn TransactionGenerator>, usize)>,
+    total_weight: usize,
+}
+
+impl MixedGenerator {
+    pub fn new(rng: StdRng, generators: Vec<(Box<dyn TransactionGenerator>, usize)>) -> Self {
+        let total_weight = generators.iter().map(|(_, weight)| weight).sum();
+        Self {
+            rng,
+            generators,
+            total_weight,
+        }
+    }
+
+    fn pick(&mut self) -> usize {
+        let mut picked = self.rng.gen_range(0, self.total_weight);
+        for (index, (_, weight)) in self.generators.iter().enumerate() {
+            if picked < *weight {
+                return index;
+            }
+            picked -= *weight;
+        }
+        panic!(
+            "Picked {} out of {}, couldn't find correct generator",
+            picked, self.total_weight
+        );
+    }
+}
+
+impl TransactionGenerator for MixedGenerator {
+    fn generate_transactions(
+        &mut self,
+        accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut accounts_per_generator: Vec<Vec<&mut LocalAccount>> =
+            self.generators.iter().map(|_| Vec::new()).collect();
+        for account in accounts {
+            let index = self.pick();
+            accounts_per_generator[index].push(account);
+        }
+
+        let mut outputs: Vec<_> = self
+            .generators
+            .iter_mut()
+            .zip(accounts_per_generator)
+            .filter(|(_, accounts)| !accounts.is_empty())
+            .map(|((generator, _), accounts)| {
+                generator
+                    .generate_transactions(accounts, transactions_per_account)
+                    .into_iter()
+            })
+            .collect();
+
+        // Interleave the outputs, so each kind of transaction is spread through the batch
+        let mut transactions = Vec::new();
+        loop {
+            let before = transactions.len();
+            transactions.extend(outputs.iter_mut().filter_map(Iterator::next));
+            if transactions.len() == before {
+                return transactions;
+            }
+        }
+    }
+}
+
+pub struct MixedGeneratorCreator {
+    creators: Vec<(Box<dyn TransactionGeneratorCreator>, usize)>,
+}
+
+impl MixedGeneratorCreator {
+    pub fn new(creators: Vec<(Box<dyn TransactionGeneratorCreator>, usize)>) -> Self {
+        Self { creators }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for MixedGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(MixedGenerator::new(
+            StdRng::from_seed(OsRng.gen()),
+            join_all(
+                self.creators
+                    .iter()
+                    .map(|(creator, weight)| async {
+                        (creator.create_transaction_generator().await, *weight)
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .await,
+        ))
+    }
+}
@@ -5,8 +5,20 @@ use aptos_sdk::types::{transaction::SignedTransaction, LocalAccount};
 use async_trait::async_trait;
 
 pub mod account_generator;
+pub mod arg_sweep_generator;
+pub mod conflict_generator;
+pub mod event_workload;
+pub mod failure_injection;
+pub mod large_package_generator;
+pub mod mixed_generator;
+pub mod multi_agent;
+pub mod nft_drop;
 pub mod nft_mint_and_transfer;
 pub mod p2p_transaction_generator;
+pub mod package_upgrade_generator;
+pub mod publishing;
+pub mod script_generator;
+pub mod table_workload;
 pub mod transaction_mix_generator;
 
 pub trait TransactionGenerator: Sync + Send {
@@ -0,0 +1,184 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::{
+    move_types::{account_address::AccountAddress, ident_str, language_storage::ModuleId},
+    transaction_builder::{
+        aptos_stdlib::{self, aptos_token_stdlib},
+        TransactionFactory,
+    },
+    types::{
+        transaction::{EntryFunction, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use std::{cmp::Ordering, collections::HashMap};
+
+const COLLECTION_NAME: &str = "multi agent collection";
+const TOKEN_NAME: &str = "multi agent token";
+const TOKEN_SUPPLY: u64 = 100_000_000_000;
+
+/// Generates multi-agent transactions, each signed by an account of the batch and co-signed by
+/// the account it's paired with.
+///
+/// Accounts are paired with their neighbour in the batch, and each account of a pair sends its
+/// transactions with the other as secondary signer. A lone account left at the end of a batch
+/// is co-signed by the first account of the batch, and an account alone in its batch sends
+/// coins to itself instead.
+///
+/// With `module_address`, the transactions call `co_sign` of the `multi_agent::co_sign` sample
+/// module in move-examples, which needs to be published there first. Otherwise they're direct
+/// transfers of a token, needing the receiver's signature, for which the first transactions of
+/// each account create its own collection and token.
+pub struct MultiAgentGenerator {
+    txn_factory: TransactionFactory,
+    module_address: Option<AccountAddress>,
+    /// Transactions each account has sent, to know whether its token setup is done
+    sent: HashMap<AccountAddress, u64>,
+}
+
+impl MultiAgentGenerator {
+    pub fn new(txn_factory: TransactionFactory, module_address: Option<AccountAddress>) -> Self {
+        Self {
+            txn_factory,
+            module_address,
+            sent: HashMap::new(),
+        }
+    }
+
+    /// Payload of the token setup transaction `step`, if the account is still in its setup
+    fn setup_payload(&self, address: AccountAddress, step: u64) -> Option<TransactionPayload> {
+        if self.module_address.is_some() {
+            return None;
+        }
+        match step {
+            0 => Some(aptos_token_stdlib::token_create_collection_script(
+                COLLECTION_NAME.to_owned().into_bytes(),
+                "description".to_owned().into_bytes(),
+                "uri".to_owned().into_bytes(),
+                u64::MAX,
+                vec![false, false, false],
+            )),
+            1 => Some(aptos_token_stdlib::token_create_token_script(
+                COLLECTION_NAME.to_owned().into_bytes(),
+                TOKEN_NAME.to_owned().into_bytes(),
+                "token description".to_owned().into_bytes(),
+                TOKEN_SUPPLY,
+                u64::MAX,
+                "uri".to_owned().into_bytes(),
+                address,
+                1,
+                0,
+                vec![false, false, false, false, false],
+                vec![],
+                vec![],
+                vec![],
+            )),
+            _ => None,
+        }
+    }
+
+    fn payload(&self, sender: AccountAddress) -> TransactionPayload {
+        match self.module_address {
+            Some(module_address) => TransactionPayload::EntryFunction(EntryFunction::new(
+                ModuleId::new(module_address, ident_str!("co_sign").to_owned()),
+                ident_str!("co_sign").to_owned(),
+                vec![],
+                vec![],
+            )),
+            None => aptos_token_stdlib::token_direct_transfer_script(
+                sender,
+                COLLECTION_NAME.to_owned().into_bytes(),
+                TOKEN_NAME.to_owned().into_bytes(),
+                0,
+                1,
+            ),
+        }
+    }
+
+    /// Transactions of `sender`, co-signed by `secondary` if there's one
+    fn sender_requests(
+        &mut self,
+        sender: &mut LocalAccount,
+        secondary: Option<&LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let address = sender.address();
+        let mut step = self.sent.get(&address).cloned().unwrap_or(0);
+        let mut requests = Vec::with_capacity(transactions_per_account);
+        for _ in 0..transactions_per_account {
+            // Sequence numbers order the setup before the transfers depending on it
+            requests.push(match (self.setup_payload(address, step), secondary) {
+                (Some(payload), _) => {
+                    sender.sign_with_transaction_builder(self.txn_factory.payload(payload))
+                }
+                (None, Some(secondary)) => sender.sign_multi_agent_with_transaction_builder(
+                    vec![secondary],
+                    self.txn_factory.payload(self.payload(address)),
+                ),
+                (None, None) => sender.sign_with_transaction_builder(
+                    self.txn_factory
+                        .payload(aptos_stdlib::aptos_coin_transfer(address, 1)),
+                ),
+            });
+            step += 1;
+        }
+        self.sent.insert(address, step);
+        requests
+    }
+}
+
+impl TransactionGenerator for MultiAgentGenerator {
+    fn generate_transactions(
+        &mut self,
+        mut accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(accounts.len() * transactions_per_account);
+        for index in 0..accounts.len() {
+            let partner = if index ^ 1 < accounts.len() {
+                index ^ 1
+            } else {
+                0
+            };
+            let (sender, secondary) = match index.cmp(&partner) {
+                Ordering::Less => {
+                    let (left, right) = accounts.split_at_mut(partner);
+                    (&mut *left[index], Some(&*right[0]))
+                }
+                Ordering::Greater => {
+                    let (left, right) = accounts.split_at_mut(index);
+                    (&mut *right[0], Some(&*left[partner]))
+                }
+                Ordering::Equal => (&mut *accounts[index], None),
+            };
+            requests.extend(self.sender_requests(sender, secondary, transactions_per_account));
+        }
+        requests
+    }
+}
+
+pub struct MultiAgentGeneratorCreator {
+    txn_factory: TransactionFactory,
+    module_address: Option<AccountAddress>,
+}
+
+impl MultiAgentGeneratorCreator {
+    pub fn new(txn_factory: TransactionFactory, module_address: Option<AccountAddress>) -> Self {
+        Self {
+            txn_factory,
+            module_address,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for MultiAgentGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(MultiAgentGenerator::new(
+            self.txn_factory.clone(),
+            self.module_address,
+        ))
+    }
+}
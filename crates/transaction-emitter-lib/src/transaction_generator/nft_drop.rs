@@ -0,0 +1,170 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::{aptos_stdlib::aptos_token_stdlib, TransactionFactory},
+    types::{
+        transaction::{SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+pub const DEFAULT_NUM_COLLECTIONS: usize = 4;
+pub const DEFAULT_PROPERTY_MAP_SIZE: usize = 4;
+
+const TOKEN_NAME: &str = "drop token";
+
+/// Emulates NFT drops: each account creates its own collections, then mints their tokens and
+/// offers them to other accounts.
+///
+/// The first transactions of an account are its setup, creating `num_collections` collections
+/// with a token each, whose property map has `property_map_size` entries. After that, its
+/// transactions alternate between minting a token, going round its collections, and offering
+/// the minted token to the next account of the batch.
+pub struct NftDropGenerator {
+    txn_factory: TransactionFactory,
+    num_collections: usize,
+    property_map_size: usize,
+    /// Transactions each account has made, including its setup
+    steps: HashMap<AccountAddress, u64>,
+}
+
+impl NftDropGenerator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        num_collections: usize,
+        property_map_size: usize,
+    ) -> Self {
+        Self {
+            txn_factory,
+            num_collections: num_collections.max(1),
+            property_map_size,
+            steps: HashMap::new(),
+        }
+    }
+
+    fn collection_name(&self, step: u64) -> Vec<u8> {
+        format!("drop collection {}", step / 2 % self.num_collections as u64).into_bytes()
+    }
+
+    /// Transactions of each account's setup
+    fn setup_len(&self) -> u64 {
+        2 * self.num_collections as u64
+    }
+
+    /// Payload of the setup transaction `step`, alternating between creating a collection and
+    /// its token
+    fn setup_payload(&self, address: AccountAddress, step: u64) -> TransactionPayload {
+        let collection_name = format!("drop collection {}", step / 2).into_bytes();
+        if step % 2 == 0 {
+            aptos_token_stdlib::token_create_collection_script(
+                collection_name,
+                "description".to_owned().into_bytes(),
+                "uri".to_owned().into_bytes(),
+                u64::MAX,
+                vec![false, false, false],
+            )
+        } else {
+            aptos_token_stdlib::token_create_token_script(
+                collection_name,
+                TOKEN_NAME.to_owned().into_bytes(),
+                "token description".to_owned().into_bytes(),
+                1,
+                u64::MAX,
+                "uri".to_owned().into_bytes(),
+                address,
+                1,
+                0,
+                vec![false, false, false, false, false],
+                (0..self.property_map_size)
+                    .map(|i| format!("property {}", i).into_bytes())
+                    .collect(),
+                // The BCS of a u64 is its little endian bytes
+                (0..self.property_map_size)
+                    .map(|i| (i as u64).to_le_bytes().to_vec())
+                    .collect(),
+                (0..self.property_map_size)
+                    .map(|_| "u64".to_owned().into_bytes())
+                    .collect(),
+            )
+        }
+    }
+}
+
+impl TransactionGenerator for NftDropGenerator {
+    fn generate_transactions(
+        &mut self,
+        accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let addresses: Vec<_> = accounts.iter().map(|account| account.address()).collect();
+        let mut requests = Vec::with_capacity(accounts.len() * transactions_per_account);
+        for (index, account) in accounts.into_iter().enumerate() {
+            let address = account.address();
+            let receiver = addresses[(index + 1) % addresses.len()];
+            let mut step = self.steps.get(&address).cloned().unwrap_or(0);
+            for _ in 0..transactions_per_account {
+                // Sequence numbers order the setup before the transactions depending on it
+                let payload = if step < self.setup_len() {
+                    self.setup_payload(address, step)
+                } else if (step - self.setup_len()) % 2 == 0 || receiver == address {
+                    // Offering to itself isn't possible, so a lone account only mints
+                    aptos_token_stdlib::token_mint_script(
+                        address,
+                        self.collection_name(step - self.setup_len()),
+                        TOKEN_NAME.to_owned().into_bytes(),
+                        1,
+                    )
+                } else {
+                    aptos_token_stdlib::token_transfers_offer_script(
+                        receiver,
+                        address,
+                        self.collection_name(step - self.setup_len()),
+                        TOKEN_NAME.to_owned().into_bytes(),
+                        0,
+                        1,
+                    )
+                };
+                requests
+                    .push(account.sign_with_transaction_builder(self.txn_factory.payload(payload)));
+                step += 1;
+            }
+            self.steps.insert(address, step);
+        }
+        requests
+    }
+}
+
+pub struct NftDropGeneratorCreator {
+    txn_factory: TransactionFactory,
+    num_collections: usize,
+    property_map_size: usize,
+}
+
+impl NftDropGeneratorCreator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        num_collections: usize,
+        property_map_size: usize,
+    ) -> Self {
+        Self {
+            txn_factory,
+            num_collections,
+            property_map_size,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for NftDropGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(NftDropGenerator::new(
+            self.txn_factory.clone(),
+            self.num_collections,
+            self.property_map_size,
+        ))
+    }
+}
@@ -14,8 +14,56 @@ use rand::{
     Rng,
 };
 use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
 use std::{cmp::max, sync::Arc};
 
+/// How recipients of transfers are picked from all addresses.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "distribution", rename_all = "snake_case")]
+pub enum RecipientDistribution {
+    /// Any address, distinct within each sender's batch, so transfers rarely conflict.
+    Uniform,
+    /// Zipfian over the addresses, the address of rank k being picked with probability
+    /// proportional to 1/k^exponent, so a few addresses receive most transfers.
+    Zipf { exponent: f64 },
+    /// Only the first `size` addresses, so all transfers write to the same few accounts.
+    HotSet { size: usize },
+}
+
+impl Default for RecipientDistribution {
+    fn default() -> Self {
+        RecipientDistribution::Uniform
+    }
+}
+
+impl RecipientDistribution {
+    /// Index of the next recipient among `num_addresses`, for the non uniform distributions.
+    pub fn sample_index(&self, rng: &mut StdRng, num_addresses: usize) -> usize {
+        match self {
+            RecipientDistribution::Uniform => rng.gen_range(0, num_addresses),
+            RecipientDistribution::Zipf { exponent } => {
+                zipf_index(rng.gen::<f64>(), *exponent, num_addresses)
+            }
+            RecipientDistribution::HotSet { size } => {
+                rng.gen_range(0, (*size).clamp(1, num_addresses))
+            }
+        }
+    }
+}
+
+/// Index of the rank picked by `u` in [0, 1) from a Zipfian distribution over `n` ranks, using
+/// the inverse of its continuous approximation, which avoids a table of the n weights.
+fn zipf_index(u: f64, exponent: f64, n: usize) -> usize {
+    let n = n as f64;
+    let rank = if (exponent - 1.0).abs() < f64::EPSILON {
+        n.powf(u)
+    } else {
+        let one_minus_s = 1.0 - exponent;
+        ((n.powf(one_minus_s) - 1.0) * u + 1.0).powf(1.0 / one_minus_s)
+    };
+    (rank.floor() as usize).clamp(1, n as usize) - 1
+}
+
 pub struct P2PTransactionGenerator {
     rng: StdRng,
     send_amount: u64,
@@ -23,6 +71,7 @@ pub struct P2PTransactionGenerator {
     all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
     invalid_transaction_ratio: usize,
     gas_price: u64,
+    recipients: RecipientDistribution,
 }
 
 impl P2PTransactionGenerator {
@@ -33,6 +82,7 @@ impl P2PTransactionGenerator {
         all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
         invalid_transaction_ratio: usize,
         gas_price: u64,
+        recipients: RecipientDistribution,
     ) -> Self {
         Self {
             rng,
@@ -41,6 +91,24 @@ impl P2PTransactionGenerator {
             all_addresses,
             invalid_transaction_ratio,
             gas_price,
+            recipients,
+        }
+    }
+
+    fn pick_receivers(&mut self, count: usize) -> Vec<AccountAddress> {
+        let all_addresses = self.all_addresses.read();
+        match self.recipients {
+            RecipientDistribution::Uniform => all_addresses
+                .choose_multiple(&mut self.rng, count)
+                .cloned()
+                .collect(),
+            _ => (0..count)
+                .map(|_| {
+                    all_addresses[self
+                        .recipients
+                        .sample_index(&mut self.rng, all_addresses.len())]
+                })
+                .collect(),
         }
     }
 
@@ -152,12 +220,7 @@ impl TransactionGenerator for P2PTransactionGenerator {
         };
         let mut num_valid_tx = transactions_per_account * (accounts.len() - invalid_size);
         for sender in accounts {
-            let receivers = self
-                .all_addresses
-                .read()
-                .choose_multiple(&mut self.rng, transactions_per_account)
-                .cloned()
-                .collect::<Vec<_>>();
+            let receivers = self.pick_receivers(transactions_per_account);
             assert!(
                 receivers.len() >= transactions_per_account,
                 "failed: {} >= {}",
@@ -197,6 +260,7 @@ pub struct P2PTransactionGeneratorCreator {
     all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
     invalid_transaction_ratio: usize,
     gas_price: u64,
+    recipients: RecipientDistribution,
 }
 
 impl P2PTransactionGeneratorCreator {
@@ -207,6 +271,7 @@ impl P2PTransactionGeneratorCreator {
         all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
         invalid_transaction_ratio: usize,
         gas_price: u64,
+        recipients: RecipientDistribution,
     ) -> Self {
         Self {
             rng,
@@ -215,6 +280,7 @@ impl P2PTransactionGeneratorCreator {
             all_addresses,
             invalid_transaction_ratio,
             gas_price,
+            recipients,
         }
     }
 }
@@ -229,6 +295,23 @@ impl TransactionGeneratorCreator for P2PTransactionGeneratorCreator {
             self.all_addresses.clone(),
             self.invalid_transaction_ratio,
             self.gas_price,
+            self.recipients.clone(),
         ))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_zipf_index() {
+        // The lowest ranks get most of the picks, and picks stay in range
+        assert_eq!(zipf_index(0.0, 1.0, 1000), 0);
+        assert!(zipf_index(0.5, 1.0, 1000) < 40);
+        assert!(zipf_index(0.999_999, 1.0, 1000) >= 990);
+        assert!(zipf_index(0.5, 2.0, 1000) < 2);
+        assert!(zipf_index(0.5, 0.5, 1000) < 300);
+        assert_eq!(zipf_index(0.5, 1.5, 1), 0);
+    }
+}
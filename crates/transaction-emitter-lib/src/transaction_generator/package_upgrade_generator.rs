@@ -0,0 +1,82 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{
+    publishing::upgrade::{upgrade_payload, UpgradeSizes},
+    TransactionGenerator, TransactionGeneratorCreator,
+};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::TransactionFactory,
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+pub const DEFAULT_INITIAL_SIZE: usize = 1_000;
+pub const DEFAULT_SIZE_DELTA: usize = 1_000;
+/// Leaves room under the 64KiB transaction size limit for the rest of the transaction.
+pub const DEFAULT_MAX_SIZE: usize = 50_000;
+
+const PACKAGE_NAME: &str = "upgraded_package";
+
+/// Publishes a package for each account, then keeps upgrading it with code that grows by
+/// `size_delta` bytes each upgrade, for loading the upgrade compatibility checks and the
+/// invalidation of the code cache.
+pub struct PackageUpgradeGenerator {
+    txn_factory: TransactionFactory,
+    sizes: UpgradeSizes,
+    /// Upgrades each account has published, the first publish included
+    upgrades: HashMap<AccountAddress, u64>,
+}
+
+impl PackageUpgradeGenerator {
+    pub fn new(txn_factory: TransactionFactory, sizes: UpgradeSizes) -> Self {
+        Self {
+            txn_factory,
+            sizes,
+            upgrades: HashMap::new(),
+        }
+    }
+}
+
+impl TransactionGenerator for PackageUpgradeGenerator {
+    fn generate_transactions(
+        &mut self,
+        accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(accounts.len() * transactions_per_account);
+        for account in accounts {
+            let upgrades = self.upgrades.entry(account.address()).or_insert(0);
+            for _ in 0..transactions_per_account {
+                let payload =
+                    upgrade_payload(account.address(), PACKAGE_NAME, *upgrades, &self.sizes);
+                requests
+                    .push(account.sign_with_transaction_builder(self.txn_factory.payload(payload)));
+                *upgrades += 1;
+            }
+        }
+        requests
+    }
+}
+
+pub struct PackageUpgradeGeneratorCreator {
+    txn_factory: TransactionFactory,
+    sizes: UpgradeSizes,
+}
+
+impl PackageUpgradeGeneratorCreator {
+    pub fn new(txn_factory: TransactionFactory, sizes: UpgradeSizes) -> Self {
+        Self { txn_factory, sizes }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for PackageUpgradeGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(PackageUpgradeGenerator::new(
+            self.txn_factory.clone(),
+            self.sizes,
+        ))
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `arg_sweep` module, whose entry functions take a vector and do nothing with it, so the
+//! cost of their transactions is all in handling the argument.
+
+use crate::transaction_generator::publishing::named_module;
+use aptos_sdk::{
+    bcs,
+    move_types::{
+        account_address::AccountAddress, ident_str, identifier::Identifier,
+        language_storage::ModuleId,
+    },
+    types::transaction::{EntryFunction, TransactionPayload},
+};
+use move_binary_format::file_format::{
+    Bytecode, CodeUnit, CompiledModule, FunctionDefinition, FunctionHandle, FunctionHandleIndex,
+    IdentifierIndex, ModuleHandleIndex, Signature, SignatureIndex, SignatureToken, TableIndex,
+    Visibility,
+};
+
+pub const MODULE_NAME: &str = "arg_sweep";
+
+/// The `arg_sweep` module at `address`, with the entry functions `bytes(&signer, vector<u8>)`
+/// and `u64s(&signer, vector<u64>)`
+pub fn arg_sweep_module(address: AccountAddress) -> CompiledModule {
+    let mut module = named_module(address, MODULE_NAME);
+    let empty = add_signature(&mut module, vec![]);
+    for (name, element) in [("bytes", SignatureToken::U8), ("u64s", SignatureToken::U64)] {
+        let parameters = add_signature(
+            &mut module,
+            vec![
+                SignatureToken::Reference(Box::new(SignatureToken::Signer)),
+                SignatureToken::Vector(Box::new(element)),
+            ],
+        );
+        module
+            .identifiers
+            .push(Identifier::new(name).expect("Function name must be an identifier"));
+        module.function_handles.push(FunctionHandle {
+            module: ModuleHandleIndex(0),
+            name: IdentifierIndex((module.identifiers.len() - 1) as TableIndex),
+            parameters,
+            return_: empty,
+            type_parameters: vec![],
+        });
+        module.function_defs.push(FunctionDefinition {
+            function: FunctionHandleIndex((module.function_handles.len() - 1) as TableIndex),
+            visibility: Visibility::Public,
+            is_entry: true,
+            acquires_global_resources: vec![],
+            code: Some(CodeUnit {
+                locals: empty,
+                code: vec![Bytecode::Ret],
+            }),
+        });
+    }
+    module
+}
+
+fn add_signature(module: &mut CompiledModule, tokens: Vec<SignatureToken>) -> SignatureIndex {
+    module.signatures.push(Signature(tokens));
+    SignatureIndex((module.signatures.len() - 1) as TableIndex)
+}
+
+/// Payload calling the module at `address` with an argument of about `size` bytes, alternating
+/// between its functions with `step`
+pub fn arg_sweep_payload(address: AccountAddress, size: usize, step: u64) -> TransactionPayload {
+    let module = ModuleId::new(address, Identifier::new(MODULE_NAME).unwrap());
+    let (function, arg) = if step % 2 == 0 {
+        (ident_str!("bytes"), bcs::to_bytes(&vec![step as u8; size]))
+    } else {
+        (ident_str!("u64s"), bcs::to_bytes(&vec![step; size / 8]))
+    };
+    TransactionPayload::EntryFunction(EntryFunction::new(
+        module,
+        function.to_owned(),
+        vec![],
+        vec![arg.expect("Argument must serialize")],
+    ))
+}
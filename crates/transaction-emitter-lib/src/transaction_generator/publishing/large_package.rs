@@ -0,0 +1,50 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Packages of a single module whose size is set by padding a constant.
+
+use crate::transaction_generator::publishing::{padded_module, publish_module_payload};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress, types::transaction::TransactionPayload,
+};
+
+/// Bytes of a publishing payload beyond its padding, covering the entry function call, the
+/// metadata, and the module's tables.
+const PAYLOAD_OVERHEAD: usize = 256;
+
+/// Payload publishing the package `name` at `address`, padded so the payload is about
+/// `target_size` bytes. Its only module, of the same name, has a `vector<u8>` constant of
+/// `fill` bytes making up the padding, so packages published with different fills have
+/// different code.
+pub fn large_package_payload(
+    address: AccountAddress,
+    name: &str,
+    target_size: usize,
+    fill: u8,
+) -> TransactionPayload {
+    let padding = target_size.saturating_sub(PAYLOAD_OVERHEAD + name.len() * 2);
+    publish_module_payload(&padded_module(address, name, padding, fill))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_sdk::bcs;
+    use move_binary_format::{access::ModuleAccess, file_format::CompiledModule};
+
+    #[test]
+    pub fn test_large_package_payload() {
+        let address = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let payload = large_package_payload(address, "large_package", 60_000, 7);
+        let size = bcs::to_bytes(&payload).unwrap().len();
+        assert!(size <= 60_000 && size > 59_000, "{}", size);
+
+        let mut code = vec![];
+        padded_module(address, "large_package", 100, 7)
+            .serialize(&mut code)
+            .unwrap();
+        let module = CompiledModule::deserialize(&code).unwrap();
+        assert_eq!(module.self_id().address(), &address);
+        assert_eq!(module.self_id().name().as_str(), "large_package");
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Move code for the generators, either precompiled or built directly as bytecode, as the
+//! emitter can't compile Move.
+
+use aptos_sdk::{
+    bcs,
+    move_types::{account_address::AccountAddress, identifier::Identifier},
+    transaction_builder::aptos_stdlib,
+    types::transaction::TransactionPayload,
+};
+use framework::natives::code::{ModuleMetadata, MoveOption, PackageMetadata, UpgradePolicy};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{empty_module, CompiledModule, Constant, SignatureToken},
+};
+
+pub mod arg_sweep;
+pub mod large_package;
+pub mod raw_script_data;
+pub mod upgrade;
+
+/// An empty module `name` at `address`
+pub fn named_module(address: AccountAddress, name: &str) -> CompiledModule {
+    let mut module = empty_module();
+    module.address_identifiers[0] = address;
+    module.identifiers[0] = Identifier::new(name).expect("Module name must be an identifier");
+    module
+}
+
+/// The empty module `name` at `address`, with a `vector<u8>` constant of `padding` bytes of `fill`
+pub fn padded_module(
+    address: AccountAddress,
+    name: &str,
+    padding: usize,
+    fill: u8,
+) -> CompiledModule {
+    let mut module = named_module(address, name);
+    module.constant_pool.push(Constant {
+        type_: SignatureToken::Vector(Box::new(SignatureToken::U8)),
+        data: bcs::to_bytes(&vec![fill; padding]).expect("Constant must serialize"),
+    });
+    module
+}
+
+/// Payload publishing `module` as a package of its own, of the same name
+pub fn publish_module_payload(module: &CompiledModule) -> TransactionPayload {
+    let name = module.self_id().name().to_string();
+    let mut code = vec![];
+    module.serialize(&mut code).expect("Module must serialize");
+
+    let metadata = PackageMetadata {
+        name: name.clone(),
+        upgrade_policy: UpgradePolicy::compat(),
+        upgrade_number: 0,
+        source_digest: String::new(),
+        manifest: vec![],
+        modules: vec![ModuleMetadata {
+            name,
+            source: vec![],
+            source_map: vec![],
+            extension: MoveOption::none(),
+        }],
+        deps: vec![],
+        extension: MoveOption::none(),
+    };
+    aptos_stdlib::code_publish_package_txn(
+        bcs::to_bytes(&metadata).expect("Metadata must serialize"),
+        vec![code],
+    )
+}
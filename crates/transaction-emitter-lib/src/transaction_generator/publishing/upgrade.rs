@@ -0,0 +1,100 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Packages of a single module upgraded with code that grows by a set number of bytes each
+//! upgrade.
+
+use crate::transaction_generator::publishing::{padded_module, publish_module_payload};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress, types::transaction::TransactionPayload,
+};
+
+/// Padding of the module of each upgrade of a package, from `initial_size` bytes growing by
+/// `size_delta` each upgrade, wrapping around to `initial_size` past `max_size`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UpgradeSizes {
+    pub initial_size: usize,
+    pub size_delta: usize,
+    pub max_size: usize,
+}
+
+impl UpgradeSizes {
+    /// Padding of the module of upgrade `upgrade`, the first publish being upgrade 0
+    pub fn padding(&self, upgrade: u64) -> usize {
+        let range = self.max_size.saturating_sub(self.initial_size) + 1;
+        self.initial_size + (upgrade % range as u64) as usize * self.size_delta % range
+    }
+}
+
+/// Payload publishing upgrade `upgrade` of the package `name` at `address`. Only the padding
+/// constant of its module changes between upgrades, filled with the upgrade's number so each one
+/// has different code, which keeps the upgrades compatible.
+pub fn upgrade_payload(
+    address: AccountAddress,
+    name: &str,
+    upgrade: u64,
+    sizes: &UpgradeSizes,
+) -> TransactionPayload {
+    publish_module_payload(&padded_module(
+        address,
+        name,
+        sizes.padding(upgrade),
+        upgrade as u8,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_sdk::bcs;
+    use move_binary_format::access::ModuleAccess;
+
+    #[test]
+    pub fn test_upgrade_sizes() {
+        let sizes = UpgradeSizes {
+            initial_size: 1_000,
+            size_delta: 500,
+            max_size: 2_000,
+        };
+        let paddings: Vec<_> = (0..5).map(|upgrade| sizes.padding(upgrade)).collect();
+        assert_eq!(paddings, vec![1_000, 1_500, 2_000, 1_499, 1_999]);
+        assert!((0..1_000).all(|upgrade| {
+            let padding = sizes.padding(upgrade);
+            padding >= sizes.initial_size && padding <= sizes.max_size
+        }));
+
+        let fixed = UpgradeSizes {
+            initial_size: 1_000,
+            size_delta: 0,
+            max_size: 1_000,
+        };
+        assert_eq!(fixed.padding(7), 1_000);
+    }
+
+    #[test]
+    pub fn test_upgrades_grow() {
+        let address = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let sizes = UpgradeSizes {
+            initial_size: 1_000,
+            size_delta: 1_000,
+            max_size: 10_000,
+        };
+        let payload_sizes: Vec<_> = (0..5)
+            .map(|upgrade| {
+                bcs::to_bytes(&upgrade_payload(address, "upgrade", upgrade, &sizes))
+                    .unwrap()
+                    .len()
+            })
+            .collect();
+        assert!(payload_sizes
+            .windows(2)
+            .all(|pair| pair[1] > pair[0] + 900 && pair[1] < pair[0] + 1_100));
+
+        // upgrades only change the padding of the module, which keeps them compatible
+        let first = padded_module(address, "upgrade", sizes.padding(0), 0);
+        let next = padded_module(address, "upgrade", sizes.padding(1), 1);
+        assert_eq!(first.self_id(), next.self_id());
+        assert!(next.function_defs.is_empty() && next.struct_defs.is_empty());
+        assert_ne!(first.constant_pool, next.constant_pool);
+    }
+}
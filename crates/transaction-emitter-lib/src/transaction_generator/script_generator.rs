@@ -0,0 +1,154 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{
+    publishing::raw_script_data::{EMPTY_SCRIPT, TWO_BY_TWO_TRANSFER},
+    TransactionGenerator, TransactionGeneratorCreator,
+};
+use aptos_infallible::RwLock;
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{Script, SignedTransaction, TransactionArgument},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use rand::{prelude::StdRng, seq::SliceRandom, Rng};
+use std::sync::Arc;
+
+/// Submits precompiled Move scripts, which go through the loader and verifier on every
+/// transaction rather than using published modules like entry functions.
+///
+/// Accounts are paired with their neighbour in the batch, and each account of a pair sends
+/// `two_by_two_transfer` with the other as second signer, withdrawing up to `send_amount` from
+/// each and depositing a random split of it to two random addresses. A lone account left at the
+/// end of a batch sends the empty script instead.
+pub struct ScriptGenerator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    send_amount: u64,
+    all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+}
+
+impl ScriptGenerator {
+    pub fn new(
+        rng: StdRng,
+        txn_factory: TransactionFactory,
+        send_amount: u64,
+        all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+    ) -> Self {
+        Self {
+            rng,
+            txn_factory,
+            send_amount,
+            all_addresses,
+        }
+    }
+
+    fn transfer_script(&mut self) -> Script {
+        let amount_first = self.rng.gen_range(1, self.send_amount.max(1) + 1);
+        let amount_second = self.rng.gen_range(1, self.send_amount.max(1) + 1);
+        let deposit_first = self.rng.gen_range(0, amount_first + amount_second + 1);
+        let (dst_first, dst_second) = {
+            let all_addresses = self.all_addresses.read();
+            (
+                *all_addresses.choose(&mut self.rng).unwrap(),
+                *all_addresses.choose(&mut self.rng).unwrap(),
+            )
+        };
+        Script::new(
+            TWO_BY_TWO_TRANSFER.to_vec(),
+            vec![],
+            vec![
+                TransactionArgument::U64(amount_first),
+                TransactionArgument::U64(amount_second),
+                TransactionArgument::Address(dst_first),
+                TransactionArgument::Address(dst_second),
+                TransactionArgument::U64(deposit_first),
+            ],
+        )
+    }
+
+    /// Transactions of `sender`, with `second` as the script's second signer
+    fn sender_requests(
+        &mut self,
+        sender: &mut LocalAccount,
+        second: &LocalAccount,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..transactions_per_account)
+            .map(|_| {
+                let script = self.transfer_script();
+                sender.sign_multi_agent_with_transaction_builder(
+                    vec![second],
+                    self.txn_factory.script(script),
+                )
+            })
+            .collect()
+    }
+}
+
+impl TransactionGenerator for ScriptGenerator {
+    fn generate_transactions(
+        &mut self,
+        mut accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(accounts.len() * transactions_per_account);
+        let mut pairs = accounts.chunks_exact_mut(2);
+        for pair in &mut pairs {
+            let (first, second) = pair.split_at_mut(1);
+            let (first, second) = (&mut *first[0], &mut *second[0]);
+            requests.extend(self.sender_requests(first, second, transactions_per_account));
+            requests.extend(self.sender_requests(second, first, transactions_per_account));
+        }
+        for account in pairs.into_remainder() {
+            for _ in 0..transactions_per_account {
+                requests.push(
+                    account.sign_with_transaction_builder(self.txn_factory.script(Script::new(
+                        EMPTY_SCRIPT.to_vec(),
+                        vec![],
+                        vec![],
+                    ))),
+                );
+            }
+        }
+        requests
+    }
+}
+
+pub struct ScriptGeneratorCreator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    send_amount: u64,
+    all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+}
+
+impl ScriptGeneratorCreator {
+    pub fn new(
+        rng: StdRng,
+        txn_factory: TransactionFactory,
+        send_amount: u64,
+        all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+    ) -> Self {
+        Self {
+            rng,
+            txn_factory,
+            send_amount,
+            all_addresses,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for ScriptGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(ScriptGenerator::new(
+            self.rng.clone(),
+            self.txn_factory.clone(),
+            self.send_amount,
+            self.all_addresses.clone(),
+        ))
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::{
+    bcs,
+    move_types::{account_address::AccountAddress, ident_str, language_storage::ModuleId},
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{EntryFunction, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+pub const DEFAULT_KEYS_PER_ACCOUNT: u64 = 1000;
+pub const DEFAULT_KEYS_PER_TRANSACTION: u64 = 10;
+pub const DEFAULT_VALUE_SIZE: u64 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operation {
+    Insert,
+    Read,
+    Remove,
+}
+
+/// Where an account is in its cycle over its keys
+#[derive(Clone, Copy, Debug)]
+struct Cursor {
+    operation: Operation,
+    next_key: u64,
+}
+
+/// Loads table items, calling the `table_workload` module of the emitter's `module_publishing/`
+/// packages, which needs to be published at `module_address` first.
+///
+/// Each account cycles over its `keys_per_account` keys, handling `keys_per_transaction` of them
+/// per transaction: it first inserts them with values of `value_size` bytes, then reads them,
+/// then removes them, before starting again.
+pub struct TableWorkloadGenerator {
+    txn_factory: TransactionFactory,
+    module_address: AccountAddress,
+    keys_per_account: u64,
+    keys_per_transaction: u64,
+    value_size: u64,
+    cursors: HashMap<AccountAddress, Cursor>,
+}
+
+impl TableWorkloadGenerator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        module_address: AccountAddress,
+        keys_per_account: u64,
+        keys_per_transaction: u64,
+        value_size: u64,
+    ) -> Self {
+        Self {
+            txn_factory,
+            module_address,
+            keys_per_account: keys_per_account.max(1),
+            keys_per_transaction: keys_per_transaction.max(1),
+            value_size,
+            cursors: HashMap::new(),
+        }
+    }
+
+    fn payload(&self, cursor: Cursor) -> TransactionPayload {
+        let count = self
+            .keys_per_transaction
+            .min(self.keys_per_account - cursor.next_key);
+        let mut args = vec![
+            bcs::to_bytes(&cursor.next_key).unwrap(),
+            bcs::to_bytes(&count).unwrap(),
+        ];
+        let function = match cursor.operation {
+            Operation::Insert => {
+                args.push(bcs::to_bytes(&self.value_size).unwrap());
+                ident_str!("insert")
+            }
+            Operation::Read => ident_str!("read"),
+            Operation::Remove => ident_str!("remove"),
+        };
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(self.module_address, ident_str!("table_workload").to_owned()),
+            function.to_owned(),
+            vec![],
+            args,
+        ))
+    }
+
+    fn cursor_after(&self, cursor: Cursor) -> Cursor {
+        let next_key = cursor.next_key + self.keys_per_transaction;
+        if next_key < self.keys_per_account {
+            return Cursor { next_key, ..cursor };
+        }
+        let operation = match cursor.operation {
+            Operation::Insert => Operation::Read,
+            Operation::Read => Operation::Remove,
+            Operation::Remove => Operation::Insert,
+        };
+        Cursor {
+            operation,
+            next_key: 0,
+        }
+    }
+}
+
+impl TransactionGenerator for TableWorkloadGenerator {
+    fn generate_transactions(
+        &mut self,
+        accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(accounts.len() * transactions_per_account);
+        for account in accounts {
+            let mut cursor = self
+                .cursors
+                .get(&account.address())
+                .copied()
+                .unwrap_or(Cursor {
+                    operation: Operation::Insert,
+                    next_key: 0,
+                });
+            for _ in 0..transactions_per_account {
+                requests.push(
+                    account.sign_with_transaction_builder(
+                        self.txn_factory.payload(self.payload(cursor)),
+                    ),
+                );
+                cursor = self.cursor_after(cursor);
+            }
+            self.cursors.insert(account.address(), cursor);
+        }
+        requests
+    }
+}
+
+pub struct TableWorkloadGeneratorCreator {
+    txn_factory: TransactionFactory,
+    module_address: AccountAddress,
+    keys_per_account: u64,
+    keys_per_transaction: u64,
+    value_size: u64,
+}
+
+impl TableWorkloadGeneratorCreator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        module_address: AccountAddress,
+        keys_per_account: u64,
+        keys_per_transaction: u64,
+        value_size: u64,
+    ) -> Self {
+        Self {
+            txn_factory,
+            module_address,
+            keys_per_account,
+            keys_per_transaction,
+            value_size,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for TableWorkloadGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(TableWorkloadGenerator::new(
+            self.txn_factory.clone(),
+            self.module_address,
+            self.keys_per_account,
+            self.keys_per_transaction,
+            self.value_size,
+        ))
+    }
+}
@@ -0,0 +1,630 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    args::TransactionType,
+    emitter::{
+        load_profile::LoadProfile,
+        tps_controller::{DEFAULT_TARGET_P50_LATENCY_MS, DEFAULT_TARGET_P99_LATENCY_MS},
+        EmitJobMode,
+    },
+    transaction_generator::{
+        failure_injection::FailureKind,
+        p2p_transaction_generator::RecipientDistribution,
+        package_upgrade_generator::{DEFAULT_INITIAL_SIZE, DEFAULT_MAX_SIZE},
+    },
+};
+use anyhow::{bail, Context, Result};
+use aptos_sdk::move_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, time::Duration};
+
+const DEFAULT_TXN_EXPIRATION_TIME_SECS: u64 = 30;
+
+/// A workload for the emitter, read from a YAML or JSON file with `--workload`.
+///
+/// Example:
+/// ```yaml
+/// mix_per_account: true
+/// generators:
+///   - type: p2p
+///     weight: 80
+///     send_amount: 10
+///     recipients:
+///       distribution: hot_set
+///       size: 100
+///   - type: account_generation
+///     weight: 20
+///     gas_price: 200
+/// phases:
+///   - duration_secs: 60
+///     target_tps: 500
+///   - duration_secs: 120
+///     mempool_backlog: 5000
+///   - duration_secs: 600
+///     max_tps: 10000
+///     target_p99_latency_ms: 5000
+///   - duration_secs: 300
+///     load_profile:
+///       shape: ramp
+///       from_tps: 100
+///       to_tps: 2000
+///       duration_secs: 240
+///   - name: publish heavy
+///     duration_secs: 300
+///     target_tps: 200
+///     generators:
+///       - type: large_package
+///         weight: 3
+///       - type: p2p
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct WorkloadSpec {
+    /// Generators of transactions, one is picked by weight for each batch. Phases with their own
+    /// generators use those instead.
+    #[serde(default)]
+    pub generators: Vec<GeneratorSpec>,
+    /// Phases run one after another, each with its own load, with stats for each of them.
+    pub phases: Vec<PhaseSpec>,
+    /// Whether each account picks its generator, so each batch has the mix of transactions,
+    /// rather than each batch picking one generator for all its accounts.
+    #[serde(default)]
+    pub mix_per_account: bool,
+    #[serde(default = "default_txn_expiration_time_secs")]
+    pub txn_expiration_time_secs: u64,
+}
+
+/// A transaction generator of a workload, with its weight in the mix.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct GeneratorSpec {
+    #[serde(flatten)]
+    pub kind: GeneratorKind,
+    #[serde(default = "default_weight")]
+    pub weight: usize,
+    /// Gas unit price of the generator's transactions, defaults to the job's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<u64>,
+}
+
+/// Kind of transactions generated, with their parameters. Parameters which aren't set default to
+/// the job's.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GeneratorKind {
+    P2p {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        send_amount: Option<u64>,
+        /// Percentage of invalid transactions.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        invalid_transaction_ratio: Option<usize>,
+        /// How recipients are picked, uniformly by default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        recipients: Option<RecipientDistribution>,
+    },
+    AccountGeneration {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        add_created_accounts_to_pool: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_account_working_set: Option<usize>,
+    },
+    NftMintAndTransfer {},
+    /// Accounts create their own collections, then mint and offer their tokens.
+    NftDrop {
+        /// Collections each account creates and mints from.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        num_collections: Option<usize>,
+        /// Entries in the property map of each token.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        property_map_size: Option<usize>,
+    },
+    /// Pairs of accounts co-signing each other's transactions.
+    MultiAgent {
+        /// Address the `multi_agent` sample package of move-examples is published at, whose
+        /// `co_sign` function is called. Tokens are transferred instead if not set.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        module_address: Option<AccountAddress>,
+    },
+    /// Precompiled Move scripts with random arguments.
+    Script {
+        /// Most coins withdrawn from each signer of a transfer.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        send_amount: Option<u64>,
+    },
+    /// Packages published near the transaction size limit.
+    LargePackage {
+        /// Bytes of each publishing payload.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        package_size: Option<usize>,
+    },
+    /// A package of each account upgraded over and over, with code growing by `size_delta`
+    /// bytes each upgrade, from `initial_size` up to `max_size` bytes before starting over.
+    PackageUpgrade {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        initial_size: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        size_delta: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_size: Option<usize>,
+    },
+    /// Entry functions called with vector arguments whose size ramps from `min_size` to
+    /// `max_size`, doubling each transaction.
+    ArgSweep {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_size: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_size: Option<usize>,
+    },
+    /// Table items inserted, read and removed, by calling the `table_workload` package of
+    /// `module_publishing/`.
+    TableWorkload {
+        /// Address the package is published at.
+        module_address: AccountAddress,
+        /// Keys each account cycles over.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        keys_per_account: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        keys_per_transaction: Option<u64>,
+        /// Bytes of each inserted value.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value_size: Option<u64>,
+    },
+    /// Events emitted by calling the `event_workload` package of move-examples.
+    EventWorkload {
+        /// Address the package is published at.
+        module_address: AccountAddress,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        events_per_transaction: Option<u64>,
+        /// Bytes of data in each event.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        event_size: Option<u64>,
+    },
+    /// Coin transfers, some of which are made to fail.
+    FailureInjection {
+        /// Percentage of transactions which fail.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        failure_ratio: Option<usize>,
+        /// Ways transactions fail, all of them by default.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        kinds: Vec<FailureKind>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        send_amount: Option<u64>,
+    },
+    /// Coin transfers, some accounts of which submit theirs out of order or with a duplicate
+    /// sequence number.
+    Conflict {
+        /// Percentage of accounts with conflicting transactions.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        conflict_ratio: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        send_amount: Option<u64>,
+    },
+}
+
+/// A phase of a workload, with either a target TPS, a mempool backlog to keep, a max TPS under
+/// which the TPS adapts to the latency, or a load profile.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PhaseSpec {
+    /// Name of the phase in its stats, its position by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub duration_secs: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_tps: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mempool_backlog: Option<usize>,
+    /// Max TPS of the adaptive controller, which converges on the max TPS sustained within the
+    /// latency targets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tps: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_p50_latency_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_p99_latency_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_profile: Option<LoadProfile>,
+    /// Generators of the phase, replacing the workload's.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generators: Vec<GeneratorSpec>,
+}
+
+fn default_weight() -> usize {
+    1
+}
+
+fn default_txn_expiration_time_secs() -> u64 {
+    DEFAULT_TXN_EXPIRATION_TIME_SECS
+}
+
+impl GeneratorSpec {
+    /// A generator of the transaction type, with the job's parameters.
+    pub fn new(transaction_type: TransactionType, weight: usize) -> Self {
+        let kind = match transaction_type {
+            TransactionType::P2P => GeneratorKind::P2p {
+                send_amount: None,
+                invalid_transaction_ratio: None,
+                recipients: None,
+            },
+            TransactionType::AccountGeneration => GeneratorKind::AccountGeneration {
+                add_created_accounts_to_pool: None,
+                max_account_working_set: None,
+            },
+            TransactionType::NftMintAndTransfer => GeneratorKind::NftMintAndTransfer {},
+            TransactionType::NftDrop => GeneratorKind::NftDrop {
+                num_collections: None,
+                property_map_size: None,
+            },
+            TransactionType::MultiAgent => GeneratorKind::MultiAgent {
+                module_address: None,
+            },
+            TransactionType::Script => GeneratorKind::Script { send_amount: None },
+            TransactionType::LargePackage => GeneratorKind::LargePackage { package_size: None },
+            TransactionType::PackageUpgrade => GeneratorKind::PackageUpgrade {
+                initial_size: None,
+                size_delta: None,
+                max_size: None,
+            },
+            TransactionType::ArgSweep => GeneratorKind::ArgSweep {
+                min_size: None,
+                max_size: None,
+            },
+            TransactionType::FailureInjection => GeneratorKind::FailureInjection {
+                failure_ratio: None,
+                kinds: vec![],
+                send_amount: None,
+            },
+            TransactionType::Conflict => GeneratorKind::Conflict {
+                conflict_ratio: None,
+                send_amount: None,
+            },
+        };
+        Self {
+            kind,
+            weight,
+            gas_price: None,
+        }
+    }
+}
+
+impl PhaseSpec {
+    pub fn mode(&self) -> Result<EmitJobMode> {
+        if self.max_tps.is_none()
+            && (self.target_p50_latency_ms.is_some() || self.target_p99_latency_ms.is_some())
+        {
+            bail!("Latency targets only apply to phases with max_tps");
+        }
+        match (
+            self.target_tps,
+            self.mempool_backlog,
+            self.max_tps,
+            &self.load_profile,
+        ) {
+            (Some(tps), None, None, None) => Ok(EmitJobMode::ConstTps { tps }),
+            (None, Some(mempool_backlog), None, None) => {
+                Ok(EmitJobMode::MaxLoad { mempool_backlog })
+            }
+            (None, None, Some(max_tps), None) => Ok(EmitJobMode::Adaptive {
+                max_tps,
+                target_p50_latency_ms: self
+                    .target_p50_latency_ms
+                    .unwrap_or(DEFAULT_TARGET_P50_LATENCY_MS),
+                target_p99_latency_ms: self
+                    .target_p99_latency_ms
+                    .unwrap_or(DEFAULT_TARGET_P99_LATENCY_MS),
+            }),
+            (None, None, None, Some(profile)) => {
+                profile.validate()?;
+                Ok(EmitJobMode::LoadProfile {
+                    profile: profile.clone(),
+                })
+            }
+            _ => bail!(
+                "Each phase needs exactly one of target_tps, mempool_backlog, max_tps and load_profile"
+            ),
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.duration_secs)
+    }
+}
+
+impl WorkloadSpec {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workload {}", path.display()))?;
+        // JSON is a subset of YAML, so both are parsed the same way
+        let spec: Self = serde_yaml::from_str(&contents)
+            .with_context(|| format!("Failed to parse workload {}", path.display()))?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.phases.is_empty() {
+            bail!("Workload needs at least one phase");
+        }
+        if self
+            .phases
+            .iter()
+            .any(|phase| self.phase_generators(phase).is_empty())
+        {
+            bail!("Each phase needs at least one generator, of its own or of the workload");
+        }
+        let generators: Vec<_> = self
+            .generators
+            .iter()
+            .chain(self.phases.iter().flat_map(|phase| &phase.generators))
+            .collect();
+        if generators.iter().any(|generator| generator.weight == 0) {
+            bail!("Generator weights must be positive");
+        }
+        if generators.iter().any(|generator| {
+            matches!(
+                generator.kind,
+                GeneratorKind::FailureInjection {
+                    failure_ratio: Some(ratio),
+                    ..
+                } if ratio > 100
+            )
+        }) {
+            bail!("Failure ratios are percentages, at most 100");
+        }
+        if generators.iter().any(|generator| {
+            matches!(
+                generator.kind,
+                GeneratorKind::Conflict {
+                    conflict_ratio: Some(ratio),
+                    ..
+                } if ratio > 100
+            )
+        }) {
+            bail!("Conflict ratios are percentages, at most 100");
+        }
+        if generators.iter().any(|generator| {
+            matches!(
+                generator.kind,
+                GeneratorKind::PackageUpgrade {
+                    initial_size,
+                    max_size,
+                    ..
+                } if initial_size.unwrap_or(DEFAULT_INITIAL_SIZE) > max_size.unwrap_or(DEFAULT_MAX_SIZE)
+            )
+        }) {
+            bail!("Package upgrades can't start above their max_size");
+        }
+        for phase in &self.phases {
+            phase.mode()?;
+        }
+        Ok(())
+    }
+
+    /// Generators of the phase, its own or else the workload's.
+    pub fn phase_generators<'a>(&'a self, phase: &'a PhaseSpec) -> &'a [GeneratorSpec] {
+        if phase.generators.is_empty() {
+            &self.generators
+        } else {
+            &phase.generators
+        }
+    }
+
+    /// Name of the phase at `index`, for its stats.
+    pub fn phase_name(&self, index: usize) -> String {
+        self.phases[index]
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("phase {}", index + 1))
+    }
+
+    /// Total duration of all phases.
+    pub fn duration(&self) -> Duration {
+        self.phases.iter().map(PhaseSpec::duration).sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(yaml: &str) -> WorkloadSpec {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn phase(yaml: &str) -> PhaseSpec {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    pub fn test_parse_generators() {
+        let spec = parse(
+            r#"
+generators:
+  - type: p2p
+    weight: 80
+    send_amount: 10
+    recipients:
+      distribution: zipf
+      exponent: 1.2
+  - type: account_generation
+    gas_price: 200
+  - type: nft_mint_and_transfer
+  - type: multi_agent
+    module_address: "0xcafe"
+phases:
+  - duration_secs: 60
+    target_tps: 500
+"#,
+        );
+        spec.validate().unwrap();
+        assert_eq!(spec.generators[0].weight, 80);
+        assert_eq!(
+            spec.generators[0].kind,
+            GeneratorKind::P2p {
+                send_amount: Some(10),
+                invalid_transaction_ratio: None,
+                recipients: Some(RecipientDistribution::Zipf { exponent: 1.2 }),
+            }
+        );
+        assert_eq!(spec.generators[1].weight, 1);
+        assert_eq!(spec.generators[1].gas_price, Some(200));
+        assert_eq!(
+            spec.generators[2].kind,
+            GeneratorKind::NftMintAndTransfer {}
+        );
+        assert_eq!(
+            spec.generators[3].kind,
+            GeneratorKind::MultiAgent {
+                module_address: Some(AccountAddress::from_hex_literal("0xcafe").unwrap()),
+            }
+        );
+        assert!(!spec.mix_per_account);
+        assert_eq!(
+            spec.txn_expiration_time_secs,
+            DEFAULT_TXN_EXPIRATION_TIME_SECS
+        );
+    }
+
+    #[test]
+    pub fn test_phase_modes() {
+        assert!(matches!(
+            phase("{duration_secs: 60, target_tps: 500}")
+                .mode()
+                .unwrap(),
+            EmitJobMode::ConstTps { tps: 500 }
+        ));
+        assert!(matches!(
+            phase("{duration_secs: 60, mempool_backlog: 5000}")
+                .mode()
+                .unwrap(),
+            EmitJobMode::MaxLoad {
+                mempool_backlog: 5000
+            }
+        ));
+        assert!(matches!(
+            phase("{duration_secs: 60, max_tps: 10000, target_p99_latency_ms: 5000}")
+                .mode()
+                .unwrap(),
+            EmitJobMode::Adaptive {
+                max_tps: 10000,
+                target_p50_latency_ms: DEFAULT_TARGET_P50_LATENCY_MS,
+                target_p99_latency_ms: 5000,
+            }
+        ));
+        let load_profile = phase(
+            r#"
+duration_secs: 300
+load_profile:
+  shape: step
+  steps:
+    - tps: 100
+      duration_secs: 60
+    - tps: 1000
+      duration_secs: 60
+"#,
+        );
+        match load_profile.mode().unwrap() {
+            EmitJobMode::LoadProfile { profile } => assert_eq!(profile.peak_tps(), 1000),
+            mode => panic!("Unexpected mode {:?}", mode),
+        }
+    }
+
+    #[test]
+    pub fn test_reject_several_modes() {
+        assert!(
+            phase("{duration_secs: 60, target_tps: 500, mempool_backlog: 1000}")
+                .mode()
+                .is_err()
+        );
+        assert!(phase("{duration_secs: 60, target_tps: 500, max_tps: 1000}")
+            .mode()
+            .is_err());
+        assert!(phase("{duration_secs: 60}").mode().is_err());
+    }
+
+    #[test]
+    pub fn test_reject_latency_targets_without_max_tps() {
+        assert!(
+            phase("{duration_secs: 60, target_tps: 500, target_p50_latency_ms: 1000}")
+                .mode()
+                .is_err()
+        );
+        assert!(
+            phase("{duration_secs: 60, mempool_backlog: 5000, target_p99_latency_ms: 5000}")
+                .mode()
+                .is_err()
+        );
+    }
+
+    #[test]
+    pub fn test_reject_invalid_load_profile() {
+        let spec = parse(
+            r#"
+generators:
+  - type: p2p
+phases:
+  - duration_secs: 60
+    load_profile:
+      shape: step
+      steps: []
+"#,
+        );
+        assert!(spec.validate().is_err());
+    }
+
+    #[test]
+    pub fn test_phase_generators() {
+        let spec = parse(
+            r#"
+generators:
+  - type: p2p
+phases:
+  - duration_secs: 60
+    target_tps: 500
+  - name: publish heavy
+    duration_secs: 300
+    target_tps: 100
+    generators:
+      - type: large_package
+"#,
+        );
+        spec.validate().unwrap();
+        assert_eq!(spec.phase_generators(&spec.phases[0]), &spec.generators[..]);
+        assert_eq!(
+            spec.phase_generators(&spec.phases[1])[0].kind,
+            GeneratorKind::LargePackage { package_size: None }
+        );
+        assert_eq!(spec.phase_name(0), "phase 1");
+        assert_eq!(spec.phase_name(1), "publish heavy");
+        assert_eq!(spec.duration(), Duration::from_secs(360));
+
+        // phases without their own generators need the workload's
+        let mut invalid = spec;
+        invalid.generators.clear();
+        assert!(invalid.validate().is_err());
+        invalid.phases.retain(|phase| !phase.generators.is_empty());
+        invalid.validate().unwrap();
+    }
+
+    #[test]
+    pub fn test_reject_invalid_generators() {
+        for generator in [
+            "{type: p2p, weight: 0}",
+            "{type: failure_injection, failure_ratio: 101}",
+            "{type: conflict, conflict_ratio: 150}",
+            "{type: package_upgrade, initial_size: 2000, max_size: 1000}",
+            "{type: package_upgrade, max_size: 500}",
+        ] {
+            let spec = parse(&format!(
+                "{{generators: [{}], phases: [{{duration_secs: 60, target_tps: 100}}]}}",
+                generator
+            ));
+            assert!(spec.validate().is_err(), "{} should be rejected", generator);
+        }
+    }
+
+    #[test]
+    pub fn test_reject_no_phases() {
+        assert!(parse("{generators: [{type: p2p}], phases: []}")
+            .validate()
+            .is_err());
+    }
+}
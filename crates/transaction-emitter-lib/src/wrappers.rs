@@ -4,26 +4,42 @@
 use crate::{
     args::{ClusterArgs, EmitArgs},
     cluster::Cluster,
-    emitter::{stats::TxnStats, EmitJobMode, EmitJobRequest, TxnEmitter},
+    emitter::{stats::TxnStats, EmitJobPhase, EmitJobRequest, TxnEmitter},
     instance::Instance,
+    workload::{PhaseSpec, WorkloadSpec},
 };
 use anyhow::{Context, Result};
+use aptos_logger::info;
 use aptos_sdk::transaction_builder::TransactionFactory;
 use rand::{rngs::StdRng, Rng};
 use rand_core::{OsRng, SeedableRng};
-use std::{
-    cmp::{max, min},
-    time::Duration,
-};
+use std::time::Duration;
+
+/// Stats of a phase of the workload.
+#[derive(Debug)]
+pub struct PhaseStats {
+    pub name: String,
+    pub duration: Duration,
+    pub stats: TxnStats,
+}
 
 pub async fn emit_transactions(
     cluster_args: &ClusterArgs,
     emit_args: &EmitArgs,
 ) -> Result<TxnStats> {
+    let phases = emit_transaction_phases(cluster_args, emit_args).await?;
+    Ok(total_stats(&phases))
+}
+
+/// Emits the transactions of the workload, with the stats of each of its phases.
+pub async fn emit_transaction_phases(
+    cluster_args: &ClusterArgs,
+    emit_args: &EmitArgs,
+) -> Result<Vec<PhaseStats>> {
     let cluster = Cluster::try_from_cluster_args(cluster_args)
         .await
         .context("Failed to build cluster")?;
-    emit_transactions_with_cluster(&cluster, emit_args, cluster_args.reuse_accounts).await
+    emit_transaction_phases_with_cluster(&cluster, emit_args, cluster_args.reuse_accounts).await
 }
 
 pub async fn emit_transactions_with_cluster(
@@ -31,40 +47,56 @@ pub async fn emit_transactions_with_cluster(
     args: &EmitArgs,
     reuse_accounts: bool,
 ) -> Result<TxnStats> {
-    let emitter_mode = EmitJobMode::create(args.mempool_backlog, args.target_tps);
+    let phases = emit_transaction_phases_with_cluster(cluster, args, reuse_accounts).await?;
+    Ok(total_stats(&phases))
+}
+
+pub fn total_stats(phases: &[PhaseStats]) -> TxnStats {
+    phases
+        .iter()
+        .fold(TxnStats::default(), |total, phase| &total + &phase.stats)
+}
 
-    let duration = Duration::from_secs(args.duration);
+pub async fn emit_transaction_phases_with_cluster(
+    cluster: &Cluster,
+    args: &EmitArgs,
+    reuse_accounts: bool,
+) -> Result<Vec<PhaseStats>> {
+    let spec = args.workload_spec()?;
     let client = cluster.random_instance().rest_client();
     let mut coin_source_account = cluster.load_coin_source_account(&client).await?;
     let mut emitter = TxnEmitter::new(
         TransactionFactory::new(cluster.chain_id)
-            .with_transaction_expiration_time(args.txn_expiration_time_secs)
+            .with_transaction_expiration_time(spec.txn_expiration_time_secs)
             .with_gas_unit_price(aptos_global_constants::GAS_UNIT_PRICE),
         StdRng::from_seed(OsRng.gen()),
     );
 
-    let transaction_mix = if args.transaction_type_weights.is_empty() {
-        args.transaction_type.iter().map(|t| (*t, 1)).collect()
-    } else {
-        assert_eq!(
-            args.transaction_type_weights.len(),
-            args.transaction_type.len(),
-            "Transaction types and weights need to be the same length"
+    let mut phases = Vec::with_capacity(spec.phases.len());
+    for (index, phase) in spec.phases.iter().enumerate() {
+        let mode = phase.mode()?;
+        info!(
+            "{} ({} of {}): {:?} for {} secs",
+            spec.phase_name(index),
+            index + 1,
+            spec.phases.len(),
+            mode,
+            phase.duration_secs
         );
-        args.transaction_type
-            .iter()
-            .cloned()
-            .zip(args.transaction_type_weights.iter().cloned())
-            .collect()
-    };
-
+        phases.push(EmitJobPhase {
+            mode,
+            generators: spec.phase_generators(phase).to_vec(),
+        });
+    }
     let mut emit_job_request =
         EmitJobRequest::new(cluster.all_instances().map(Instance::rest_client).collect())
-            .mode(emitter_mode)
+            .phases(phases)
             .invalid_transaction_ratio(args.invalid_tx)
-            .transaction_mix(transaction_mix)
-            .txn_expiration_time_secs(args.txn_expiration_time_secs)
+            .txn_expiration_time_secs(spec.txn_expiration_time_secs)
             .gas_price(aptos_global_constants::GAS_UNIT_PRICE);
+    if spec.mix_per_account {
+        emit_job_request = emit_job_request.mix_per_account();
+    }
     if reuse_accounts {
         emit_job_request = emit_job_request.reuse_accounts();
     }
@@ -77,13 +109,75 @@ pub async fn emit_transactions_with_cluster(
     if !cluster.coin_source_is_root {
         emit_job_request = emit_job_request.prompt_before_spending();
     }
+    // a single job runs through the phases, so accounts are only created once, and workers
+    // switch to the next phase without a gap
+    let durations: Vec<_> = spec.phases.iter().map(PhaseSpec::duration).collect();
     let stats = emitter
-        .emit_txn_for_with_stats(
-            &mut coin_source_account,
-            emit_job_request,
-            duration,
-            min(10, max(args.duration / 5, 1)),
-        )
+        .emit_txn_phases_with_stats(&mut coin_source_account, emit_job_request, &durations)
         .await?;
-    Ok(stats)
+    Ok(phase_stats(&spec, stats))
+}
+
+/// Names the stats of each phase of the workload.
+fn phase_stats(spec: &WorkloadSpec, stats: Vec<TxnStats>) -> Vec<PhaseStats> {
+    spec.phases
+        .iter()
+        .zip(stats)
+        .enumerate()
+        .map(|(index, (phase, stats))| {
+            let name = spec.phase_name(index);
+            info!("{} rate: {}", name, stats.rate(phase.duration()));
+            PhaseStats {
+                name,
+                duration: phase.duration(),
+                stats,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_phase_stats() {
+        let spec: WorkloadSpec = serde_yaml::from_str(
+            r#"
+phases:
+  - duration_secs: 60
+    target_tps: 100
+  - name: burst
+    duration_secs: 30
+    mempool_backlog: 1000
+"#,
+        )
+        .unwrap();
+        let stats = vec![
+            TxnStats {
+                submitted: 6000,
+                committed: 5900,
+                expired: 100,
+                ..TxnStats::default()
+            },
+            TxnStats {
+                submitted: 3000,
+                committed: 3000,
+                ..TxnStats::default()
+            },
+        ];
+
+        let phases = phase_stats(&spec, stats);
+        assert_eq!(phases[0].name, "phase 1");
+        assert_eq!(phases[0].duration, Duration::from_secs(60));
+        assert_eq!(phases[0].stats.expired, 100);
+        assert_eq!(phases[1].name, "burst");
+        assert_eq!(phases[1].duration, Duration::from_secs(30));
+        assert_eq!(phases[1].stats.committed, 3000);
+
+        let total = total_stats(&phases);
+        assert_eq!(total.submitted, 9000);
+        assert_eq!(total.committed, 8900);
+        assert_eq!(total.expired, 100);
+    }
 }
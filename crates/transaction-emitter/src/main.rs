@@ -7,8 +7,9 @@ use ::aptos_logger::{Level, Logger};
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use diag::diag;
-use std::time::Duration;
-use transaction_emitter_lib::{emit_transactions, Cluster, ClusterArgs, EmitArgs};
+use transaction_emitter_lib::{
+    emit_transaction_phases, total_stats, Cluster, ClusterArgs, EmitArgs,
+};
 
 #[derive(Parser, Debug)]
 struct Args {
@@ -52,14 +53,19 @@ pub async fn main() -> Result<()> {
     // TODO: Check if I need DisplayChain here in the error case.
     match args.command {
         TxnEmitterCommand::EmitTx(args) => {
-            let stats = emit_transactions(&args.cluster_args, &args.emit_args)
+            let duration = args.emit_args.workload_spec()?.duration();
+            let phases = emit_transaction_phases(&args.cluster_args, &args.emit_args)
                 .await
                 .context("Emit transactions failed")?;
+            if phases.len() > 1 {
+                for phase in &phases {
+                    println!("{} stats: {}", phase.name, phase.stats);
+                    println!("{} rate: {}", phase.name, phase.stats.rate(phase.duration));
+                }
+            }
+            let stats = total_stats(&phases);
             println!("Total stats: {}", stats);
-            println!(
-                "Average rate: {}",
-                stats.rate(Duration::from_secs(args.emit_args.duration))
-            );
+            println!("Average rate: {}", stats.rate(duration));
             Ok(())
         }
         TxnEmitterCommand::Diag(args) => {
@@ -1077,7 +1077,8 @@ async fn test_owner_create_and_delegate_flow() {
         .transfer_coins(owner_cli_index, voter_cli_index, voter_initial_coins, None)
         .await
         .unwrap()
-        .gas_used;
+        .gas_used
+        .unwrap();
     owner_gas += cli
         .transfer_coins(
             owner_cli_index,
@@ -1087,7 +1088,8 @@ async fn test_owner_create_and_delegate_flow() {
         )
         .await
         .unwrap()
-        .gas_used;
+        .gas_used
+        .unwrap();
 
     cli.assert_account_balance_now(
         owner_cli_index,
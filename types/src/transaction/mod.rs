@@ -327,6 +327,31 @@ impl RawTransaction {
         self.sender
     }
 
+    /// Return the sequence number of this transaction.
+    pub fn sequence_number(&self) -> u64 {
+        self.sequence_number
+    }
+
+    /// Return the payload of this transaction.
+    pub fn payload(&self) -> &TransactionPayload {
+        &self.payload
+    }
+
+    /// Return the maximal total gas to spend for this transaction.
+    pub fn max_gas_amount(&self) -> u64 {
+        self.max_gas_amount
+    }
+
+    /// Return the price to be paid per gas unit.
+    pub fn gas_unit_price(&self) -> u64 {
+        self.gas_unit_price
+    }
+
+    /// Return the expiration timestamp of this transaction, in seconds from the Unix Epoch.
+    pub fn expiration_timestamp_secs(&self) -> u64 {
+        self.expiration_timestamp_secs
+    }
+
     /// Return the signing message for creating transaction signature.
     pub fn signing_message(&self) -> Result<Vec<u8>, CryptoMaterialError> {
         signing_message(self)